@@ -0,0 +1,33 @@
+//! Run a graph headlessly through the library API
+//!
+//! Run with: cargo run --example run_engine [graph.yml]
+
+use anyhow::Result;
+use gidterm::ai::GidEvent;
+use gidterm::{GidTermEngine, Graph};
+use std::path::PathBuf;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    env_logger::init();
+
+    let graph_path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "simple-test.yml".to_string());
+    let graph = Graph::from_file(&PathBuf::from(graph_path))?;
+
+    let summary = GidTermEngine::new(graph)
+        .on_event(|event| match event {
+            GidEvent::TaskOutput { task_id, line, .. } => println!("[{}] {}", task_id, line),
+            GidEvent::TaskCompleted { task_id, exit_code } => {
+                println!("[{}] => done (exit {})", task_id, exit_code)
+            }
+            GidEvent::TaskFailed { task_id, error } => println!("[{}] => failed: {}", task_id, error),
+            _ => {}
+        })
+        .run_to_completion()
+        .await?;
+
+    print!("\n{}", summary);
+    Ok(())
+}
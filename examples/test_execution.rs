@@ -42,7 +42,7 @@ async fn main() -> Result<()> {
                 TaskEvent::Started { task_id } => {
                     println!("  ⚙  {} started", task_id);
                 }
-                TaskEvent::Output { task_id, line } => {
+                TaskEvent::Output { task_id, line, .. } => {
                     if !line.is_empty() {
                         println!("  │  {}: {}", task_id, line);
                     }
@@ -55,6 +55,7 @@ async fn main() -> Result<()> {
                     println!("  ✗  {} failed: {}", task_id, error);
                     completed_clone.lock().unwrap().push(task_id);
                 }
+                TaskEvent::Resources { .. } => {}
             }
         }
     });
@@ -83,7 +84,7 @@ async fn main() -> Result<()> {
                 
                 if let Some(command) = &task.command {
                     // Actually start the task
-                    executor.start_task(task_id, command).await?;
+                    executor.start_task(task_id, command, &Default::default(), &task.success_codes).await?;
                     scheduler.mark_started(task_id)?;
                 } else {
                     // No command, just mark as done
@@ -14,7 +14,7 @@
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::process::Command;
 use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
@@ -69,6 +69,7 @@ impl AgentType {
     }
 
     /// Parse from string
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Self {
         match s.to_lowercase().as_str() {
             "claude" | "claude-code" | "claudecode" => Self::Claude,
@@ -212,7 +213,7 @@ pub struct AgentState {
     /// Last status update time
     pub last_update: Instant,
     /// Recent output lines (for status detection)
-    pub recent_output: Vec<String>,
+    pub recent_output: VecDeque<String>,
 }
 
 impl AgentState {
@@ -225,16 +226,16 @@ impl AgentState {
             status: AgentRuntimeStatus::NotRunning,
             process: None,
             last_update: Instant::now(),
-            recent_output: Vec::new(),
+            recent_output: VecDeque::new(),
         }
     }
 
     /// Update with new output line
     pub fn add_output(&mut self, line: &str) {
-        self.recent_output.push(line.to_string());
+        self.recent_output.push_back(line.to_string());
         // Keep last 50 lines for status detection
         if self.recent_output.len() > 50 {
-            self.recent_output.remove(0);
+            self.recent_output.pop_front();
         }
         self.last_update = Instant::now();
     }
@@ -598,7 +599,7 @@ impl AgentManager {
                 .map(|p| self.detector.is_process_running(p.pid))
                 .unwrap_or(false);
 
-            state.status = self.parser.parse_status(&state.recent_output, process_running);
+            state.status = self.parser.parse_status(state.recent_output.make_contiguous(), process_running);
         }
     }
 
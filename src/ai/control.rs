@@ -5,10 +5,20 @@
 //! - MCP: Claude Code calls gidterm via MCP tool server
 //! - Agent: Clawdbot or other automation drives programmatically
 
+use crate::semantic::advisor::Severity;
 use crate::semantic::TaskMetrics;
 use anyhow::Result;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use super::events::{EventStream, GidEvent};
+use tokio::sync::{broadcast, mpsc, oneshot};
+
+/// Max commands queued for the app loop before transports wait
+const COMMAND_QUEUE_SIZE: usize = 64;
+
+/// Version of the `StateSnapshot` layout, bumped whenever its fields change
+pub const SNAPSHOT_SCHEMA_VERSION: u32 = 2;
 
 /// Control mode determines how gidterm is being operated
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -23,8 +33,11 @@ pub enum ControlMode {
 }
 
 /// Snapshot of current gidterm state for AI consumers
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct StateSnapshot {
+    /// `SNAPSHOT_SCHEMA_VERSION` of the gidterm that built the snapshot
+    #[serde(default)]
+    pub schema_version: u32,
     pub tasks: Vec<TaskSnapshot>,
     pub running_count: usize,
     pub done_count: usize,
@@ -32,7 +45,7 @@ pub struct StateSnapshot {
     pub total_count: usize,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TaskSnapshot {
     pub id: String,
     pub status: String,
@@ -40,6 +53,22 @@ pub struct TaskSnapshot {
     pub progress: Option<f64>,
     pub metrics: Option<HashMap<String, serde_json::Value>>,
     pub last_output: Vec<String>,
+    /// Estimated seconds until the task finishes
+    #[serde(default)]
+    pub eta_secs: Option<u64>,
+    /// Advisories the user hasn't dismissed, most recent first
+    #[serde(default)]
+    pub advisories: Vec<AdvisorySnapshot>,
+    /// Port allocated to the task's project
+    #[serde(default)]
+    pub port: Option<u16>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AdvisorySnapshot {
+    pub severity: Severity,
+    pub message: String,
+    pub suggestion: String,
 }
 
 /// Unified control interface for all modes
@@ -67,21 +96,44 @@ pub trait ControlAPI {
 }
 
 /// Command that can be sent to gidterm from any control mode
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "action", rename_all = "snake_case")]
 pub enum ControlCommand {
     /// Start all ready tasks
     StartAll,
+    /// Turn step mode on or off; while on, ready tasks wait for `step`
+    SetStepMode {
+        /// `true` pauses new tasks until `step`; `false` resumes normal scheduling
+        enabled: bool,
+    },
+    /// In step mode, start the next wave of ready tasks
+    Step,
     /// Start a specific task
-    StartTask { task_id: String },
+    StartTask {
+        /// Task ID from the graph (`project:task` in workspace mode)
+        task_id: String,
+    },
     /// Stop a task
-    StopTask { task_id: String },
+    StopTask {
+        /// Task ID from the graph
+        task_id: String,
+    },
     /// Send input to task stdin
-    SendInput { task_id: String, input: String },
+    SendInput {
+        /// Task ID of a running task
+        task_id: String,
+        /// Text to send; a newline is appended
+        input: String,
+    },
     /// Request state snapshot
     GetState,
     /// Request task output
-    GetOutput { task_id: String, lines: usize },
+    GetOutput {
+        /// Task ID from the graph
+        task_id: String,
+        /// Number of most recent lines to return
+        lines: usize,
+    },
     /// Quit gidterm
     Quit,
 }
@@ -115,6 +167,59 @@ impl ControlResponse {
     }
 }
 
+/// A command in flight from a transport (HTTP, socket) to the running app
+///
+/// The app loop owns all state, so transports hand commands over a channel
+/// and wait on `reply` for the response.
+#[derive(Debug)]
+pub struct CommandRequest {
+    pub command: ControlCommand,
+    pub reply: oneshot::Sender<ControlResponse>,
+}
+
+impl CommandRequest {
+    /// Create a request and the receiver its response will arrive on
+    pub fn new(command: ControlCommand) -> (Self, oneshot::Receiver<ControlResponse>) {
+        let (reply, rx) = oneshot::channel();
+        (Self { command, reply }, rx)
+    }
+}
+
+/// Transport-side handle onto the running app
+///
+/// Every transport (HTTP, Unix socket) dispatches through a clone of the
+/// same handle, so the app loop has a single command queue to drain.
+#[derive(Clone)]
+pub struct ControlHandle {
+    commands: mpsc::Sender<CommandRequest>,
+    events: EventStream,
+}
+
+impl ControlHandle {
+    /// Create a handle publishing `events`, plus the receiver the app loop drains
+    pub fn new(events: EventStream) -> (Self, mpsc::Receiver<CommandRequest>) {
+        let (commands, rx) = mpsc::channel(COMMAND_QUEUE_SIZE);
+        (Self { commands, events }, rx)
+    }
+
+    /// Hand a command to the app loop and wait for its response
+    pub async fn dispatch(&self, command: ControlCommand) -> ControlResponse {
+        let (request, reply) = CommandRequest::new(command);
+        if self.commands.send(request).await.is_err() {
+            return ControlResponse::error("gidterm is shutting down");
+        }
+
+        reply
+            .await
+            .unwrap_or_else(|_| ControlResponse::error("gidterm is shutting down"))
+    }
+
+    /// Subscribe to the app's event stream
+    pub fn subscribe(&self) -> broadcast::Receiver<GidEvent> {
+        self.events.subscribe()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -156,7 +261,15 @@ mod tests {
                 progress: Some(1.0),
                 metrics: None,
                 last_output: vec!["Compiling...".to_string()],
+                eta_secs: Some(42),
+                advisories: vec![AdvisorySnapshot {
+                    severity: Severity::Warning,
+                    message: "Build is slow".to_string(),
+                    suggestion: "Enable incremental builds".to_string(),
+                }],
+                port: Some(3000),
             }],
+            schema_version: SNAPSHOT_SCHEMA_VERSION,
             running_count: 0,
             done_count: 1,
             failed_count: 0,
@@ -165,5 +278,21 @@ mod tests {
         let json = serde_json::to_string(&snap).unwrap();
         assert!(json.contains("build"));
         assert!(json.contains("Build project"));
+
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["schema_version"], SNAPSHOT_SCHEMA_VERSION);
+        let task = &value["tasks"][0];
+        assert_eq!(task["eta_secs"], 42);
+        assert_eq!(task["port"], 3000);
+        assert_eq!(task["advisories"][0]["severity"], "warning");
+        assert_eq!(task["advisories"][0]["suggestion"], "Enable incremental builds");
+
+        // Snapshots from before the new fields still parse
+        let old: StateSnapshot = serde_json::from_str(
+            r#"{"tasks":[{"id":"a","status":"todo","description":"","progress":null,"metrics":null,"last_output":[]}],"running_count":0,"done_count":0,"failed_count":0,"total_count":1}"#,
+        )
+        .unwrap();
+        assert_eq!(old.schema_version, 0);
+        assert!(old.tasks[0].advisories.is_empty());
     }
 }
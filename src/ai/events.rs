@@ -4,12 +4,13 @@
 
 use crate::semantic::advisor::Advisory;
 use crate::semantic::TaskMetrics;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use tokio::sync::broadcast;
 
 /// Events emitted by gidterm for AI/automation consumers
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum GidEvent {
     /// Task started executing
@@ -20,6 +21,9 @@ pub enum GidEvent {
     TaskOutput {
         task_id: String,
         line: String,
+        /// Replaces the previous line (a `\r` progress redraw)
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        replace: bool,
     },
     /// Task completed successfully
     TaskCompleted {
@@ -58,15 +62,7 @@ impl GidEvent {
         let json_metrics: HashMap<String, serde_json::Value> = metrics
             .metrics
             .iter()
-            .map(|(k, v)| {
-                let jv = match v {
-                    crate::semantic::MetricValue::Float(f) => serde_json::json!(f),
-                    crate::semantic::MetricValue::Int(i) => serde_json::json!(i),
-                    crate::semantic::MetricValue::String(s) => serde_json::json!(s),
-                    crate::semantic::MetricValue::Bool(b) => serde_json::json!(b),
-                };
-                (k.clone(), jv)
-            })
+            .map(|(k, v)| (k.clone(), v.to_json()))
             .collect();
 
         GidEvent::MetricsUpdated {
@@ -96,6 +92,10 @@ impl GidEvent {
 }
 
 /// Broadcast-based event stream for multiple consumers
+///
+/// Cloning yields another handle to the same channel, so transports can
+/// subscribe new consumers while the app keeps emitting.
+#[derive(Clone)]
 pub struct EventStream {
     tx: broadcast::Sender<GidEvent>,
 }
@@ -0,0 +1,112 @@
+//! MCP tool schemas - lets an MCP server register gidterm's commands
+//!
+//! Each `ControlCommand` variant becomes one tool named after its `action`.
+//! A host calls a tool by sending the tool's arguments with `"action"` set
+//! to the tool name, e.g. `{"action": "start_task", "task_id": "build"}`.
+//! Everything is derived from the serde types, so the schemas can't drift
+//! from what the control server accepts.
+
+use super::control::{ControlCommand, StateSnapshot, SNAPSHOT_SCHEMA_VERSION};
+use super::events::GidEvent;
+use schemars::schema_for;
+use serde_json::{json, Map, Value};
+
+/// One MCP tool definition
+fn tool(variant: &Value) -> Option<Value> {
+    let mut properties = variant.get("properties")?.as_object()?.clone();
+    let name = properties.remove("action")?.get("enum")?.get(0)?.as_str()?.to_string();
+    let required: Vec<Value> = variant
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|r| r.iter().filter(|f| *f != "action").cloned().collect())
+        .unwrap_or_default();
+
+    let mut input_schema = Map::new();
+    input_schema.insert("type".to_string(), json!("object"));
+    input_schema.insert("properties".to_string(), Value::Object(properties));
+    if !required.is_empty() {
+        input_schema.insert("required".to_string(), Value::Array(required));
+    }
+
+    Some(json!({
+        "name": name,
+        "description": variant.get("description").cloned().unwrap_or(json!("")),
+        "inputSchema": input_schema,
+    }))
+}
+
+/// Tool definitions for every `ControlCommand`, in declaration order
+pub fn tools() -> Vec<Value> {
+    let schema = serde_json::to_value(schema_for!(ControlCommand)).unwrap_or_default();
+    schema
+        .get("oneOf")
+        .and_then(Value::as_array)
+        .map(|variants| variants.iter().filter_map(tool).collect())
+        .unwrap_or_default()
+}
+
+/// The full document: tools plus the `StateSnapshot` and `GidEvent` schemas
+pub fn schema_document() -> Value {
+    json!({
+        "name": "gidterm",
+        "version": env!("CARGO_PKG_VERSION"),
+        "snapshot_schema_version": SNAPSHOT_SCHEMA_VERSION,
+        "tools": tools(),
+        "schemas": {
+            "StateSnapshot": schema_for!(StateSnapshot),
+            "GidEvent": schema_for!(GidEvent),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tools_match_control_commands() {
+        let tools = tools();
+        let names: Vec<&str> = tools.iter().filter_map(|t| t["name"].as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "start_all",
+                "set_step_mode",
+                "step",
+                "start_task",
+                "stop_task",
+                "send_input",
+                "get_state",
+                "get_output",
+                "quit"
+            ]
+        );
+
+        let get_output = &tools[7];
+        assert_eq!(get_output["description"], "Request task output");
+        let schema = &get_output["inputSchema"];
+        assert!(schema["properties"].get("action").is_none());
+        assert!(schema["properties"]["lines"]["description"].is_string());
+        assert_eq!(schema["required"], json!(["lines", "task_id"]));
+
+        // A call is the tool's arguments tagged with its name
+        let mut call = json!({"task_id": "build", "lines": 5});
+        call["action"] = json!("get_output");
+        let command: ControlCommand = serde_json::from_value(call).unwrap();
+        assert!(matches!(command, ControlCommand::GetOutput { lines: 5, .. }));
+        for tool in tools.iter().filter(|t| t["inputSchema"].get("required").is_none()) {
+            let call = json!({"action": tool["name"]});
+            assert!(serde_json::from_value::<ControlCommand>(call).is_ok(), "{}", tool["name"]);
+        }
+    }
+
+    #[test]
+    fn test_schema_document_includes_snapshot_and_events() {
+        let doc = schema_document();
+        assert_eq!(doc["snapshot_schema_version"], SNAPSHOT_SCHEMA_VERSION);
+        assert_eq!(doc["schemas"]["StateSnapshot"]["title"], "StateSnapshot");
+        assert!(doc["schemas"]["StateSnapshot"]["definitions"]["TaskSnapshot"]["properties"]["eta_secs"].is_object());
+        let events = doc["schemas"]["GidEvent"]["oneOf"].as_array().unwrap();
+        assert!(events.iter().any(|e| e["properties"]["type"]["enum"][0] == "all_done"));
+    }
+}
@@ -6,9 +6,19 @@
 //! 3. Clawdbot automation - autonomous agent drives gidterm programmatically
 //!
 //! All modes share the same event stream and control interface.
+//! With the `server` feature, [`server::ControlServer`] exposes them over HTTP;
+//! on Unix, [`socket`] exposes them over a local domain socket. [`mcp`]
+//! describes the commands as MCP tools.
 
 pub mod control;
 pub mod events;
+pub mod mcp;
+pub mod policy;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(unix)]
+pub mod socket;
 
-pub use control::{ControlAPI, ControlMode};
+pub use control::{CommandRequest, ControlAPI, ControlHandle, ControlMode};
+pub use policy::AgentPolicy;
 pub use events::{GidEvent, EventStream};
@@ -0,0 +1,107 @@
+//! Agent policy - guardrails for an autonomous agent driving gidterm
+//!
+//! With `safe_mode` on, a control client (HTTP, WebSocket or Unix socket)
+//! may only start and stop allowlisted tasks and send input only when
+//! `allow_input` is set, and an agent (`ControlMode::Agent`) only runs
+//! allowlisted semantic commands. Reading state and output is always fine.
+
+use super::control::ControlCommand;
+use anyhow::Result;
+use serde::Deserialize;
+
+/// Agent settings read from the `[agent]` table of ~/.gidterm/config.toml
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AgentPolicy {
+    /// Enforce the allowlists below. Off unless set.
+    #[serde(default)]
+    pub safe_mode: bool,
+    /// Task IDs an agent may start, stop or send input to
+    #[serde(default)]
+    pub allowed_tasks: Vec<String>,
+    /// Semantic command labels an agent may run as auto-actions
+    #[serde(default)]
+    pub allowed_commands: Vec<String>,
+    /// Let an agent type into allowlisted tasks
+    #[serde(default)]
+    pub allow_input: bool,
+}
+
+impl AgentPolicy {
+    fn task_allowed(&self, task_id: &str) -> bool {
+        self.allowed_tasks.iter().any(|t| t == task_id)
+    }
+
+    /// Whether an agent may run the semantic command `label`
+    pub fn command_allowed(&self, label: &str) -> bool {
+        !self.safe_mode || self.allowed_commands.iter().any(|c| c == label)
+    }
+
+    /// Reject a command an agent isn't allowed to issue
+    pub fn check(&self, command: &ControlCommand) -> Result<()> {
+        if !self.safe_mode {
+            return Ok(());
+        }
+
+        match command {
+            ControlCommand::StartAll | ControlCommand::Step => {
+                anyhow::bail!("Safe mode: starting every ready task is not allowed; start allowlisted tasks by ID")
+            }
+            ControlCommand::StartTask { task_id } | ControlCommand::StopTask { task_id } => {
+                if !self.task_allowed(task_id) {
+                    anyhow::bail!("Safe mode: task '{}' is not in allowed_tasks", task_id);
+                }
+            }
+            ControlCommand::SendInput { task_id, .. } => {
+                if !self.allow_input {
+                    anyhow::bail!("Safe mode: send_input is disabled (set allow_input)");
+                }
+                if !self.task_allowed(task_id) {
+                    anyhow::bail!("Safe mode: task '{}' is not in allowed_tasks", task_id);
+                }
+            }
+            ControlCommand::SetStepMode { .. }
+            | ControlCommand::GetState
+            | ControlCommand::GetOutput { .. }
+            | ControlCommand::Quit => {}
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn test_safe_mode_allowlists() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(
+            &path,
+            "[advisor]\nauto_actions = true\n\n[agent]\nsafe_mode = true\nallowed_tasks = [\"test\"]\nallowed_commands = [\"Reduce LR\"]\n",
+        )
+        .unwrap();
+        let policy = Config::load_layers(&path, &dir.path().join("missing.toml")).unwrap().agent;
+
+        let start = |id: &str| ControlCommand::StartTask { task_id: id.to_string() };
+        assert!(policy.check(&start("test")).is_ok());
+        assert!(policy.check(&start("deploy")).is_err());
+        assert!(policy.check(&ControlCommand::StartAll).is_err());
+        assert!(policy.check(&ControlCommand::GetState).is_ok());
+        let input = ControlCommand::SendInput {
+            task_id: "test".to_string(),
+            input: "y".to_string(),
+        };
+        assert!(policy.check(&input).is_err());
+        assert!(policy.command_allowed("Reduce LR"));
+        assert!(!policy.command_allowed("Deploy"));
+
+        // Without safe mode everything goes
+        let open = AgentPolicy::default();
+        assert!(open.check(&start("deploy")).is_ok());
+        assert!(open.check(&input).is_ok());
+        assert!(open.command_allowed("Deploy"));
+    }
+}
@@ -0,0 +1,324 @@
+//! HTTP control server for the MCP/agent modes
+//!
+//! Exposes the control protocol over HTTP:
+//! - `POST /command` - takes a `ControlCommand` JSON body, returns a `ControlResponse`
+//! - `GET /events` - streams `GidEvent`s as server-sent events
+//! - `GET /ws` - WebSocket: a `StateSnapshot` frame, then every `GidEvent`
+//!
+//! The server never touches `App` directly. Commands go through a
+//! [`ControlHandle`] to the main loop, which drains them between frames.
+//!
+//! There is no authentication: anyone who can reach the server can run
+//! commands in the tasks. It only binds loopback addresses unless remote
+//! access is allowed, and then only answers requests addressed to a
+//! loopback host, so a web page can't reach it through DNS rebinding.
+//! WebSocket upgrades (which CORS doesn't cover) must also come from a
+//! loopback origin, so other sites' pages can't read the event stream.
+
+use super::control::{ControlCommand, ControlHandle, ControlResponse};
+use anyhow::Result;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Request, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::Response;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
+use tokio::net::TcpListener;
+use tokio::sync::broadcast::error::RecvError;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::{Stream, StreamExt};
+
+/// HTTP front end that forwards commands to the app loop
+#[derive(Clone)]
+pub struct ControlServer {
+    handle: ControlHandle,
+    allow_remote: bool,
+}
+
+impl ControlServer {
+    /// Create a server dispatching through `handle`
+    pub fn new(handle: ControlHandle) -> Self {
+        Self {
+            handle,
+            allow_remote: false,
+        }
+    }
+
+    /// Allow binding non-loopback addresses and requests for any host
+    pub fn allow_remote(mut self, allow: bool) -> Self {
+        self.allow_remote = allow;
+        self
+    }
+
+    /// Build the axum router
+    pub fn router(self) -> Router {
+        Router::new()
+            .route("/command", post(command_handler))
+            .route("/events", get(events_handler))
+            .route("/ws", get(ws_handler))
+            .layer(middleware::from_fn_with_state(self.clone(), check_host))
+            .with_state(self)
+    }
+
+    /// Bind `addr`, refusing non-loopback addresses unless remote access is allowed
+    pub async fn bind(&self, addr: SocketAddr) -> Result<TcpListener> {
+        if !addr.ip().is_loopback() && !self.allow_remote {
+            anyhow::bail!(
+                "Refusing to serve the control API on {}: anyone who can reach it could run commands in your tasks. \
+                 Use a loopback address such as 127.0.0.1, or pass --allow-remote",
+                addr
+            );
+        }
+        Ok(TcpListener::bind(addr).await?)
+    }
+
+    /// Bind `addr` and serve until the process exits
+    pub async fn serve(self, addr: SocketAddr) -> Result<()> {
+        let listener = self.bind(addr).await?;
+        self.serve_on(listener).await
+    }
+
+    /// Serve on an already-bound listener
+    pub async fn serve_on(self, listener: TcpListener) -> Result<()> {
+        log::info!("Control server listening on http://{}", listener.local_addr()?);
+        axum::serve(listener, self.router()).await?;
+        Ok(())
+    }
+}
+
+/// Reject requests addressed to anything but a loopback host
+async fn check_host(State(server): State<ControlServer>, request: Request, next: Next) -> Result<Response, StatusCode> {
+    if !server.allow_remote {
+        let host = request.headers().get(header::HOST).and_then(|h| h.to_str().ok());
+        if !host.is_some_and(is_loopback_host) {
+            log::warn!("Rejected control request for host {:?}", host);
+            return Err(StatusCode::FORBIDDEN);
+        }
+    }
+    Ok(next.run(request).await)
+}
+
+/// Whether `host` (as in a Host header, port optional) names this machine
+fn is_loopback_host(host: &str) -> bool {
+    let name = match host.strip_prefix('[') {
+        Some(bracketed) => bracketed.split(']').next().unwrap_or_default(),
+        None => host.rsplit_once(':').map_or(host, |(name, _)| name),
+    };
+    name.eq_ignore_ascii_case("localhost") || name.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback())
+}
+
+async fn command_handler(
+    State(server): State<ControlServer>,
+    Json(command): Json<ControlCommand>,
+) -> Json<ControlResponse> {
+    Json(server.handle.dispatch(command).await)
+}
+
+async fn events_handler(
+    State(server): State<ControlServer>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    // Lagging consumers silently skip the events they missed
+    let stream = BroadcastStream::new(server.handle.subscribe())
+        .filter_map(|event| event.ok())
+        .map(|event| Ok(Event::default().data(event.to_json_line())));
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+async fn ws_handler(
+    ws: WebSocketUpgrade,
+    headers: HeaderMap,
+    State(server): State<ControlServer>,
+) -> Result<Response, StatusCode> {
+    // Browsers always send Origin; other clients may leave it out
+    if let Some(origin) = headers.get(header::ORIGIN) {
+        let origin = origin.to_str().unwrap_or_default();
+        if !is_loopback_origin(origin) {
+            log::warn!("Rejected WebSocket client from origin {:?}", origin);
+            return Err(StatusCode::FORBIDDEN);
+        }
+    }
+    Ok(ws.on_upgrade(move |socket| forward_events(socket, server)))
+}
+
+/// Whether `origin` (`scheme://host[:port]`) is a page served from this machine
+fn is_loopback_origin(origin: &str) -> bool {
+    origin
+        .split_once("://")
+        .is_some_and(|(_, authority)| is_loopback_host(authority))
+}
+
+/// Send a state snapshot, then every event, until the client goes away
+async fn forward_events(mut socket: WebSocket, server: ControlServer) {
+    // Subscribe before taking the snapshot so no event falls in between
+    let mut events = server.handle.subscribe();
+
+    let snapshot = match server.handle.dispatch(ControlCommand::GetState).await {
+        ControlResponse::Ok { data: Some(snapshot) } => snapshot,
+        other => {
+            log::warn!("Closing WebSocket client, state snapshot unavailable: {:?}", other);
+            return;
+        }
+    };
+    if socket.send(Message::Text(snapshot.to_string())).await.is_err() {
+        return;
+    }
+
+    loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Ok(event) => {
+                    if socket.send(Message::Text(event.to_json_line())).await.is_err() {
+                        break;
+                    }
+                }
+                Err(RecvError::Lagged(missed)) => {
+                    // Never let a slow client hold up the app; make it reconnect
+                    log::warn!("Dropping WebSocket client that fell {} events behind", missed);
+                    break;
+                }
+                Err(RecvError::Closed) => break,
+            },
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::control::CommandRequest;
+    use crate::ai::{EventStream, GidEvent};
+    use std::time::Duration;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::sync::mpsc;
+    use tokio::net::TcpStream;
+
+    async fn start_server() -> (SocketAddr, EventStream, mpsc::Receiver<CommandRequest>) {
+        let events = EventStream::default();
+        let (handle, rx) = ControlHandle::new(events.clone());
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(ControlServer::new(handle).serve_on(listener));
+        (addr, events, rx)
+    }
+
+    #[tokio::test]
+    async fn test_post_command_round_trip() {
+        let (addr, _events, mut rx) = start_server().await;
+
+        tokio::spawn(async move {
+            let request = rx.recv().await.unwrap();
+            assert!(matches!(request.command, ControlCommand::GetState));
+            let _ = request.reply.send(ControlResponse::ok_with_data(serde_json::json!("pong")));
+        });
+
+        let body = r#"{"action":"get_state"}"#;
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let request = format!(
+            "POST /command HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"));
+        assert!(response.contains(r#""status":"ok""#));
+        assert!(response.contains("pong"));
+    }
+
+    /// Send a raw HTTP request and return the whole response
+    async fn request(addr: SocketAddr, head: &str) -> String {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream.write_all(head.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn test_rejects_foreign_host() {
+        let (addr, _events, _rx) = start_server().await;
+
+        let response = request(addr, "GET /events HTTP/1.1\r\nHost: evil.example:7777\r\nConnection: close\r\n\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 403"), "{}", response);
+
+        for host in ["localhost:7777", "127.0.0.1", "[::1]:7777"] {
+            assert!(is_loopback_host(host), "{}", host);
+        }
+        assert!(!is_loopback_host("127.0.0.1.evil.example"));
+        assert!(!is_loopback_host("192.168.1.5:7777"));
+    }
+
+    #[tokio::test]
+    async fn test_websocket_rejects_foreign_origin() {
+        let (addr, _events, _rx) = start_server().await;
+
+        let upgrade = |origin: &str| {
+            format!(
+                "GET /ws HTTP/1.1\r\nHost: localhost\r\nConnection: Upgrade\r\nUpgrade: websocket\r\n\
+                 Sec-WebSocket-Version: 13\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\nOrigin: {}\r\n\r\n",
+                origin
+            )
+        };
+        for (origin, status) in [("https://evil.example", "403"), ("null", "403"), ("http://localhost:3000", "101")] {
+            let mut stream = TcpStream::connect(addr).await.unwrap();
+            stream.write_all(upgrade(origin).as_bytes()).await.unwrap();
+            let mut buf = vec![0u8; 1024];
+            let n = stream.read(&mut buf).await.unwrap();
+            let response = String::from_utf8_lossy(&buf[..n]);
+            assert!(response.starts_with(&format!("HTTP/1.1 {}", status)), "{}: {}", origin, response);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bind_refuses_remote_addresses() {
+        let (handle, _rx) = ControlHandle::new(EventStream::default());
+        let server = ControlServer::new(handle);
+        let err = server.bind("0.0.0.0:0".parse().unwrap()).await.unwrap_err();
+        assert!(err.to_string().contains("--allow-remote"));
+
+        assert!(server.bind("127.0.0.1:0".parse().unwrap()).await.is_ok());
+        assert!(server.allow_remote(true).bind("0.0.0.0:0".parse().unwrap()).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_events_stream() {
+        let (addr, events, _rx) = start_server().await;
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(b"GET /events HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+
+        // Headers arrive once the handler has subscribed
+        let mut buf = vec![0u8; 4096];
+        let n = stream.read(&mut buf).await.unwrap();
+        assert!(String::from_utf8_lossy(&buf[..n]).contains("text/event-stream"));
+
+        events.emit(GidEvent::TaskStarted {
+            task_id: "build".to_string(),
+        });
+
+        let mut received = String::new();
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !received.contains("task_started") {
+                let n = stream.read(&mut buf).await.unwrap();
+                received.push_str(&String::from_utf8_lossy(&buf[..n]));
+            }
+        })
+        .await
+        .expect("event not streamed");
+        assert!(received.contains("build"));
+    }
+}
@@ -0,0 +1,147 @@
+//! Unix domain socket control for local agents
+//!
+//! Speaks newline-delimited JSON: each `ControlCommand` line written by the
+//! client is answered with one `ControlResponse` line. Every `GidEvent` is
+//! interleaved on the same connection; responses carry a `status` field and
+//! events a `type` field, so clients can tell them apart.
+
+use super::control::{ControlCommand, ControlHandle, ControlResponse};
+use anyhow::{Context, Result};
+use std::os::unix::fs::FileTypeExt;
+use std::path::Path;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::unix::OwnedWriteHalf;
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::broadcast::error::RecvError;
+
+/// Bind a control socket at `path`
+///
+/// A stale socket file left by a crashed run is replaced, but a socket
+/// another gidterm is still listening on is reported as an error, and so is
+/// anything at `path` that isn't a socket.
+pub fn bind(path: &Path) -> Result<UnixListener> {
+    if let Ok(meta) = std::fs::symlink_metadata(path) {
+        if !meta.file_type().is_socket() {
+            anyhow::bail!("{} exists and is not a socket", path.display());
+        }
+        if std::os::unix::net::UnixStream::connect(path).is_ok() {
+            anyhow::bail!("Control socket {} is already in use", path.display());
+        }
+        std::fs::remove_file(path)
+            .with_context(|| format!("Failed to remove stale socket {}", path.display()))?;
+    }
+
+    UnixListener::bind(path).with_context(|| format!("Failed to bind {}", path.display()))
+}
+
+/// Accept connections until the process exits
+pub async fn serve(listener: UnixListener, handle: ControlHandle) -> Result<()> {
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let handle = handle.clone();
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(stream, handle).await {
+                log::debug!("Control socket client disconnected: {}", e);
+            }
+        });
+    }
+}
+
+/// Remove the socket file on shutdown
+pub fn cleanup(path: &Path) {
+    if let Err(e) = std::fs::remove_file(path) {
+        log::warn!("Failed to remove control socket {}: {}", path.display(), e);
+    }
+}
+
+async fn handle_connection(stream: UnixStream, handle: ControlHandle) -> Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+    let mut events = handle.subscribe();
+
+    loop {
+        tokio::select! {
+            line = lines.next_line() => {
+                let Some(line) = line? else {
+                    break;
+                };
+                if line.trim().is_empty() {
+                    continue;
+                }
+
+                let response = match serde_json::from_str::<ControlCommand>(&line) {
+                    Ok(command) => handle.dispatch(command).await,
+                    Err(e) => ControlResponse::error(format!("Invalid command: {}", e)),
+                };
+                write_line(&mut writer, &serde_json::to_string(&response)?).await?;
+            }
+            event = events.recv() => match event {
+                Ok(event) => write_line(&mut writer, &event.to_json_line()).await?,
+                Err(RecvError::Lagged(missed)) => {
+                    log::warn!("Control socket client skipped {} events", missed);
+                }
+                Err(RecvError::Closed) => break,
+            },
+        }
+    }
+
+    Ok(())
+}
+
+async fn write_line(writer: &mut OwnedWriteHalf, line: &str) -> Result<()> {
+    writer.write_all(line.as_bytes()).await?;
+    writer.write_all(b"\n").await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::{EventStream, GidEvent};
+
+    #[tokio::test]
+    async fn test_socket_commands_and_events() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("gidterm.sock");
+
+        let events = EventStream::default();
+        let (handle, mut rx) = ControlHandle::new(events.clone());
+        tokio::spawn(serve(bind(&path).unwrap(), handle));
+
+        tokio::spawn(async move {
+            while let Some(request) = rx.recv().await {
+                let _ = request.reply.send(ControlResponse::ok());
+            }
+        });
+
+        let stream = UnixStream::connect(&path).await.unwrap();
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+
+        writer.write_all(b"{\"action\":\"start_all\"}\nnot json\n").await.unwrap();
+        let ok = lines.next_line().await.unwrap().unwrap();
+        assert_eq!(ok, r#"{"status":"ok"}"#);
+        let invalid = lines.next_line().await.unwrap().unwrap();
+        assert!(invalid.contains("Invalid command"));
+
+        events.emit(GidEvent::TaskStarted {
+            task_id: "build".to_string(),
+        });
+        let event = lines.next_line().await.unwrap().unwrap();
+        assert!(event.contains(r#""type":"task_started""#));
+
+        // A live socket can't be bound twice
+        assert!(bind(&path).is_err());
+    }
+
+    #[test]
+    fn test_bind_keeps_files_that_arent_sockets() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("graph.yml");
+        std::fs::write(&path, "tasks: {}\n").unwrap();
+
+        let err = bind(&path).unwrap_err();
+        assert!(err.to_string().contains("not a socket"), "{}", err);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "tasks: {}\n");
+    }
+}
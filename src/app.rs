@@ -1,26 +1,45 @@
 //! Application state and main event loop
 
 use crate::agents::{AgentManager, AgentRuntimeStatus, AgentType};
-use crate::core::{Executor, Graph, Scheduler, TaskEvent};
+use crate::ai::control::{
+    AdvisorySnapshot, ControlCommand, ControlMode, ControlResponse, StateSnapshot, TaskSnapshot, SNAPSHOT_SCHEMA_VERSION,
+};
+use crate::ai::policy::AgentPolicy;
+use crate::config::Config;
+use crate::ai::{CommandRequest, EventStream, GidEvent};
+use crate::core::{
+    CommandVars, CompletionCheck, Executor, Graph, GraphTaskStatus, GraphWatcher, ReadinessCheck, ReconcileReport, ResourceUsage,
+    RingBuffer, Scheduler, TaskEvent, DEFAULT_SHELL,
+};
+use crate::hooks::{self, HookTrigger};
 use crate::notifications::NotificationManager;
 use crate::ports::PortManager;
-use crate::semantic::advisor::{Advisory, SmartAdvisor};
-use crate::semantic::commands::TaskCommands;
+use crate::semantic::advisor::{Advisory, AdvisoryHistory, Severity, SmartAdvisor, DEFAULT_STALL_SECS};
+use crate::semantic::commands::{ParamKind, SemanticCommand, TaskCommands};
 use crate::semantic::history::{self, TaskMetricHistory};
-use crate::semantic::parsers::{BuildParser, MLTrainingParser, RegexParser};
-use crate::semantic::{MetricValue, ParserRegistry, TaskMetrics};
-use crate::session::{Session, TaskStatus};
+use crate::semantic::parsers::{BuildParser, DockerParser, GoTestParser, MLTrainingParser, RegexParser, TqdmParser};
+use crate::semantic::{LevelClassifier, LogLevel, MetricValue, ParseThrottle, ParserRegistry, TaskMetrics};
+use crate::logs::{self, TaskLog, DEFAULT_BUFFER_LINES};
+use crate::session::{Session, SessionSaver, TaskStatus, UiState};
+use crate::ui::Theme;
 use anyhow::Result;
 use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
-use std::collections::HashMap;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::vec_deque::Iter;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::OnceLock;
 use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 
-/// Max output lines stored per task in App
-const MAX_APP_OUTPUT_LINES: usize = 2000;
-
 /// Active view mode
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ViewMode {
     /// Live dashboard with task list and output
     Dashboard,
@@ -34,6 +53,153 @@ pub enum ViewMode {
     ProjectOverview,
 }
 
+/// Collects `{param}` values for a semantic command before sending it
+#[derive(Debug, Clone)]
+pub struct CommandPrompt {
+    pub task_id: String,
+    pub label: String,
+    /// Parameters still to be entered, in template order
+    pub params: Vec<String>,
+    pub values: HashMap<String, String>,
+    /// Declared type of each parameter
+    pub kinds: HashMap<String, ParamKind>,
+    /// Text typed for the current parameter
+    pub input: String,
+    /// Why the last entered value was rejected
+    pub error: Option<String>,
+}
+
+impl CommandPrompt {
+    /// Parameter currently being entered
+    pub fn current_param(&self) -> Option<&str> {
+        self.params.get(self.values.len()).map(|p| p.as_str())
+    }
+
+    /// Declared type of the parameter currently being entered
+    pub fn current_kind(&self) -> ParamKind {
+        self.current_param()
+            .and_then(|p| self.kinds.get(p).copied())
+            .unwrap_or_default()
+    }
+}
+
+/// A line of input being typed for a task that is waiting for it
+#[derive(Debug, Clone, Default)]
+pub struct InputPrompt {
+    pub task_id: String,
+    pub input: String,
+}
+
+/// Search within the selected task's output (Terminal view)
+#[derive(Debug, Clone, Default)]
+pub struct OutputSearch {
+    pub query: String,
+    /// Still typing the query
+    pub editing: bool,
+    /// Which match (in output order) is in view
+    pub current: usize,
+}
+
+impl OutputSearch {
+    /// Case-insensitive matcher for the query, `None` while it is empty
+    pub fn regex(&self) -> Option<Regex> {
+        if self.query.is_empty() {
+            return None;
+        }
+        Regex::new(&format!("(?i){}", regex::escape(&self.query))).ok()
+    }
+}
+
+/// How long a notification toast stays on screen
+pub const TOAST_DURATION: Duration = Duration::from_secs(4);
+
+/// Session changes are written to disk at most this often
+pub const SESSION_SAVE_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Most executor events handled by one `process_events` call
+pub const MAX_EVENTS_PER_TICK: usize = 1000;
+
+/// Main loop tick while tasks are producing output
+pub const DEFAULT_ACTIVE_TICK: Duration = Duration::from_millis(30);
+
+/// Main loop tick once nothing has happened for `ACTIVE_WINDOW`
+pub const IDLE_TICK: Duration = Duration::from_millis(250);
+
+/// How long after the last task event the loop keeps ticking fast
+pub const ACTIVE_WINDOW: Duration = Duration::from_secs(1);
+
+/// Dashboard group for tasks without a `component`
+pub const UNGROUPED_COMPONENT: &str = "ungrouped";
+
+/// Main loop pacing: short ticks while task events arrive, long ones when
+/// everything is idle or quietly waiting
+#[derive(Debug, Clone)]
+pub struct TickRate {
+    active: Duration,
+    idle: Duration,
+    last_activity: Option<Instant>,
+}
+
+impl TickRate {
+    /// Tick every `active` while busy (`--tick-ms`); the idle tick is never
+    /// shorter than that
+    pub fn new(active: Duration) -> Self {
+        Self {
+            active,
+            idle: IDLE_TICK.max(active),
+            last_activity: None,
+        }
+    }
+
+    /// A task event arrived
+    pub fn record_activity(&mut self, now: Instant) {
+        self.last_activity = Some(now);
+    }
+
+    /// How long the loop should wait before its next tick
+    pub fn interval(&self, now: Instant) -> Duration {
+        match self.last_activity {
+            Some(last) if now.saturating_duration_since(last) < ACTIVE_WINDOW => self.active,
+            _ => self.idle,
+        }
+    }
+}
+
+impl Default for TickRate {
+    fn default() -> Self {
+        Self::new(DEFAULT_ACTIVE_TICK)
+    }
+}
+
+/// Whether ready tasks start on their own or one wave at a time
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StepMode {
+    /// Ready tasks start as soon as they're ready
+    #[default]
+    Off,
+    /// Nothing new starts until a step is requested
+    Paused,
+    /// The next batch of ready tasks starts, then the run pauses again
+    Step,
+}
+
+/// A notification shown briefly inside the TUI
+#[derive(Debug, Clone)]
+pub struct Toast {
+    pub message: String,
+    pub expires: Instant,
+}
+
+/// Advisory auto-action waiting for the user's confirmation
+#[derive(Debug, Clone)]
+pub struct PendingAction {
+    pub task_id: String,
+    /// Semantic command to run
+    pub label: String,
+    /// Advisory that suggested it
+    pub message: String,
+}
+
 /// Agent/task status for quick visibility
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AgentStatus {
@@ -61,15 +227,14 @@ impl AgentStatus {
         }
     }
 
-    /// Get color for status (ratatui Color)
-    pub fn color(&self) -> ratatui::style::Color {
-        use ratatui::style::Color;
+    /// Get color for status from the theme
+    pub fn color(&self, theme: &Theme) -> ratatui::style::Color {
         match self {
-            Self::Running => Color::Green,
-            Self::WaitingInput => Color::Blue,
-            Self::Completed => Color::Gray,
-            Self::Error => Color::Red,
-            Self::Idle => Color::DarkGray,
+            Self::Running => theme.done,
+            Self::WaitingInput => theme.waiting,
+            Self::Completed => theme.pending,
+            Self::Error => theme.failed,
+            Self::Idle => theme.planned,
         }
     }
 }
@@ -87,41 +252,185 @@ pub struct ProjectSummary {
     pub recent_event: Option<String>,
 }
 
+/// Final outcome of a run, printed once the TUI is torn down
+#[derive(Debug, Clone, Serialize)]
+pub struct RunSummary {
+    pub total: usize,
+    pub done: usize,
+    pub failed: usize,
+    /// Failed tasks marked `allow_failure`, included in `failed`
+    pub allowed_failures: usize,
+    pub wall_time_secs: f64,
+    pub slowest_task: Option<TaskDuration>,
+    /// Tasks that never finished (pending, planned or still running)
+    pub pending: Vec<String>,
+}
+
+/// How long a task's most recent run took
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskDuration {
+    pub task_id: String,
+    pub duration_secs: f64,
+}
+
+impl fmt::Display for RunSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let allowed = if self.allowed_failures > 0 {
+            format!(" ({} allowed)", self.allowed_failures)
+        } else {
+            String::new()
+        };
+        writeln!(
+            f,
+            "Run summary: {} tasks, {} done, {} failed{} in {}",
+            self.total,
+            self.done,
+            self.failed,
+            allowed,
+            history::format_eta(Duration::from_secs_f64(self.wall_time_secs))
+        )?;
+        if let Some(slowest) = &self.slowest_task {
+            writeln!(
+                f,
+                "  Slowest: {} ({})",
+                slowest.task_id,
+                history::format_eta(Duration::from_secs_f64(slowest.duration_secs))
+            )?;
+        }
+        if !self.pending.is_empty() {
+            writeln!(f, "  Pending: {}", self.pending.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
 /// Application state
 pub struct App {
     pub scheduler: Scheduler,
     pub executor: Executor,
-    pub event_rx: mpsc::UnboundedReceiver<TaskEvent>,
-    pub task_outputs: HashMap<String, Vec<String>>,
+    pub event_rx: mpsc::Receiver<TaskEvent>,
+    pub task_outputs: HashMap<String, RingBuffer<String>>,
+    /// Level of each line in `task_outputs`, index for index
+    pub task_levels: HashMap<String, RingBuffer<LogLevel>>,
+    level_classifier: LevelClassifier,
+    /// Output lines kept in memory per task (`[output] buffer_lines`)
+    pub buffer_lines: usize,
+    /// Where task output is spilled to disk, if enabled (`[output] spill`)
+    pub log_dir: Option<PathBuf>,
+    task_logs: HashMap<String, TaskLog>,
     pub should_quit: bool,
     pub selected_task: usize,
     pub last_update: Instant,
     pub session: Session,
+    /// Writes `session` to disk at most every `SESSION_SAVE_INTERVAL`
+    pub session_saver: SessionSaver,
+    /// How long the main loop waits between ticks
+    pub tick: TickRate,
     pub workspace_mode: bool,
     pub project_names: Vec<String>,
     pub parser_registry: ParserRegistry,
+    /// Merge every matching parser's metrics (`[parsers] merge` in config.toml)
+    pub merge_parsers: bool,
     pub task_metrics: HashMap<String, TaskMetrics>,
+    /// Limits how often each task's output goes through the parsers
+    pub metric_throttle: ParseThrottle,
     pub metric_history: HashMap<String, TaskMetricHistory>,
+    /// Peak memory and CPU time sampled from each task's processes
+    pub task_resources: HashMap<String, ResourceUsage>,
     pub advisor: SmartAdvisor,
-    pub advisories: HashMap<String, Vec<Advisory>>,
+    /// Distinct advisories per task, deduplicated over time
+    pub advisories: HashMap<String, AdvisoryHistory>,
     pub view_mode: ViewMode,
+    /// Show the graph's nodes, grouped by layer, beside the task DAG (`n` in the graph view)
+    pub show_nodes: bool,
+    /// Group the dashboard's tasks by `component` instead of by project (`G`)
+    pub group_by_component: bool,
+    /// Lines scrolled up from the tail of the selected task's output
     pub scroll_offset: usize,
+    /// Jump to the tail as output arrives; when off, the view stays put
+    pub follow: bool,
+    /// Show only error lines in the terminal view
+    pub errors_only: bool,
     // Phase 1: Multi-Project DX
     pub port_manager: PortManager,
     pub notification_manager: NotificationManager,
+    /// Latest notification, shown in the TUI until it expires
+    pub toast: Option<Toast>,
+    /// Colors for every view (`~/.gidterm/theme.toml`, `--theme`)
+    pub theme: Theme,
     pub selected_project: usize,
     pub search_query: String,
     pub search_mode: bool,
-    pub recent_events: Vec<(Instant, String, String)>, // (time, project, message)
+    /// Narrow the task and project lists as the query is typed; off means
+    /// the query only jumps to the first match on Enter (`Tab` switches)
+    pub search_filter: bool,
+    /// In-output search for the Terminal view, separate from project search
+    pub output_search: Option<OutputSearch>,
+    /// Only show tasks with a tag containing this text
+    pub tag_filter: Option<String>,
+    pub tag_filter_mode: bool,
+    /// Parameter prompt for a semantic command triggered by an F-key
+    pub command_prompt: Option<CommandPrompt>,
+    /// Reply being typed for a task waiting for input (`i`)
+    pub input_prompt: Option<InputPrompt>,
+    /// Task whose PTY gets every keystroke until `Esc` (`Ctrl-A i`)
+    pub interact_task: Option<String>,
+    /// `Ctrl-A` was pressed in the terminal view; `i` enters interact mode
+    interact_prefix: bool,
+    /// Running tasks whose output looks like a prompt, until answered
+    waiting_input: HashSet<String>,
+    /// Who is driving; in `Agent` mode auto-actions run without asking
+    pub control_mode: ControlMode,
+    /// What an agent may do (`[agent]` in config.toml); only applies in `Agent` mode
+    pub agent_policy: AgentPolicy,
+    /// Act on advisory auto-actions (`[advisor] auto_actions` in config.toml)
+    pub auto_actions: bool,
+    /// Auto-action waiting for y/n
+    pub pending_action: Option<PendingAction>,
+    /// `X` was pressed: abort every running task if the next key is `y`
+    pub confirm_abort: bool,
+    /// `q` was pressed with tasks running: quit if the next key is `y`
+    pub confirm_quit: bool,
+    /// Quit on `q` without asking, even with tasks running (`--force-quit`)
+    pub force_quit: bool,
+    /// Run one dependency wave per step instead of everything ready (`S`)
+    pub step_mode: StepMode,
+    /// Tasks already recorded as finished (aborted, or done by `complete_on`)
+    /// whose exit event hasn't arrived yet
+    settled: HashSet<String>,
+    auto_actions_offered: HashSet<(String, String)>,
+    pub recent_events: VecDeque<(Instant, String, String)>, // (time, project, message)
     pub task_start_times: HashMap<String, Instant>,
+    /// When each running task last produced output
+    pub last_output_time: HashMap<String, Instant>,
+    /// Seconds without output before a task is reported stalled (`[advisor] stall_secs`)
+    pub stall_secs: u64,
+    stalled: HashSet<String>,
+    /// `ready_when` checks of running tasks that aren't ready yet
+    readiness: HashMap<String, ReadinessCheck>,
+    /// `complete_on` checks of running tasks that haven't matched yet
+    completion: HashMap<String, CompletionCheck>,
     // Phase 2: Agent Integration
     pub agent_manager: AgentManager,
     pub last_agent_scan: Instant,
+    // AI integration: events for MCP/agent consumers
+    pub events: EventStream,
+    /// Quit once a failure halts a `FailFast` run
+    pub quit_on_failure: bool,
+    /// Quit once the remaining tasks are all blocked (headless runs)
+    pub quit_on_deadlock: bool,
+    all_done_emitted: bool,
+    deadlock_reported: bool,
 }
 
 impl App {
     /// Create a new app from graph (single project mode)
     pub fn new(graph: Graph) -> Self {
+        Self::with_config(graph, &Config::load_or_default())
+    }
+
+    /// Create a single-project app with already loaded settings
+    pub fn with_config(graph: Graph, config: &Config) -> Self {
         let scheduler = Scheduler::new(graph.clone());
         let (executor, event_rx) = Executor::new();
 
@@ -133,9 +442,13 @@ impl App {
 
         let session = Session::new(project_name.clone());
         let parser_registry = Self::build_parser_registry();
+        let advisor_config = &config.advisor;
 
         // Initialize port manager and allocate port for this project
-        let mut port_manager = PortManager::default();
+        let mut port_manager = PortManager::new(config).unwrap_or_else(|e| {
+            log::warn!("Failed to load port registry: {}", e);
+            PortManager::default()
+        });
         if let Err(e) = port_manager.allocate(&project_name, None) {
             log::warn!("Failed to allocate port for {}: {}", project_name, e);
         }
@@ -144,40 +457,93 @@ impl App {
         let mut agent_manager = AgentManager::new();
         agent_manager.register_project(&project_name, AgentType::Generic);
 
-        Self {
+        let mut app = Self {
             scheduler,
             executor,
             event_rx,
             task_outputs: HashMap::new(),
+            task_levels: HashMap::new(),
+            level_classifier: LevelClassifier::new(),
+            buffer_lines: DEFAULT_BUFFER_LINES,
+            log_dir: None,
+            task_logs: HashMap::new(),
             should_quit: false,
             selected_task: 0,
             last_update: Instant::now(),
+            session_saver: SessionSaver::new(SESSION_SAVE_INTERVAL),
+            tick: TickRate::default(),
             session,
             workspace_mode: false,
             project_names: vec![project_name],
             parser_registry,
+            merge_parsers: config.parsers.merge,
             task_metrics: HashMap::new(),
+            metric_throttle: ParseThrottle::default(),
             metric_history: HashMap::new(),
+            task_resources: HashMap::new(),
             advisor: SmartAdvisor::new(),
             advisories: HashMap::new(),
             view_mode: ViewMode::Dashboard,
+            show_nodes: true,
+            group_by_component: false,
             scroll_offset: 0,
+            follow: true,
+            errors_only: false,
             // Phase 1: Multi-Project DX
             port_manager,
-            notification_manager: NotificationManager::new(),
+            notification_manager: NotificationManager::with_config(config.notifications.clone()),
+            toast: None,
+            theme: Self::load_theme(),
             selected_project: 0,
             search_query: String::new(),
             search_mode: false,
-            recent_events: Vec::new(),
+            search_filter: true,
+            output_search: None,
+            tag_filter: None,
+            tag_filter_mode: false,
+            command_prompt: None,
+            input_prompt: None,
+            interact_task: None,
+            interact_prefix: false,
+            waiting_input: HashSet::new(),
+            control_mode: ControlMode::Manual,
+            agent_policy: config.agent.clone(),
+            auto_actions: advisor_config.auto_actions,
+            pending_action: None,
+            confirm_abort: false,
+            confirm_quit: false,
+            force_quit: false,
+            step_mode: StepMode::Off,
+            settled: HashSet::new(),
+            auto_actions_offered: HashSet::new(),
+            recent_events: VecDeque::new(),
             task_start_times: HashMap::new(),
+            last_output_time: HashMap::new(),
+            stall_secs: advisor_config.stall_secs.unwrap_or(DEFAULT_STALL_SECS),
+            stalled: HashSet::new(),
+            readiness: HashMap::new(),
+            completion: HashMap::new(),
             // Phase 2: Agent Integration
             agent_manager,
             last_agent_scan: Instant::now(),
-        }
+            events: EventStream::default(),
+            quit_on_failure: false,
+            quit_on_deadlock: false,
+            all_done_emitted: false,
+            deadlock_reported: false,
+        };
+        app.apply_config(config);
+        app.restore_ui_state();
+        app
     }
 
     /// Create app from workspace (multi-project mode)
     pub fn from_workspace(workspace: &crate::workspace::Workspace) -> Self {
+        Self::from_workspace_with_config(workspace, &Config::load_or_default())
+    }
+
+    /// Create a workspace app with already loaded settings
+    pub fn from_workspace_with_config(workspace: &crate::workspace::Workspace, config: &Config) -> Self {
         let unified_graph = workspace.to_unified_graph();
         let scheduler = Scheduler::new(unified_graph);
         let (executor, event_rx) = Executor::new();
@@ -185,11 +551,15 @@ impl App {
         let session = Session::new("workspace".to_string());
         let project_names = workspace.project_names();
         let parser_registry = Self::build_parser_registry();
+        let advisor_config = &config.advisor;
 
         // Initialize port manager and allocate ports for all projects
-        let mut port_manager = PortManager::default();
+        let mut port_manager = PortManager::new(config).unwrap_or_else(|e| {
+            log::warn!("Failed to load port registry: {}", e);
+            PortManager::default()
+        });
         for (idx, name) in project_names.iter().enumerate() {
-            let preferred_port = 3000 + idx as u16;
+            let preferred_port = port_manager.range().0.saturating_add(idx as u16);
             if let Err(e) = port_manager.allocate(name, Some(preferred_port)) {
                 log::warn!("Failed to allocate port for {}: {}", name, e);
             }
@@ -201,82 +571,683 @@ impl App {
             agent_manager.register_project(name, AgentType::Generic);
         }
 
-        Self {
+        let mut app = Self {
             scheduler,
             executor,
             event_rx,
             task_outputs: HashMap::new(),
+            task_levels: HashMap::new(),
+            level_classifier: LevelClassifier::new(),
+            buffer_lines: DEFAULT_BUFFER_LINES,
+            log_dir: None,
+            task_logs: HashMap::new(),
             should_quit: false,
             selected_task: 0,
             last_update: Instant::now(),
+            session_saver: SessionSaver::new(SESSION_SAVE_INTERVAL),
+            tick: TickRate::default(),
             session,
             workspace_mode: true,
             project_names: project_names.clone(),
             parser_registry,
+            merge_parsers: config.parsers.merge,
             task_metrics: HashMap::new(),
+            metric_throttle: ParseThrottle::default(),
             metric_history: HashMap::new(),
+            task_resources: HashMap::new(),
             advisor: SmartAdvisor::new(),
             advisories: HashMap::new(),
             view_mode: ViewMode::ProjectOverview, // Start with project overview in workspace mode
+            show_nodes: true,
+            group_by_component: false,
             scroll_offset: 0,
+            follow: true,
+            errors_only: false,
             // Phase 1: Multi-Project DX
             port_manager,
-            notification_manager: NotificationManager::new(),
+            notification_manager: NotificationManager::with_config(config.notifications.clone()),
+            toast: None,
+            theme: Self::load_theme(),
             selected_project: 0,
             search_query: String::new(),
             search_mode: false,
-            recent_events: Vec::new(),
+            search_filter: true,
+            output_search: None,
+            tag_filter: None,
+            tag_filter_mode: false,
+            command_prompt: None,
+            input_prompt: None,
+            interact_task: None,
+            interact_prefix: false,
+            waiting_input: HashSet::new(),
+            control_mode: ControlMode::Manual,
+            agent_policy: config.agent.clone(),
+            auto_actions: advisor_config.auto_actions,
+            pending_action: None,
+            confirm_abort: false,
+            confirm_quit: false,
+            force_quit: false,
+            step_mode: StepMode::Off,
+            settled: HashSet::new(),
+            auto_actions_offered: HashSet::new(),
+            recent_events: VecDeque::new(),
             task_start_times: HashMap::new(),
+            last_output_time: HashMap::new(),
+            stall_secs: advisor_config.stall_secs.unwrap_or(DEFAULT_STALL_SECS),
+            stalled: HashSet::new(),
+            readiness: HashMap::new(),
+            completion: HashMap::new(),
             // Phase 2: Agent Integration
             agent_manager,
             last_agent_scan: Instant::now(),
+            events: EventStream::default(),
+            quit_on_failure: false,
+            quit_on_deadlock: false,
+            all_done_emitted: false,
+            deadlock_reported: false,
+        };
+        app.apply_config(config);
+        app.restore_ui_state();
+        app
+    }
+
+    /// Apply the `[output]` and `[ui]` settings
+    fn apply_config(&mut self, config: &Config) {
+        if let Some(lines) = config.output.buffer_lines {
+            self.set_buffer_lines(lines);
+        }
+        if config.output.spill {
+            self.enable_log_spill();
+        }
+        if let Some(lines) = config.output.session_lines {
+            self.session.set_output_limit(lines);
+        }
+        if let Some(dir) = &config.output.task_fifo_dir {
+            self.executor.set_fifo_dir(Some(dir.clone()));
+        }
+        if let Some(ms) = config.ui.tick_ms {
+            self.tick = TickRate::new(Duration::from_millis(ms.max(1)));
+        }
+    }
+
+    /// Keep `lines` lines of output per task, in the app and in each PTY
+    pub fn set_buffer_lines(&mut self, lines: usize) {
+        let lines = lines.max(1);
+        self.buffer_lines = lines;
+        self.executor.set_history_lines(lines);
+    }
+
+    /// Write task output to `.gidterm/logs/<session>/` as well as memory
+    pub fn enable_log_spill(&mut self) {
+        self.log_dir = Some(logs::session_log_dir(&self.session.id));
+    }
+
+    /// Log file holding a task's complete output, when spilling is enabled
+    pub fn get_full_log_path(&self, task_id: &str) -> Option<PathBuf> {
+        let path = logs::log_path(self.log_dir.as_ref()?, task_id);
+        path.exists().then_some(path)
+    }
+
+    /// Store a line of task output, returning whether it added a line
+    ///
+    /// `\r` redraws overwrite the last line. Beyond `buffer_lines`, the
+    /// oldest lines are dropped from memory; with spilling enabled they are
+    /// already in the task's log file.
+    pub fn store_output(&mut self, task_id: &str, line: &str, replace: bool) -> bool {
+        let lines = self
+            .task_outputs
+            .entry(task_id.to_string())
+            .or_insert_with(|| RingBuffer::new(self.buffer_lines));
+        let levels = self
+            .task_levels
+            .entry(task_id.to_string())
+            .or_insert_with(|| RingBuffer::new(self.buffer_lines));
+        lines.set_capacity(self.buffer_lines);
+        levels.set_capacity(self.buffer_lines);
+
+        let level = self.level_classifier.classify(line);
+        let appended = match (lines.last_mut(), levels.last_mut()) {
+            (Some(last), Some(last_level)) if replace => {
+                *last = line.to_string();
+                *last_level = level;
+                false
+            }
+            _ => {
+                lines.push(line.to_string());
+                levels.push(level);
+                true
+            }
+        };
+
+        if let Some(dir) = &self.log_dir {
+            if !self.task_logs.contains_key(task_id) {
+                match TaskLog::open(dir, task_id) {
+                    Ok(log) => {
+                        self.task_logs.insert(task_id.to_string(), log);
+                    }
+                    Err(e) => log::warn!("Failed to open log for {}: {}", task_id, e),
+                }
+            }
+            if let Some(log) = self.task_logs.get_mut(task_id) {
+                if appended {
+                    if let Err(e) = log.push(line) {
+                        log::warn!("Failed to write log for {}: {}", task_id, e);
+                    }
+                } else {
+                    log.replace(line);
+                }
+            }
+        }
+
+        appended
+    }
+
+    /// Restore the last selection and view saved for this graph/workspace
+    fn restore_ui_state(&mut self) {
+        match UiState::load() {
+            Ok(Some(state)) => self.apply_ui_state(&state),
+            Ok(None) => {}
+            Err(e) => log::warn!("Ignoring UI state: {}", e),
+        }
+    }
+
+    /// What the UI state is saved for: the project, or the workspace's projects
+    fn ui_state_source(&self) -> String {
+        if self.workspace_mode {
+            format!("workspace:{}", self.project_names.join(","))
+        } else {
+            self.session.project.clone()
+        }
+    }
+
+    /// Current selection and view, for saving on exit
+    pub fn ui_state(&self) -> UiState {
+        UiState {
+            source: self.ui_state_source(),
+            selected_project: self.selected_project,
+            selected_task: self.selected_task,
+            view_mode: self.view_mode,
+        }
+    }
+
+    /// Apply a saved UI state if it belongs to the loaded graph/workspace
+    ///
+    /// Indices are clamped in case tasks or projects were removed since.
+    pub fn apply_ui_state(&mut self, state: &UiState) {
+        if state.source != self.ui_state_source() {
+            return;
+        }
+
+        self.selected_task = state.selected_task.min(self.get_task_ids().len().saturating_sub(1));
+        self.selected_project = state.selected_project.min(self.project_names.len().saturating_sub(1));
+        if state.view_mode != ViewMode::ProjectOverview || self.workspace_mode {
+            self.view_mode = state.view_mode;
         }
     }
 
     /// Build the default parser registry with all built-in parsers
+    /// Read `~/.gidterm/theme.toml`, falling back to the default palette
+    fn load_theme() -> Theme {
+        Theme::load(None).unwrap_or_else(|e| {
+            log::warn!("Failed to read theme: {}", e);
+            Theme::default()
+        })
+    }
+
     fn build_parser_registry() -> ParserRegistry {
         let mut registry = ParserRegistry::new();
 
+        // Register tqdm/Lightning bar parser ahead of the ML parser, which
+        // also matches their `Epoch N` descriptions; ties go to the first
+        registry.register(Box::new(TqdmParser::new()));
+
         // Register ML training parser
         registry.register(Box::new(MLTrainingParser::new()));
 
         // Register build output parser (cargo, npm, make)
         registry.register(Box::new(BuildParser::new()));
 
+        // Register Docker parser (BuildKit builds, compose up)
+        registry.register(Box::new(DockerParser::new()));
+
+        // Register Go test parser
+        registry.register(Box::new(GoTestParser::new()));
+
         // Register generic regex parser (catches progress bars, percentages, etc.)
         registry.register(Box::new(RegexParser::default_parser()));
 
         registry
     }
 
+    /// Reconcile a previous session's history into graph state
+    ///
+    /// Task status normally lives in the graph YAML, so a crashed run would
+    /// start over from scratch. Tasks whose last run in `previous` ended
+    /// `Done` are marked done so the scheduler skips them; tasks that were
+    /// still `Running` or had `Failed` are reset to pending and re-queued.
+    /// Returns the number of tasks restored as done.
+    pub fn resume_from_session(&mut self, previous: &Session) -> usize {
+        let mut restored = 0;
+
+        for task_id in self.all_task_ids() {
+            let result = match previous.last_status(&task_id) {
+                Some(TaskStatus::Done) => {
+                    restored += 1;
+                    self.scheduler.mark_done(&task_id)
+                }
+                Some(TaskStatus::Running) | Some(TaskStatus::Failed) => {
+                    self.scheduler.mark_pending(&task_id)
+                }
+                _ => Ok(()),
+            };
+
+            if let Err(e) = result {
+                log::warn!("Failed to resume task {}: {}", task_id, e);
+            }
+        }
+
+        log::info!("Resumed session {}: {} task(s) already done", previous.id, restored);
+        restored
+    }
+
+    /// Only run `targets` and their transitive dependencies
+    pub fn select_targets(&mut self, targets: &[String]) -> Result<()> {
+        let subgraph = self.scheduler.graph().subgraph(targets)?;
+        log::info!(
+            "Selected {} of {} tasks for targets: {}",
+            subgraph.tasks.len(),
+            self.scheduler.graph().tasks.len(),
+            targets.join(", ")
+        );
+        self.scheduler.set_graph(subgraph);
+        Ok(())
+    }
+
+    /// Merge an edited graph into the run (`--watch`)
+    ///
+    /// See `Scheduler::reconcile` for what happens to each task. A graph
+    /// with validation errors is rejected and the run carries on unchanged.
+    pub fn reload_graph(&mut self, graph: Graph) -> Result<ReconcileReport> {
+        if let Some(error) = graph.validate().into_iter().find(|d| d.is_error()) {
+            anyhow::bail!("{}", error);
+        }
+
+        let report = self.scheduler.reconcile(graph);
+        self.selected_task = self.selected_task.min(self.get_task_ids().len().saturating_sub(1));
+
+        if !report.is_empty() {
+            log::info!("Reloaded graph: {}", report);
+            let project = self.session.project.clone();
+            self.add_recent_event(&project, format!("Reloaded graph: {}", report));
+        }
+        Ok(report)
+    }
+
+    /// An edited graph couldn't be loaded; the run goes on with the old one
+    pub fn reload_failed(&mut self, error: &anyhow::Error) {
+        log::warn!("Not reloading graph: {:#}", error);
+        let project = self.session.project.clone();
+        self.add_recent_event(&project, format!("Graph not reloaded: {}", error));
+    }
+
+    /// Merge the watched graph file into the run if it has changed since
+    /// the last call, narrowed to `targets` unless that is empty
+    pub fn apply_graph_changes(&mut self, watcher: &mut GraphWatcher, targets: &[String]) {
+        let Some(result) = watcher.poll() else {
+            return;
+        };
+
+        let reloaded = result
+            .and_then(|graph| {
+                if targets.is_empty() {
+                    Ok(graph)
+                } else {
+                    graph.subgraph(targets)
+                }
+            })
+            .and_then(|graph| self.reload_graph(graph));
+        if let Err(e) = reloaded {
+            self.reload_failed(&e);
+        }
+    }
+
     /// Start all ready tasks
+    ///
+    /// In step mode only a requested step starts anything: the tasks ready
+    /// at that point, after which the run pauses again. A step requested
+    /// while nothing is ready waits for the next batch.
     pub async fn start_ready_tasks(&mut self) -> Result<()> {
+        if self.step_mode == StepMode::Paused {
+            return Ok(());
+        }
         let ready = self.scheduler.schedule_next();
+        if self.step_mode == StepMode::Step && !ready.is_empty() {
+            self.step_mode = StepMode::Paused;
+            let project = self.session.project.clone();
+            self.add_recent_event(&project, format!("Step: starting {}", ready.join(", ")));
+        }
+
+        for task_id in &ready {
+            self.launch_task(task_id).await?;
+        }
 
-        for task_id in ready {
-            let task = self.scheduler.graph().get_task(&task_id).unwrap();
+        // Written by the next `process_events`
+        if !ready.is_empty() {
+            self.session_saver.mark_dirty();
+        }
 
-            if let Some(command) = &task.command {
-                log::info!("Starting task: {} ({})", task_id, command);
+        Ok(())
+    }
 
-                self.session.start_task(task_id.clone());
-                self.executor.start_task(&task_id, command).await?;
-                self.scheduler.mark_started(&task_id)?;
-            } else {
-                // No command, mark as done immediately
-                self.scheduler.mark_done(&task_id)?;
+    /// Turn step mode on (paused) or off (everything ready starts again)
+    pub fn set_step_mode(&mut self, enabled: bool) {
+        self.step_mode = if enabled { StepMode::Paused } else { StepMode::Off };
+    }
+
+    /// Start the next wave of ready tasks; only meaningful in step mode
+    pub fn step(&mut self) -> Result<()> {
+        if self.step_mode == StepMode::Off {
+            anyhow::bail!("Not in step mode");
+        }
+        self.step_mode = StepMode::Step;
+        Ok(())
+    }
+
+    /// Start a single task on request, regardless of its current status
+    ///
+    /// Dependencies must still be done; this lets a controller re-run a
+    /// failed or finished task without restarting the whole graph.
+    pub async fn start_task(&mut self, task_id: &str) -> Result<()> {
+        if self.scheduler.graph().get_task(task_id).is_none() {
+            anyhow::bail!("Task {} not found", task_id);
+        }
+        if self.executor.is_running(task_id) {
+            anyhow::bail!("Task {} is already running", task_id);
+        }
+        if !self.scheduler.graph().can_start(task_id) {
+            anyhow::bail!("Task {} has unfinished dependencies", task_id);
+        }
+
+        self.launch_task(task_id).await?;
+        self.session_saver.mark_dirty();
+
+        Ok(())
+    }
+
+    /// Start every task tagged `tag` that isn't running or done and whose
+    /// dependencies are met, returning the tasks started
+    pub async fn start_tasks_by_tag(&mut self, tag: &str) -> Result<Vec<String>> {
+        let tagged = self.scheduler.graph().tasks_with_tag(tag);
+        if tagged.is_empty() {
+            anyhow::bail!("No tasks tagged '{}'", tag);
+        }
+
+        let mut started = Vec::new();
+        for task_id in tagged {
+            let graph = self.scheduler.graph();
+            let done = graph.get_task(&task_id).is_some_and(|t| t.status == GraphTaskStatus::Done);
+            if done || self.executor.is_running(&task_id) || !graph.can_start(&task_id) {
+                continue;
             }
+            self.launch_task(&task_id).await?;
+            started.push(task_id);
         }
 
-        if let Err(e) = self.session.save() {
-            log::warn!("Failed to save session: {}", e);
+        if !started.is_empty() {
+            self.session_saver.mark_dirty();
+            let project = self.session.project.clone();
+            self.add_recent_event(&project, format!("Started {} task(s) tagged {}", started.len(), tag));
+        }
+        Ok(started)
+    }
+
+    /// Stop every running task tagged `tag`, returning the tasks stopped
+    pub fn stop_tasks_by_tag(&mut self, tag: &str) -> Result<Vec<String>> {
+        let tagged = self.scheduler.graph().tasks_with_tag(tag);
+        if tagged.is_empty() {
+            anyhow::bail!("No tasks tagged '{}'", tag);
+        }
+
+        let mut stopped = Vec::new();
+        for task_id in tagged {
+            if !self.executor.is_running(&task_id) {
+                continue;
+            }
+            match self.executor.stop_task(&task_id) {
+                Ok(()) => stopped.push(task_id),
+                Err(e) => log::warn!("Failed to stop task {}: {}", task_id, e),
+            }
+        }
+
+        if !stopped.is_empty() {
+            let project = self.session.project.clone();
+            self.add_recent_event(&project, format!("Stopped {} task(s) tagged {}", stopped.len(), tag));
+        }
+        Ok(stopped)
+    }
+
+    /// Spawn a task's command, or mark it done if it has none
+    async fn launch_task(&mut self, task_id: &str) -> Result<()> {
+        let task = self.scheduler.graph().get_task(task_id).unwrap();
+
+        if let Some(command) = task.command.clone() {
+            log::info!("Starting task: {} ({})", task_id, command);
+            let success_codes = task.success_codes.clone();
+            let shell = task.shell.clone();
+
+            let project = self.get_project_name(task_id).unwrap_or_else(|| self.session.project.clone());
+            let env = self.port_manager.get_env(&project);
+            let vars = CommandVars::new(&project, self.port_manager.get_port(&project))?;
+            let readiness = task.ready_when.as_ref().map(ReadinessCheck::new).transpose()?;
+            let completion = task.complete_on.as_ref().map(CompletionCheck::new).transpose()?;
+
+            self.session.start_task(task_id.to_string());
+            self.all_done_emitted = false;
+            self.deadlock_reported = false;
+            self.auto_actions_offered.retain(|(t, _)| t != task_id);
+            let pid = self
+                .executor
+                .start_task_with_vars(task_id, shell.as_deref(), &command, &vars, &env, &success_codes)
+                .await?;
+            self.scheduler.mark_started(task_id)?;
+            match readiness {
+                Some(check) => self.readiness.insert(task_id.to_string(), check),
+                None => self.readiness.remove(task_id),
+            };
+            match completion {
+                Some(check) => self.completion.insert(task_id.to_string(), check),
+                None => self.completion.remove(task_id),
+            };
+
+            if let Some(pid) = pid {
+                if let Err(e) = self.port_manager.activate(&project, Some(pid)) {
+                    log::warn!("Failed to activate port for {}: {}", project, e);
+                }
+            }
+        } else {
+            // No command, mark as done immediately
+            self.scheduler.mark_done(task_id)?;
         }
 
         Ok(())
     }
 
+    /// Answer any control commands queued by the transports
+    pub async fn handle_command_requests(&mut self, requests: &mut mpsc::Receiver<CommandRequest>) {
+        while let Ok(request) = requests.try_recv() {
+            let response = self.handle_command(request.command).await;
+            let _ = request.reply.send(response);
+        }
+    }
+
+    /// Execute a control command from an MCP/agent transport
+    ///
+    /// Every transport client is held to the agent policy, whether or not
+    /// the run was started with `--agent`.
+    pub async fn handle_command(&mut self, command: ControlCommand) -> ControlResponse {
+        if let Err(e) = self.agent_policy.check(&command) {
+            log::warn!("Rejected control command {:?}: {}", command, e);
+            return ControlResponse::error(e.to_string());
+        }
+
+        let result = match command {
+            ControlCommand::StartAll => self.start_ready_tasks().await.map(|_| None),
+            ControlCommand::SetStepMode { enabled } => {
+                self.set_step_mode(enabled);
+                Ok(None)
+            }
+            ControlCommand::Step => self.step().map(|_| None),
+            ControlCommand::StartTask { task_id } => self.start_task(&task_id).await.map(|_| None),
+            ControlCommand::StopTask { task_id } => self.executor.stop_task(&task_id).map(|_| None),
+            ControlCommand::SendInput { task_id, input } => {
+                self.executor.send_input(&task_id, &input).map(|_| None)
+            }
+            ControlCommand::GetState => serde_json::to_value(self.state_snapshot())
+                .map(Some)
+                .map_err(Into::into),
+            ControlCommand::GetOutput { task_id, lines } => {
+                Ok(Some(serde_json::json!(self.get_task_output(&task_id, lines))))
+            }
+            ControlCommand::Quit => {
+                self.should_quit = true;
+                Ok(None)
+            }
+        };
+
+        match result {
+            Ok(Some(data)) => ControlResponse::ok_with_data(data),
+            Ok(None) => ControlResponse::ok(),
+            Err(e) => ControlResponse::error(e.to_string()),
+        }
+    }
+
+    /// Summarize the run from graph state and session history
+    pub fn summary(&self) -> RunSummary {
+        let graph = self.scheduler.graph();
+        let task_ids = self.all_task_ids();
+
+        let count = |status: GraphTaskStatus| {
+            task_ids
+                .iter()
+                .filter(|id| graph.get_task(id).is_some_and(|t| t.status == status))
+                .count()
+        };
+
+        let allowed_failures = task_ids
+            .iter()
+            .filter(|id| {
+                graph
+                    .get_task(id)
+                    .is_some_and(|t| t.status == GraphTaskStatus::Failed && t.allow_failure)
+            })
+            .count();
+
+        let pending = task_ids
+            .iter()
+            .filter(|id| {
+                graph.get_task(id).is_some_and(|t| {
+                    !matches!(t.status, GraphTaskStatus::Done | GraphTaskStatus::Failed)
+                })
+            })
+            .cloned()
+            .collect();
+
+        let slowest_task = self
+            .session
+            .tasks
+            .values()
+            .filter_map(|history| {
+                let run = history.runs.last()?;
+                let duration = (run.ended? - run.started).to_std().ok()?;
+                Some(TaskDuration {
+                    task_id: history.task_id.clone(),
+                    duration_secs: duration.as_secs_f64(),
+                })
+            })
+            .max_by(|a, b| a.duration_secs.total_cmp(&b.duration_secs));
+
+        let ended = self.session.ended_at.unwrap_or_else(chrono::Utc::now);
+        let wall_time_secs = (ended - self.session.started_at)
+            .to_std()
+            .map(|d| d.as_secs_f64())
+            .unwrap_or(0.0);
+
+        RunSummary {
+            total: task_ids.len(),
+            done: count(GraphTaskStatus::Done),
+            failed: count(GraphTaskStatus::Failed),
+            allowed_failures,
+            wall_time_secs,
+            slowest_task,
+            pending,
+        }
+    }
+
+    /// Build a snapshot of all tasks for AI consumers
+    pub fn state_snapshot(&self) -> StateSnapshot {
+        let graph = self.scheduler.graph();
+        let mut snapshot = StateSnapshot {
+            schema_version: SNAPSHOT_SCHEMA_VERSION,
+            tasks: Vec::new(),
+            running_count: 0,
+            done_count: 0,
+            failed_count: 0,
+            total_count: 0,
+        };
+
+        for task_id in self.all_task_ids() {
+            let Some(task) = graph.get_task(&task_id) else {
+                continue;
+            };
+
+            match task.status {
+                GraphTaskStatus::Done => snapshot.done_count += 1,
+                GraphTaskStatus::InProgress => snapshot.running_count += 1,
+                GraphTaskStatus::Failed => snapshot.failed_count += 1,
+                _ => {}
+            }
+
+            let metrics = self.task_metrics.get(&task_id);
+            let advisories = self
+                .get_advisories(&task_id)
+                .map(|history| {
+                    history
+                        .active()
+                        .into_iter()
+                        .map(|(_, record)| AdvisorySnapshot {
+                            severity: record.advisory.severity,
+                            message: record.advisory.message.clone(),
+                            suggestion: record.advisory.suggestion.clone(),
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            let project = self.get_project_name(&task_id).unwrap_or_else(|| self.session.project.clone());
+            snapshot.tasks.push(TaskSnapshot {
+                status: task.status.to_string(),
+                description: task.description.clone(),
+                progress: metrics.map(|m| m.progress as f64),
+                metrics: metrics.map(|m| {
+                    m.metrics.iter().map(|(k, v)| (k.clone(), v.to_json())).collect()
+                }),
+                last_output: self.get_task_output(&task_id, 10),
+                eta_secs: self.task_eta(&task_id).map(|eta| eta.as_secs()),
+                advisories,
+                port: self.port_manager.get_port(&project),
+                id: task_id,
+            });
+        }
+
+        snapshot.total_count = snapshot.tasks.len();
+        snapshot
+    }
+
     /// Process events from executor
-    pub fn process_events(&mut self) {
+    ///
+    /// Handles at most `MAX_EVENTS_PER_TICK` events so a flood of output
+    /// can't hold up rendering and input. Returns whether more are waiting,
+    /// in which case the caller should tick again without sleeping.
+    pub fn process_events(&mut self) -> bool {
         let mut session_updated = false;
 
         // Periodically scan for agent processes (every 5 seconds)
@@ -287,11 +1258,22 @@ impl App {
             self.last_agent_scan = Instant::now();
         }
 
-        while let Ok(event) = self.event_rx.try_recv() {
+        let selected_task_id = self.get_task_ids().get(self.selected_task).cloned();
+
+        let mut handled = 0;
+        while handled < MAX_EVENTS_PER_TICK {
+            let Ok(event) = self.event_rx.try_recv() else {
+                break;
+            };
+            handled += 1;
+            self.tick.record_activity(Instant::now());
             match event {
                 TaskEvent::Started { task_id } => {
                     log::info!("Task started: {}", task_id);
                     self.task_start_times.insert(task_id.clone(), Instant::now());
+                    self.last_output_time.insert(task_id.clone(), Instant::now());
+                    self.stalled.remove(&task_id);
+                    self.events.emit(GidEvent::TaskStarted { task_id: task_id.clone() });
                     
                     // Add recent event
                     let project = self.get_project_name(&task_id).unwrap_or_else(|| self.session.project.clone());
@@ -301,27 +1283,43 @@ impl App {
                     // Send notification
                     let _ = self.notification_manager.notify_started(&project, &task_display);
                 }
-                TaskEvent::Output { task_id, line } => {
+                TaskEvent::Output { task_id, line, replace } => {
+                    // A task done by `complete_on` may keep running, but can't stall
+                    if !self.settled.contains(&task_id) {
+                        self.last_output_time.insert(task_id.clone(), Instant::now());
+                    }
+                    self.stalled.remove(&task_id);
+
                     if !line.is_empty() {
-                        // Store output
-                        let lines = self.task_outputs
-                            .entry(task_id.clone())
-                            .or_insert_with(Vec::new);
-                        lines.push(line.clone());
-
-                        // Cap output history
-                        if lines.len() > MAX_APP_OUTPUT_LINES {
-                            let drain_count = lines.len() - MAX_APP_OUTPUT_LINES;
-                            lines.drain(0..drain_count);
+                        let appended = self.store_output(&task_id, &line, replace);
+                        if appended && selected_task_id.as_deref() == Some(task_id.as_str()) {
+                            self.output_appended();
                         }
 
                         // Track in session
-                        self.session.add_output(&task_id, line.clone());
+                        if replace {
+                            self.session.replace_last_output(&task_id, line.clone());
+                        } else {
+                            self.session.add_output(&task_id, line.clone());
+                        }
                         session_updated = true;
+                        self.events.emit(GidEvent::TaskOutput {
+                            task_id: task_id.clone(),
+                            line: line.clone(),
+                            replace,
+                        });
 
-                        // Run through semantic parser
-                        self.update_task_metrics(&task_id);
+                        // Parsed with the rest of this tick's output, below
+                        self.metric_throttle.mark(&task_id);
                         
+                        if self.readiness.get(&task_id).is_some_and(|check| check.matches_line(&line)) {
+                            self.mark_task_ready(&task_id);
+                        }
+                        if self.completion.get(&task_id).is_some_and(|check| check.matches_line(&line)) {
+                            self.complete_task_on_match(&task_id);
+                            session_updated = true;
+                        }
+
                         // Check for waiting-for-input patterns
                         self.check_waiting_input(&task_id, &line);
                         
@@ -330,13 +1328,33 @@ impl App {
                         self.agent_manager.update_output(&project, &line);
                     }
                 }
+                TaskEvent::Completed { task_id, .. } | TaskEvent::Failed { task_id, .. }
+                    if self.settled.contains(&task_id) =>
+                {
+                    // Already recorded by `abort_all` or `complete_on`
+                    self.settled.remove(&task_id);
+                    self.waiting_input.remove(&task_id);
+                    self.task_logs.remove(&task_id);
+                    session_updated = true;
+                }
                 TaskEvent::Completed { task_id, exit_code } => {
                     log::info!("Task completed: {} (exit: {})", task_id, exit_code);
+                    self.flush_task_metrics(&task_id);
+                    self.last_output_time.remove(&task_id);
+                    // Closing the log writes out the final line
+                    self.task_logs.remove(&task_id);
+                    self.readiness.remove(&task_id);
+                    self.completion.remove(&task_id);
+                    self.waiting_input.remove(&task_id);
                     if let Err(e) = self.scheduler.mark_done(&task_id) {
                         log::warn!("Failed to mark task {} done: {}", task_id, e);
                     }
                     self.session.end_task(&task_id, TaskStatus::Done, Some(exit_code));
                     session_updated = true;
+                    self.events.emit(GidEvent::TaskCompleted {
+                        task_id: task_id.clone(),
+                        exit_code,
+                    });
                     
                     // Add recent event and send notification
                     let project = self.get_project_name(&task_id).unwrap_or_else(|| self.session.project.clone());
@@ -345,17 +1363,39 @@ impl App {
                     
                     self.add_recent_event(&project, format!("Completed: {}", task_display));
                     let _ = self.notification_manager.notify_complete(&project, &task_display, duration);
+                    self.run_hook(&task_id, HookTrigger::Success);
                     
                     // Deactivate port if this was the main task
                     let _ = self.port_manager.deactivate(&project);
                 }
+                TaskEvent::Resources { task_id, usage } => {
+                    self.task_resources.insert(task_id.clone(), usage);
+                    let metrics = self.task_metrics.entry(task_id).or_insert_with(|| TaskMetrics {
+                        progress: 0.0,
+                        metrics: HashMap::new(),
+                        phase: None,
+                        errors: Vec::new(),
+                    });
+                    Self::apply_resource_metrics(metrics, &usage);
+                }
                 TaskEvent::Failed { task_id, error } => {
                     log::warn!("Task failed: {} - {}", task_id, error);
+                    self.flush_task_metrics(&task_id);
+                    self.last_output_time.remove(&task_id);
+                    // Closing the log writes out the final line
+                    self.task_logs.remove(&task_id);
+                    self.readiness.remove(&task_id);
+                    self.completion.remove(&task_id);
+                    self.waiting_input.remove(&task_id);
                     if let Err(e) = self.scheduler.mark_failed(&task_id) {
                         log::warn!("Failed to mark task {} failed: {}", task_id, e);
                     }
                     self.session.end_task(&task_id, TaskStatus::Failed, None);
                     session_updated = true;
+                    self.events.emit(GidEvent::TaskFailed {
+                        task_id: task_id.clone(),
+                        error: error.clone(),
+                    });
                     
                     // Add recent event and send notification
                     let project = self.get_project_name(&task_id).unwrap_or_else(|| self.session.project.clone());
@@ -363,24 +1403,265 @@ impl App {
                     
                     self.add_recent_event(&project, format!("Failed: {} - {}", task_display, &error));
                     let _ = self.notification_manager.notify_error(&project, &task_display, &error);
+                    self.run_hook(&task_id, HookTrigger::Failure);
+
+                    if self.scheduler.is_halted() {
+                        log::warn!("Fail-fast: not starting new tasks after {} failed", task_id);
+                        if self.quit_on_failure {
+                            self.should_quit = true;
+                        }
+                    }
                 }
             }
         }
 
+        for task_id in self.metric_throttle.take_due(Instant::now()) {
+            self.update_task_metrics(&task_id);
+        }
+        self.check_ready_ports();
+        self.check_stalls(Instant::now());
+        let _ = self.notification_manager.flush(Instant::now());
+        if let Some(notification) = self.notification_manager.take_last_sent() {
+            self.toast = Some(Toast {
+                message: format!("{}: {}", notification.formatted_title(), notification.message),
+                expires: Instant::now() + TOAST_DURATION,
+            });
+        }
+
+        // A verbose task updates the session many times per tick
         if session_updated {
-            if let Err(e) = self.session.save() {
-                log::warn!("Failed to save session: {}", e);
+            self.session_saver.mark_dirty();
+        }
+        if let Err(e) = self.session_saver.save_if_due(&self.session, Instant::now()) {
+            log::warn!("Failed to save session: {}", e);
+        }
+
+        if !self.all_done_emitted && self.scheduler.all_done() {
+            let snapshot = self.state_snapshot();
+            self.events.emit(GidEvent::AllDone {
+                total: snapshot.total_count,
+                succeeded: snapshot.done_count,
+                failed: snapshot.failed_count,
+            });
+            self.all_done_emitted = true;
+        }
+
+        if self.scheduler.is_deadlocked() {
+            if !self.deadlock_reported {
+                let message = self.deadlock_message().unwrap_or_default();
+                log::warn!("{}", message);
+                let project = self.session.project.clone();
+                self.add_recent_event(&project, message.clone());
+                let _ = self.notification_manager.notify_warning(&project, &message);
+                self.deadlock_reported = true;
+            }
+            if self.quit_on_deadlock {
+                self.should_quit = true;
             }
         }
 
         self.last_update = Instant::now();
+        !self.event_rx.is_empty()
     }
     
+    /// Toast message to show at `now`, if one hasn't expired
+    pub fn active_toast(&self, now: Instant) -> Option<&str> {
+        self.toast
+            .as_ref()
+            .filter(|toast| now < toast.expires)
+            .map(|toast| toast.message.as_str())
+    }
+
+    /// Explain why nothing is running, if the remaining tasks are all blocked
+    pub fn deadlock_message(&self) -> Option<String> {
+        self.scheduler.is_deadlocked().then(|| {
+            format!(
+                "deadlocked: {} tasks blocked by failures",
+                self.scheduler.unfinished_count()
+            )
+        })
+    }
+
+    /// Stop every running task and start no new ones, keeping the UI open
+    ///
+    /// The tasks are recorded as failed straight away, without notifications
+    /// or hooks. Returns how many tasks were aborted.
+    pub fn abort_all(&mut self) -> usize {
+        let running = self.scheduler.get_running();
+        self.scheduler.halt();
+
+        for task_id in &running {
+            if let Err(e) = self.executor.stop_task(task_id) {
+                log::warn!("Failed to stop task {}: {}", task_id, e);
+            }
+            if let Err(e) = self.scheduler.mark_failed(task_id) {
+                log::warn!("Failed to mark task {} failed: {}", task_id, e);
+            }
+            self.session.end_task(task_id, TaskStatus::Failed, None);
+            self.last_output_time.remove(task_id);
+            self.readiness.remove(task_id);
+            self.completion.remove(task_id);
+            self.waiting_input.remove(task_id);
+            self.settled.insert(task_id.clone());
+            self.events.emit(GidEvent::TaskFailed {
+                task_id: task_id.clone(),
+                error: "Aborted".to_string(),
+            });
+        }
+
+        if !running.is_empty() {
+            self.session_saver.mark_dirty();
+            let message = format!("Aborted {} running task(s)", running.len());
+            log::warn!("{}", message);
+            let project = self.session.project.clone();
+            self.add_recent_event(&project, message);
+        }
+        running.len()
+    }
+
+    /// Start a finished task's `on_success` or `on_failure` hook, if it has one
+    fn run_hook(&self, task_id: &str, trigger: HookTrigger) {
+        let Some(task) = self.scheduler.graph().get_task(task_id) else {
+            return;
+        };
+        let command = match trigger {
+            HookTrigger::Success => &task.on_success,
+            HookTrigger::Failure => &task.on_failure,
+        };
+        let shell = task.shell.as_deref();
+        let Some(command) = command else {
+            return;
+        };
+
+        let project = self.get_project_name(task_id).unwrap_or_else(|| self.session.project.clone());
+        let env = self.port_manager.get_env(&project);
+        let result = CommandVars::new(&project, self.port_manager.get_port(&project))
+            .and_then(|vars| vars.interpolate(command, shell.unwrap_or(DEFAULT_SHELL)))
+            .and_then(|command| hooks::spawn_hook(task_id, trigger, shell, &command, &env));
+        if let Err(e) = result {
+            log::warn!("Failed to run {} hook for {}: {}", trigger, task_id, e);
+        }
+    }
+
+    /// Mark tasks waiting on `ready_when: { port }` ready once the port is taken
+    fn check_ready_ports(&mut self) {
+        let mut listening: Vec<String> = self
+            .readiness
+            .iter()
+            .filter(|(_, check)| check.port_in_use())
+            .map(|(task_id, _)| task_id.clone())
+            .collect();
+        listening.sort();
+
+        for task_id in listening {
+            self.mark_task_ready(&task_id);
+        }
+    }
+
+    /// A running task passed its `ready_when` check: its dependents may start
+    fn mark_task_ready(&mut self, task_id: &str) {
+        self.readiness.remove(task_id);
+        self.scheduler.mark_ready(task_id);
+        log::info!("Task ready: {}", task_id);
+
+        let project = self.get_project_name(task_id).unwrap_or_else(|| self.session.project.clone());
+        let task_display = self.get_task_display_name(task_id);
+        self.add_recent_event(&project, format!("Ready: {}", task_display));
+    }
+
+    /// A running task's output matched its `complete_on`: record it as done
+    /// so its dependents may start, stopping it if asked to
+    fn complete_task_on_match(&mut self, task_id: &str) {
+        let Some(check) = self.completion.remove(task_id) else {
+            return;
+        };
+        log::info!("Task completed on output match: {}", task_id);
+
+        if check.kill {
+            if let Err(e) = self.executor.stop_task(task_id) {
+                log::warn!("Failed to stop task {}: {}", task_id, e);
+            }
+        }
+        self.flush_task_metrics(task_id);
+        self.last_output_time.remove(task_id);
+        self.readiness.remove(task_id);
+        if let Err(e) = self.scheduler.mark_done(task_id) {
+            log::warn!("Failed to mark task {} done: {}", task_id, e);
+        }
+        self.session.end_task(task_id, TaskStatus::Done, None);
+        self.settled.insert(task_id.to_string());
+        self.events.emit(GidEvent::TaskCompleted {
+            task_id: task_id.to_string(),
+            exit_code: 0,
+        });
+
+        let project = self.get_project_name(task_id).unwrap_or_else(|| self.session.project.clone());
+        let task_display = self.get_task_display_name(task_id);
+        let duration = self.task_start_times.get(task_id).map(|t| t.elapsed());
+        self.add_recent_event(&project, format!("Completed: {} (output matched)", task_display));
+        let _ = self.notification_manager.notify_complete(&project, &task_display, duration);
+        self.run_hook(task_id, HookTrigger::Success);
+    }
+
+    /// Report running tasks that have gone quiet for longer than their
+    /// stall threshold. Each stall is reported once, until output resumes.
+    pub fn check_stalls(&mut self, now: Instant) {
+        let mut newly_stalled: Vec<(String, Duration)> = self
+            .last_output_time
+            .iter()
+            .filter(|(task_id, _)| !self.stalled.contains(*task_id))
+            .filter_map(|(task_id, last)| {
+                let silent = now.saturating_duration_since(*last);
+                let threshold = self
+                    .scheduler
+                    .graph()
+                    .get_task(task_id)
+                    .and_then(|t| t.stall_secs)
+                    .unwrap_or(self.stall_secs);
+                (silent >= Duration::from_secs(threshold)).then(|| (task_id.clone(), silent))
+            })
+            .collect();
+        newly_stalled.sort();
+
+        for (task_id, silent) in newly_stalled {
+            if !self.executor.is_running(&task_id) {
+                continue;
+            }
+            self.stalled.insert(task_id.clone());
+
+            let task_display = self.get_task_display_name(&task_id);
+            let message = format!(
+                "{} appears stalled (no output for {})",
+                task_display,
+                history::format_eta(silent)
+            );
+            log::warn!("{}", message);
+
+            let advisory = Advisory {
+                severity: Severity::Warning,
+                message: message.clone(),
+                suggestion: "Check whether the task is hung or waiting for input".to_string(),
+                auto_action: None,
+            };
+            let fresh = self.advisories
+                .entry(task_id.clone())
+                .or_default()
+                .record(&[advisory], now);
+            for event in GidEvent::from_advisories(&task_id, &fresh) {
+                self.events.emit(event);
+            }
+
+            let project = self.get_project_name(&task_id).unwrap_or_else(|| self.session.project.clone());
+            self.add_recent_event(&project, format!("Stalled: {}", task_display));
+            let _ = self.notification_manager.notify_warning(&project, &message);
+        }
+    }
+
     /// Add a recent event (keeps last 50)
     fn add_recent_event(&mut self, project: &str, message: String) {
-        self.recent_events.push((Instant::now(), project.to_string(), message));
+        self.recent_events.push_back((Instant::now(), project.to_string(), message));
         if self.recent_events.len() > 50 {
-            self.recent_events.remove(0);
+            self.recent_events.pop_front();
         }
     }
     
@@ -406,8 +1687,8 @@ impl App {
             "proceed?",
             "confirm",
             "waiting for input",
-            "Enter your",
-            "Type your",
+            "enter your",
+            "type your",
         ];
         
         let lower = line.to_lowercase();
@@ -416,6 +1697,7 @@ impl App {
                 let project = self.get_project_name(task_id).unwrap_or_else(|| self.session.project.clone());
                 let task_display = self.get_task_display_name(task_id);
                 
+                self.waiting_input.insert(task_id.to_string());
                 self.add_recent_event(&project, format!("Waiting: {} - {}", task_display, line));
                 let _ = self.notification_manager.notify_waiting(&project, &task_display);
                 break;
@@ -423,7 +1705,46 @@ impl App {
         }
     }
 
+    /// Whether a task's output looks like it is waiting for input
+    pub fn is_waiting_input(&self, task_id: &str) -> bool {
+        self.waiting_input.contains(task_id)
+    }
+
+    /// The task `i` answers: the selected one if it is waiting, else the
+    /// first waiting task in list order
+    fn waiting_task(&self) -> Option<String> {
+        let task_ids = self.get_task_ids();
+        task_ids
+            .get(self.selected_task)
+            .filter(|id| self.is_waiting_input(id))
+            .or_else(|| task_ids.iter().find(|id| self.is_waiting_input(id)))
+            .cloned()
+    }
+
+    /// Send a line typed at the input prompt to a task's PTY
+    fn send_task_input(&mut self, task_id: &str, input: &str) {
+        let project = self.get_project_name(task_id).unwrap_or_else(|| self.session.project.clone());
+        let task_display = self.get_task_display_name(task_id);
+        match self.executor.send_input(task_id, input) {
+            Ok(()) => {
+                self.waiting_input.remove(task_id);
+                self.add_recent_event(&project, format!("Input sent: {}", task_display));
+            }
+            Err(e) => {
+                log::warn!("Failed to send input to {}: {}", task_id, e);
+                self.add_recent_event(&project, format!("Input not sent: {} - {}", task_display, e));
+            }
+        }
+    }
+
     /// Update semantic metrics for a task based on its output
+    /// Parse whatever output an exiting task produced since its last parse
+    fn flush_task_metrics(&mut self, task_id: &str) {
+        if self.metric_throttle.take_now(task_id, Instant::now()) {
+            self.update_task_metrics(task_id);
+        }
+    }
+
     fn update_task_metrics(&mut self, task_id: &str) {
         // Get the task type for parser selection
         let task_type = self.scheduler.graph().get_task(task_id)
@@ -431,10 +1752,7 @@ impl App {
 
         // Get recent output (last 20 lines for parsing)
         let output = self.task_outputs.get(task_id)
-            .map(|lines| {
-                let start = lines.len().saturating_sub(20);
-                lines[start..].join("\n")
-            })
+            .map(|lines| lines.tail(20).map(String::as_str).collect::<Vec<_>>().join("\n"))
             .unwrap_or_default();
 
         if output.is_empty() {
@@ -442,13 +1760,18 @@ impl App {
         }
 
         // Parse through registry
-        if let Ok(metrics) = self.parser_registry.parse(task_type.as_deref(), &output) {
+        let parsed = if self.merge_parsers {
+            self.parser_registry.parse_all(task_type.as_deref(), &output)
+        } else {
+            self.parser_registry.parse(task_type.as_deref(), &output)
+        };
+        if let Ok(mut metrics) = parsed {
             // Only update if we got meaningful data
             if metrics.progress > 0.0 || !metrics.metrics.is_empty() || !metrics.errors.is_empty() {
                 // Record to history for trend tracking
                 let history = self.metric_history
                     .entry(task_id.to_string())
-                    .or_insert_with(TaskMetricHistory::new);
+                    .or_default();
 
                 let float_metrics: HashMap<String, f64> = metrics.metrics.iter()
                     .filter_map(|(k, v)| match v {
@@ -458,30 +1781,103 @@ impl App {
                     })
                     .collect();
 
+                self.session.record_metrics(task_id, &float_metrics);
                 history.record(metrics.progress, float_metrics);
 
                 // Run advisor
                 let history_ref = self.metric_history.get(task_id);
                 let new_advisories = self.advisor.evaluate(&metrics, history_ref);
                 if !new_advisories.is_empty() {
-                    self.advisories.insert(task_id.to_string(), new_advisories);
+                    // Only advisories that aren't repeats are announced
+                    let fresh = self.advisories
+                        .entry(task_id.to_string())
+                        .or_default()
+                        .record(&new_advisories, Instant::now());
+                    self.offer_auto_actions(task_id, &fresh);
+                    for event in GidEvent::from_advisories(task_id, &fresh) {
+                        self.events.emit(event);
+                    }
+                }
+
+                // Parsed output replaces the metrics, so carry resource stats over
+                if let Some(usage) = self.task_resources.get(task_id) {
+                    Self::apply_resource_metrics(&mut metrics, usage);
                 }
 
+                self.events.emit(GidEvent::from_metrics(task_id, &metrics));
                 self.task_metrics.insert(task_id.to_string(), metrics);
             }
         }
     }
 
+    /// Expose resource usage as `peak_mem_mb` and `cpu_secs` metrics
+    fn apply_resource_metrics(metrics: &mut TaskMetrics, usage: &ResourceUsage) {
+        metrics
+            .metrics
+            .insert("peak_mem_mb".to_string(), MetricValue::Float(usage.peak_mem_mb));
+        metrics
+            .metrics
+            .insert("cpu_secs".to_string(), MetricValue::Float(usage.cpu_secs));
+    }
+
     /// Get advisories for a task
-    pub fn get_advisories(&self, task_id: &str) -> Option<&Vec<Advisory>> {
+    pub fn get_advisories(&self, task_id: &str) -> Option<&AdvisoryHistory> {
         self.advisories.get(task_id)
     }
 
+    /// Dismiss a task's advisory by its index in the history
+    pub fn acknowledge_advisory(&mut self, task_id: &str, index: usize) -> bool {
+        self.advisories
+            .get_mut(task_id)
+            .is_some_and(|history| history.acknowledge(index))
+    }
+
     /// Get ETA for a task as formatted string
     pub fn get_eta(&self, task_id: &str) -> Option<String> {
-        let h = self.metric_history.get(task_id)?;
-        let remaining = h.estimate_remaining()?;
-        Some(history::format_eta(remaining))
+        self.task_eta(task_id).map(history::format_eta)
+    }
+
+    /// Estimated time remaining for a task
+    ///
+    /// Uses the progress history, cross-checked against the tool's own
+    /// estimate (`remaining_secs`, e.g. from a tqdm bar) when there is one:
+    /// if the two disagree by more than 2x, the tool's figure wins.
+    pub fn task_eta(&self, task_id: &str) -> Option<Duration> {
+        let estimated = self
+            .metric_history
+            .get(task_id)
+            .and_then(|h| h.estimate_remaining());
+        let reported = self
+            .task_metrics
+            .get(task_id)
+            .and_then(|m| m.metrics.get("remaining_secs"))
+            .and_then(|v| v.as_int())
+            .map(|secs| Duration::from_secs(secs.max(0) as u64));
+
+        match (estimated, reported) {
+            (Some(ours), Some(theirs)) => {
+                let ratio = ours.as_secs_f64().max(1.0) / theirs.as_secs_f64().max(1.0);
+                if (0.5..=2.0).contains(&ratio) {
+                    Some(ours)
+                } else {
+                    Some(theirs)
+                }
+            }
+            (ours, theirs) => ours.or(theirs),
+        }
+    }
+
+    /// Longest remaining estimate across a project's running tasks
+    ///
+    /// None when no running task has an estimate yet.
+    pub fn project_eta(&self, project: &str) -> Option<Duration> {
+        let graph = self.scheduler.graph();
+        self.get_tasks_by_project()
+            .get(project)?
+            .iter()
+            .filter(|id| graph.get_task(id).is_some_and(|t| t.status == GraphTaskStatus::InProgress))
+            .filter_map(|id| self.task_eta(id))
+            .max()
     }
 
     /// Get metric history for a task
@@ -491,46 +1887,278 @@ impl App {
 
     /// Handle keyboard input
     pub fn handle_key(&mut self, key: KeyEvent) {
+        // Interact mode passes keys straight through to the task
+        if let Some(task_id) = self.interact_task.clone() {
+            if key.code == KeyCode::Esc {
+                self.interact_task = None;
+            } else if let Some(bytes) = key_bytes(key) {
+                if let Err(e) = self.executor.send_raw(&task_id, &bytes) {
+                    log::warn!("Leaving interact mode for {}: {}", task_id, e);
+                    self.interact_task = None;
+                    let project = self.get_project_name(&task_id).unwrap_or_else(|| self.session.project.clone());
+                    self.add_recent_event(&project, format!("Interact ended: {}", e));
+                }
+            }
+            return;
+        }
+
+        if std::mem::take(&mut self.interact_prefix) && key.code == KeyCode::Char('i') {
+            let task_id = self.get_task_ids().get(self.selected_task).cloned();
+            if let Some(task_id) = task_id.filter(|id| self.executor.is_running(id)) {
+                self.interact_task = Some(task_id);
+                self.follow = true;
+                self.scroll_offset = 0;
+            }
+            return;
+        }
+
+        // Handle tag filter input (list updates as you type)
+        if self.tag_filter_mode {
+            match key.code {
+                KeyCode::Esc => {
+                    self.tag_filter_mode = false;
+                    self.tag_filter = None;
+                }
+                KeyCode::Enter => {
+                    self.tag_filter_mode = false;
+                    if self.tag_filter.as_deref() == Some("") {
+                        self.tag_filter = None;
+                    }
+                }
+                KeyCode::Backspace => {
+                    if let Some(filter) = self.tag_filter.as_mut() {
+                        filter.pop();
+                    }
+                }
+                KeyCode::Char(c) => {
+                    self.tag_filter.get_or_insert_with(String::new).push(c);
+                }
+                _ => {}
+            }
+            self.selected_task = 0;
+            self.scroll_offset = 0;
+            return;
+        }
+
+        // Only `y` confirms aborting everything; any other key cancels
+        if self.confirm_abort {
+            self.confirm_abort = false;
+            if key.code == KeyCode::Char('y') {
+                self.abort_all();
+            } else {
+                log::info!("Abort cancelled");
+            }
+            return;
+        }
+
+        // Same for quitting while tasks are still running
+        if self.confirm_quit {
+            self.confirm_quit = false;
+            if key.code == KeyCode::Char('y') {
+                self.should_quit = true;
+            } else {
+                log::info!("Quit cancelled");
+            }
+            return;
+        }
+
+        // Handle semantic command parameter input
+        if let Some(prompt) = self.command_prompt.as_mut() {
+            match key.code {
+                KeyCode::Esc => self.command_prompt = None,
+                KeyCode::Enter => {
+                    if let Some(param) = prompt.current_param().map(str::to_string) {
+                        let kind = prompt.current_kind();
+                        if !kind.accepts(&prompt.input) {
+                            prompt.error = Some(format!("'{}' is not a valid {}", prompt.input, kind));
+                            return;
+                        }
+                        let value = std::mem::take(&mut prompt.input);
+                        prompt.values.insert(param, value);
+                        prompt.error = None;
+                    }
+                    if prompt.current_param().is_none() {
+                        let prompt = self.command_prompt.take().unwrap();
+                        self.send_semantic_command(&prompt.task_id, &prompt.label, &prompt.values);
+                    }
+                }
+                KeyCode::Backspace => {
+                    prompt.input.pop();
+                }
+                KeyCode::Char(c) => prompt.input.push(c),
+                _ => {}
+            }
+            return;
+        }
+
+        // Handle input for a waiting task
+        if let Some(prompt) = self.input_prompt.as_mut() {
+            match key.code {
+                KeyCode::Esc => self.input_prompt = None,
+                KeyCode::Enter => {
+                    let prompt = self.input_prompt.take().unwrap();
+                    self.send_task_input(&prompt.task_id, &prompt.input);
+                }
+                KeyCode::Backspace => {
+                    prompt.input.pop();
+                }
+                KeyCode::Char(c) => prompt.input.push(c),
+                _ => {}
+            }
+            return;
+        }
+
+        // Handle in-output search input
+        if let Some(search) = self.output_search.as_mut().filter(|s| s.editing) {
+            match key.code {
+                KeyCode::Esc => self.output_search = None,
+                KeyCode::Enter => {
+                    search.editing = false;
+                    // Start from the newest match
+                    if let Some(task_id) = self.get_task_ids().get(self.selected_task).cloned() {
+                        let count = self.output_matches(&task_id).len();
+                        self.select_match(&task_id, count.saturating_sub(1));
+                    }
+                }
+                KeyCode::Backspace => {
+                    search.query.pop();
+                }
+                KeyCode::Char(c) => search.query.push(c),
+                _ => {}
+            }
+            return;
+        }
+
         // Handle search mode input
         if self.search_mode {
             match key.code {
                 KeyCode::Esc => {
                     self.search_mode = false;
-                    self.search_query.clear();
+                    self.keep_selection(|app| app.search_query.clear());
                 }
                 KeyCode::Enter => {
                     self.search_mode = false;
-                    // Jump to first matching project/task
-                    self.apply_search();
+                    // A filter stays on with the selection already on a
+                    // match; otherwise jump to the first matching project/task
+                    if !self.search_filter {
+                        self.apply_search();
+                    }
+                }
+                KeyCode::Tab => {
+                    self.keep_selection(|app| app.search_filter = !app.search_filter);
                 }
                 KeyCode::Backspace => {
-                    self.search_query.pop();
+                    self.keep_selection(|app| {
+                        app.search_query.pop();
+                    });
+                    self.select_best_match();
                 }
                 KeyCode::Char(c) => {
-                    self.search_query.push(c);
+                    self.keep_selection(|app| app.search_query.push(c));
+                    self.select_best_match();
                 }
                 _ => {}
             }
             return;
         }
 
+        // Confirm or dismiss an offered auto-action; other keys pass through.
+        // Text entry above comes first, so typing never answers it.
+        if let Some(action) = &self.pending_action {
+            match key.code {
+                KeyCode::Char('y') => {
+                    let action = self.pending_action.take().unwrap();
+                    if let Some(cmd) = self
+                        .get_semantic_commands(&action.task_id)
+                        .and_then(|cmds| cmds.get(&action.label).cloned())
+                    {
+                        log::info!("Auto-action '{}' on {} confirmed", action.label, action.task_id);
+                        self.send_or_prompt(action.task_id, cmd);
+                    }
+                    return;
+                }
+                KeyCode::Char('n') | KeyCode::Esc => {
+                    log::info!("Auto-action '{}' on {} dismissed", action.label, action.task_id);
+                    self.pending_action = None;
+                    return;
+                }
+                _ => {}
+            }
+        }
+
         match key.code {
             KeyCode::Char('q') => {
-                if key.modifiers.contains(KeyModifiers::CONTROL) || self.view_mode == ViewMode::Dashboard || self.view_mode == ViewMode::ProjectOverview {
+                if key.modifiers.contains(KeyModifiers::CONTROL) {
                     self.should_quit = true;
+                } else if self.view_mode == ViewMode::Dashboard || self.view_mode == ViewMode::ProjectOverview {
+                    // Quitting kills every running task, so ask first
+                    if self.force_quit || self.scheduler.get_running().is_empty() {
+                        self.should_quit = true;
+                    } else {
+                        self.confirm_quit = true;
+                    }
                 } else {
                     // Return to main view from sub-views
                     self.view_mode = if self.workspace_mode { ViewMode::ProjectOverview } else { ViewMode::Dashboard };
                 }
             }
-            KeyCode::Esc => {
-                if self.view_mode != ViewMode::Dashboard && self.view_mode != ViewMode::ProjectOverview {
-                    self.view_mode = if self.workspace_mode { ViewMode::ProjectOverview } else { ViewMode::Dashboard };
-                }
+            KeyCode::Esc if self.view_mode == ViewMode::Terminal && self.output_search.is_some() => {
+                self.output_search = None;
+            }
+            KeyCode::Esc
+                if self.view_mode != ViewMode::Dashboard && self.view_mode != ViewMode::ProjectOverview =>
+            {
+                self.view_mode = if self.workspace_mode { ViewMode::ProjectOverview } else { ViewMode::Dashboard };
+            }
+            KeyCode::Esc if self.active_search_filter().is_some() => {
+                self.keep_selection(|app| app.search_query.clear());
+            }
+            KeyCode::Esc if self.tag_filter.is_some() => {
+                self.tag_filter = None;
+                self.selected_task = 0;
             }
             KeyCode::Char('r') => {
                 log::info!("Manual refresh requested");
             }
+            KeyCode::Char('/') if self.view_mode == ViewMode::Terminal => {
+                self.output_search = Some(OutputSearch {
+                    editing: true,
+                    ..Default::default()
+                });
+            }
+            KeyCode::Char(c @ ('n' | 'N')) if self.view_mode == ViewMode::Terminal && self.output_search.is_some() => {
+                self.step_match(if c == 'n' { 1 } else { -1 });
+            }
+            KeyCode::Char('F') => {
+                self.follow = !self.follow;
+                if self.follow {
+                    self.scroll_offset = 0;
+                }
+            }
+            KeyCode::Char('n') if self.view_mode == ViewMode::Graph => self.show_nodes = !self.show_nodes,
+            KeyCode::Char('E') if self.view_mode == ViewMode::Terminal => {
+                self.errors_only = !self.errors_only;
+                self.scroll_offset = 0;
+            }
+            KeyCode::F(n) if self.view_mode == ViewMode::Terminal => {
+                self.trigger_semantic_command(n as usize);
+            }
+            KeyCode::Char('a')
+                if key.modifiers.contains(KeyModifiers::CONTROL) && self.view_mode == ViewMode::Terminal =>
+            {
+                self.interact_prefix = true;
+            }
+            KeyCode::Char('a') if self.view_mode == ViewMode::Terminal => {
+                // Acknowledge the selected task's most recent advisory
+                if let Some(task_id) = self.get_task_ids().get(self.selected_task).cloned() {
+                    let index = self
+                        .get_advisories(&task_id)
+                        .and_then(|h| h.active().first().map(|(i, _)| *i));
+                    if let Some(index) = index {
+                        self.acknowledge_advisory(&task_id, index);
+                    }
+                }
+            }
             KeyCode::Char('k') => {
                 // Kill selected task
                 let task_ids = self.get_task_ids();
@@ -540,6 +2168,25 @@ impl App {
                     }
                 }
             }
+            KeyCode::Char('i') => {
+                if let Some(task_id) = self.waiting_task() {
+                    self.input_prompt = Some(InputPrompt {
+                        task_id,
+                        input: String::new(),
+                    });
+                }
+            }
+            KeyCode::Char('G') => self.toggle_component_grouping(),
+            KeyCode::Char('S') => self.set_step_mode(self.step_mode == StepMode::Off),
+            KeyCode::Char('s') => {
+                if let Err(e) = self.step() {
+                    log::info!("{}", e);
+                }
+            }
+            KeyCode::Char('X') => {
+                // Ask before stopping everything
+                self.confirm_abort = !self.scheduler.get_running().is_empty();
+            }
             // Quick Switch: 1-9 to switch projects
             KeyCode::Char(c) if c.is_ascii_digit() && self.workspace_mode => {
                 let idx = c.to_digit(10).unwrap_or(0) as usize;
@@ -557,10 +2204,15 @@ impl App {
             KeyCode::Char('g') => self.view_mode = ViewMode::Graph,
             KeyCode::Char('c') => self.view_mode = ViewMode::Comparison,
             KeyCode::Char('p') if self.workspace_mode => self.view_mode = ViewMode::ProjectOverview,
+            // Tag filter
+            KeyCode::Char('f') => {
+                self.tag_filter_mode = true;
+                self.tag_filter.get_or_insert_with(String::new);
+            }
             // Search mode
             KeyCode::Char('/') => {
                 self.search_mode = true;
-                self.search_query.clear();
+                self.keep_selection(|app| app.search_query.clear());
             }
             KeyCode::Enter => {
                 if self.view_mode == ViewMode::ProjectOverview {
@@ -586,9 +2238,7 @@ impl App {
             }
             KeyCode::Up => {
                 if self.view_mode == ViewMode::ProjectOverview {
-                    if self.selected_project > 0 {
-                        self.selected_project -= 1;
-                    }
+                    self.step_project(false);
                 } else if self.selected_task > 0 {
                     self.selected_task -= 1;
                     self.scroll_offset = 0;
@@ -596,33 +2246,81 @@ impl App {
             }
             KeyCode::Down => {
                 if self.view_mode == ViewMode::ProjectOverview {
-                    if self.selected_project + 1 < self.project_names.len() {
-                        self.selected_project += 1;
-                    }
+                    self.step_project(true);
                 } else {
-                    let task_count = self.scheduler.graph().all_tasks().len();
+                    let task_count = self.get_task_ids().len();
                     if self.selected_task + 1 < task_count {
                         self.selected_task += 1;
                         self.scroll_offset = 0;
                     }
                 }
             }
-            KeyCode::Left | KeyCode::Right => {
-                // Navigate between projects in workspace mode
-                if self.workspace_mode {
-                    if key.code == KeyCode::Left && self.selected_project > 0 {
-                        self.selected_project -= 1;
-                        self.jump_to_project(self.selected_project);
-                    } else if key.code == KeyCode::Right && self.selected_project + 1 < self.project_names.len() {
-                        self.selected_project += 1;
-                        self.jump_to_project(self.selected_project);
-                    }
+            // Navigate between projects in workspace mode
+            KeyCode::Left | KeyCode::Right if self.workspace_mode => {
+                let moved = self.step_project(key.code == KeyCode::Right);
+                if moved {
+                    self.jump_to_project(self.selected_project);
                 }
             }
             _ => {}
         }
     }
     
+    /// Keep the terminal view at the tail (follow) or on the same lines
+    /// (pinned) after a line is appended to the selected task's output
+    pub fn output_appended(&mut self) {
+        if self.follow {
+            self.scroll_offset = 0;
+        } else {
+            self.scroll_offset += 1;
+        }
+    }
+
+    /// Output line indices matching the in-output search, oldest first
+    pub fn output_matches(&self, task_id: &str) -> Vec<usize> {
+        let (Some(re), Some(lines)) = (
+            self.output_search.as_ref().and_then(|s| s.regex()),
+            self.task_outputs.get(task_id),
+        ) else {
+            return Vec::new();
+        };
+        lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| re.is_match(line))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Move `step` matches forward (newer) or back (older), wrapping around
+    fn step_match(&mut self, step: isize) {
+        let Some(task_id) = self.get_task_ids().get(self.selected_task).cloned() else {
+            return;
+        };
+        let count = self.output_matches(&task_id).len();
+        let Some(search) = self.output_search.as_ref() else {
+            return;
+        };
+        if count > 0 {
+            let index = (search.current.min(count - 1) as isize + step).rem_euclid(count as isize);
+            self.select_match(&task_id, index as usize);
+        }
+    }
+
+    /// Make match `index` current and scroll it to the bottom of the view
+    fn select_match(&mut self, task_id: &str, index: usize) {
+        let matches = self.output_matches(task_id);
+        let (Some(&line), Some(search)) = (matches.get(index), self.output_search.as_mut()) else {
+            return;
+        };
+        search.current = index;
+        let total = self.task_outputs.get(task_id).map_or(0, |lines| lines.len());
+        self.scroll_offset = total - 1 - line;
+        // Stay on the match while output keeps coming, in the full output
+        self.follow = false;
+        self.errors_only = false;
+    }
+
     /// Jump to a specific project (select first task of that project)
     fn jump_to_project(&mut self, project_idx: usize) {
         if let Some(project_name) = self.project_names.get(project_idx) {
@@ -637,36 +2335,112 @@ impl App {
         }
     }
     
-    /// Apply search query to find matching project/task
+    /// Move the project selection to the next (or previous) project the
+    /// search filter keeps; returns whether it moved
+    fn step_project(&mut self, forward: bool) -> bool {
+        let visible = self.visible_project_indices();
+        let Some(pos) = visible.iter().position(|&idx| idx == self.selected_project) else {
+            return false;
+        };
+        let next = if forward { visible.get(pos + 1) } else { pos.checked_sub(1).and_then(|p| visible.get(p)) };
+        match next {
+            Some(&idx) => {
+                self.selected_project = idx;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Apply search query to find the best matching project/task
     fn apply_search(&mut self) {
         if self.search_query.is_empty() {
             return;
         }
         
-        let query = self.search_query.to_lowercase();
-        
         // First try to match project names
-        for (idx, name) in self.project_names.iter().enumerate() {
-            if name.to_lowercase().contains(&query) {
-                self.selected_project = idx;
-                self.jump_to_project(idx);
-                return;
-            }
+        let projects = self.project_names.iter().map(String::as_str).enumerate();
+        if let Some(idx) = best_match(&self.search_query, projects, self.selected_project) {
+            self.selected_project = idx;
+            self.jump_to_project(idx);
+            return;
         }
         
         // Then try to match task IDs
         let task_ids = self.get_task_ids();
-        for (idx, task_id) in task_ids.iter().enumerate() {
-            if task_id.to_lowercase().contains(&query) {
-                self.selected_task = idx;
-                return;
-            }
+        let tasks = task_ids.iter().map(String::as_str).enumerate();
+        if let Some(idx) = best_match(&self.search_query, tasks, self.selected_task) {
+            self.selected_task = idx;
+        }
+    }
+
+    /// While filtering, select the best-scoring task and project. The lists
+    /// keep their execution (and grouping) order; only the selection ranks.
+    fn select_best_match(&mut self) {
+        let Some(query) = self.active_search_filter().map(str::to_string) else {
+            return;
+        };
+
+        let task_ids = self.get_task_ids();
+        let tasks = task_ids.iter().map(String::as_str).enumerate();
+        if let Some(idx) = best_match(&query, tasks, self.selected_task) {
+            self.selected_task = idx;
+            self.scroll_offset = 0;
+        }
+
+        let projects = self
+            .visible_project_indices()
+            .into_iter()
+            .map(|idx| (idx, self.project_names[idx].as_str()));
+        if let Some(idx) = best_match(&query, projects, self.selected_project) {
+            self.selected_project = idx;
+        }
+    }
+
+    /// The query narrowing the lists, when filtering is on and non-empty
+    fn active_search_filter(&self) -> Option<&str> {
+        Some(self.search_query.as_str()).filter(|q| self.search_filter && !q.is_empty())
+    }
+
+    /// Indices into `project_names` of the projects the search filter keeps
+    pub fn visible_project_indices(&self) -> Vec<usize> {
+        let query = self.active_search_filter();
+        (0..self.project_names.len())
+            .filter(|&idx| query.is_none_or(|q| matches_search(q, &self.project_names[idx])))
+            .collect()
+    }
+
+    /// Number of entries the search filter keeps in the current view
+    pub fn search_match_count(&self) -> usize {
+        if self.view_mode == ViewMode::ProjectOverview {
+            self.visible_project_indices().len()
+        } else {
+            self.get_task_ids().len()
+        }
+    }
+
+    /// Header text for the project/task search, empty when not searching
+    pub fn search_indicator(&self) -> String {
+        let cursor = if self.search_mode { "_" } else { "" };
+        match self.active_search_filter() {
+            Some(query) => format!(" | Search: {}{} ({} matches)", query, cursor, self.search_match_count()),
+            None if self.search_mode => format!(" | Search: {}_", self.search_query),
+            None => String::new(),
+        }
+    }
+
+    /// Footer help while typing a search query
+    pub fn search_help(&self) -> &'static str {
+        if self.search_filter {
+            "Type to filter │ Enter: Keep filter │ Tab: Jump instead │ Esc: Cancel"
+        } else {
+            "Type to search │ Enter: Jump │ Tab: Filter instead │ Esc: Cancel"
         }
     }
 
-    /// Check if we should poll for input
-    pub fn should_poll_input() -> Result<bool> {
-        Ok(event::poll(Duration::from_millis(100))?)
+    /// Wait up to `timeout` for input; returns early as soon as a key arrives
+    pub fn should_poll_input(timeout: Duration) -> Result<bool> {
+        Ok(event::poll(timeout)?)
     }
 
     /// Get keyboard event
@@ -676,27 +2450,93 @@ impl App {
 
     /// Get task output lines (last N)
     pub fn get_task_output(&self, task_id: &str, last_n: usize) -> Vec<String> {
-        self.task_outputs
+        self.with_tail(task_id, last_n, |lines| lines.cloned().collect())
+    }
+
+    /// Run `f` over a task's last `n` output lines, oldest first, without
+    /// copying them
+    pub fn with_tail<R>(&self, task_id: &str, n: usize, f: impl FnOnce(Iter<'_, String>) -> R) -> R {
+        match self.task_outputs.get(task_id) {
+            Some(lines) => f(lines.tail(n)),
+            None => f(RingBuffer::new(0).tail(n)),
+        }
+    }
+
+    /// Level of a task's output line, by its index in `task_outputs`
+    pub fn output_level(&self, task_id: &str, index: usize) -> LogLevel {
+        self.task_levels
             .get(task_id)
-            .map(|lines| {
-                let start = lines.len().saturating_sub(last_n);
-                lines[start..].to_vec()
-            })
+            .and_then(|levels| levels.get(index))
+            .copied()
             .unwrap_or_default()
     }
 
+    /// Indices of a task's error lines, oldest first
+    pub fn error_lines(&self, task_id: &str) -> Vec<usize> {
+        self.task_levels.get(task_id).map_or_else(Vec::new, |levels| {
+            levels
+                .iter()
+                .enumerate()
+                .filter(|(_, level)| **level == LogLevel::Error)
+                .map(|(i, _)| i)
+                .collect()
+        })
+    }
+
     /// Get semantic metrics for a task
     pub fn get_task_metrics(&self, task_id: &str) -> Option<&TaskMetrics> {
         self.task_metrics.get(task_id)
     }
 
-    /// Get all task IDs sorted
+    /// Get visible task IDs in execution order
+    ///
+    /// In workspace mode tasks stay grouped by project (or by component,
+    /// when grouping by component) so the flat index matches the grouped
+    /// layout of the dashboard. The tag filter, if set, hides non-matching
+    /// tasks.
     pub fn get_task_ids(&self) -> Vec<String> {
-        let mut ids: Vec<String> = self.scheduler.graph().all_tasks().keys().cloned().collect();
-        ids.sort();
+        if self.group_by_component {
+            return self
+                .get_tasks_by_component()
+                .into_iter()
+                .flat_map(|(_, ids)| ids)
+                .collect();
+        }
+        self.filtered_task_ids()
+    }
+
+    /// Task IDs in execution order, minus those hidden by the tag or
+    /// search filter
+    fn filtered_task_ids(&self) -> Vec<String> {
+        let mut ids = self.all_task_ids();
+        if let Some(filter) = self.tag_filter.as_deref().filter(|f| !f.is_empty()) {
+            ids.retain(|id| self.task_has_tag(id, filter));
+        }
+        if let Some(query) = self.active_search_filter() {
+            ids.retain(|id| matches_search(query, id));
+        }
+        ids
+    }
+
+    /// Get all task IDs in execution order, ignoring UI filters
+    pub fn all_task_ids(&self) -> Vec<String> {
+        let mut ids = self.scheduler.graph().topological_order();
+        if self.workspace_mode {
+            ids.sort_by_cached_key(|id| self.get_project_name(id));
+        }
         ids
     }
 
+    /// Check whether a task has a tag containing `filter` (case-insensitive)
+    fn task_has_tag(&self, task_id: &str, filter: &str) -> bool {
+        let filter = filter.to_lowercase();
+        self.scheduler
+            .graph()
+            .get_task(task_id)
+            .and_then(|t| t.tags.as_ref())
+            .is_some_and(|tags| tags.iter().any(|tag| tag.to_lowercase().contains(&filter)))
+    }
+
     /// Get semantic commands for a task (from graph YAML semantic_commands field)
     pub fn get_semantic_commands(&self, task_id: &str) -> Option<TaskCommands> {
         let task = self.scheduler.graph().get_task(task_id)?;
@@ -718,8 +2558,106 @@ impl App {
             .ok_or_else(|| anyhow::anyhow!("No semantic commands for task {}", task_id))?;
         let cmd = cmds.get(label)
             .ok_or_else(|| anyhow::anyhow!("Command '{}' not found for task {}", label, task_id))?;
-        let rendered = cmd.render(params);
-        self.executor.send_input(task_id, &rendered)
+        cmd.validate(params)?;
+        self.executor.send_raw(task_id, &cmd.render_input(params))
+    }
+
+    /// Run the selected task's nth (1-based) semantic command, prompting
+    /// for its parameters first if it has any
+    fn trigger_semantic_command(&mut self, n: usize) {
+        let Some(task_id) = self.get_task_ids().get(self.selected_task).cloned() else {
+            return;
+        };
+        let Some(cmd) = self
+            .get_semantic_commands(&task_id)
+            .and_then(|cmds| n.checked_sub(1).and_then(|i| cmds.commands.get(i).cloned()))
+        else {
+            return;
+        };
+
+        self.send_or_prompt(task_id, cmd);
+    }
+
+    /// Send a semantic command, or open the prompt if it needs parameters
+    fn send_or_prompt(&mut self, task_id: String, cmd: SemanticCommand) {
+        if cmd.needs_params() {
+            let mut params = cmd.params.clone();
+            let mut seen = HashSet::new();
+            params.retain(|p| seen.insert(p.clone()));
+
+            self.command_prompt = Some(CommandPrompt {
+                task_id,
+                label: cmd.label,
+                params,
+                values: HashMap::new(),
+                kinds: cmd.kinds,
+                input: String::new(),
+                error: None,
+            });
+        } else {
+            self.send_semantic_command(&task_id, &cmd.label, &HashMap::new());
+        }
+    }
+
+    /// Act on advisories whose `auto_action` names one of the task's
+    /// semantic commands: run it in agent mode, otherwise ask first
+    ///
+    /// Each action is offered at most once per task run.
+    pub fn offer_auto_actions(&mut self, task_id: &str, advisories: &[Advisory]) {
+        if !self.auto_actions {
+            return;
+        }
+        let Some(cmds) = self.get_semantic_commands(task_id) else {
+            return;
+        };
+
+        for advisory in advisories {
+            let Some(cmd) = advisory.auto_action.as_deref().and_then(|label| cmds.get(label)) else {
+                continue;
+            };
+            let key = (task_id.to_string(), cmd.label.clone());
+            if self.auto_actions_offered.contains(&key) {
+                continue;
+            }
+
+            if self.control_mode == ControlMode::Agent {
+                self.auto_actions_offered.insert(key);
+                if !self.agent_policy.command_allowed(&cmd.label) {
+                    log::warn!("Skipping auto-action '{}' on {}: not in allowed_commands", cmd.label, task_id);
+                    continue;
+                }
+                if cmd.needs_params() {
+                    log::warn!(
+                        "Skipping auto-action '{}' on {}: it needs parameters",
+                        cmd.label,
+                        task_id
+                    );
+                    continue;
+                }
+                log::info!("Auto-action '{}' on {} ({})", cmd.label, task_id, advisory.message);
+                self.send_semantic_command(task_id, &cmd.label, &HashMap::new());
+            } else if self.pending_action.is_none() {
+                self.auto_actions_offered.insert(key);
+                log::info!("Offering auto-action '{}' on {} ({})", cmd.label, task_id, advisory.message);
+                self.pending_action = Some(PendingAction {
+                    task_id: task_id.to_string(),
+                    label: cmd.label.clone(),
+                    message: advisory.message.clone(),
+                });
+            }
+        }
+    }
+
+    /// Execute a semantic command, reporting the outcome as a recent event
+    fn send_semantic_command(&mut self, task_id: &str, label: &str, params: &HashMap<String, String>) {
+        let project = self.get_project_name(task_id).unwrap_or_else(|| self.session.project.clone());
+        match self.execute_semantic_command(task_id, label, params) {
+            Ok(()) => self.add_recent_event(&project, format!("Sent '{}' to {}", label, task_id)),
+            Err(e) => {
+                log::warn!("Semantic command '{}' failed: {}", label, e);
+                self.add_recent_event(&project, format!("Command '{}' failed: {}", label, e));
+            }
+        }
     }
 
     /// Extract project name from namespaced task ID
@@ -740,7 +2678,7 @@ impl App {
                 if let Some(project) = self.get_project_name(&task_id) {
                     grouped
                         .entry(project)
-                        .or_insert_with(Vec::new)
+                        .or_default()
                         .push(task_id);
                 }
             }
@@ -752,6 +2690,52 @@ impl App {
         grouped
     }
     
+    /// Visible tasks grouped by `component`, components sorted by name
+    ///
+    /// Tasks without a component come last, under `UNGROUPED_COMPONENT`.
+    /// Within a group tasks keep their execution order.
+    pub fn get_tasks_by_component(&self) -> Vec<(String, Vec<String>)> {
+        let graph = self.scheduler.graph();
+        let mut grouped: BTreeMap<(bool, String), Vec<String>> = BTreeMap::new();
+
+        for task_id in self.filtered_task_ids() {
+            let key = match graph.get_task(&task_id).and_then(|t| t.component.clone()) {
+                Some(component) => (false, component),
+                None => (true, UNGROUPED_COMPONENT.to_string()),
+            };
+            grouped.entry(key).or_default().push(task_id);
+        }
+
+        grouped.into_iter().map(|((_, name), ids)| (name, ids)).collect()
+    }
+
+    /// Switch the dashboard between flat (or per-project) and per-component
+    /// grouping, keeping the same task selected
+    pub fn toggle_component_grouping(&mut self) {
+        self.keep_selection(|app| app.group_by_component = !app.group_by_component);
+    }
+
+    /// Change what the lists show, keeping the same task (and project)
+    /// selected if still visible, else selecting the first one
+    fn keep_selection(&mut self, change: impl FnOnce(&mut Self)) {
+        let selected = self.get_task_ids().get(self.selected_task).cloned();
+        change(self);
+        if let Some(task_id) = selected {
+            self.selected_task = self
+                .get_task_ids()
+                .iter()
+                .position(|id| *id == task_id)
+                .unwrap_or(0);
+        }
+
+        let visible = self.visible_project_indices();
+        if !visible.contains(&self.selected_project) {
+            if let Some(&first) = visible.first() {
+                self.selected_project = first;
+            }
+        }
+    }
+
     /// Get project summaries for unified dashboard
     pub fn get_project_summaries(&self) -> Vec<ProjectSummary> {
         let mut summaries = Vec::new();
@@ -878,3 +2862,52 @@ impl App {
         self.agent_manager.get_status(project).emoji()
     }
 }
+
+/// Fuzzy score of `candidate` (a task ID or project name) against a search
+/// query, ignoring case, or `None` if it doesn't match. The query's letters
+/// must appear in order but not together, so `wapi` finds `web-api`;
+/// contiguous runs and matches at the start of a word score higher.
+pub fn search_score(query: &str, candidate: &str) -> Option<i64> {
+    static MATCHER: OnceLock<SkimMatcherV2> = OnceLock::new();
+    MATCHER
+        .get_or_init(|| SkimMatcherV2::default().ignore_case())
+        .fuzzy_match(candidate, query)
+}
+
+/// Whether `candidate` matches a search query at all
+pub fn matches_search(query: &str, candidate: &str) -> bool {
+    search_score(query, candidate).is_some()
+}
+
+/// Position of the best-scoring candidate; ties go to `current`, then to
+/// the earliest
+fn best_match<'a>(query: &str, candidates: impl Iterator<Item = (usize, &'a str)>, current: usize) -> Option<usize> {
+    candidates
+        .filter_map(|(idx, candidate)| search_score(query, candidate).map(|score| (idx, score)))
+        .min_by_key(|&(idx, score)| (Reverse(score), idx != current))
+        .map(|(idx, _)| idx)
+}
+
+/// Bytes a terminal sends for a key, for passing it through to a PTY
+fn key_bytes(key: KeyEvent) -> Option<Vec<u8>> {
+    let bytes: &[u8] = match key.code {
+        KeyCode::Char(c) if key.modifiers.contains(KeyModifiers::CONTROL) && c.is_ascii_alphabetic() => {
+            return Some(vec![c.to_ascii_lowercase() as u8 & 0x1f]);
+        }
+        KeyCode::Char(c) => return Some(c.to_string().into_bytes()),
+        KeyCode::Enter => b"\r",
+        KeyCode::Backspace => b"\x7f",
+        KeyCode::Tab => b"\t",
+        KeyCode::Up => b"\x1b[A",
+        KeyCode::Down => b"\x1b[B",
+        KeyCode::Right => b"\x1b[C",
+        KeyCode::Left => b"\x1b[D",
+        KeyCode::Home => b"\x1b[H",
+        KeyCode::End => b"\x1b[F",
+        KeyCode::Delete => b"\x1b[3~",
+        KeyCode::PageUp => b"\x1b[5~",
+        KeyCode::PageDown => b"\x1b[6~",
+        _ => return None,
+    };
+    Some(bytes.to_vec())
+}
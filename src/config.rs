@@ -0,0 +1,169 @@
+//! Layered configuration - every `config.toml` table in one place
+//!
+//! Settings are read from, in increasing precedence:
+//!
+//! 1. `~/.gidterm/config.toml` (user defaults)
+//! 2. `.gidterm/config.toml` in the current directory (project overrides)
+//! 3. command-line flags, applied by the caller on the loaded `Config`
+//!
+//! Both files hold the same tables. A project file only needs the keys it
+//! changes: tables are merged key by key, so a project that sets
+//! `[notifications] quiet_hours = false` keeps the user's other notification
+//! settings. Anything left unset falls back to the built-in defaults
+//! (`DEFAULT_BUFFER_LINES`, ports 3000-3999, quiet hours 23:00-08:00, ...).
+//!
+//! The `[agent]` table is only read from the user file, so a checked-out
+//! project can't loosen an agent's guardrails.
+
+use crate::ai::AgentPolicy;
+use crate::core::GraphConfig;
+use crate::logs::OutputConfig;
+use crate::notifications::NotificationConfig;
+use crate::ports::PortConfig;
+use crate::semantic::advisor::AdvisorConfig;
+use crate::semantic::ParserConfig;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// Tables a project config may not set
+const USER_ONLY_TABLES: [&str; 1] = ["agent"];
+
+/// Display settings read from the `[ui]` table
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct UiConfig {
+    /// Milliseconds between redraws while tasks produce output
+    /// (default `DEFAULT_ACTIVE_TICK`)
+    pub tick_ms: Option<u64>,
+}
+
+/// Every setting from config.toml, after layering
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub output: OutputConfig,
+    pub ports: PortConfig,
+    pub notifications: NotificationConfig,
+    pub advisor: AdvisorConfig,
+    pub parsers: ParserConfig,
+    pub agent: AgentPolicy,
+    pub graph: GraphConfig,
+    pub ui: UiConfig,
+}
+
+impl Config {
+    /// The user's config file
+    pub fn user_path() -> PathBuf {
+        dirs::home_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join(".gidterm")
+            .join("config.toml")
+    }
+
+    /// The project's config file, relative to the current directory
+    pub fn project_path() -> PathBuf {
+        PathBuf::from(".gidterm").join("config.toml")
+    }
+
+    /// Load the user config overlaid by the project config (defaults if
+    /// neither exists)
+    pub fn load() -> Result<Self> {
+        Self::load_layers(&Self::user_path(), &Self::project_path())
+    }
+
+    /// Like `load`, but log a bad file and fall back to the defaults
+    pub fn load_or_default() -> Self {
+        Self::load().unwrap_or_else(|e| {
+            log::warn!("Failed to read config: {:#}", e);
+            Self::default()
+        })
+    }
+
+    /// Load `user` overlaid by `project`; either may be missing
+    pub fn load_layers(user: &Path, project: &Path) -> Result<Self> {
+        let mut merged = read_table(user)?;
+        let mut overrides = read_table(project)?;
+        for table in USER_ONLY_TABLES {
+            if overrides.remove(table).is_some() {
+                log::warn!("Ignoring [{}] in {}; set it in {}", table, project.display(), user.display());
+            }
+        }
+        merge(&mut merged, overrides);
+
+        let config = toml::Value::Table(merged).try_into()?;
+        Ok(config)
+    }
+}
+
+/// A config file's tables, or nothing if it doesn't exist
+fn read_table(path: &Path) -> Result<toml::Table> {
+    if !path.exists() {
+        return Ok(toml::Table::new());
+    }
+
+    let content = std::fs::read_to_string(path)?;
+    toml::from_str(&content).with_context(|| format!("Invalid {}", path.display()))
+}
+
+/// Overlay `overrides` onto `base`: tables merge key by key, any other
+/// value replaces the one below it
+fn merge(base: &mut toml::Table, overrides: toml::Table) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(below)), toml::Value::Table(above)) => merge(below, above),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_config_overrides_user_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let user = dir.path().join("user.toml");
+        let project = dir.path().join("project.toml");
+        std::fs::write(
+            &user,
+            "[output]\nbuffer_lines = 500\n\n[notifications]\nsound = false\nquiet_start = 22\n\n[agent]\nsafe_mode = true\n",
+        )
+        .unwrap();
+        std::fs::write(
+            &project,
+            "[notifications]\nquiet_start = 1\n\n[ports]\nrange = [8000, 8010]\n\n[ui]\ntick_ms = 15\n\n[advisor]\nstall_secs = 90\n\n[graph]\ncache = true\n\n[agent]\nsafe_mode = false\n",
+        )
+        .unwrap();
+
+        let config = Config::load_layers(&user, &project).unwrap();
+        // The project wins key by key; untouched keys come from the user file
+        assert_eq!(config.notifications.quiet_start, 1);
+        assert!(!config.notifications.sound);
+        assert_eq!(config.output.buffer_lines, Some(500));
+        assert_eq!(config.ports.range, Some((8000, 8010)));
+        assert_eq!(config.ui.tick_ms, Some(15));
+        assert_eq!(config.advisor.stall_secs, Some(90));
+        assert!(config.graph.cache);
+        // Keys set nowhere keep their defaults
+        assert_eq!(config.notifications.quiet_end, 8);
+        assert!(config.notifications.on_error);
+        // A project can't turn off the user's safe mode
+        assert!(config.agent.safe_mode);
+
+        // Either layer may be missing
+        let missing = dir.path().join("missing.toml");
+        let user_only = Config::load_layers(&user, &missing).unwrap();
+        assert_eq!(user_only.notifications.quiet_start, 22);
+        let defaults = Config::load_layers(&missing, &missing).unwrap();
+        assert_eq!(defaults.notifications.quiet_start, 23);
+        assert!(defaults.output.buffer_lines.is_none());
+        assert!(!defaults.graph.cache);
+
+        std::fs::write(&project, "[notifications\n").unwrap();
+        let err = Config::load_layers(&user, &project).unwrap_err();
+        assert!(err.to_string().contains("project.toml"));
+    }
+}
@@ -0,0 +1,90 @@
+//! ANSI escape sequence stripping for PTY output
+//!
+//! Tools detect the PTY and emit colors, cursor movement and window titles.
+//! Parsers and the TUI both want plain text, so escapes are removed as soon
+//! as a line is read.
+
+use std::borrow::Cow;
+
+const ESC: char = '\x1b';
+const BEL: char = '\x07';
+/// 8-bit CSI introducer, equivalent to `ESC [`
+const C1_CSI: char = '\u{9b}';
+
+/// Remove ANSI escape sequences (CSI, OSC and two-byte escapes) from a line
+///
+/// Borrows the input unchanged when it contains no escapes.
+pub fn strip_ansi(line: &str) -> Cow<'_, str> {
+    if !line.contains([ESC, C1_CSI]) {
+        return Cow::Borrowed(line);
+    }
+
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            ESC => match chars.next() {
+                // CSI: parameters and intermediates, ended by a final byte in @..~
+                Some('[') => skip_csi(&mut chars),
+                // OSC: ended by BEL or ST (ESC \)
+                Some(']') => {
+                    while let Some(c) = chars.next() {
+                        if c == BEL || (c == ESC && chars.next_if_eq(&'\\').is_some()) {
+                            break;
+                        }
+                    }
+                }
+                // Charset designation takes one more byte
+                Some('(' | ')' | '*' | '+') => {
+                    chars.next();
+                }
+                // Two-byte escape (keypad mode, cursor save, ...)
+                _ => {}
+            },
+            C1_CSI => skip_csi(&mut chars),
+            _ => out.push(c),
+        }
+    }
+
+    Cow::Owned(out)
+}
+
+fn skip_csi(chars: &mut impl Iterator<Item = char>) {
+    for c in chars {
+        if ('@'..='~').contains(&c) {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+
+    #[test]
+    fn test_plain_text_is_borrowed() {
+        assert!(matches!(strip_ansi("Compiling foo v0.1.0"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_strips_colorized_cargo_output() {
+        let raw = "\x1b[0m\x1b[1m\x1b[38;5;9merror[E0308]\x1b[0m\x1b[0m\x1b[1m: mismatched types\x1b[0m";
+        let clean = strip_ansi(raw);
+        assert_eq!(clean, "error[E0308]: mismatched types");
+
+        let error_re = Regex::new(r"(?i)^error(?:\[[\w]+\])?:").unwrap();
+        assert!(error_re.is_match(&clean));
+
+        let raw = "\x1b[1m\x1b[92m   Compiling\x1b[0m gidterm v0.5.0";
+        assert_eq!(strip_ansi(raw), "   Compiling gidterm v0.5.0");
+    }
+
+    #[test]
+    fn test_strips_osc_and_cursor_sequences() {
+        assert_eq!(strip_ansi("\x1b]0;build\x07\x1b[2Kdone\x1b[1A"), "done");
+        assert_eq!(strip_ansi("\x1b]8;;http://x\x1b\\link\x1b]8;;\x1b\\"), "link");
+        assert_eq!(strip_ansi("\x1b(Bplain"), "plain");
+    }
+}
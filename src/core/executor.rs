@@ -1,49 +1,136 @@
 //! Task Executor - Runs tasks in PTY and monitors them
 //!
-//! Uses tokio::task::spawn_blocking for PTY reads to avoid
-//! blocking the async runtime.
+//! PTY reads wait on the tokio reactor (non-blocking master on Unix), so
+//! a running task doesn't hold a thread while it is quiet.
+//!
+//! Events go through a bounded channel. Output lines that don't fit are
+//! dropped and later reported as one "N output lines dropped" line; the
+//! lifecycle events (Started/Completed/Failed) are never dropped.
 
-use super::pty::PTYHandle;
-use anyhow::Result;
+use super::pty::{PTYHandle, DEFAULT_HISTORY_LINES, DEFAULT_PTY_COLS, DEFAULT_PTY_ROWS, DEFAULT_SHELL};
+use super::resources::{ProcessTable, ResourceUsage, RESOURCE_SAMPLE_INTERVAL};
+use super::vars::CommandVars;
+#[cfg(unix)]
+use super::fifo::TaskFifo;
+use anyhow::{Context, Result};
 use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tokio::sync::mpsc;
 
+/// How often to check for the exit status once a task's output closes
+const EXIT_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Most events waiting for the app at once
+pub const EVENT_CHANNEL_CAPACITY: usize = 4096;
+
+/// Slots output lines leave free, so lifecycle events always find room
+const LIFECYCLE_HEADROOM: usize = 256;
+
 /// Task execution event
 #[derive(Debug, Clone)]
 pub enum TaskEvent {
     Started { task_id: String },
-    Output { task_id: String, line: String },
+    /// `replace` marks an in-place (`\r`) update of the previous line
+    Output { task_id: String, line: String, replace: bool },
     Completed { task_id: String, exit_code: i32 },
     Failed { task_id: String, error: String },
+    /// Running totals for the task's process tree, sent after each sample
+    Resources { task_id: String, usage: ResourceUsage },
 }
 
 /// Task executor - manages running tasks
 pub struct Executor {
     handles: Arc<Mutex<HashMap<String, PTYHandle>>>,
-    event_tx: mpsc::UnboundedSender<TaskEvent>,
+    event_tx: mpsc::Sender<TaskEvent>,
+    /// (rows, cols) for newly spawned PTYs
+    pty_size: Mutex<(u16, u16)>,
+    /// Output history kept by newly spawned PTYs
+    history_lines: Mutex<usize>,
+    /// Where newly spawned tasks mirror their output as `<task>.fifo`
+    #[cfg_attr(not(unix), allow(dead_code))]
+    fifo_dir: Mutex<Option<PathBuf>>,
+    /// The resource sampler runs once the first task starts
+    sampler_started: AtomicBool,
 }
 
 impl Executor {
     /// Create a new executor
-    pub fn new() -> (Self, mpsc::UnboundedReceiver<TaskEvent>) {
-        let (tx, rx) = mpsc::unbounded_channel();
+    ///
+    /// PTYs start at the size of the controlling terminal, if there is one.
+    pub fn new() -> (Self, mpsc::Receiver<TaskEvent>) {
+        let (tx, rx) = mpsc::channel(EVENT_CHANNEL_CAPACITY);
+        let pty_size = crossterm::terminal::size()
+            .map(|(cols, rows)| (rows, cols))
+            .unwrap_or((DEFAULT_PTY_ROWS, DEFAULT_PTY_COLS));
 
         (
             Self {
                 handles: Arc::new(Mutex::new(HashMap::new())),
                 event_tx: tx,
+                pty_size: Mutex::new(pty_size),
+                history_lines: Mutex::new(DEFAULT_HISTORY_LINES),
+                fifo_dir: Mutex::new(None),
+                sampler_started: AtomicBool::new(false),
             },
             rx,
         )
     }
 
-    /// Start a task
-    pub async fn start_task(&self, task_id: &str, command: &str) -> Result<()> {
+    /// Start a task after filling in its command's `${...}` variables,
+    /// running it through `shell` if the task picks one (values are quoted
+    /// for that shell)
+    ///
+    /// Fails without spawning anything if a variable can't be resolved.
+    pub async fn start_task_with_vars(
+        &self,
+        task_id: &str,
+        shell: Option<&str>,
+        command: &str,
+        vars: &CommandVars,
+        env: &HashMap<String, String>,
+        success_codes: &[i32],
+    ) -> Result<Option<u32>> {
+        let command = vars
+            .interpolate(command, shell.unwrap_or(DEFAULT_SHELL))
+            .with_context(|| format!("Cannot start task '{}'", task_id))?;
+        self.start_task_in_shell(task_id, shell, &command, env, success_codes).await
+    }
+
+    /// Start a task with extra environment variables
+    ///
+    /// The task completes if it exits with one of `success_codes` and fails
+    /// otherwise. Returns the child process ID when the platform reports one.
+    pub async fn start_task(
+        &self,
+        task_id: &str,
+        command: &str,
+        env: &HashMap<String, String>,
+        success_codes: &[i32],
+    ) -> Result<Option<u32>> {
+        self.start_task_in_shell(task_id, None, command, env, success_codes).await
+    }
+
+    /// Like `start_task`, running the command through `shell` instead of
+    /// the default shell
+    pub async fn start_task_in_shell(
+        &self,
+        task_id: &str,
+        shell: Option<&str>,
+        command: &str,
+        env: &HashMap<String, String>,
+        success_codes: &[i32],
+    ) -> Result<Option<u32>> {
         log::info!("Starting task: {} with command: {}", task_id, command);
 
         // Create PTY
-        let handle = PTYHandle::spawn(task_id, command)?;
+        let (rows, cols) = *self.pty_size.lock().unwrap();
+        let history_lines = *self.history_lines.lock().unwrap();
+        let handle = PTYHandle::spawn_in_shell(task_id, shell, command, env, rows, cols)?
+            .with_history_limit(history_lines);
+        let pid = handle.pid();
 
         // Store handle
         {
@@ -51,76 +138,88 @@ impl Executor {
             handles.insert(task_id.to_string(), handle.clone());
         }
 
-        // Send started event
-        let _ = self.event_tx.send(TaskEvent::Started {
+        // Send started event. The app awaits this call and is also the one
+        // draining the channel, so wait for room elsewhere if there is none.
+        let started = TaskEvent::Started {
             task_id: task_id.to_string(),
-        });
+        };
+        if let Err(mpsc::error::TrySendError::Full(started)) = self.event_tx.try_send(started) {
+            let event_tx = self.event_tx.clone();
+            tokio::spawn(async move { event_tx.send(started).await });
+        }
+
+        self.start_sampler();
 
-        // Spawn reader task — uses spawn_blocking for the actual I/O
+        // Spawn reader task
         let task_id_owned = task_id.to_string();
         let event_tx = self.event_tx.clone();
+        let mut output_tx = OutputSender::new(task_id, self.event_tx.clone());
+        #[cfg(unix)]
+        let mut fifo = self.open_fifo(task_id);
         let handles_ref = self.handles.clone();
         let reader_handle = handle.clone();
+        let success_codes = success_codes.to_vec();
 
         tokio::spawn(async move {
             loop {
-                // Clone handle for the blocking read
-                let rh = reader_handle.clone();
-
-                // Read one line in a blocking thread
-                let line_result = tokio::task::spawn_blocking(move || {
-                    rh.read_line_blocking()
-                })
-                .await;
-
-                match line_result {
-                    Ok(Ok(Some(line))) => {
-                        if !line.is_empty() {
-                            let _ = event_tx.send(TaskEvent::Output {
-                                task_id: task_id_owned.clone(),
-                                line,
-                            });
-                        }
-                    }
-                    Ok(Ok(None)) => {
-                        // EOF — process ended, get exit code
-                        let exit_code = reader_handle
-                            .try_wait()
-                            .ok()
-                            .flatten()
-                            .map(|r| r.code)
-                            .unwrap_or(0);
-
-                        if exit_code == 0 {
-                            log::info!("Task {} completed (exit: {})", task_id_owned, exit_code);
-                            let _ = event_tx.send(TaskEvent::Completed {
-                                task_id: task_id_owned.clone(),
-                                exit_code,
-                            });
-                        } else {
-                            log::warn!("Task {} failed (exit: {})", task_id_owned, exit_code);
-                            let _ = event_tx.send(TaskEvent::Failed {
-                                task_id: task_id_owned.clone(),
-                                error: format!("Process exited with code {}", exit_code),
-                            });
+                match reader_handle.read_line().await {
+                    Ok(Some(line)) => {
+                        if !line.text.is_empty() {
+                            #[cfg(unix)]
+                            if let Some(fifo) = fifo.as_mut() {
+                                fifo.write_line(&line.text);
+                            }
+                            output_tx.send(line.text, line.replace);
                         }
-                        break;
                     }
-                    Ok(Err(e)) => {
-                        log::error!("Task {} read error: {}", task_id_owned, e);
-                        let _ = event_tx.send(TaskEvent::Failed {
-                            task_id: task_id_owned.clone(),
-                            error: e.to_string(),
-                        });
+                    Ok(None) => {
+                        // EOF — output closed, but the child may not be reaped yet
+                        let exit = loop {
+                            match reader_handle.try_wait() {
+                                Ok(Some(result)) => break Ok(result),
+                                Ok(None) => tokio::time::sleep(EXIT_POLL_INTERVAL).await,
+                                Err(e) => break Err(e),
+                            }
+                        };
+                        output_tx.flush().await;
+
+                        // A signaled process never counts as success, whatever its code
+                        let event = match exit {
+                            Ok(exit) if exit.signal.is_none() && success_codes.contains(&exit.code) => {
+                                log::info!("Task {} completed (exit: {})", task_id_owned, exit.code);
+                                TaskEvent::Completed {
+                                    task_id: task_id_owned.clone(),
+                                    exit_code: exit.code,
+                                }
+                            }
+                            Ok(exit) => {
+                                log::warn!("Task {} failed ({})", task_id_owned, exit.describe());
+                                TaskEvent::Failed {
+                                    task_id: task_id_owned.clone(),
+                                    error: format!("Process {}", exit.describe()),
+                                }
+                            }
+                            // The outcome is unknown, so dependents must not start
+                            Err(e) => {
+                                log::warn!("Task {} wait error: {}", task_id_owned, e);
+                                TaskEvent::Failed {
+                                    task_id: task_id_owned.clone(),
+                                    error: format!("Cannot get exit status: {}", e),
+                                }
+                            }
+                        };
+                        let _ = event_tx.send(event).await;
                         break;
                     }
                     Err(e) => {
-                        // spawn_blocking join error
-                        log::error!("Task {} spawn_blocking error: {}", task_id_owned, e);
-                        let _ = event_tx.send(TaskEvent::Failed {
-                            task_id: task_id_owned.clone(),
-                            error: format!("Internal error: {}", e),
-                        });
+                        log::error!("Task {} read error: {}", task_id_owned, e);
+                        output_tx.flush().await;
+                        let _ = event_tx
+                            .send(TaskEvent::Failed {
+                                task_id: task_id_owned.clone(),
+                                error: e.to_string(),
+                            })
+                            .await;
                         break;
                     }
                 }
@@ -131,7 +230,70 @@ impl Executor {
             handles.remove(&task_id_owned);
         });
 
-        Ok(())
+        Ok(pid)
+    }
+
+    /// The FIFO mirroring a task's output, if FIFOs are enabled
+    #[cfg(unix)]
+    fn open_fifo(&self, task_id: &str) -> Option<TaskFifo> {
+        let dir = self.fifo_dir.lock().unwrap().clone()?;
+        TaskFifo::create(&dir, task_id)
+            .map_err(|e| log::warn!("No FIFO for task {}: {}", task_id, e))
+            .ok()
+    }
+
+    /// Sample every running task's resource usage, sharing one `/proc`
+    /// scan per interval, until the executor or the app goes away
+    fn start_sampler(&self) {
+        if self.sampler_started.swap(true, Ordering::Relaxed) {
+            return;
+        }
+        let handles = Arc::downgrade(&self.handles);
+        let event_tx = self.event_tx.clone();
+
+        tokio::spawn(async move {
+            // Totals per task, with the PID they belong to, so a rerun starts over
+            let mut totals: HashMap<String, (u32, ResourceUsage)> = HashMap::new();
+            let mut interval = tokio::time::interval(RESOURCE_SAMPLE_INTERVAL);
+
+            loop {
+                interval.tick().await;
+                let Some(handles) = handles.upgrade() else {
+                    break;
+                };
+                let running: Vec<(String, u32)> = handles
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .filter_map(|(task_id, handle)| Some((task_id.clone(), handle.pid()?)))
+                    .collect();
+                drop(handles);
+
+                totals.retain(|task_id, (pid, _)| running.iter().any(|(id, p)| id == task_id && p == pid));
+                if running.is_empty() {
+                    continue;
+                }
+
+                let Ok(table) = tokio::task::spawn_blocking(ProcessTable::scan).await else {
+                    continue;
+                };
+                for (task_id, pid) in running {
+                    let Some(sample) = table.tree_usage(pid) else {
+                        continue;
+                    };
+                    let (_, usage) = totals
+                        .entry(task_id.clone())
+                        .or_insert((pid, ResourceUsage::default()));
+                    usage.update(sample);
+                    let usage = *usage;
+                    // A newer sample follows soon, so a full channel just skips this one
+                    let event = TaskEvent::Resources { task_id, usage };
+                    if let Err(mpsc::error::TrySendError::Closed(_)) = event_tx.try_send(event) {
+                        return;
+                    }
+                }
+            }
+        });
     }
 
     /// Stop a task (sends kill signal)
@@ -159,6 +321,19 @@ impl Executor {
         Ok(())
     }
 
+    /// Send bytes to a task's PTY without appending a newline
+    pub fn send_raw(&self, task_id: &str, bytes: &[u8]) -> Result<()> {
+        let handles = self.handles.lock().unwrap();
+
+        if let Some(handle) = handles.get(task_id) {
+            handle.send_raw(bytes)?;
+        } else {
+            anyhow::bail!("Task {} not running", task_id);
+        }
+
+        Ok(())
+    }
+
     /// Get task output history
     pub fn get_output(&self, task_id: &str) -> Vec<String> {
         let handles = self.handles.lock().unwrap();
@@ -176,6 +351,38 @@ impl Executor {
         handles.contains_key(task_id)
     }
 
+    /// Set how many output lines newly spawned PTYs keep
+    pub fn set_history_lines(&self, lines: usize) {
+        *self.history_lines.lock().unwrap() = lines;
+    }
+
+    /// Mirror the output of tasks started from now on to `<dir>/<task>.fifo`
+    /// (Unix only)
+    pub fn set_fifo_dir(&self, dir: Option<PathBuf>) {
+        #[cfg(not(unix))]
+        if dir.is_some() {
+            log::warn!("Task FIFOs are only supported on Unix");
+        }
+        *self.fifo_dir.lock().unwrap() = dir;
+    }
+
+    /// Resize all running PTYs, and size future ones to match
+    pub fn resize_all(&self, rows: u16, cols: u16) {
+        *self.pty_size.lock().unwrap() = (rows, cols);
+
+        let handles = self.handles.lock().unwrap();
+        for (task_id, handle) in handles.iter() {
+            if let Err(e) = handle.resize(rows, cols) {
+                log::warn!("Failed to resize PTY for task {}: {}", task_id, e);
+            }
+        }
+    }
+
+    /// Current (rows, cols) used for PTYs
+    pub fn pty_size(&self) -> (u16, u16) {
+        *self.pty_size.lock().unwrap()
+    }
+
     /// Stop all running tasks
     pub fn stop_all(&self) {
         let handles = self.handles.lock().unwrap();
@@ -186,3 +393,68 @@ impl Executor {
         }
     }
 }
+
+/// Sends a task's output lines without ever waiting on the app
+///
+/// Lines that arrive while the channel is nearly full are counted instead of
+/// queued (the PTY history still has them), and the count goes out as a
+/// single line once there is room again.
+struct OutputSender {
+    task_id: String,
+    tx: mpsc::Sender<TaskEvent>,
+    dropped: usize,
+}
+
+impl OutputSender {
+    fn new(task_id: &str, tx: mpsc::Sender<TaskEvent>) -> Self {
+        Self {
+            task_id: task_id.to_string(),
+            tx,
+            dropped: 0,
+        }
+    }
+
+    /// Queue a line, or count it as dropped if there is no room
+    fn send(&mut self, line: String, replace: bool) {
+        if self.dropped > 0 && !self.try_send_dropped() {
+            self.dropped += 1;
+            return;
+        }
+        if !self.has_room() || self.tx.try_send(self.output(line, replace)).is_err() {
+            self.dropped += 1;
+        }
+    }
+
+    /// Report any dropped lines, waiting for room if need be
+    async fn flush(&mut self) {
+        if self.dropped > 0 {
+            let marker = self.dropped_marker();
+            self.dropped = 0;
+            let _ = self.tx.send(marker).await;
+        }
+    }
+
+    fn try_send_dropped(&mut self) -> bool {
+        if !self.has_room() || self.tx.try_send(self.dropped_marker()).is_err() {
+            return false;
+        }
+        self.dropped = 0;
+        true
+    }
+
+    fn has_room(&self) -> bool {
+        self.tx.capacity() > LIFECYCLE_HEADROOM
+    }
+
+    fn dropped_marker(&self) -> TaskEvent {
+        self.output(format!("[gidterm] {} output lines dropped", self.dropped), false)
+    }
+
+    fn output(&self, line: String, replace: bool) -> TaskEvent {
+        TaskEvent::Output {
+            task_id: self.task_id.clone(),
+            line,
+            replace,
+        }
+    }
+}
@@ -0,0 +1,221 @@
+//! Graph export - the task DAG as Graphviz DOT or a Mermaid flowchart
+//!
+//! Nodes are filled by task status and edges run from each dependency to
+//! its dependent. Workspace graphs (`project:task` IDs) can be clustered
+//! by project.
+
+use super::{Graph, GraphTaskStatus};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+/// Fill color for a task status
+fn status_color(status: &GraphTaskStatus) -> &'static str {
+    match status {
+        GraphTaskStatus::Done => "#b7e4c7",
+        GraphTaskStatus::InProgress => "#ffe08a",
+        GraphTaskStatus::Failed => "#f4a6a6",
+        GraphTaskStatus::Pending => "#ffffff",
+        GraphTaskStatus::Planned => "#e0e0e0",
+    }
+}
+
+/// Mermaid class name for a task status
+fn status_class(status: &GraphTaskStatus) -> &'static str {
+    match status {
+        GraphTaskStatus::Done => "done",
+        GraphTaskStatus::InProgress => "running",
+        GraphTaskStatus::Failed => "failed",
+        GraphTaskStatus::Pending => "pending",
+        GraphTaskStatus::Planned => "planned",
+    }
+}
+
+const STATUSES: [GraphTaskStatus; 5] = [
+    GraphTaskStatus::Done,
+    GraphTaskStatus::InProgress,
+    GraphTaskStatus::Failed,
+    GraphTaskStatus::Pending,
+    GraphTaskStatus::Planned,
+];
+
+/// Task IDs by dependency layer; a cyclic graph is one layer, alphabetical
+fn layers(graph: &Graph) -> Vec<Vec<String>> {
+    graph.layers().unwrap_or_else(|| vec![graph.alphabetical_order()])
+}
+
+/// Tasks grouped by the project before the `:` in their ID, in execution order
+fn by_project(graph: &Graph) -> BTreeMap<String, Vec<String>> {
+    let mut projects: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for task_id in layers(graph).into_iter().flatten() {
+        let project = task_id.split_once(':').map_or("", |(project, _)| project).to_string();
+        projects.entry(project).or_default().push(task_id);
+    }
+    projects
+}
+
+/// `(dependency, dependent)` pairs between tasks of the graph, in
+/// execution order; dependencies on nodes are left out
+fn edges(graph: &Graph) -> Vec<(String, String)> {
+    let mut edges = Vec::new();
+    for task_id in layers(graph).into_iter().flatten() {
+        let Some(deps) = graph.get_task(&task_id).and_then(|t| t.depends_on.as_ref()) else {
+            continue;
+        };
+        for dep in deps.iter().filter(|dep| graph.get_task(dep).is_some()) {
+            edges.push((dep.clone(), task_id.clone()));
+        }
+    }
+    edges
+}
+
+fn status(graph: &Graph, task_id: &str) -> GraphTaskStatus {
+    graph.get_task(task_id).map(|t| t.status.clone()).unwrap_or_default()
+}
+
+/// A DOT double-quoted string
+fn dot_quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// A Mermaid node label, with quotes as entities
+fn mermaid_label(text: &str) -> String {
+    format!("\"{}\"", text.replace('"', "#quot;"))
+}
+
+impl Graph {
+    /// Graphviz DOT for the task DAG
+    ///
+    /// Tasks of one dependency layer share a rank, or with `cluster_projects`
+    /// each project becomes a `cluster_` subgraph labelled with its name.
+    pub fn to_dot(&self, cluster_projects: bool) -> String {
+        let name = self.metadata.as_ref().map_or("tasks", |m| m.project.as_str());
+        let mut out = String::new();
+        let _ = writeln!(out, "digraph {} {{", dot_quote(name));
+        out.push_str("  rankdir=LR;\n");
+        out.push_str("  node [shape=box, style=\"rounded,filled\"];\n\n");
+
+        let node = |out: &mut String, indent: &str, task_id: &str, label: &str| {
+            let _ = writeln!(
+                out,
+                "{}{} [label={}, fillcolor=\"{}\"];",
+                indent,
+                dot_quote(task_id),
+                dot_quote(label),
+                status_color(&status(self, task_id))
+            );
+        };
+
+        if cluster_projects {
+            for (project, task_ids) in by_project(self) {
+                let _ = writeln!(out, "  subgraph {} {{", dot_quote(&format!("cluster_{}", project)));
+                let _ = writeln!(out, "    label={};", dot_quote(&project));
+                for task_id in &task_ids {
+                    let label = task_id.split_once(':').map_or(task_id.as_str(), |(_, task)| task);
+                    node(&mut out, "    ", task_id, label);
+                }
+                out.push_str("  }\n");
+            }
+        } else {
+            for layer in layers(self) {
+                for task_id in &layer {
+                    node(&mut out, "  ", task_id, task_id);
+                }
+                if layer.len() > 1 {
+                    let ids: Vec<String> = layer.iter().map(|id| dot_quote(id)).collect();
+                    let _ = writeln!(out, "  {{ rank=same; {}; }}", ids.join("; "));
+                }
+            }
+        }
+
+        let edges = edges(self);
+        if !edges.is_empty() {
+            out.push('\n');
+        }
+        for (from, to) in edges {
+            let _ = writeln!(out, "  {} -> {};", dot_quote(&from), dot_quote(&to));
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// Mermaid `flowchart` for the task DAG
+    ///
+    /// Task IDs can hold characters Mermaid doesn't allow in IDs, so nodes
+    /// are numbered `t0`, `t1`, ... in execution order and labelled with
+    /// the task ID. With `cluster_projects` each project becomes a `subgraph`.
+    pub fn to_mermaid(&self, cluster_projects: bool) -> String {
+        let order: Vec<String> = layers(self).into_iter().flatten().collect();
+        let ids: HashMap<&str, String> = order
+            .iter()
+            .enumerate()
+            .map(|(idx, task_id)| (task_id.as_str(), format!("t{}", idx)))
+            .collect();
+
+        let mut out = String::from("flowchart LR\n");
+        let node = |out: &mut String, indent: &str, task_id: &str, label: &str| {
+            let _ = writeln!(
+                out,
+                "{}{}[{}]:::{}",
+                indent,
+                ids[task_id],
+                mermaid_label(label),
+                status_class(&status(self, task_id))
+            );
+        };
+
+        if cluster_projects {
+            for (idx, (project, task_ids)) in by_project(self).into_iter().enumerate() {
+                let _ = writeln!(out, "  subgraph p{}[{}]", idx, mermaid_label(&project));
+                for task_id in &task_ids {
+                    let label = task_id.split_once(':').map_or(task_id.as_str(), |(_, task)| task);
+                    node(&mut out, "    ", task_id, label);
+                }
+                out.push_str("  end\n");
+            }
+        } else {
+            for task_id in &order {
+                node(&mut out, "  ", task_id, task_id);
+            }
+        }
+
+        for (from, to) in edges(self) {
+            let _ = writeln!(out, "  {} --> {}", ids[from.as_str()], ids[to.as_str()]);
+        }
+        for status in &STATUSES {
+            let _ = writeln!(out, "  classDef {} fill:{},stroke:#555", status_class(status), status_color(status));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_workspace_export_clusters_by_project() {
+        let graph: Graph = serde_yaml::from_str(
+            r#"
+tasks:
+  "api:build":
+    description: b
+    status: done
+  "web:build":
+    description: w
+    depends_on: ["api:build", "api:schema-node"]
+"#,
+        )
+        .unwrap();
+
+        let dot = graph.to_dot(true);
+        assert!(dot.contains("subgraph \"cluster_api\" {\n    label=\"api\";\n    \"api:build\" [label=\"build\", fillcolor=\"#b7e4c7\"];"));
+        assert!(dot.contains("subgraph \"cluster_web\""));
+        assert!(dot.contains("\"api:build\" -> \"web:build\";"));
+        // Dependencies that aren't tasks get no edge
+        assert!(!dot.contains("schema-node"));
+
+        let mermaid = graph.to_mermaid(true);
+        assert!(mermaid.contains("  subgraph p0[\"api\"]\n    t0[\"build\"]:::done\n  end"));
+        assert!(mermaid.contains("  t0 --> t1\n"));
+    }
+}
@@ -0,0 +1,145 @@
+//! Task FIFOs - mirror a task's output lines to `<dir>/<task>.fifo`
+//!
+//! Writes never block the executor: the FIFO is opened non-blocking, so
+//! while nobody reads it the open fails and lines are skipped, and lines
+//! that don't fit in a full pipe are dropped. Each line goes out in one
+//! write of at most `PIPE_BUF` bytes, which the pipe takes whole or not at
+//! all, so a reader never sees part of a line; longer lines are truncated.
+//! When a reader goes away the next write notices, and the FIFO is
+//! reopened once someone reads again.
+
+use super::task_file_stem;
+use anyhow::{Context, Result};
+use std::ffi::CString;
+use std::fs::{self, File, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::{FileTypeExt, OpenOptionsExt};
+use std::path::{Path, PathBuf};
+
+/// FIFO for a task within `dir`, named by [`task_file_stem`]
+pub fn fifo_path(dir: &Path, task_id: &str) -> PathBuf {
+    dir.join(format!("{}.fifo", task_file_stem(task_id)))
+}
+
+/// Write end of one task's FIFO
+pub struct TaskFifo {
+    path: PathBuf,
+    file: Option<File>,
+}
+
+impl TaskFifo {
+    /// Create the FIFO for a task in `dir`, or reuse one left by an
+    /// earlier run
+    pub fn create(dir: &Path, task_id: &str) -> Result<Self> {
+        fs::create_dir_all(dir)?;
+        let path = fifo_path(dir, task_id);
+
+        match fs::metadata(&path) {
+            Ok(meta) if meta.file_type().is_fifo() => {}
+            Ok(_) => anyhow::bail!("{} exists and is not a FIFO", path.display()),
+            Err(_) => {
+                let c_path = CString::new(path.as_os_str().as_bytes())?;
+                if unsafe { libc::mkfifo(c_path.as_ptr(), 0o600) } != 0 {
+                    return Err(std::io::Error::last_os_error())
+                        .with_context(|| format!("Cannot create FIFO {}", path.display()));
+                }
+            }
+        }
+
+        Ok(Self { path, file: None })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Send a line to the reader, if there is one, without waiting
+    pub fn write_line(&mut self, line: &str) {
+        if self.file.is_none() {
+            // Fails with ENXIO while no one has the FIFO open for reading
+            self.file = OpenOptions::new()
+                .write(true)
+                .custom_flags(libc::O_NONBLOCK)
+                .open(&self.path)
+                .ok();
+        }
+        let Some(file) = self.file.as_mut() else {
+            return;
+        };
+
+        let line = truncate(line, libc::PIPE_BUF - 1);
+        let mut buf = Vec::with_capacity(line.len() + 1);
+        buf.extend_from_slice(line.as_bytes());
+        buf.push(b'\n');
+        match file.write(&buf) {
+            Ok(n) if n == buf.len() => {}
+            Ok(n) => log::debug!("Short write to {} ({} of {} bytes)", self.path.display(), n, buf.len()),
+            // The reader is behind; this line is lost, later ones may fit
+            Err(e) if e.kind() == ErrorKind::WouldBlock => {}
+            // The reader went away; try again once someone reopens it
+            Err(_) => self.file = None,
+        }
+    }
+}
+
+/// `line` cut to at most `max` bytes, on a character boundary
+fn truncate(line: &str, max: usize) -> &str {
+    if line.len() <= max {
+        return line;
+    }
+    let mut end = max;
+    while !line.is_char_boundary(end) {
+        end -= 1;
+    }
+    &line[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn test_fifo_skips_lines_without_reader() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut fifo = TaskFifo::create(dir.path(), "web:build").unwrap();
+        assert_eq!(fifo.path(), dir.path().join("web%3Abuild.fifo"));
+
+        // Nobody is reading: returns at once and the line is gone
+        fifo.write_line("before");
+
+        let mut reader = OpenOptions::new()
+            .read(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open(fifo.path())
+            .unwrap();
+        fifo.write_line("hello");
+        fifo.write_line("world");
+        let mut text = String::new();
+        reader.read_to_string(&mut text).ok();
+        assert_eq!(text, "hello\nworld\n");
+
+        // The reader leaves; writing notices without blocking or panicking
+        drop(reader);
+        fifo.write_line("after");
+        assert!(fifo.file.is_none());
+
+        // Lines too long for one atomic write are cut, never split
+        let mut reader = OpenOptions::new()
+            .read(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open(fifo.path())
+            .unwrap();
+        fifo.write_line(&"é".repeat(libc::PIPE_BUF));
+        let mut text = String::new();
+        reader.read_to_string(&mut text).ok();
+        assert!(text.len() <= libc::PIPE_BUF && text.ends_with("é\n"), "{} bytes", text.len());
+        drop(reader);
+
+        // A rerun reuses the FIFO, but a regular file in the way is an error
+        assert!(TaskFifo::create(dir.path(), "web:build").is_ok());
+        std::fs::write(dir.path().join("other.fifo"), "").unwrap();
+        assert!(TaskFifo::create(dir.path(), "other").is_err());
+    }
+}
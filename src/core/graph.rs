@@ -1,15 +1,19 @@
 //! Graph parser - parses .gid/graph.yml and builds task DAG
 
+use super::graph_cache::{FileStamp, GraphCache};
+use super::vars::unknown_variables;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// Task status enum — replaces raw status strings
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum GraphTaskStatus {
+    #[default]
     Pending,
     #[serde(alias = "in-progress")]
     InProgress,
@@ -18,12 +22,6 @@ pub enum GraphTaskStatus {
     Planned,
 }
 
-impl Default for GraphTaskStatus {
-    fn default() -> Self {
-        Self::Pending
-    }
-}
-
 impl fmt::Display for GraphTaskStatus {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -36,14 +34,103 @@ impl fmt::Display for GraphTaskStatus {
     }
 }
 
+/// Task priority, lowest first so that ordering compares naturally
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TaskPriority {
+    Low,
+    #[default]
+    Medium,
+    High,
+    Critical,
+}
+
+impl TaskPriority {
+    /// Parse a priority name (case-insensitive); `None` if unrecognized
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "low" => Some(Self::Low),
+            "medium" | "normal" => Some(Self::Medium),
+            "high" => Some(Self::High),
+            "critical" => Some(Self::Critical),
+            _ => None,
+        }
+    }
+}
+
+/// Severity of a graph validation finding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DiagnosticLevel {
+    Warning,
+    Error,
+}
+
+impl fmt::Display for DiagnosticLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Warning => write!(f, "warning"),
+            Self::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// A problem found by `Graph::validate`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub level: DiagnosticLevel,
+    /// Task the finding is about, if any
+    pub task_id: Option<String>,
+    pub message: String,
+}
+
+impl Diagnostic {
+    fn error(task_id: &str, message: String) -> Self {
+        Self {
+            level: DiagnosticLevel::Error,
+            task_id: Some(task_id.to_string()),
+            message,
+        }
+    }
+
+    pub(crate) fn warning(task_id: &str, message: String) -> Self {
+        Self {
+            level: DiagnosticLevel::Warning,
+            task_id: Some(task_id.to_string()),
+            message,
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.level == DiagnosticLevel::Error
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.task_id {
+            Some(task_id) => write!(f, "{}: {}: {}", self.level, task_id, self.message),
+            None => write!(f, "{}: {}", self.level, self.message),
+        }
+    }
+}
+
+/// Usual architecture layers, outermost first; other layer names sort
+/// alphabetically after these
+const LAYER_ORDER: [&str; 4] = ["interface", "application", "domain", "infrastructure"];
+
 /// Task graph representation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Graph {
     pub metadata: Option<Metadata>,
+    /// Other graph files merged into this one, relative to this file
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub includes: Vec<String>,
     #[serde(default)]
     pub nodes: HashMap<String, Node>,
     #[serde(default)]
     pub tasks: HashMap<String, Task>,
+    /// Running tasks whose `ready_when` check has passed
+    #[serde(skip)]
+    pub(crate) ready: HashSet<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -65,7 +152,7 @@ pub struct Node {
     pub path: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Task {
     #[serde(rename = "type", default)]
     pub task_type: String,
@@ -79,16 +166,285 @@ pub struct Task {
     pub estimated_hours: Option<u32>,
     pub tags: Option<Vec<String>>,
     pub semantic_commands: Option<HashMap<String, String>>,
+    /// Exit codes that count as success (default `[0]`)
+    #[serde(default = "default_success_codes", skip_serializing_if = "is_default_success_codes")]
+    pub success_codes: Vec<i32>,
+    /// A failure of this task does not block its dependents
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub allow_failure: bool,
+    /// Seconds without output before the task is reported as stalled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stall_secs: Option<u64>,
+    /// Let dependents start once this holds, while the task keeps running
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ready_when: Option<ReadyWhen>,
+    /// Count the task as done once its output matches, without waiting for it to exit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub complete_on: Option<CompleteOn>,
+    /// Shell command run in the background when the task succeeds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_success: Option<String>,
+    /// Shell command run in the background when the task fails
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_failure: Option<String>,
+    /// Color of the task's name in the dashboard and graph views: a color
+    /// name, `#rrggbb` or a 0-255 palette index
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// Shown in place of the status icon in the dashboard and graph views
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    /// Shell that runs `command`, e.g. `bash` or `pwsh` (default `sh`, or
+    /// `cmd` on Windows)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shell: Option<String>,
+}
+
+/// Readiness check for a long-running task such as a dev server
+///
+/// Written as `{ port: 8080 }` or `{ log_match: "Listening on" }`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged, deny_unknown_fields)]
+pub enum ReadyWhen {
+    /// Something is listening on this port
+    Port { port: u16 },
+    /// A line of the task's output matches this regex
+    LogMatch { log_match: String },
+}
+
+/// Completion check for a task that never exits on its own, such as a watcher
+///
+/// Written as `{ log_match: "Watching for changes" }`; add `kill: true` to
+/// stop the process once it matches.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CompleteOn {
+    /// A line of the task's output matches this regex
+    pub log_match: String,
+    /// Stop the process once the line is seen
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub kill: bool,
+}
+
+fn default_success_codes() -> Vec<i32> {
+    vec![0]
+}
+
+fn is_default_success_codes(codes: &Vec<i32>) -> bool {
+    codes.as_slice() == [0]
+}
+
+/// A task ID made safe to use as a file name
+///
+/// Path separators, `:` (not allowed on Windows) and `%` itself are
+/// percent-escaped, so `services/api:build` becomes `services%2Fapi%3Abuild`
+/// and different IDs never share a file.
+pub fn task_file_stem(task_id: &str) -> String {
+    let mut stem = String::with_capacity(task_id.len());
+    for c in task_id.chars() {
+        match c {
+            '%' | '/' | '\\' | ':' => stem.push_str(&format!("%{:02X}", c as u32)),
+            c => stem.push(c),
+        }
+    }
+    stem
+}
+
+impl Task {
+    /// Whether two tasks are defined the same, ignoring their status
+    pub fn same_definition(&self, other: &Task) -> bool {
+        let mut this = self.clone();
+        this.status = other.status.clone();
+        this == *other
+    }
+
+    /// Parsed priority; missing or unrecognized values count as medium
+    pub fn priority_level(&self) -> TaskPriority {
+        self.priority
+            .as_deref()
+            .and_then(TaskPriority::parse)
+            .unwrap_or_default()
+    }
+
+    /// Whether the task carries `tag` (case-insensitive)
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags
+            .as_ref()
+            .is_some_and(|tags| tags.iter().any(|t| t.eq_ignore_ascii_case(tag)))
+    }
 }
 
 impl Graph {
-    /// Load graph from YAML file
+    /// Load graph from YAML file, recursively merging any `includes`
     pub fn from_file(path: &Path) -> Result<Self> {
-        let content = std::fs::read_to_string(path)?;
-        let graph: Graph = serde_yaml::from_str(&content)?;
+        Self::from_file_with_cache(path, None)
+    }
+
+    /// Load a graph file, reusing `cache` entries for files that haven't
+    /// changed since they were parsed
+    pub fn from_file_with_cache(path: &Path, cache: Option<&GraphCache>) -> Result<Self> {
+        let mut stack = Vec::new();
+        let mut merged = HashSet::new();
+        Self::load_with_includes(path, &mut stack, &mut merged, cache)
+    }
+
+    /// Load a graph file and merge its includes.
+    ///
+    /// `stack` holds the canonical paths currently being loaded, so an
+    /// include that points back at one of them is reported as a cycle.
+    /// `merged` holds the files already loaded, so a file included from two
+    /// places (a diamond) is only merged once.
+    fn load_with_includes(
+        path: &Path,
+        stack: &mut Vec<PathBuf>,
+        merged: &mut HashSet<PathBuf>,
+        cache: Option<&GraphCache>,
+    ) -> Result<Self> {
+        let canonical = path
+            .canonicalize()
+            .map_err(|e| anyhow::anyhow!("Failed to read graph {}: {}", path.display(), e))?;
+
+        if stack.contains(&canonical) {
+            let chain: Vec<String> = stack
+                .iter()
+                .chain(std::iter::once(&canonical))
+                .map(|p| p.display().to_string())
+                .collect();
+            anyhow::bail!("Include cycle detected: {}", chain.join(" -> "));
+        }
+
+        // Stamped before reading, so an edit made meanwhile is a miss next time
+        let stamp = cache.and_then(|_| FileStamp::of(&canonical).ok());
+        let cached = cache.zip(stamp.as_ref()).and_then(|(cache, stamp)| cache.get(&canonical, stamp));
+        let mut graph = match cached {
+            Some(graph) => graph,
+            None => {
+                let content = std::fs::read_to_string(path)?;
+                let graph: Graph = serde_yaml::from_str(&content)
+                    .map_err(|e| anyhow::anyhow!("Failed to parse graph {}: {}", path.display(), e))?;
+                if let Some((cache, stamp)) = cache.zip(stamp.as_ref()) {
+                    if let Err(e) = cache.put(&canonical, stamp, &graph) {
+                        log::debug!("Failed to cache graph {}: {}", path.display(), e);
+                    }
+                }
+                graph
+            }
+        };
+
+        if graph.includes.is_empty() {
+            merged.insert(canonical);
+            return Ok(graph);
+        }
+
+        stack.push(canonical);
+        let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+        for include in graph.includes.clone() {
+            let include_path = base_dir.join(&include);
+            if include_path.canonicalize().is_ok_and(|c| merged.contains(&c)) {
+                continue;
+            }
+            let included = Self::load_with_includes(&include_path, stack, merged, cache)?;
+            graph.merge(included, &include_path)?;
+        }
+        if let Some(canonical) = stack.pop() {
+            merged.insert(canonical);
+        }
+
         Ok(graph)
     }
 
+    /// Write task statuses back to the graph file at `path` and its includes
+    ///
+    /// Only `status` values change: each file is round-tripped through a
+    /// generic YAML value, so key order and unknown keys survive, but
+    /// comments and custom formatting do not. Tasks still in progress are
+    /// written as `pending`, since the run that owned them is over.
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        let mut stack = Vec::new();
+        self.save_with_includes(path, &mut stack)
+    }
+
+    fn save_with_includes(&self, path: &Path, stack: &mut Vec<PathBuf>) -> Result<()> {
+        let canonical = path
+            .canonicalize()
+            .map_err(|e| anyhow::anyhow!("Failed to read graph {}: {}", path.display(), e))?;
+        if stack.contains(&canonical) {
+            return Ok(());
+        }
+
+        let content = std::fs::read_to_string(path)?;
+        let mut doc: serde_yaml::Value = serde_yaml::from_str(&content)
+            .map_err(|e| anyhow::anyhow!("Failed to parse graph {}: {}", path.display(), e))?;
+
+        let mut changed = false;
+        let tasks = doc.get_mut("tasks").and_then(|t| t.as_mapping_mut());
+        for (id, entry) in tasks.into_iter().flat_map(|t| t.iter_mut()) {
+            let (Some(task), Some(entry)) = (id.as_str().and_then(|id| self.tasks.get(id)), entry.as_mapping_mut())
+            else {
+                continue;
+            };
+
+            let status = match task.status {
+                GraphTaskStatus::InProgress => GraphTaskStatus::Pending,
+                ref status => status.clone(),
+            }
+            .to_string();
+            // Inserting an existing key keeps its position
+            if entry.get("status").and_then(|v| v.as_str()) != Some(status.as_str()) {
+                entry.insert("status".into(), status.into());
+                changed = true;
+            }
+        }
+
+        if changed {
+            std::fs::write(path, serde_yaml::to_string(&doc)?)?;
+        }
+
+        let includes: Vec<String> = doc
+            .get("includes")
+            .and_then(|i| i.as_sequence())
+            .into_iter()
+            .flatten()
+            .filter_map(|i| i.as_str().map(String::from))
+            .collect();
+
+        stack.push(canonical);
+        let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+        for include in includes {
+            self.save_with_includes(&base_dir.join(include), stack)?;
+        }
+        stack.pop();
+
+        Ok(())
+    }
+
+    /// Merge tasks and nodes from an included graph, rejecting duplicate IDs
+    fn merge(&mut self, other: Graph, source: &Path) -> Result<()> {
+        for (id, task) in other.tasks {
+            if self.tasks.contains_key(&id) {
+                anyhow::bail!(
+                    "Duplicate task ID '{}' in included graph {}",
+                    id,
+                    source.display()
+                );
+            }
+            self.tasks.insert(id, task);
+        }
+
+        for (id, node) in other.nodes {
+            if self.nodes.contains_key(&id) {
+                anyhow::bail!(
+                    "Duplicate node ID '{}' in included graph {}",
+                    id,
+                    source.display()
+                );
+            }
+            self.nodes.insert(id, node);
+        }
+
+        Ok(())
+    }
+
     /// Load from gid project directory
     pub fn from_gid_project(project_dir: &Path) -> Result<Self> {
         let gid_path = project_dir.join(".gid/graph.yml");
@@ -98,41 +454,46 @@ impl Graph {
         Self::from_file(&gid_path)
     }
 
+    /// The graph file `auto_load` would read, if any
+    pub fn default_path() -> Option<PathBuf> {
+        [".gid/graph.yml", "gidterm.yml"]
+            .into_iter()
+            .map(PathBuf::from)
+            .find(|p| p.exists())
+    }
+
     /// Auto-detect and load graph from current directory
     /// Priority:
     /// 1. .gid/graph.yml (gid project)
     /// 2. gidterm.yml (standalone config)
     /// 3. Return error if none found
     pub fn auto_load() -> Result<Self> {
-        // Try .gid/graph.yml first
-        let gid_path = Path::new(".gid/graph.yml");
-        if gid_path.exists() {
-            return Self::from_file(gid_path);
-        }
+        Self::auto_load_with_cache(None)
+    }
 
-        // Fall back to gidterm.yml
-        let standalone_path = Path::new("gidterm.yml");
-        if standalone_path.exists() {
-            return Self::from_file(standalone_path);
+    /// Like `auto_load`, reusing `cache` entries (see `from_file_with_cache`)
+    pub fn auto_load_with_cache(cache: Option<&GraphCache>) -> Result<Self> {
+        match Self::default_path() {
+            Some(path) => Self::from_file_with_cache(&path, cache),
+            None => anyhow::bail!(
+                "No graph file found. Expected .gid/graph.yml or gidterm.yml in current directory."
+            ),
         }
-
-        anyhow::bail!(
-            "No graph file found. Expected .gid/graph.yml or gidterm.yml in current directory."
-        )
     }
 
     /// Get all tasks ready to run (dependencies met)
+    ///
+    /// Highest priority first, then by ID, so the order doesn't depend on
+    /// how the task map happens to iterate.
     pub fn get_ready_tasks(&self) -> Vec<String> {
-        self.tasks
+        let mut ready: Vec<(Reverse<TaskPriority>, &String)> = self
+            .tasks
             .iter()
-            .filter_map(|(id, task)| {
-                if self.can_start(id) && task.status == GraphTaskStatus::Pending {
-                    Some(id.clone())
-                } else {
-                    None
-                }
-            })
-            .collect()
+            .filter(|(id, task)| self.can_start(id) && task.status == GraphTaskStatus::Pending)
+            .map(|(id, task)| (Reverse(task.priority_level()), id))
+            .collect();
+        ready.sort();
+        ready.into_iter().map(|(_, id)| id.clone()).collect()
     }
 
     /// Check if a task can start (all dependencies done)
@@ -146,25 +507,44 @@ impl Graph {
             return true;
         };
 
-        // All dependencies must be in "done" status
+        // All dependencies must be done, running and ready, or have failed
+        // with `allow_failure`
         deps.iter().all(|dep_id| {
             self.tasks
                 .get(dep_id)
-                .map(|dep_task| dep_task.status == GraphTaskStatus::Done)
+                .map(|dep_task| {
+                    dep_task.status == GraphTaskStatus::Done
+                        || (dep_task.status == GraphTaskStatus::InProgress && self.ready.contains(dep_id))
+                        || (dep_task.status == GraphTaskStatus::Failed && dep_task.allow_failure)
+                })
                 .unwrap_or(false)
         })
     }
 
-    /// Update task status
+    /// Update task status (a task that stops running is no longer ready)
     pub fn update_task_status(&mut self, task_id: &str, new_status: GraphTaskStatus) -> Result<()> {
         if let Some(task) = self.tasks.get_mut(task_id) {
             task.status = new_status;
+            self.ready.remove(task_id);
             Ok(())
         } else {
             anyhow::bail!("Task {} not found", task_id)
         }
     }
 
+    /// Record that a running task passed its `ready_when` check, so its
+    /// dependents can start
+    pub fn mark_ready(&mut self, task_id: &str) {
+        if self.tasks.get(task_id).is_some_and(|t| t.status == GraphTaskStatus::InProgress) {
+            self.ready.insert(task_id.to_string());
+        }
+    }
+
+    /// Whether a running task has passed its `ready_when` check
+    pub fn is_ready(&self, task_id: &str) -> bool {
+        self.ready.contains(task_id)
+    }
+
     /// Get task by ID
     pub fn get_task(&self, task_id: &str) -> Option<&Task> {
         self.tasks.get(task_id)
@@ -174,14 +554,674 @@ impl Graph {
     pub fn all_tasks(&self) -> &HashMap<String, Task> {
         &self.tasks
     }
+
+    /// Group tasks into layers by dependency depth.
+    ///
+    /// Layer 0 holds tasks without dependencies; each task sits one layer
+    /// below its deepest dependency. Tasks within a layer are sorted by ID.
+    /// Returns `None` if the graph contains a dependency cycle.
+    pub fn layers(&self) -> Option<Vec<Vec<String>>> {
+        let mut depths: HashMap<String, usize> = HashMap::new();
+        let mut visiting: HashSet<String> = HashSet::new();
+
+        for task_id in self.tasks.keys() {
+            self.task_depth(task_id, &mut depths, &mut visiting)?;
+        }
+
+        let max_depth = depths.values().copied().max().unwrap_or(0);
+        let mut layers: Vec<Vec<String>> = vec![Vec::new(); max_depth + 1];
+
+        let mut sorted_tasks: Vec<(String, usize)> = depths.into_iter().collect();
+        sorted_tasks.sort_by(|a, b| a.1.cmp(&b.1).then(a.0.cmp(&b.0)));
+
+        for (task_id, depth) in sorted_tasks {
+            layers[depth].push(task_id);
+        }
+
+        Some(layers)
+    }
+
+    /// Task IDs in execution order: by dependency depth, then by name.
+    ///
+    /// Falls back to alphabetical order if the graph contains a cycle.
+    pub fn topological_order(&self) -> Vec<String> {
+        match self.layers() {
+            Some(layers) => layers.into_iter().flatten().collect(),
+            None => self.alphabetical_order(),
+        }
+    }
+
+    /// Node IDs grouped by `layer`, in `LAYER_ORDER`
+    ///
+    /// Nodes without a layer come last, under `None`. IDs within a layer are
+    /// sorted.
+    pub fn nodes_by_layer(&self) -> Vec<(Option<String>, Vec<String>)> {
+        let mut groups: HashMap<Option<String>, Vec<String>> = HashMap::new();
+        for (id, node) in &self.nodes {
+            groups.entry(node.layer.clone()).or_default().push(id.clone());
+        }
+
+        let mut groups: Vec<_> = groups.into_iter().collect();
+        for (_, ids) in &mut groups {
+            ids.sort();
+        }
+        groups.sort_by_cached_key(|(layer, _)| match layer {
+            Some(layer) => {
+                let known = LAYER_ORDER.iter().position(|l| l.eq_ignore_ascii_case(layer));
+                (0, known.unwrap_or(LAYER_ORDER.len()), layer.clone())
+            }
+            None => (1, 0, String::new()),
+        });
+        groups
+    }
+
+    /// IDs of the tasks tagged `tag`, in execution order
+    pub fn tasks_with_tag(&self, tag: &str) -> Vec<String> {
+        self.topological_order()
+            .into_iter()
+            .filter(|id| self.tasks[id].has_tag(tag))
+            .collect()
+    }
+
+    /// Restrict the graph to `targets` and everything they transitively depend on
+    ///
+    /// Like `make <target>`: tasks outside the closure are dropped, so they
+    /// are never scheduled. Errors if a target is not a task in this graph.
+    pub fn subgraph(&self, targets: &[String]) -> Result<Graph> {
+        let mut keep: HashSet<String> = HashSet::new();
+        let mut stack: Vec<String> = Vec::new();
+
+        for target in targets {
+            if !self.tasks.contains_key(target) {
+                anyhow::bail!("Unknown target task '{}'", target);
+            }
+            stack.push(target.clone());
+        }
+
+        while let Some(task_id) = stack.pop() {
+            if !keep.insert(task_id.clone()) {
+                continue;
+            }
+            if let Some(deps) = self.tasks.get(&task_id).and_then(|t| t.depends_on.as_ref()) {
+                stack.extend(deps.iter().filter(|dep| self.tasks.contains_key(*dep)).cloned());
+            }
+        }
+
+        let mut subgraph = self.clone();
+        subgraph.tasks.retain(|id, _| keep.contains(id));
+        Ok(subgraph)
+    }
+
+    /// Check the graph for problems that would stop or confuse a run
+    ///
+    /// Errors: dependency cycles, dependencies on unknown tasks, unknown
+    /// `${var}`s in commands, invalid `ready_when` patterns and malformed
+    /// `{param}` placeholders in semantic commands. Warnings:
+    /// tasks with no command that nothing depends on, and braces in
+    /// templates that aren't parameters. Sorted by task ID.
+    ///
+    /// Task colors are the theme's business; see
+    /// [`color_diagnostics`](crate::ui::color_diagnostics).
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for cycle in self.find_cycles() {
+            diagnostics.push(Diagnostic {
+                level: DiagnosticLevel::Error,
+                task_id: cycle.first().cloned(),
+                message: format!("dependency cycle: {}", cycle.join(" -> ")),
+            });
+        }
+
+        let depended_on: HashSet<&str> = self
+            .tasks
+            .values()
+            .flat_map(|t| t.depends_on.iter().flatten())
+            .map(String::as_str)
+            .collect();
+
+        for task_id in self.alphabetical_order() {
+            let task = &self.tasks[&task_id];
+
+            for dep in task.depends_on.iter().flatten() {
+                if self.tasks.contains_key(dep) {
+                    continue;
+                }
+                if dep.contains(':') {
+                    // `project:task` only resolves once the workspace is unified
+                    diagnostics.push(Diagnostic::warning(
+                        &task_id,
+                        format!("depends on '{}' from another project; it only runs in workspace mode", dep),
+                    ));
+                } else {
+                    diagnostics.push(Diagnostic::error(&task_id, format!("depends on unknown task '{}'", dep)));
+                }
+            }
+
+            if task.command.is_none() && !depended_on.contains(task_id.as_str()) {
+                diagnostics.push(Diagnostic::warning(
+                    &task_id,
+                    "has no command and no dependents, so it does nothing".to_string(),
+                ));
+            }
+
+            if let Some(ReadyWhen::LogMatch { log_match: pattern }) = &task.ready_when {
+                if regex::Regex::new(pattern).is_err() {
+                    diagnostics.push(Diagnostic::error(
+                        &task_id,
+                        format!("invalid ready_when log_match '{}'", pattern),
+                    ));
+                }
+            }
+
+            if let Some(CompleteOn { log_match: pattern, .. }) = &task.complete_on {
+                if regex::Regex::new(pattern).is_err() {
+                    diagnostics.push(Diagnostic::error(
+                        &task_id,
+                        format!("invalid complete_on log_match '{}'", pattern),
+                    ));
+                }
+            }
+
+            let shell_commands = [
+                ("command", &task.command),
+                ("on_success", &task.on_success),
+                ("on_failure", &task.on_failure),
+            ];
+            for (field, command) in shell_commands {
+                for name in command.as_deref().map(unknown_variables).unwrap_or_default() {
+                    diagnostics.push(Diagnostic::error(
+                        &task_id,
+                        format!("unknown variable '${{{}}}' in {} (use project, port, cwd or env:NAME)", name, field),
+                    ));
+                }
+            }
+
+            let mut commands: Vec<_> = task.semantic_commands.iter().flatten().collect();
+            commands.sort();
+            for (label, template) in commands {
+                diagnostics.extend(
+                    check_template(template)
+                        .into_iter()
+                        .map(|(level, problem)| Diagnostic {
+                            level,
+                            task_id: Some(task_id.clone()),
+                            message: format!("semantic command '{}': {}", label, problem),
+                        }),
+                );
+            }
+        }
+
+        diagnostics.sort_by(|a, b| a.task_id.cmp(&b.task_id));
+        diagnostics
+    }
+
+    /// Dependency cycles, each as a path that starts and ends on the same task
+    fn find_cycles(&self) -> Vec<Vec<String>> {
+        fn visit(
+            graph: &Graph,
+            task_id: &str,
+            path: &mut Vec<String>,
+            done: &mut HashSet<String>,
+            cycles: &mut Vec<Vec<String>>,
+        ) {
+            if let Some(pos) = path.iter().position(|id| id == task_id) {
+                let mut cycle = path[pos..].to_vec();
+                cycle.push(task_id.to_string());
+                cycles.push(cycle);
+                return;
+            }
+            if done.contains(task_id) {
+                return;
+            }
+            let Some(task) = graph.tasks.get(task_id) else {
+                return;
+            };
+
+            path.push(task_id.to_string());
+            let mut deps: Vec<&String> = task.depends_on.iter().flatten().collect();
+            deps.sort();
+            for dep in deps {
+                visit(graph, dep, path, done, cycles);
+            }
+            path.pop();
+            done.insert(task_id.to_string());
+        }
+
+        let mut cycles = Vec::new();
+        let mut done = HashSet::new();
+        for task_id in self.alphabetical_order() {
+            visit(self, &task_id, &mut Vec::new(), &mut done, &mut cycles);
+        }
+        cycles
+    }
+
+    /// Task IDs sorted alphabetically
+    pub fn alphabetical_order(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self.tasks.keys().cloned().collect();
+        ids.sort();
+        ids
+    }
+
+    /// Compute dependency depth of a task, memoized in `depths`.
+    ///
+    /// Dependencies that are not tasks in this graph count as depth 0.
+    /// Returns `None` when a cycle is found through `visiting`.
+    fn task_depth(
+        &self,
+        task_id: &str,
+        depths: &mut HashMap<String, usize>,
+        visiting: &mut HashSet<String>,
+    ) -> Option<usize> {
+        if let Some(&depth) = depths.get(task_id) {
+            return Some(depth);
+        }
+
+        let Some(task) = self.tasks.get(task_id) else {
+            return Some(0);
+        };
+
+        if !visiting.insert(task_id.to_string()) {
+            return None;
+        }
+
+        let mut depth = 0;
+        for dep in task.depends_on.iter().flatten() {
+            depth = depth.max(self.task_depth(dep, depths, visiting)? + 1);
+        }
+
+        visiting.remove(task_id);
+        depths.insert(task_id.to_string(), depth);
+        Some(depth)
+    }
+}
+
+/// Problems with the `{param}` placeholders in a semantic command template
+fn check_template(template: &str) -> Vec<(DiagnosticLevel, String)> {
+    let mut problems = Vec::new();
+    let mut rest = template;
+
+    while let Some(open) = rest.find(['{', '}']) {
+        if rest[open..].starts_with('}') {
+            problems.push((DiagnosticLevel::Error, "unmatched '}'".to_string()));
+            rest = &rest[open + 1..];
+            continue;
+        }
+
+        let after = &rest[open + 1..];
+        let Some(close) = after.find(['{', '}']).filter(|&i| after[i..].starts_with('}')) else {
+            problems.push((DiagnosticLevel::Error, "unclosed '{'".to_string()));
+            rest = after;
+            continue;
+        };
+
+        let placeholder = &after[..close];
+        let is_name = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_alphanumeric() || c == '_');
+        let name = match placeholder.split_once(':') {
+            // `{value:float}`: only int and float are known types
+            Some((name, kind)) if is_name(name) && is_name(kind) => {
+                if !matches!(kind, "int" | "float") {
+                    problems.push((
+                        DiagnosticLevel::Error,
+                        format!("unknown parameter type '{}' in '{{{}}}' (use int or float)", kind, placeholder),
+                    ));
+                }
+                name
+            }
+            _ => placeholder,
+        };
+        if name.is_empty() {
+            problems.push((DiagnosticLevel::Error, "empty placeholder '{}'".to_string()));
+        } else if !is_name(name) {
+            // Could be a literal brace (e.g. a dict); it is sent unchanged
+            problems.push((
+                DiagnosticLevel::Warning,
+                format!("'{{{}}}' is not a parameter name and will be sent as-is", name),
+            ));
+        }
+        rest = &after[close + 1..];
+    }
+
+    problems
 }
 
 #[cfg(test)]
 mod tests {
-    
+    use super::*;
+    use tempfile::TempDir;
 
     #[test]
     fn test_parse_graph() {
         // TODO: Add test
     }
+
+    #[test]
+    fn test_task_file_stem() {
+        assert_eq!(task_file_stem("build"), "build");
+        assert_eq!(task_file_stem("services/api:build"), "services%2Fapi%3Abuild");
+
+        // IDs that would flatten to the same name get different stems
+        let stems = ["a/b", "a:b", "a_b", "a%2Fb", "a\\b"].map(task_file_stem);
+        for (i, stem) in stems.iter().enumerate() {
+            assert!(!stems[i + 1..].contains(stem), "{}", stem);
+        }
+    }
+
+    #[test]
+    fn test_nodes_by_layer() {
+        let graph: Graph = serde_yaml::from_str(
+            "nodes:\n  Db:\n    type: Component\n    description: d\n    layer: infrastructure\n    status: active\n  Cli:\n    type: Component\n    description: c\n    layer: interface\n    status: active\n  Auth:\n    type: Component\n    description: a\n    layer: security\n    status: planned\n  Cache:\n    type: Component\n    description: c\n    layer: infrastructure\n    status: active\n  Notes:\n    type: Doc\n    description: n\n    status: done\ntasks: {}\n",
+        )
+        .unwrap();
+
+        let layers = graph.nodes_by_layer();
+        assert_eq!(
+            layers,
+            vec![
+                (Some("interface".to_string()), vec!["Cli".to_string()]),
+                (Some("infrastructure".to_string()), vec!["Cache".to_string(), "Db".to_string()]),
+                (Some("security".to_string()), vec!["Auth".to_string()]),
+                (None, vec!["Notes".to_string()]),
+            ]
+        );
+        let no_nodes: Graph = serde_yaml::from_str("tasks: {}\n").unwrap();
+        assert!(no_nodes.nodes_by_layer().is_empty());
+    }
+
+    #[test]
+    fn test_topological_order() {
+        let graph: Graph = serde_yaml::from_str(
+            "tasks:\n  deploy:\n    description: d\n    depends_on: [test]\n  build:\n    description: b\n  test:\n    description: t\n    depends_on: [build]\n  lint:\n    description: l\n",
+        )
+        .unwrap();
+
+        assert_eq!(graph.topological_order(), vec!["build", "lint", "test", "deploy"]);
+        assert_eq!(graph.layers().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_topological_order_cycle_fallback() {
+        let graph: Graph = serde_yaml::from_str(
+            "tasks:\n  b:\n    description: b\n    depends_on: [a]\n  a:\n    description: a\n    depends_on: [b]\n  c:\n    description: c\n",
+        )
+        .unwrap();
+
+        assert!(graph.layers().is_none());
+        assert_eq!(graph.topological_order(), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_subgraph_keeps_transitive_dependencies() {
+        let graph: Graph = serde_yaml::from_str(
+            "tasks:\n  deploy:\n    description: d\n    depends_on: [test]\n  build:\n    description: b\n  test:\n    description: t\n    depends_on: [build]\n  lint:\n    description: l\n",
+        )
+        .unwrap();
+
+        let sub = graph.subgraph(&["test".to_string()]).unwrap();
+        assert_eq!(sub.alphabetical_order(), vec!["build", "test"]);
+
+        let err = graph.subgraph(&["nope".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("nope"));
+    }
+
+    #[test]
+    fn test_success_codes_and_allow_failure() {
+        let mut graph: Graph = serde_yaml::from_str(
+            "tasks:\n  lint:\n    description: l\n    success_codes: [0, 1]\n    allow_failure: true\n  build:\n    description: b\n  deploy:\n    description: d\n    depends_on: [lint, build]\n",
+        )
+        .unwrap();
+
+        assert_eq!(graph.get_task("lint").unwrap().success_codes, vec![0, 1]);
+        assert_eq!(graph.get_task("build").unwrap().success_codes, vec![0]);
+
+        graph.update_task_status("lint", GraphTaskStatus::Failed).unwrap();
+        graph.update_task_status("build", GraphTaskStatus::Done).unwrap();
+        assert!(graph.can_start("deploy"));
+
+        graph.update_task_status("build", GraphTaskStatus::Failed).unwrap();
+        assert!(!graph.can_start("deploy"));
+    }
+
+    #[test]
+    fn test_ready_when_unblocks_dependents() {
+        let mut graph: Graph = serde_yaml::from_str(
+            "tasks:\n  api:\n    description: a\n    ready_when:\n      port: 8080\n  web:\n    description: w\n    ready_when: { log_match: \"Listening on\" }\n  e2e:\n    description: e\n    depends_on: [api, web]\n",
+        )
+        .unwrap();
+        assert_eq!(graph.get_task("api").unwrap().ready_when, Some(ReadyWhen::Port { port: 8080 }));
+        assert_eq!(
+            graph.get_task("web").unwrap().ready_when,
+            Some(ReadyWhen::LogMatch { log_match: "Listening on".to_string() })
+        );
+
+        // Only running tasks can be ready
+        graph.mark_ready("api");
+        assert!(!graph.is_ready("api"));
+
+        graph.update_task_status("api", GraphTaskStatus::InProgress).unwrap();
+        graph.update_task_status("web", GraphTaskStatus::InProgress).unwrap();
+        graph.mark_ready("api");
+        assert!(!graph.can_start("e2e"));
+        graph.mark_ready("web");
+        assert!(graph.can_start("e2e"));
+
+        // A restart has to pass the check again
+        graph.update_task_status("web", GraphTaskStatus::Pending).unwrap();
+        graph.update_task_status("web", GraphTaskStatus::InProgress).unwrap();
+        assert!(!graph.can_start("e2e"));
+    }
+
+    #[test]
+    fn test_includes_merge_tasks() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join("parts")).unwrap();
+        std::fs::write(
+            dir.path().join("graph.yml"),
+            "metadata:\n  project: main\nincludes: [parts/build.yml]\ntasks:\n  test:\n    description: Test\n    depends_on: [build]\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("parts/build.yml"),
+            "tasks:\n  build:\n    description: Build\n",
+        )
+        .unwrap();
+
+        let graph = Graph::from_file(&dir.path().join("graph.yml")).unwrap();
+        assert_eq!(graph.metadata.unwrap().project, "main");
+        assert!(graph.tasks.contains_key("build"));
+        assert!(graph.tasks.contains_key("test"));
+    }
+
+    #[test]
+    fn test_includes_duplicate_task_id() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("graph.yml"),
+            "includes: [other.yml]\ntasks:\n  build:\n    description: Build\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("other.yml"),
+            "tasks:\n  build:\n    description: Build again\n",
+        )
+        .unwrap();
+
+        let err = Graph::from_file(&dir.path().join("graph.yml")).unwrap_err();
+        assert!(err.to_string().contains("Duplicate task ID 'build'"));
+    }
+
+    #[test]
+    fn test_includes_diamond_merges_shared_once() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("graph.yml"), "includes: [a.yml, b.yml]
+").unwrap();
+        std::fs::write(
+            dir.path().join("a.yml"),
+            "includes: [shared.yml]
+tasks:
+  a:
+    description: A
+    depends_on: [setup]
+",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("b.yml"),
+            "includes: [./shared.yml]
+tasks:
+  b:
+    description: B
+    depends_on: [setup]
+",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("shared.yml"), "tasks:
+  setup:
+    description: Setup
+").unwrap();
+
+        let graph = Graph::from_file(&dir.path().join("graph.yml")).unwrap();
+        let mut ids: Vec<&String> = graph.tasks.keys().collect();
+        ids.sort();
+        assert_eq!(ids, ["a", "b", "setup"]);
+    }
+
+    #[test]
+    fn test_includes_cycle() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("a.yml"), "includes: [b.yml]\n").unwrap();
+        std::fs::write(dir.path().join("b.yml"), "includes: [a.yml]\n").unwrap();
+
+        let err = Graph::from_file(&dir.path().join("a.yml")).unwrap_err();
+        assert!(err.to_string().contains("Include cycle"));
+    }
+
+    #[test]
+    fn test_validate_reports_problems() {
+        let graph: Graph = serde_yaml::from_str(
+            r#"
+tasks:
+  build:
+    description: b
+    command: make PORT=${port} NAME=${projct}
+    on_failure: notify ${task}
+    depends_on: [compile]
+  a:
+    description: a
+    command: "true"
+    depends_on: [b]
+  b:
+    description: b
+    command: "true"
+    depends_on: [a]
+  docs:
+    description: placeholder
+  serve:
+    description: s
+    command: ./serve
+    ready_when:
+      log_match: "Listening on (port"
+  train:
+    description: t
+    command: python train.py
+    semantic_commands:
+      adjust_lr: "set_lr({value:float})"
+      untyped: "set_lr({value})"
+      typo: "set_lr({value:flaot})"
+      broken: "set_lr({value)"
+      empty: "run({})"
+      config: "update({'lr': 1})"
+"#,
+        )
+        .unwrap();
+
+        let diagnostics = graph.validate();
+        let rendered: Vec<String> = diagnostics.iter().map(|d| d.to_string()).collect();
+        assert_eq!(
+            rendered,
+            vec![
+                "error: a: dependency cycle: a -> b -> a",
+                "error: build: depends on unknown task 'compile'",
+                "error: build: unknown variable '${projct}' in command (use project, port, cwd or env:NAME)",
+                "error: build: unknown variable '${task}' in on_failure (use project, port, cwd or env:NAME)",
+                "warning: docs: has no command and no dependents, so it does nothing",
+                "error: serve: invalid ready_when log_match 'Listening on (port'",
+                "error: train: semantic command 'broken': unclosed '{'",
+                "warning: train: semantic command 'config': '{'lr': 1}' is not a parameter name and will be sent as-is",
+                "error: train: semantic command 'empty': empty placeholder '{}'",
+                "error: train: semantic command 'typo': unknown parameter type 'flaot' in '{value:flaot}' (use int or float)",
+            ]
+        );
+        assert_eq!(diagnostics.iter().filter(|d| d.is_error()).count(), 8);
+    }
+
+    #[test]
+    fn test_validate_clean_graph() {
+        let graph: Graph = serde_yaml::from_str(
+            "tasks:\n  setup:\n    description: grouping only\n  build:\n    description: b\n    command: make\n    depends_on: [setup]\n",
+        )
+        .unwrap();
+
+        assert!(graph.validate().is_empty());
+    }
+
+    #[test]
+    fn test_validate_cross_project_dependency_is_warning() {
+        let graph: Graph = serde_yaml::from_str(
+            "tasks:\n  build:\n    description: b\n    command: make\n    depends_on: [\"lib:publish\"]\n",
+        )
+        .unwrap();
+
+        let diagnostics = graph.validate();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].level, DiagnosticLevel::Warning);
+    }
+
+    #[test]
+    fn test_save_to_updates_status_only() {
+        let dir = TempDir::new().unwrap();
+        let main = dir.path().join("graph.yml");
+        std::fs::write(
+            &main,
+            "metadata:\n  project: main\nincludes: [extra.yml]\ntasks:\n  test:\n    description: Test\n    owner: alice\n    status: pending\n    depends_on: [build]\n  build:\n    description: Build\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("extra.yml"),
+            "tasks:\n  deploy:\n    description: Deploy\n    status: pending\n",
+        )
+        .unwrap();
+
+        let mut graph = Graph::from_file(&main).unwrap();
+        graph.update_task_status("build", GraphTaskStatus::Done).unwrap();
+        graph.update_task_status("test", GraphTaskStatus::InProgress).unwrap();
+        graph.update_task_status("deploy", GraphTaskStatus::Failed).unwrap();
+        graph.save_to(&main).unwrap();
+
+        let reloaded = Graph::from_file(&main).unwrap();
+        assert_eq!(reloaded.get_task("build").unwrap().status, GraphTaskStatus::Done);
+        // Interrupted tasks go back to pending
+        assert_eq!(reloaded.get_task("test").unwrap().status, GraphTaskStatus::Pending);
+        assert_eq!(reloaded.get_task("deploy").unwrap().status, GraphTaskStatus::Failed);
+
+        // Unknown keys and key order survive
+        let text = std::fs::read_to_string(&main).unwrap();
+        assert!(text.contains("owner: alice"));
+        assert!(text.find("test:").unwrap() < text.find("build:").unwrap());
+        assert!(text.find("metadata:").unwrap() < text.find("includes:").unwrap());
+    }
+
+    #[test]
+    fn test_priority_level() {
+        let graph: Graph = serde_yaml::from_str(
+            "tasks:\n  a:\n    description: a\n    priority: Critical\n  b:\n    description: b\n  c:\n    description: c\n    priority: whenever\n  d:\n    description: d\n    priority: low\n",
+        )
+        .unwrap();
+
+        assert_eq!(graph.get_task("a").unwrap().priority_level(), TaskPriority::Critical);
+        assert_eq!(graph.get_task("b").unwrap().priority_level(), TaskPriority::Medium);
+        assert_eq!(graph.get_task("c").unwrap().priority_level(), TaskPriority::Medium);
+        assert_eq!(graph.get_task("d").unwrap().priority_level(), TaskPriority::Low);
+        assert!(TaskPriority::Critical > TaskPriority::High && TaskPriority::Medium > TaskPriority::Low);
+    }
 }
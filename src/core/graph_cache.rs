@@ -0,0 +1,164 @@
+//! Graph cache - parsed graph files kept on disk, reused while the source
+//! file's modification time and size are unchanged
+//!
+//! Off unless `[graph] cache = true` is set in config.toml.
+
+use super::Graph;
+use crate::config::Config;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// Graph settings, the `[graph]` table of config.toml (see [`Config`](crate::config::Config))
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct GraphConfig {
+    /// Cache parsed graph files under ~/.gidterm/cache/graphs
+    #[serde(default)]
+    pub cache: bool,
+}
+
+/// Modification time and size of a graph file when it was parsed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileStamp {
+    modified_secs: u64,
+    modified_nanos: u32,
+    size: u64,
+}
+
+impl FileStamp {
+    /// Stamp of the file at `path` as it is now
+    pub fn of(path: &Path) -> Result<Self> {
+        let meta = fs::metadata(path).with_context(|| format!("Cannot read {}", path.display()))?;
+        let modified = meta.modified()?.duration_since(UNIX_EPOCH).unwrap_or_default();
+        Ok(Self {
+            modified_secs: modified.as_secs(),
+            modified_nanos: modified.subsec_nanos(),
+            size: meta.len(),
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    path: PathBuf,
+    stamp: FileStamp,
+    graph: Graph,
+}
+
+/// Directory of cached graph files, one JSON entry per source file
+#[derive(Debug, Clone)]
+pub struct GraphCache {
+    dir: PathBuf,
+}
+
+impl GraphCache {
+    /// Cache entries kept in `dir`
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// The cache under ~/.gidterm/cache/graphs, if `[graph] cache` is on
+    pub fn from_config(config: &Config) -> Option<Self> {
+        config
+            .graph
+            .cache
+            .then(|| Self::new(gidterm_dir().join("cache").join("graphs")))
+    }
+
+    /// The graph parsed from `path` when it still had `stamp`
+    ///
+    /// `path` should be canonical. Any mismatch, or an unreadable entry,
+    /// is a miss.
+    pub fn get(&self, path: &Path, stamp: &FileStamp) -> Option<Graph> {
+        let content = fs::read(self.entry_path(path)).ok()?;
+        let entry: CacheEntry = serde_json::from_slice(&content).ok()?;
+        (entry.path == path && entry.stamp == *stamp).then_some(entry.graph)
+    }
+
+    /// Remember the graph parsed from `path` with `stamp`
+    pub fn put(&self, path: &Path, stamp: &FileStamp, graph: &Graph) -> Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let entry = CacheEntry {
+            path: path.to_path_buf(),
+            stamp: *stamp,
+            graph: graph.clone(),
+        };
+        // Written aside and renamed so a concurrent reader never sees half an entry
+        let target = self.entry_path(path);
+        let tmp = target.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_vec(&entry)?)?;
+        fs::rename(&tmp, &target)?;
+        Ok(())
+    }
+
+    fn entry_path(&self, path: &Path) -> PathBuf {
+        let mut hasher = DefaultHasher::new();
+        path.hash(&mut hasher);
+        self.dir.join(format!("{:016x}.json", hasher.finish()))
+    }
+}
+
+fn gidterm_dir() -> PathBuf {
+    dirs::home_dir().unwrap_or_else(|| PathBuf::from(".")).join(".gidterm")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, SystemTime};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_changed_mtime_bypasses_cache() {
+        let dir = TempDir::new().unwrap();
+        let cache = GraphCache::new(dir.path().join("cache"));
+        let path = dir.path().join("graph.yml");
+        let mtime = SystemTime::now() - Duration::from_secs(60);
+
+        let write = |content: &str, modified: SystemTime| {
+            fs::write(&path, content).unwrap();
+            fs::File::options().write(true).open(&path).unwrap().set_modified(modified).unwrap();
+        };
+
+        write("tasks:\n  aaa:\n    description: first\n", mtime);
+        let graph = Graph::from_file_with_cache(&path, Some(&cache)).unwrap();
+        assert!(graph.tasks.contains_key("aaa"));
+
+        // Same size and mtime: the cached parse is returned without reading the file
+        write("tasks:\n  bbb:\n    description: first\n", mtime);
+        let graph = Graph::from_file_with_cache(&path, Some(&cache)).unwrap();
+        assert!(graph.tasks.contains_key("aaa"));
+
+        // A new mtime is a miss, and the fresh parse replaces the entry
+        write("tasks:\n  bbb:\n    description: first\n", mtime + Duration::from_secs(1));
+        let graph = Graph::from_file_with_cache(&path, Some(&cache)).unwrap();
+        assert!(graph.tasks.contains_key("bbb"));
+        let graph = Graph::from_file_with_cache(&path, Some(&cache)).unwrap();
+        assert!(graph.tasks.contains_key("bbb"));
+    }
+
+    #[test]
+    fn test_cached_graph_roundtrip() {
+        let dir = TempDir::new().unwrap();
+        let cache = GraphCache::new(dir.path());
+        let path = dir.path().join("graph.yml");
+        fs::write(
+            &path,
+            "metadata:\n  project: demo\ntasks:\n  serve:\n    description: s\n    command: ./serve\n    status: done\n    success_codes: [0, 3]\n    ready_when: { log_match: \"Listening\" }\n    tags: [web]\n",
+        )
+        .unwrap();
+
+        let parsed = Graph::from_file(&path).unwrap();
+        let stamp = FileStamp::of(&path).unwrap();
+        cache.put(&path, &stamp, &parsed).unwrap();
+
+        let cached = cache.get(&path, &stamp).unwrap();
+        assert_eq!(cached.tasks, parsed.tasks);
+        assert_eq!(cached.metadata.map(|m| m.project), Some("demo".to_string()));
+        assert!(cache.get(&dir.path().join("other.yml"), &stamp).is_none());
+    }
+}
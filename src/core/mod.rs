@@ -1,11 +1,33 @@
 //! Core engine - graph parsing, PTY management, task scheduling
 
+mod ansi;
 mod graph;
+mod graph_cache;
 mod pty;
+mod readiness;
+mod ring;
+mod resources;
 mod scheduler;
 mod executor;
+mod export;
+#[cfg(unix)]
+mod fifo;
+mod vars;
+mod watch;
 
-pub use graph::{Graph, GraphTaskStatus, Metadata, Node, Task};
-pub use pty::{ExitResult, PTYHandle};
-pub use scheduler::Scheduler;
-pub use executor::{Executor, TaskEvent};
+pub use ansi::strip_ansi;
+pub use graph::{
+    task_file_stem, CompleteOn, Diagnostic, DiagnosticLevel, Graph, GraphTaskStatus, Metadata, Node, ReadyWhen, Task,
+    TaskPriority,
+};
+pub use graph_cache::{FileStamp, GraphCache, GraphConfig};
+pub use pty::{find_shell, shell_flags, ExitResult, OutputLine, PTYHandle, DEFAULT_SHELL};
+pub use readiness::{CompletionCheck, ReadinessCheck};
+pub use ring::RingBuffer;
+pub use resources::ResourceUsage;
+pub use scheduler::{FailurePolicy, ReconcileReport, Scheduler};
+pub use executor::{Executor, TaskEvent, EVENT_CHANNEL_CAPACITY};
+#[cfg(unix)]
+pub use fifo::{fifo_path, TaskFifo};
+pub use vars::CommandVars;
+pub use watch::{GraphWatcher, RELOAD_SETTLE};
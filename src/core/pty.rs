@@ -1,48 +1,329 @@
 //! PTY (pseudo-terminal) management - spawn and monitor processes
 
-use anyhow::Result;
-use portable_pty::{native_pty_system, Child, CommandBuilder, MasterPty, PtySize};
-use std::io::{BufRead, BufReader, Read};
+use super::ansi::strip_ansi;
+use anyhow::{Context, Result};
+use portable_pty::{native_pty_system, Child, CommandBuilder, ExitStatus, MasterPty, PtySize};
+use std::collections::{HashMap, VecDeque};
+use std::io::{BufRead, BufReader, ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+#[cfg(unix)]
+use std::{
+    fs::File,
+    os::fd::{FromRawFd, RawFd},
+};
+#[cfg(unix)]
+use tokio::io::unix::AsyncFd;
 
-/// Output line limit per task
-const MAX_OUTPUT_LINES: usize = 1000;
+/// Output lines kept per task unless set with `with_history_limit`
+pub const DEFAULT_HISTORY_LINES: usize = 1000;
+
+/// PTY size used when the real terminal size is unknown (e.g. no tty)
+pub const DEFAULT_PTY_ROWS: u16 = 24;
+pub const DEFAULT_PTY_COLS: u16 = 120;
+
+/// Shell that runs task commands unless a task sets `shell`
+#[cfg(not(windows))]
+pub const DEFAULT_SHELL: &str = "sh";
+#[cfg(windows)]
+pub const DEFAULT_SHELL: &str = "cmd";
+
+/// Where `shell` is: the file itself if given as a path, else the first
+/// match on `PATH`
+pub fn find_shell(shell: &str) -> Result<PathBuf> {
+    let path = Path::new(shell);
+    if path.components().count() > 1 {
+        if path.is_file() {
+            return Ok(path.to_path_buf());
+        }
+        anyhow::bail!("Shell '{}' does not exist", shell);
+    }
+
+    let names = if cfg!(windows) && path.extension().is_none() {
+        vec![format!("{}.exe", shell), shell.to_string()]
+    } else {
+        vec![shell.to_string()]
+    };
+    std::env::var_os("PATH")
+        .iter()
+        .flat_map(std::env::split_paths)
+        .flat_map(|dir| names.iter().map(move |name| dir.join(name)))
+        .find(|candidate| candidate.is_file())
+        .ok_or_else(|| anyhow::anyhow!("Shell '{}' not found on PATH", shell))
+}
+
+/// Arguments that make `shell` run the command that follows them
+///
+/// `cmd` takes the command after `/C` and PowerShell after `-Command`;
+/// any other shell is expected to take `-c` like `sh`.
+pub fn shell_flags(shell: &str) -> &'static [&'static str] {
+    match shell_name(shell).as_str() {
+        "cmd" => &["/C"],
+        "pwsh" | "powershell" => &["-NoProfile", "-Command"],
+        _ => &["-c"],
+    }
+}
+
+/// `value` as a single word of a `shell` command line
+///
+/// Plain words are left alone. Otherwise sh-like shells and PowerShell get
+/// a single-quoted string and `cmd` a double-quoted one; `cmd` has no way
+/// to escape `"` or `%` inside quotes, so values with those are refused.
+pub(crate) fn shell_quote(shell: &str, value: &str) -> Result<String> {
+    let name = shell_name(shell);
+    let plain = |extra: &str| {
+        !value.is_empty() && value.chars().all(|c| c.is_ascii_alphanumeric() || "_-./:".contains(c) || extra.contains(c))
+    };
+    match name.as_str() {
+        "cmd" => {
+            if plain("\\") {
+                return Ok(value.to_string());
+            }
+            if value.contains(['"', '%', '\n', '\r']) {
+                anyhow::bail!("Cannot quote {:?} for cmd", value);
+            }
+            Ok(format!("\"{}\"", value))
+        }
+        "pwsh" | "powershell" if plain("\\") => Ok(value.to_string()),
+        "pwsh" | "powershell" => Ok(format!("'{}'", value.replace('\'', "''"))),
+        _ if plain(",=+@%") => Ok(value.to_string()),
+        _ => Ok(format!("'{}'", value.replace('\'', "'\\''"))),
+    }
+}
+
+/// Lower-case file stem of `shell`, e.g. `cmd` for `CMD.EXE`
+fn shell_name(shell: &str) -> String {
+    Path::new(shell)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default()
+}
+
+/// `command` run through `shell`
+fn shell_command(shell: &str, command: &str) -> CommandBuilder {
+    let mut cmd = CommandBuilder::new(shell);
+    cmd.args(shell_flags(shell));
+    cmd.arg(command);
+    cmd
+}
+
+/// Buffered reader over the PTY master
+type PtyReader = BufReader<Box<dyn Read + Send>>;
+
+/// How long `send_raw` keeps retrying a full non-blocking PTY
+const WRITE_RETRY_LIMIT: Duration = Duration::from_secs(1);
+
+/// How often a ConPTY child is checked for exit
+#[cfg(windows)]
+const CONPTY_EXIT_POLL: Duration = Duration::from_millis(50);
+
+/// The PTY master registered with the tokio reactor
+///
+/// The descriptor is a non-blocking duplicate of the master, so reads never
+/// park a thread. Reading after the slave side closes reports EOF, as
+/// portable-pty's own reader does, rather than `EIO`.
+#[cfg(unix)]
+#[derive(Clone)]
+struct AsyncMaster(Arc<AsyncFd<File>>);
+
+#[cfg(unix)]
+impl AsyncMaster {
+    /// Duplicate `fd` and register it, if there is a runtime to register with
+    fn new(fd: RawFd) -> Option<Self> {
+        tokio::runtime::Handle::try_current().ok()?;
+
+        // SAFETY: dup and fcntl only operate on descriptors; a valid new
+        // descriptor is owned by the File from here on
+        let file = unsafe {
+            let dup = libc::dup(fd);
+            if dup == -1 {
+                return None;
+            }
+            let file = File::from_raw_fd(dup);
+            let flags = libc::fcntl(dup, libc::F_GETFL);
+            if flags == -1 || libc::fcntl(dup, libc::F_SETFL, flags | libc::O_NONBLOCK) == -1 {
+                return None;
+            }
+            file
+        };
+
+        match AsyncFd::new(file) {
+            Ok(fd) => Some(Self(Arc::new(fd))),
+            Err(e) => {
+                log::debug!("Falling back to blocking PTY reads: {}", e);
+                None
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Read for AsyncMaster {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self.0.get_ref().read(buf) {
+            Err(e) if e.raw_os_error() == Some(libc::EIO) => Ok(0),
+            result => result,
+        }
+    }
+}
+
+/// A line of task output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputLine {
+    pub text: String,
+    /// The line was redrawn in place with `\r` and replaces the previous one
+    pub replace: bool,
+}
+
+/// Splits output on both `\n` and `\r`, tracking in-place rewrites
+///
+/// Progress bars (pip, docker, tqdm) redraw a single line with `\r`. The
+/// line after a `\r` replaces its predecessor instead of adding a new one.
+/// The PTY turns `\n` into `\r\n`, so an empty segment ending in `\n` right
+/// after a `\r` just finishes the previous line.
+struct LineReader<R> {
+    inner: R,
+    pending: Vec<u8>,
+    after_cr: bool,
+}
+
+impl<R: BufRead> LineReader<R> {
+    fn new(inner: R) -> Self {
+        Self {
+            inner,
+            pending: Vec::new(),
+            after_cr: false,
+        }
+    }
+
+    /// Read the next line, or `None` at EOF
+    fn next_line(&mut self) -> std::io::Result<Option<OutputLine>> {
+        loop {
+            let terminator = self.read_segment()?;
+            if terminator.is_none() && self.pending.is_empty() {
+                return Ok(None);
+            }
+
+            let raw = String::from_utf8_lossy(&self.pending).into_owned();
+            self.pending.clear();
+            let text = strip_ansi(&raw).trim_end().to_string();
+
+            match terminator {
+                // A bare `\r` at column 0 doesn't move the cursor
+                Some(b'\r') if text.is_empty() => continue,
+                // Second half of `\r\n`
+                Some(b'\n') if text.is_empty() && self.after_cr => {
+                    self.after_cr = false;
+                    continue;
+                }
+                _ => {}
+            }
+
+            let replace = self.after_cr;
+            self.after_cr = terminator == Some(b'\r');
+            return Ok(Some(OutputLine { text, replace }));
+        }
+    }
+
+    /// Buffer bytes up to the next `\r` or `\n`, returning the terminator
+    fn read_segment(&mut self) -> std::io::Result<Option<u8>> {
+        loop {
+            let available = match self.inner.fill_buf() {
+                Ok(available) => available,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            if available.is_empty() {
+                return Ok(None);
+            }
+
+            if let Some(pos) = available.iter().position(|&b| b == b'\n' || b == b'\r') {
+                let terminator = available[pos];
+                self.pending.extend_from_slice(&available[..pos]);
+                self.inner.consume(pos + 1);
+                return Ok(Some(terminator));
+            }
+
+            let len = available.len();
+            self.pending.extend_from_slice(available);
+            self.inner.consume(len);
+        }
+    }
+}
 
 /// PTY handle for a single task
 #[derive(Clone)]
 pub struct PTYHandle {
     pub id: String,
-    output_history: Arc<Mutex<Vec<String>>>,
-    reader: Arc<Mutex<Option<BufReader<Box<dyn Read + Send>>>>>,
+    pid: Option<u32>,
+    output_history: Arc<Mutex<VecDeque<String>>>,
+    history_limit: usize,
+    reader: Arc<Mutex<Option<LineReader<PtyReader>>>>,
+    /// Set when `reader` is non-blocking and reads go through the reactor
+    #[cfg(unix)]
+    async_master: Option<AsyncMaster>,
     child: Arc<Mutex<Option<Box<dyn Child + Send + Sync>>>>,
     master: Arc<Mutex<Option<Box<dyn MasterPty + Send>>>>,
+    /// The master's writer can only be taken once, and dropping it sends EOF
+    writer: Arc<Mutex<Option<Box<dyn Write + Send>>>>,
 }
 
 impl PTYHandle {
     /// Spawn a new process in a PTY
     ///
-    /// Commands are wrapped in `sh -c "..."` to support:
+    /// Commands are wrapped in `sh -c "..."` (`cmd /C "..."` on Windows) to
+    /// support:
     /// - Pipes: `cat file | grep foo`
     /// - Chaining: `cd dir && npm run dev`
     /// - Quoted args: `echo "hello world"`
     /// - Environment variables: `FOO=bar cmd`
-    pub fn spawn(task_id: &str, command: &str) -> Result<Self> {
+    ///
+    /// `env` is added on top of the inherited environment (e.g. `$PORT`).
+    pub fn spawn(
+        task_id: &str,
+        command: &str,
+        env: &HashMap<String, String>,
+        rows: u16,
+        cols: u16,
+    ) -> Result<Self> {
+        Self::spawn_in_shell(task_id, None, command, env, rows, cols)
+    }
+
+    /// Spawn a process in a PTY, running the command through `shell`
+    /// (`DEFAULT_SHELL` if `None`)
+    ///
+    /// Fails before spawning anything if the shell can't be found.
+    pub fn spawn_in_shell(
+        task_id: &str,
+        shell: Option<&str>,
+        command: &str,
+        env: &HashMap<String, String>,
+        rows: u16,
+        cols: u16,
+    ) -> Result<Self> {
         log::info!("Spawning PTY for task {}: {}", task_id, command);
 
         if command.trim().is_empty() {
             anyhow::bail!("Empty command");
         }
 
-        // Wrap in sh -c for proper shell interpretation
-        let mut cmd = CommandBuilder::new("sh");
-        cmd.arg("-c");
-        cmd.arg(command);
+        let mut cmd = match shell {
+            Some(shell) => {
+                find_shell(shell).with_context(|| format!("Cannot start task '{}'", task_id))?;
+                shell_command(shell, command)
+            }
+            None => shell_command(DEFAULT_SHELL, command),
+        };
+        for (key, value) in env {
+            cmd.env(key, value);
+        }
 
         // Create PTY
         let pty_system = native_pty_system();
         let pty_size = PtySize {
-            rows: 24,
-            cols: 120,
+            rows,
+            cols,
             pixel_width: 0,
             pixel_height: 0,
         };
@@ -51,75 +332,155 @@ impl PTYHandle {
 
         // Spawn command
         let child = pair.slave.spawn_command(cmd)?;
+        let pid = child.process_id();
         drop(pair.slave); // Close slave side
 
-        // Get reader from master
+        // Read through the reactor when possible, else from a blocking clone
+        #[cfg(unix)]
+        let async_master = pair.master.as_raw_fd().and_then(AsyncMaster::new);
+        #[cfg(unix)]
+        let reader: Box<dyn Read + Send> = match &async_master {
+            Some(master) => Box::new(master.clone()),
+            None => pair.master.try_clone_reader()?,
+        };
+        #[cfg(not(unix))]
         let reader = pair.master.try_clone_reader()?;
-        let buf_reader = BufReader::new(reader);
+        let line_reader = LineReader::new(BufReader::new(reader));
+        let writer = pair.master.take_writer()?;
 
-        Ok(Self {
+        let handle = Self {
             id: task_id.to_string(),
-            output_history: Arc::new(Mutex::new(Vec::new())),
-            reader: Arc::new(Mutex::new(Some(buf_reader))),
+            pid,
+            output_history: Arc::new(Mutex::new(VecDeque::new())),
+            history_limit: DEFAULT_HISTORY_LINES,
+            reader: Arc::new(Mutex::new(Some(line_reader))),
+            #[cfg(unix)]
+            async_master,
             child: Arc::new(Mutex::new(Some(child))),
             master: Arc::new(Mutex::new(Some(pair.master))),
-        })
+            writer: Arc::new(Mutex::new(Some(writer))),
+        };
+        #[cfg(windows)]
+        handle.close_master_on_exit();
+        Ok(handle)
     }
 
-    /// Read one line of output (blocking — call from spawn_blocking!)
-    pub fn read_line_blocking(&self) -> Result<Option<String>> {
-        let mut reader_guard = self.reader.lock().unwrap();
+    /// Close the pseudoconsole once the child exits
+    ///
+    /// Unlike a Unix PTY, ConPTY keeps its output pipe open after the child
+    /// is gone, until the pseudoconsole itself is closed. Without this the
+    /// reader would never see EOF and the task would never finish.
+    #[cfg(windows)]
+    fn close_master_on_exit(&self) {
+        let child = self.child.clone();
+        let master = self.master.clone();
+        std::thread::spawn(move || loop {
+            let exited = match child.lock().unwrap().as_mut() {
+                Some(child) => !matches!(child.try_wait(), Ok(None)),
+                None => true,
+            };
+            if exited {
+                *master.lock().unwrap() = None;
+                return;
+            }
+            std::thread::sleep(CONPTY_EXIT_POLL);
+        });
+    }
 
-        if let Some(reader) = reader_guard.as_mut() {
-            let mut line = String::new();
+    /// Keep up to `lines` lines of output history
+    pub fn with_history_limit(mut self, lines: usize) -> Self {
+        self.history_limit = lines;
+        self
+    }
 
-            match reader.read_line(&mut line) {
-                Ok(0) => {
-                    // EOF - process ended
-                    *reader_guard = None;
-                    Ok(None)
-                }
-                Ok(_) => {
-                    let trimmed = line.trim_end().to_string();
-
-                    // Store in history
-                    {
-                        let mut history = self.output_history.lock().unwrap();
-                        history.push(trimmed.clone());
-
-                        // Cap history
-                        if history.len() > MAX_OUTPUT_LINES {
-                            let drain_count = history.len() - MAX_OUTPUT_LINES;
-                            history.drain(0..drain_count);
-                        }
-                    }
-
-                    Ok(Some(trimmed))
-                }
-                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
-                    // No data available yet
-                    Ok(Some(String::new()))
+    /// Read one line of output, or `None` once the process's output closes
+    ///
+    /// Waits on the tokio reactor when the PTY is non-blocking, and on a
+    /// blocking-pool thread otherwise.
+    pub async fn read_line(&self) -> Result<Option<OutputLine>> {
+        #[cfg(unix)]
+        if let Some(master) = &self.async_master {
+            loop {
+                let mut guard = master.0.readable().await?;
+                if let Ok(result) = guard.try_io(|_| self.next_line()) {
+                    return Ok(result?);
                 }
-                Err(e) => Err(e.into()),
             }
-        } else {
-            Ok(None)
         }
+
+        let handle = self.clone();
+        tokio::task::spawn_blocking(move || handle.read_line_blocking())
+            .await
+            .map_err(|e| anyhow::anyhow!("Internal error: {}", e))?
+    }
+
+    /// Read one line of output (blocking — call from spawn_blocking!)
+    ///
+    /// ANSI escapes are stripped so parsers and the TUI see plain text.
+    /// Lines redrawn with `\r` replace the last history entry.
+    pub fn read_line_blocking(&self) -> Result<Option<OutputLine>> {
+        match self.next_line() {
+            Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                // No data available yet
+                Ok(Some(OutputLine {
+                    text: String::new(),
+                    replace: false,
+                }))
+            }
+            result => Ok(result?),
+        }
+    }
+
+    /// Next line from the reader, recorded in the history
+    ///
+    /// `WouldBlock` means a non-blocking PTY has no complete line yet; any
+    /// partial line is kept for the next call.
+    fn next_line(&self) -> std::io::Result<Option<OutputLine>> {
+        let mut reader_guard = self.reader.lock().unwrap();
+        let Some(reader) = reader_guard.as_mut() else {
+            return Ok(None);
+        };
+
+        let Some(line) = reader.next_line()? else {
+            // EOF - process ended
+            *reader_guard = None;
+            return Ok(None);
+        };
+
+        let mut history = self.output_history.lock().unwrap();
+        match history.back_mut() {
+            Some(last) if line.replace => *last = line.text.clone(),
+            _ => history.push_back(line.text.clone()),
+        }
+
+        // Cap history
+        while history.len() > self.history_limit {
+            history.pop_front();
+        }
+
+        Ok(Some(line))
+    }
+
+    /// OS process ID of the spawned child, if known
+    pub fn pid(&self) -> Option<u32> {
+        self.pid
     }
 
     /// Get output history
     pub fn get_output(&self) -> Vec<String> {
-        self.output_history.lock().unwrap().clone()
+        self.output_history.lock().unwrap().iter().cloned().collect()
     }
 
     /// Send input to the PTY (for semantic commands)
     pub fn send_input(&self, input: &str) -> Result<()> {
-        let master_guard = self.master.lock().unwrap();
-        if let Some(master) = master_guard.as_ref() {
-            use std::io::Write;
-            let mut writer = master.take_writer()?;
-            writer.write_all(input.as_bytes())?;
-            writer.write_all(b"\n")?;
+        self.send_raw(format!("{}\n", input).as_bytes())
+    }
+
+    /// Write bytes to the PTY exactly as given (e.g. `\x03` for Ctrl-C)
+    pub fn send_raw(&self, bytes: &[u8]) -> Result<()> {
+        let mut writer_guard = self.writer.lock().unwrap();
+        if let Some(writer) = writer_guard.as_mut() {
+            write_all_retrying(writer, bytes)?;
             writer.flush()?;
             Ok(())
         } else {
@@ -127,28 +488,52 @@ impl PTYHandle {
         }
     }
 
+    /// Resize the PTY so the child sees the new terminal dimensions
+    pub fn resize(&self, rows: u16, cols: u16) -> Result<()> {
+        let master_guard = self.master.lock().unwrap();
+        if let Some(master) = master_guard.as_ref() {
+            master.resize(PtySize {
+                rows,
+                cols,
+                pixel_width: 0,
+                pixel_height: 0,
+            })?;
+        }
+        Ok(())
+    }
+
     /// Try to get exit status (non-blocking)
     pub fn try_wait(&self) -> Result<Option<ExitResult>> {
         let mut child_guard = self.child.lock().unwrap();
         if let Some(child) = child_guard.as_mut() {
             match child.try_wait() {
-                Ok(Some(status)) => {
-                    let code = status
-                        .exit_code()
-                        .try_into()
-                        .unwrap_or(1);
-                    Ok(Some(ExitResult { code }))
-                }
+                Ok(Some(status)) => Ok(Some(ExitResult::from_status(&status))),
                 Ok(None) => Ok(None), // Still running
                 Err(e) => Err(e.into()),
             }
         } else {
-            Ok(Some(ExitResult { code: -1 })) // Child already gone
+            // Child already gone
+            Ok(Some(ExitResult {
+                code: -1,
+                signal: None,
+            }))
         }
     }
 
-    /// Kill the process (SIGKILL equivalent)
+    /// Kill the process and everything it spawned (SIGKILL equivalent)
     pub fn kill(&self) -> Result<()> {
+        // The child runs in its own session, so its process group holds any
+        // pipelines and background jobs `sh -c` started
+        #[cfg(unix)]
+        if let Some(pid) = self.pid {
+            kill_process_group(pid);
+        }
+        // Terminating `cmd` alone would leave the programs it started running
+        #[cfg(windows)]
+        if let Some(pid) = self.pid {
+            kill_process_tree(pid);
+        }
+
         // Kill child process
         {
             let mut child_guard = self.child.lock().unwrap();
@@ -166,6 +551,7 @@ impl PTYHandle {
 
         // Close master
         {
+            *self.writer.lock().unwrap() = None;
             let mut master = self.master.lock().unwrap();
             *master = None;
         }
@@ -180,17 +566,305 @@ impl PTYHandle {
     }
 }
 
+/// `write_all` that waits out `WouldBlock`
+///
+/// The writer shares its file status flags with the non-blocking reader, so
+/// a child that isn't reading its input can fill the PTY.
+fn write_all_retrying(writer: &mut dyn Write, mut bytes: &[u8]) -> std::io::Result<()> {
+    let deadline = Instant::now() + WRITE_RETRY_LIMIT;
+    while !bytes.is_empty() {
+        match writer.write(bytes) {
+            Ok(0) => return Err(ErrorKind::WriteZero.into()),
+            Ok(n) => bytes = &bytes[n..],
+            Err(e) if e.kind() == ErrorKind::Interrupted => {}
+            Err(e) if e.kind() == ErrorKind::WouldBlock && Instant::now() < deadline => {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+/// Send SIGKILL to the process group led by `pid`
+#[cfg(unix)]
+fn kill_process_group(pid: u32) {
+    // SAFETY: killpg only sends a signal; it has no memory-safety preconditions
+    let result = unsafe { libc::killpg(pid as libc::pid_t, libc::SIGKILL) };
+    if result == -1 {
+        let err = std::io::Error::last_os_error();
+        // ESRCH: the whole group has already exited
+        if err.raw_os_error() != Some(libc::ESRCH) {
+            log::warn!("Failed to kill process group {}: {}", pid, err);
+        }
+    }
+}
+
+/// Force-kill `pid` and all of its descendants
+#[cfg(windows)]
+fn kill_process_tree(pid: u32) {
+    let status = std::process::Command::new("taskkill")
+        .args(["/PID", &pid.to_string(), "/T", "/F"])
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status();
+    if let Err(e) = status {
+        log::warn!("Failed to kill process tree {}: {}", pid, e);
+    }
+}
+
 /// Result from process exit
 #[derive(Debug, Clone)]
 pub struct ExitResult {
     pub code: i32,
+    /// Signal that terminated the process (Unix only; `code` is then meaningless)
+    pub signal: Option<i32>,
+}
+
+impl ExitResult {
+    fn from_status(status: &ExitStatus) -> Self {
+        // portable-pty only exposes the signal as `strsignal` text via Display
+        #[cfg(unix)]
+        let signal = status
+            .to_string()
+            .strip_prefix("Terminated by ")
+            .and_then(signal_from_description);
+        #[cfg(not(unix))]
+        let signal = None;
+
+        Self {
+            code: status.exit_code().try_into().unwrap_or(1),
+            signal,
+        }
+    }
+
+    /// How the process ended, e.g. `killed by SIGTERM` or `exited with code 2`
+    pub fn describe(&self) -> String {
+        match self.signal {
+            Some(signal) => format!("killed by {}", signal_name(signal)),
+            None => format!("exited with code {}", self.code),
+        }
+    }
+}
+
+/// Map a `strsignal` description (`Terminated`) back to its signal number
+#[cfg(unix)]
+fn signal_from_description(description: &str) -> Option<i32> {
+    if let Some(number) = description.strip_prefix("Signal ") {
+        return number.parse().ok();
+    }
+    (1..65).find(|&signal| {
+        // SAFETY: strsignal returns a NUL-terminated string (or NULL), read immediately
+        let name = unsafe { libc::strsignal(signal) };
+        !name.is_null() && unsafe { std::ffi::CStr::from_ptr(name) }.to_string_lossy() == description
+    })
+}
+
+/// Conventional name of a signal number, e.g. `SIGTERM`
+fn signal_name(signal: i32) -> String {
+    #[cfg(unix)]
+    {
+        let name = match signal {
+            libc::SIGHUP => "SIGHUP",
+            libc::SIGINT => "SIGINT",
+            libc::SIGQUIT => "SIGQUIT",
+            libc::SIGILL => "SIGILL",
+            libc::SIGABRT => "SIGABRT",
+            libc::SIGBUS => "SIGBUS",
+            libc::SIGFPE => "SIGFPE",
+            libc::SIGKILL => "SIGKILL",
+            libc::SIGUSR1 => "SIGUSR1",
+            libc::SIGSEGV => "SIGSEGV",
+            libc::SIGUSR2 => "SIGUSR2",
+            libc::SIGPIPE => "SIGPIPE",
+            libc::SIGALRM => "SIGALRM",
+            libc::SIGTERM => "SIGTERM",
+            _ => return format!("signal {}", signal),
+        };
+        name.to_string()
+    }
+    #[cfg(not(unix))]
+    format!("signal {}", signal)
 }
 
 impl std::fmt::Debug for PTYHandle {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("PTYHandle")
             .field("id", &self.id)
+            .field("pid", &self.pid)
             .field("alive", &self.is_alive())
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn read_all(input: &[u8]) -> Vec<(String, bool)> {
+        let mut reader = LineReader::new(Cursor::new(input.to_vec()));
+        let mut lines = Vec::new();
+        while let Some(line) = reader.next_line().unwrap() {
+            lines.push((line.text, line.replace));
+        }
+        lines
+    }
+
+    #[test]
+    fn test_crlf_lines_append() {
+        assert_eq!(
+            read_all(b"one\r\ntwo\r\n"),
+            vec![("one".to_string(), false), ("two".to_string(), false)]
+        );
+    }
+
+    #[test]
+    fn test_carriage_return_progress_replaces() {
+        let lines = read_all(b"Downloading\r\n\r 10%\r 50%\r100%\r\ndone\r\n");
+        assert_eq!(
+            lines,
+            vec![
+                ("Downloading".to_string(), false),
+                (" 10%".to_string(), false),
+                (" 50%".to_string(), true),
+                ("100%".to_string(), true),
+                ("done".to_string(), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unterminated_final_line() {
+        assert_eq!(read_all(b"partial"), vec![("partial".to_string(), false)]);
+    }
+
+    /// Whether `pid` is a live (non-zombie) process
+    #[cfg(unix)]
+    fn process_exists(pid: libc::pid_t) -> bool {
+        // SAFETY: signal 0 only checks that the process exists
+        if unsafe { libc::kill(pid, 0) } != 0 {
+            return false;
+        }
+        // Killed orphans linger as zombies until init reaps them
+        std::fs::read_to_string(format!("/proc/{}/stat", pid)).map_or(true, |stat| !stat.contains(") Z "))
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_kill_reaches_grandchildren() {
+        // Ignoring SIGHUP (like `nohup`) means closing the PTY alone won't end it
+        let handle = PTYHandle::spawn("bg", "trap '' HUP; sleep 100 & echo $!; wait", &HashMap::new(), 24, 80)
+            .unwrap();
+
+        let grandchild = loop {
+            let line = handle.read_line_blocking().unwrap().expect("shell exited early");
+            if let Ok(pid) = line.text.trim().parse::<libc::pid_t>() {
+                break pid;
+            }
+        };
+        assert!(process_exists(grandchild));
+
+        handle.kill().unwrap();
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        while process_exists(grandchild) && std::time::Instant::now() < deadline {
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+        assert!(!process_exists(grandchild), "background sleep {} survived kill", grandchild);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_send_raw_bytes_unmodified() {
+        // Raw mode: the line discipline passes control characters through
+        let handle = PTYHandle::spawn(
+            "raw",
+            "stty raw -echo; echo ready; head -c 3 | od -An -tx1",
+            &HashMap::new(),
+            24,
+            80,
+        )
+        .unwrap();
+        while handle.read_line_blocking().unwrap().expect("exited early").text != "ready" {}
+
+        handle.send_raw(b"\x03\x04a").unwrap();
+        let line = handle.read_line_blocking().unwrap().expect("no od output");
+        assert_eq!(line.text.split_whitespace().collect::<Vec<_>>(), vec!["03", "04", "61"]);
+    }
+
+    fn wait_exit(handle: &PTYHandle) -> ExitResult {
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        loop {
+            if let Some(result) = handle.try_wait().unwrap() {
+                return result;
+            }
+            assert!(std::time::Instant::now() < deadline, "process did not exit");
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+    }
+
+    #[test]
+    fn test_shell_flags() {
+        assert_eq!(shell_flags("sh"), ["-c"]);
+        assert_eq!(shell_flags("/usr/bin/bash"), ["-c"]);
+        assert_eq!(shell_flags("cmd"), ["/C"]);
+        assert_eq!(shell_flags("CMD.EXE"), ["/C"]);
+        assert_eq!(shell_flags("pwsh"), ["-NoProfile", "-Command"]);
+        assert_eq!(shell_flags("powershell.exe"), ["-NoProfile", "-Command"]);
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("sh", "/work/api").unwrap(), "/work/api");
+        assert_eq!(shell_quote("sh", "my dir").unwrap(), "'my dir'");
+        assert_eq!(shell_quote("bash", "a; rm -rf ~").unwrap(), "'a; rm -rf ~'");
+        assert_eq!(shell_quote("sh", "it's $HOME").unwrap(), "'it'\\''s $HOME'");
+        assert_eq!(shell_quote("sh", "").unwrap(), "''");
+        assert_eq!(shell_quote("pwsh", "it's $env:X").unwrap(), "'it''s $env:X'");
+        assert_eq!(shell_quote("cmd", "C:\\work\\api").unwrap(), "C:\\work\\api");
+        assert_eq!(shell_quote("cmd", "a & b").unwrap(), "\"a & b\"");
+        assert!(shell_quote("cmd", "%PATH%").is_err());
+    }
+
+    #[cfg(windows)]
+    #[tokio::test]
+    async fn test_conpty_smoke() {
+        // Runs as `cmd /C echo hi`; the output must arrive and the reader must
+        // reach EOF once cmd exits
+        let handle = PTYHandle::spawn("hi", "echo hi", &HashMap::new(), 24, 80).unwrap();
+        let lines = tokio::time::timeout(std::time::Duration::from_secs(10), async {
+            let mut lines = Vec::new();
+            while let Some(line) = handle.read_line().await.unwrap() {
+                lines.push(line.text);
+            }
+            lines
+        })
+        .await
+        .expect("ConPTY output never reached EOF");
+        assert!(lines.iter().any(|line| line == "hi"), "{:?}", lines);
+        assert_eq!(wait_exit(&handle).code, 0);
+
+        // Killing reaches the child through ConPTY too
+        let handle = PTYHandle::spawn("wait", "ping -n 100 127.0.0.1", &HashMap::new(), 24, 80).unwrap();
+        handle.kill().unwrap();
+        assert!(!handle.is_alive());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_exit_signal_reported() {
+        let handle = PTYHandle::spawn("term", "sleep 100", &HashMap::new(), 24, 80).unwrap();
+        // SAFETY: kill only sends a signal
+        unsafe { libc::kill(handle.pid().unwrap() as libc::pid_t, libc::SIGTERM) };
+
+        let result = wait_exit(&handle);
+        assert_eq!(result.signal, Some(libc::SIGTERM));
+        assert_eq!(result.describe(), "killed by SIGTERM");
+
+        let handle = PTYHandle::spawn("exit", "exit 3", &HashMap::new(), 24, 80).unwrap();
+        let result = wait_exit(&handle);
+        assert_eq!((result.code, result.signal), (3, None));
+        assert_eq!(result.describe(), "exited with code 3");
+    }
+}
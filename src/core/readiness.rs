@@ -0,0 +1,115 @@
+//! Readiness checks - when a running task's `ready_when` or `complete_on` holds
+
+use super::{CompleteOn, ReadyWhen};
+use crate::ports::is_port_available;
+use anyhow::{Context, Result};
+use regex::Regex;
+
+/// A compiled `ready_when` check for one running task
+#[derive(Debug, Clone)]
+pub enum ReadinessCheck {
+    /// Ready once the port can no longer be bound
+    Port(u16),
+    /// Ready once an output line matches
+    LogMatch(Regex),
+}
+
+impl ReadinessCheck {
+    /// Compile a task's `ready_when`
+    pub fn new(ready_when: &ReadyWhen) -> Result<Self> {
+        match ready_when {
+            ReadyWhen::Port { port } => Ok(Self::Port(*port)),
+            ReadyWhen::LogMatch { log_match } => Regex::new(log_match)
+                .map(Self::LogMatch)
+                .with_context(|| format!("Invalid ready_when log_match '{}'", log_match)),
+        }
+    }
+
+    /// Whether a new output line makes the task ready
+    pub fn matches_line(&self, line: &str) -> bool {
+        match self {
+            Self::LogMatch(re) => re.is_match(line),
+            Self::Port(_) => false,
+        }
+    }
+
+    /// Whether something is now listening on the port
+    pub fn port_in_use(&self) -> bool {
+        match self {
+            Self::Port(port) => !is_port_available(*port),
+            Self::LogMatch(_) => false,
+        }
+    }
+}
+
+/// A compiled `complete_on` check for one running task
+#[derive(Debug, Clone)]
+pub struct CompletionCheck {
+    pattern: Regex,
+    /// Stop the process once it matches
+    pub kill: bool,
+}
+
+impl CompletionCheck {
+    /// Compile a task's `complete_on`
+    pub fn new(complete_on: &CompleteOn) -> Result<Self> {
+        let pattern = Regex::new(&complete_on.log_match)
+            .with_context(|| format!("Invalid complete_on log_match '{}'", complete_on.log_match))?;
+        Ok(Self {
+            pattern,
+            kill: complete_on.kill,
+        })
+    }
+
+    /// Whether a new output line completes the task
+    pub fn matches_line(&self, line: &str) -> bool {
+        self.pattern.is_match(line)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    #[test]
+    fn test_port_readiness() {
+        let listener = TcpListener::bind(("0.0.0.0", 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let check = ReadinessCheck::new(&ReadyWhen::Port { port }).unwrap();
+
+        assert!(check.port_in_use());
+        assert!(!check.matches_line("Listening on 0.0.0.0"));
+        drop(listener);
+        assert!(!check.port_in_use());
+    }
+
+    #[test]
+    fn test_log_match_readiness() {
+        let log_match = r"Listening on \S+:\d+".to_string();
+        let check = ReadinessCheck::new(&ReadyWhen::LogMatch { log_match }).unwrap();
+        assert!(!check.matches_line("Compiling..."));
+        assert!(check.matches_line("INFO Listening on 127.0.0.1:8080"));
+        assert!(!check.port_in_use());
+
+        assert!(ReadinessCheck::new(&ReadyWhen::LogMatch { log_match: "(".to_string() }).is_err());
+    }
+
+    #[test]
+    fn test_log_match_completion() {
+        let complete_on = CompleteOn {
+            log_match: r"^Watching for \w+ changes".to_string(),
+            kill: true,
+        };
+        let check = CompletionCheck::new(&complete_on).unwrap();
+        assert!(check.kill);
+        assert!(!check.matches_line("Building..."));
+        assert!(check.matches_line("Watching for file changes"));
+
+        let bad = CompleteOn {
+            log_match: "(".to_string(),
+            kill: false,
+        };
+        assert!(CompletionCheck::new(&bad).is_err());
+    }
+}
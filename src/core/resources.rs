@@ -0,0 +1,174 @@
+//! Per-task resource sampling
+//!
+//! Each sample is one pass over `/proc`: every process's CPU time and
+//! memory, and which processes each one started. A task's usage is summed
+//! over its process and all of its descendants, so one scan serves every
+//! running task. On other platforms no samples are taken.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// How often running tasks are sampled
+pub const RESOURCE_SAMPLE_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Resource usage of a task and its children
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ResourceUsage {
+    /// Peak resident memory across samples, in MiB
+    pub peak_mem_mb: f64,
+    /// CPU time (user + system), including reaped children
+    pub cpu_secs: f64,
+}
+
+impl ResourceUsage {
+    /// Fold a new sample in, keeping the peak memory
+    pub fn update(&mut self, sample: ResourceUsage) {
+        self.peak_mem_mb = self.peak_mem_mb.max(sample.peak_mem_mb);
+        self.cpu_secs = self.cpu_secs.max(sample.cpu_secs);
+    }
+}
+
+/// Every process seen by one `/proc` scan, with its children
+#[derive(Debug, Default)]
+pub struct ProcessTable {
+    stats: HashMap<u32, ProcStat>,
+    children: HashMap<u32, Vec<u32>>,
+}
+
+impl ProcessTable {
+    /// Scan `/proc` once (an empty table where it is unavailable)
+    #[cfg(target_os = "linux")]
+    pub fn scan() -> Self {
+        let Ok(entries) = std::fs::read_dir("/proc") else {
+            return Self::default();
+        };
+
+        let stats = entries.flatten().filter_map(|entry| {
+            let pid: u32 = entry.file_name().to_str()?.parse().ok()?;
+            // Processes can exit mid-scan; skip them
+            let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+            Some((pid, parse_stat(&stat)?))
+        });
+        Self::from_stats(stats)
+    }
+
+    /// Scan `/proc` once (an empty table where it is unavailable)
+    #[cfg(not(target_os = "linux"))]
+    pub fn scan() -> Self {
+        Self::default()
+    }
+
+    fn from_stats(stats: impl IntoIterator<Item = (u32, ProcStat)>) -> Self {
+        let stats: HashMap<u32, ProcStat> = stats.into_iter().collect();
+        let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+        for (&pid, stat) in &stats {
+            children.entry(stat.ppid).or_default().push(pid);
+        }
+        Self { stats, children }
+    }
+
+    /// Current memory and CPU of `pid` and all of its descendants, or
+    /// `None` once `pid` is gone
+    pub fn tree_usage(&self, pid: u32) -> Option<ResourceUsage> {
+        self.stats.get(&pid)?;
+
+        let mut usage = ResourceUsage::default();
+        let mut stack = vec![pid];
+        while let Some(pid) = stack.pop() {
+            let Some(stat) = self.stats.get(&pid) else {
+                continue;
+            };
+            usage.cpu_secs += stat.cpu_ticks as f64 / CLOCK_TICKS_PER_SEC;
+            usage.peak_mem_mb += stat.rss_pages as f64 * page_size() / (1024.0 * 1024.0);
+            stack.extend(self.children.get(&pid).into_iter().flatten());
+        }
+        Some(usage)
+    }
+}
+
+/// `/proc` reports CPU times in USER_HZ, which Linux fixes at 100
+const CLOCK_TICKS_PER_SEC: f64 = 100.0;
+
+/// Bytes per memory page, the unit of `rss` in `/proc/<pid>/stat`
+#[cfg(unix)]
+fn page_size() -> f64 {
+    // SAFETY: sysconf only reads a system constant
+    match unsafe { libc::sysconf(libc::_SC_PAGESIZE) } {
+        size if size > 0 => size as f64,
+        _ => 4096.0,
+    }
+}
+
+#[cfg(not(unix))]
+fn page_size() -> f64 {
+    4096.0
+}
+
+/// Fields of `/proc/<pid>/stat` used for sampling
+#[derive(Debug, PartialEq)]
+struct ProcStat {
+    ppid: u32,
+    /// utime + stime + cutime + cstime
+    cpu_ticks: u64,
+    /// Resident set size, in pages
+    rss_pages: u64,
+}
+
+fn parse_stat(stat: &str) -> Option<ProcStat> {
+    // The command name is parenthesized and may contain spaces
+    let rest = &stat[stat.rfind(')')? + 1..];
+    let fields: Vec<&str> = rest.split_whitespace().collect();
+
+    // Numbered from field 3 (state); see proc(5)
+    let field = |n: usize| fields.get(n - 3).and_then(|f| f.parse::<u64>().ok());
+    let ppid = field(4)? as u32;
+    let cpu_ticks = (14..=17).map(field).sum::<Option<u64>>()?;
+    let rss_pages = field(24)?;
+
+    Some(ProcStat { ppid, cpu_ticks, rss_pages })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stat() {
+        let stat = "4242 (cargo (build)) S 4200 4242 4242 34816 4242 4194304 1 2 3 4 150 50 20 10 20 0 1 0 100 1000 300";
+        assert_eq!(
+            parse_stat(stat),
+            Some(ProcStat {
+                ppid: 4200,
+                cpu_ticks: 230,
+                rss_pages: 300,
+            })
+        );
+        assert_eq!(parse_stat("garbage"), None);
+    }
+
+    #[test]
+    fn test_tree_usage_sums_descendants() {
+        let stat = |ppid, cpu_ticks| ProcStat { ppid, cpu_ticks, rss_pages: 0 };
+        // 10 -> 11 -> 12, plus 20 (another task) and 30 (unrelated)
+        let table = ProcessTable::from_stats([
+            (10, stat(1, 100)),
+            (11, stat(10, 50)),
+            (12, stat(11, 25)),
+            (20, stat(1, 1000)),
+            (30, stat(20, 1000)),
+        ]);
+
+        assert_eq!(table.tree_usage(10).unwrap().cpu_secs, 1.75);
+        assert_eq!(table.tree_usage(11).unwrap().cpu_secs, 0.75);
+        assert_eq!(table.tree_usage(20).unwrap().cpu_secs, 20.0);
+        assert_eq!(table.tree_usage(99), None);
+    }
+
+    #[test]
+    fn test_update_keeps_peak() {
+        let mut usage = ResourceUsage::default();
+        usage.update(ResourceUsage { peak_mem_mb: 100.0, cpu_secs: 1.0 });
+        usage.update(ResourceUsage { peak_mem_mb: 40.0, cpu_secs: 2.5 });
+        assert_eq!(usage, ResourceUsage { peak_mem_mb: 100.0, cpu_secs: 2.5 });
+    }
+}
@@ -0,0 +1,119 @@
+//! Ring buffer - bounded storage for task output, oldest lines dropped first
+
+use std::collections::vec_deque::{Iter, VecDeque};
+use std::ops::{Index, RangeBounds};
+
+/// Keeps the newest `capacity` items
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RingBuffer<T> {
+    items: VecDeque<T>,
+    capacity: usize,
+}
+
+impl<T> RingBuffer<T> {
+    /// An empty buffer holding at most `capacity` items
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            items: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    /// Most items the buffer keeps
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Change the capacity, dropping the oldest items if it shrinks
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.trim();
+    }
+
+    /// Append an item, dropping the oldest once full
+    pub fn push(&mut self, item: T) {
+        self.items.push_back(item);
+        self.trim();
+    }
+
+    /// The newest item, for in-place updates
+    pub fn last_mut(&mut self) -> Option<&mut T> {
+        self.items.back_mut()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.items.get(index)
+    }
+
+    /// All items, oldest first
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.items.iter()
+    }
+
+    /// The newest `n` items, oldest first, without copying
+    pub fn tail(&self, n: usize) -> Iter<'_, T> {
+        self.items.range(self.items.len().saturating_sub(n)..)
+    }
+
+    /// Items in an index range, oldest first
+    pub fn range(&self, range: impl RangeBounds<usize>) -> Iter<'_, T> {
+        self.items.range(range)
+    }
+
+    fn trim(&mut self) {
+        while self.items.len() > self.capacity {
+            self.items.pop_front();
+        }
+    }
+}
+
+impl<T> Index<usize> for RingBuffer<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        &self.items[index]
+    }
+}
+
+/// Collects into a buffer whose capacity is whatever was collected
+impl<T> FromIterator<T> for RingBuffer<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let items: VecDeque<T> = iter.into_iter().collect();
+        Self {
+            capacity: items.len(),
+            items,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ring_buffer_drops_oldest() {
+        let mut ring = RingBuffer::new(3);
+        for i in 0..5 {
+            ring.push(i);
+        }
+        assert_eq!(ring.iter().copied().collect::<Vec<_>>(), vec![2, 3, 4]);
+        assert_eq!(ring.tail(2).copied().collect::<Vec<_>>(), vec![3, 4]);
+        assert_eq!(ring.tail(10).len(), 3);
+        assert_eq!((ring[0], ring.get(3)), (2, None));
+
+        *ring.last_mut().unwrap() = 40;
+        assert_eq!(ring.range(1..).copied().collect::<Vec<_>>(), vec![3, 40]);
+
+        ring.set_capacity(1);
+        assert_eq!(ring.iter().copied().collect::<Vec<_>>(), vec![40]);
+        assert_eq!(ring.capacity(), 1);
+    }
+}
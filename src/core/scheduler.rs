@@ -2,12 +2,63 @@
 
 use super::{Graph, GraphTaskStatus};
 use anyhow::Result;
-use std::collections::HashSet;
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// What the scheduler does when a task fails
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FailurePolicy {
+    /// Stop scheduling new tasks after the first failure
+    FailFast,
+    /// Keep running branches that don't depend on the failed task
+    #[default]
+    ContinueOthers,
+}
+
+/// What `Scheduler::reconcile` changed
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReconcileReport {
+    /// Tasks new in the graph
+    pub added: Vec<String>,
+    /// Tasks gone from the graph and dropped
+    pub removed: Vec<String>,
+    /// Tasks whose definition changed
+    pub changed: Vec<String>,
+    /// Tasks gone from the graph but kept because they are still running
+    pub kept_running: Vec<String>,
+}
+
+impl ReconcileReport {
+    /// Whether the reload changed nothing
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty() && self.kept_running.is_empty()
+    }
+}
+
+impl fmt::Display for ReconcileReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} added, {} changed, {} removed",
+            self.added.len(),
+            self.changed.len(),
+            self.removed.len()
+        )?;
+        if !self.kept_running.is_empty() {
+            write!(f, ", {} kept while running", self.kept_running.len())?;
+        }
+        Ok(())
+    }
+}
 
 /// Task scheduler with dependency resolution
 pub struct Scheduler {
     graph: Graph,
-    running: HashSet<String>,
+    /// Ordered so `get_running` is stable
+    running: BTreeSet<String>,
+    policy: FailurePolicy,
+    halted: bool,
+    max_parallel: Option<usize>,
 }
 
 impl Scheduler {
@@ -15,19 +66,74 @@ impl Scheduler {
     pub fn new(graph: Graph) -> Self {
         Self {
             graph,
-            running: HashSet::new(),
+            running: BTreeSet::new(),
+            policy: FailurePolicy::default(),
+            halted: false,
+            max_parallel: None,
         }
     }
 
+    /// Schedule `graph` from scratch, keeping the failure policy and
+    /// parallel limit
+    pub fn set_graph(&mut self, graph: Graph) {
+        *self = Self {
+            policy: self.policy,
+            max_parallel: self.max_parallel,
+            ..Self::new(graph)
+        };
+    }
+
+    /// Set the failure policy
+    pub fn set_failure_policy(&mut self, policy: FailurePolicy) {
+        self.policy = policy;
+    }
+
+    /// Get the failure policy
+    pub fn failure_policy(&self) -> FailurePolicy {
+        self.policy
+    }
+
+    /// Limit how many tasks run at once (`None` = unlimited)
+    pub fn set_max_parallel(&mut self, limit: Option<usize>) {
+        self.max_parallel = limit;
+    }
+
+    /// Get the concurrency limit
+    pub fn max_parallel(&self) -> Option<usize> {
+        self.max_parallel
+    }
+
+    /// Whether a failure under `FailFast` has stopped scheduling
+    pub fn is_halted(&self) -> bool {
+        self.halted
+    }
+
+    /// Stop scheduling new tasks, as after a `FailFast` failure
+    pub fn halt(&mut self) {
+        self.halted = true;
+    }
+
     /// Schedule next tasks to run
+    ///
+    /// Highest priority first, then by ID; capped so that no more than
+    /// `max_parallel` tasks run at once.
     pub fn schedule_next(&mut self) -> Vec<String> {
-        let ready = self.graph.get_ready_tasks();
-        
-        // Filter out tasks that are already running
-        ready
+        if self.halted {
+            return Vec::new();
+        }
+
+        // Filter out tasks that are already running; the graph has sorted them
+        let mut ready: Vec<String> = self
+            .graph
+            .get_ready_tasks()
             .into_iter()
             .filter(|id| !self.running.contains(id))
-            .collect()
+            .collect();
+
+        if let Some(limit) = self.max_parallel {
+            ready.truncate(limit.saturating_sub(self.running.len()));
+        }
+        ready
     }
 
     /// Mark task as started
@@ -37,6 +143,11 @@ impl Scheduler {
         Ok(())
     }
 
+    /// Mark a running task as ready, letting its dependents start
+    pub fn mark_ready(&mut self, task_id: &str) {
+        self.graph.mark_ready(task_id);
+    }
+
     /// Mark task as completed
     pub fn mark_done(&mut self, task_id: &str) -> Result<()> {
         self.graph.update_task_status(task_id, GraphTaskStatus::Done)?;
@@ -45,13 +156,77 @@ impl Scheduler {
     }
 
     /// Mark task as failed
+    ///
+    /// Under `FailFast` this halts scheduling, unless the task is marked
+    /// `allow_failure`.
     pub fn mark_failed(&mut self, task_id: &str) -> Result<()> {
         self.graph.update_task_status(task_id, GraphTaskStatus::Failed)?;
         self.running.remove(task_id);
+
+        let allowed = self.graph.get_task(task_id).is_some_and(|t| t.allow_failure);
+        if self.policy == FailurePolicy::FailFast && !allowed {
+            self.halted = true;
+        }
+        Ok(())
+    }
+
+    /// Reset a task to pending so it is scheduled again
+    pub fn mark_pending(&mut self, task_id: &str) -> Result<()> {
+        self.graph.update_task_status(task_id, GraphTaskStatus::Pending)?;
+        self.running.remove(task_id);
         Ok(())
     }
 
-    /// Get currently running tasks
+    /// Merge a freshly loaded graph into the running one
+    ///
+    /// - Running tasks are never disturbed: they keep their status, and a
+    ///   running task missing from `graph` is kept until a reload after it
+    ///   has finished.
+    /// - Tasks whose definition is unchanged keep their current status.
+    /// - Changed tasks that aren't running take the status written in
+    ///   `graph` (usually pending, so they run again); a running task takes
+    ///   the new definition for its next run.
+    /// - New tasks are added; removed tasks that aren't running are dropped.
+    pub fn reconcile(&mut self, mut graph: Graph) -> ReconcileReport {
+        let mut report = ReconcileReport::default();
+        let old_tasks = std::mem::take(&mut self.graph.tasks);
+
+        for id in graph.tasks.keys() {
+            if !old_tasks.contains_key(id) {
+                report.added.push(id.clone());
+            }
+        }
+
+        for (id, old) in old_tasks {
+            let running = self.running.contains(&id);
+            match graph.tasks.get_mut(&id) {
+                Some(task) if task.same_definition(&old) => task.status = old.status,
+                Some(task) => {
+                    if running {
+                        task.status = old.status;
+                    }
+                    report.changed.push(id);
+                }
+                None if running => {
+                    graph.tasks.insert(id.clone(), old);
+                    report.kept_running.push(id);
+                }
+                None => report.removed.push(id),
+            }
+        }
+
+        graph.ready = std::mem::take(&mut self.graph.ready);
+        graph.ready.retain(|id| self.running.contains(id));
+        self.graph = graph;
+
+        report.added.sort();
+        report.removed.sort();
+        report.changed.sort();
+        report.kept_running.sort();
+        report
+    }
+
+    /// Get currently running tasks, by ID
     pub fn get_running(&self) -> Vec<String> {
         self.running.iter().cloned().collect()
     }
@@ -61,13 +236,32 @@ impl Scheduler {
         &self.graph
     }
 
-    /// Check if all tasks are done
+    /// Tasks that haven't finished (pending, or in progress)
+    pub fn unfinished_count(&self) -> usize {
+        self.graph
+            .all_tasks()
+            .values()
+            .filter(|task| task.status != GraphTaskStatus::Done && task.status != GraphTaskStatus::Failed)
+            .count()
+    }
+
+    /// Check if the run is stuck: nothing running, nothing ready, but tasks
+    /// remain (blocked by failed or unsatisfiable dependencies)
+    pub fn is_deadlocked(&self) -> bool {
+        !self.halted
+            && self.running.is_empty()
+            && self.graph.get_ready_tasks().is_empty()
+            && self.unfinished_count() > 0
+    }
+
+    /// Check if all tasks are done, or nothing more will run after a halt
     pub fn all_done(&self) -> bool {
         self.running.is_empty()
-            && self
-                .graph
-                .all_tasks()
-                .values()
-                .all(|task| task.status == GraphTaskStatus::Done || task.status == GraphTaskStatus::Failed)
+            && (self.halted
+                || self
+                    .graph
+                    .all_tasks()
+                    .values()
+                    .all(|task| task.status == GraphTaskStatus::Done || task.status == GraphTaskStatus::Failed))
     }
 }
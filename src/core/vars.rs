@@ -0,0 +1,187 @@
+//! Command variables - `${project}`, `${port}`, `${cwd}` and `${env:VAR}`
+//! in a task's command, filled in when the task is spawned
+//!
+//! Values are quoted for the shell that runs the command, so each variable
+//! is one word however many spaces or `;`s it holds. `$${` stands for a
+//! literal `${`, for shell syntax such as `$${HOME}`.
+
+use super::pty::shell_quote;
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+
+/// Values for the variables of one task's command
+#[derive(Debug, Clone)]
+pub struct CommandVars {
+    /// Name of the task's project
+    pub project: String,
+    /// Port allocated to the project, if any
+    pub port: Option<u16>,
+    /// Working directory the task runs in
+    pub cwd: PathBuf,
+}
+
+impl CommandVars {
+    /// Variables for a project, running in the current directory
+    pub fn new(project: &str, port: Option<u16>) -> Result<Self> {
+        Ok(Self {
+            project: project.to_string(),
+            port,
+            cwd: std::env::current_dir().context("Cannot read the current directory")?,
+        })
+    }
+
+    /// Replace every `${...}` in `command` with its value, quoted for `shell`
+    ///
+    /// Unknown variables, `${port}` without an allocated port and unset
+    /// `${env:VAR}`s are errors rather than being passed to the shell.
+    pub fn interpolate(&self, command: &str, shell: &str) -> Result<String> {
+        let mut out = String::with_capacity(command.len());
+        let mut rest = command;
+
+        while let Some(start) = rest.find("${") {
+            if rest[..start].ends_with('$') {
+                out.push_str(&rest[..start - 1]);
+                out.push_str("${");
+                rest = &rest[start + 2..];
+                continue;
+            }
+            out.push_str(&rest[..start]);
+
+            let after = &rest[start + 2..];
+            let Some(end) = after.find('}') else {
+                anyhow::bail!("Unclosed '${{' in command: {}", command);
+            };
+            let name = &after[..end];
+            let value = self.resolve(name)?;
+            out.push_str(&shell_quote(shell, &value).with_context(|| format!("Cannot use '${{{}}}'", name))?);
+            rest = &after[end + 1..];
+        }
+
+        out.push_str(rest);
+        Ok(out)
+    }
+
+    fn resolve(&self, name: &str) -> Result<String> {
+        if let Some(var) = name.strip_prefix("env:") {
+            return std::env::var(var)
+                .map_err(|_| anyhow::anyhow!("Environment variable '{}' is not set (used as '${{{}}}')", var, name));
+        }
+        match name {
+            "project" => Ok(self.project.clone()),
+            "port" => self
+                .port
+                .map(|p| p.to_string())
+                .ok_or_else(|| anyhow::anyhow!("'${{port}}' used but project '{}' has no allocated port", self.project)),
+            "cwd" => Ok(self.cwd.display().to_string()),
+            _ => Err(unknown_variable(name)),
+        }
+    }
+}
+
+/// Names of the `${...}` variables in a command that can never resolve
+///
+/// Used by `gidterm validate`; unset environment variables are only caught
+/// at spawn time.
+pub(crate) fn unknown_variables(command: &str) -> Vec<String> {
+    let mut unknown = Vec::new();
+    let mut rest = command;
+
+    while let Some(start) = rest.find("${") {
+        let escaped = rest[..start].ends_with('$');
+        let after = &rest[start + 2..];
+        let Some(end) = after.find('}') else {
+            break;
+        };
+        let name = &after[..end];
+        let known = matches!(name, "project" | "port" | "cwd")
+            || name.strip_prefix("env:").is_some_and(|v| !v.is_empty());
+        if !escaped && !known {
+            unknown.push(name.to_string());
+        }
+        rest = &after[end + 1..];
+    }
+
+    unknown
+}
+
+fn unknown_variable(name: &str) -> anyhow::Error {
+    anyhow::anyhow!(
+        "Unknown variable '${{{}}}' in command (use project, port, cwd or env:NAME; write $${{ for a literal ${{)",
+        name
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars() -> CommandVars {
+        CommandVars {
+            project: "api".to_string(),
+            port: Some(3001),
+            cwd: PathBuf::from("/work/api"),
+        }
+    }
+
+    #[test]
+    fn test_interpolate() {
+        let vars = vars();
+        assert_eq!(
+            vars.interpolate("serve --name ${project} --port ${port} --root ${cwd}", "sh").unwrap(),
+            "serve --name api --port 3001 --root /work/api"
+        );
+        assert_eq!(vars.interpolate("echo $PORT", "sh").unwrap(), "echo $PORT");
+        assert_eq!(vars.interpolate("echo $${HOME}", "sh").unwrap(), "echo ${HOME}");
+
+        std::env::set_var("GIDTERM_VARS_TEST", "staging");
+        assert_eq!(vars.interpolate("deploy ${env:GIDTERM_VARS_TEST}", "sh").unwrap(), "deploy staging");
+    }
+
+    #[test]
+    fn test_interpolate_quotes_values() {
+        let vars = CommandVars {
+            cwd: PathBuf::from("/work/my api"),
+            ..vars()
+        };
+        assert_eq!(vars.interpolate("ls ${cwd}", "sh").unwrap(), "ls '/work/my api'");
+        assert_eq!(vars.interpolate("ls ${cwd}", "pwsh").unwrap(), "ls '/work/my api'");
+        assert_eq!(vars.interpolate("ls ${cwd}", "cmd").unwrap(), "ls \"/work/my api\"");
+
+        std::env::set_var("GIDTERM_VARS_QUOTE_TEST", "x; touch pwned");
+        assert_eq!(
+            vars.interpolate("echo ${env:GIDTERM_VARS_QUOTE_TEST}", "sh").unwrap(),
+            "echo 'x; touch pwned'"
+        );
+
+        std::env::set_var("GIDTERM_VARS_QUOTE_TEST", "100%");
+        let err = vars.interpolate("echo ${env:GIDTERM_VARS_QUOTE_TEST}", "cmd").unwrap_err();
+        assert_eq!(err.to_string(), "Cannot use '${env:GIDTERM_VARS_QUOTE_TEST}'");
+    }
+
+    #[test]
+    fn test_interpolate_errors() {
+        let vars = vars();
+        let err = vars.interpolate("echo ${prot}", "sh").unwrap_err();
+        assert!(err.to_string().starts_with("Unknown variable '${prot}'"));
+
+        let err = vars.interpolate("echo ${env:GIDTERM_VARS_UNSET}", "sh").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Environment variable 'GIDTERM_VARS_UNSET' is not set (used as '${env:GIDTERM_VARS_UNSET}')"
+        );
+
+        let no_port = CommandVars { port: None, ..vars };
+        let err = no_port.interpolate("serve ${port}", "sh").unwrap_err();
+        assert_eq!(err.to_string(), "'${port}' used but project 'api' has no allocated port");
+
+        assert!(no_port.interpolate("echo ${project", "sh").is_err());
+    }
+
+    #[test]
+    fn test_unknown_variables() {
+        assert_eq!(
+            unknown_variables("run ${project} ${prot} $${HOME} ${env:X} ${env:}"),
+            vec!["prot".to_string(), "env:".to_string()]
+        );
+    }
+}
@@ -0,0 +1,111 @@
+//! Graph file watching for `gidterm run --watch`
+
+use super::Graph;
+use anyhow::{Context, Result};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::time::{Duration, Instant};
+
+/// How long the graph files must be quiet before a change is reloaded, so
+/// a half-written file isn't parsed
+pub const RELOAD_SETTLE: Duration = Duration::from_millis(200);
+
+/// Watches a graph file, and the YAML files next to it that it may include
+pub struct GraphWatcher {
+    path: PathBuf,
+    rx: Receiver<notify::Result<Event>>,
+    changed_at: Option<Instant>,
+    // Dropping the watcher stops the events
+    _watcher: RecommendedWatcher,
+}
+
+impl GraphWatcher {
+    /// Start watching the directory tree holding `path`
+    ///
+    /// The directory rather than the file is watched, since editors often
+    /// save by replacing the file.
+    pub fn new(path: &Path) -> Result<Self> {
+        let path = path
+            .canonicalize()
+            .with_context(|| format!("Cannot watch {}", path.display()))?;
+        let dir = path.parent().unwrap_or(Path::new("/")).to_path_buf();
+
+        let (tx, rx) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(move |event| {
+            let _ = tx.send(event);
+        })
+        .context("Cannot start the file watcher")?;
+        watcher
+            .watch(&dir, RecursiveMode::Recursive)
+            .with_context(|| format!("Cannot watch {}", dir.display()))?;
+
+        Ok(Self {
+            path,
+            rx,
+            changed_at: None,
+            _watcher: watcher,
+        })
+    }
+
+    /// The reloaded graph, once a change has settled
+    ///
+    /// Returns `None` while nothing has changed or the files are still being
+    /// written. A graph that fails to load is returned as an error once;
+    /// the next change tries again.
+    pub fn poll(&mut self) -> Option<Result<Graph>> {
+        while let Ok(event) = self.rx.try_recv() {
+            match event {
+                Ok(event) if is_graph_change(&event) => self.changed_at = Some(Instant::now()),
+                Ok(_) => {}
+                Err(e) => log::warn!("File watcher error: {}", e),
+            }
+        }
+
+        if self.changed_at?.elapsed() < RELOAD_SETTLE {
+            return None;
+        }
+        self.changed_at = None;
+        Some(Graph::from_file(&self.path))
+    }
+}
+
+/// Whether an event touches a YAML file, the only kind a graph can include
+fn is_graph_change(event: &Event) -> bool {
+    !matches!(event.kind, EventKind::Access(_))
+        && event
+            .paths
+            .iter()
+            .any(|p| p.extension().is_some_and(|ext| ext == "yml" || ext == "yaml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_watcher_reloads_changed_graph() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("graph.yml");
+        std::fs::write(&path, "tasks:\n  build:\n    description: b\n").unwrap();
+
+        let mut watcher = GraphWatcher::new(&path).unwrap();
+        assert!(watcher.poll().is_none());
+
+        // Unrelated files don't trigger a reload
+        std::fs::write(dir.path().join("session.json"), "{}").unwrap();
+        std::fs::write(&path, "tasks:\n  build:\n    description: b\n  test:\n    description: t\n").unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let graph = loop {
+            if let Some(graph) = watcher.poll() {
+                break graph.unwrap();
+            }
+            assert!(Instant::now() < deadline, "no reload");
+            std::thread::sleep(Duration::from_millis(20));
+        };
+        assert_eq!(graph.tasks.len(), 2);
+        assert!(watcher.poll().is_none());
+    }
+}
@@ -0,0 +1,234 @@
+//! Library run API - drive a graph to completion without the TUI
+//!
+//! `GidTermEngine` is the headless run loop packaged for embedders: it
+//! starts ready tasks, processes their events and resolves with a
+//! `RunSummary` once nothing is left to run (or the graph deadlocks).
+//!
+//! ```no_run
+//! # async fn demo() -> anyhow::Result<()> {
+//! use gidterm::ai::GidEvent;
+//! use gidterm::{GidTermEngine, Graph};
+//!
+//! let graph = Graph::from_file(std::path::Path::new("gid.yml"))?;
+//! let summary = GidTermEngine::new(graph)
+//!     .on_event(|event| {
+//!         if let GidEvent::TaskCompleted { task_id, .. } = event {
+//!             println!("{} done", task_id);
+//!         }
+//!     })
+//!     .run_to_completion()
+//!     .await?;
+//! print!("{}", summary);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The future owns the run: dropping it before it resolves kills every
+//! task it started.
+//!
+//! `gidterm run --headless` is this engine plus control commands
+//! ([`with_commands`](GidTermEngine::with_commands)), `--watch`
+//! ([`with_watcher`](GidTermEngine::with_watcher)) and Ctrl-C
+//! ([`stop_on`](GidTermEngine::stop_on)).
+
+use crate::ai::{CommandRequest, EventStream, GidEvent};
+use crate::app::{App, RunSummary};
+use crate::core::{Graph, GraphWatcher};
+use crate::workspace::Workspace;
+use anyhow::Result;
+use std::future::Future;
+use std::pin::Pin;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast::{self, error::TryRecvError};
+use tokio::sync::mpsc;
+
+/// Event buffer for headless runs, which drain output between polls
+pub const HEADLESS_EVENT_CAPACITY: usize = 16384;
+
+type EventCallback = Box<dyn FnMut(&GidEvent) + Send>;
+type StopSignal = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// Runs a graph or workspace headlessly
+pub struct GidTermEngine {
+    app: App,
+    on_event: Option<EventCallback>,
+    commands: Option<mpsc::Receiver<CommandRequest>>,
+    /// Graph file watcher and the targets a reloaded graph is narrowed to
+    watcher: Option<(GraphWatcher, Vec<String>)>,
+    stop: Option<StopSignal>,
+    stopped: bool,
+}
+
+impl GidTermEngine {
+    /// Engine for a single graph
+    pub fn new(graph: Graph) -> Self {
+        Self::from_app(App::new(graph))
+    }
+
+    /// Engine for every project in a workspace
+    pub fn from_workspace(workspace: &Workspace) -> Self {
+        Self::from_app(App::from_workspace(workspace))
+    }
+
+    /// Engine around an already configured app
+    pub fn from_app(mut app: App) -> Self {
+        app.events = EventStream::new(HEADLESS_EVENT_CAPACITY);
+        app.quit_on_deadlock = true;
+        Self {
+            app,
+            on_event: None,
+            commands: None,
+            watcher: None,
+            stop: None,
+            stopped: false,
+        }
+    }
+
+    /// The app being driven
+    pub fn app(&self) -> &App {
+        &self.app
+    }
+
+    /// The app being driven, e.g. to set a failure policy or job limit
+    pub fn app_mut(&mut self) -> &mut App {
+        &mut self.app
+    }
+
+    /// Answer control commands (from a [`ControlHandle`](crate::ai::ControlHandle)
+    /// on this engine's events) between ticks
+    pub fn with_commands(mut self, commands: mpsc::Receiver<CommandRequest>) -> Self {
+        self.commands = Some(commands);
+        self
+    }
+
+    /// Merge changes to the watched graph file into the run, narrowed to
+    /// `targets` and their dependencies unless it is empty
+    pub fn with_watcher(mut self, watcher: GraphWatcher, targets: Vec<String>) -> Self {
+        self.watcher = Some((watcher, targets));
+        self
+    }
+
+    /// End the run early, killing its tasks, once `signal` resolves
+    /// (e.g. on Ctrl-C)
+    pub fn stop_on(mut self, signal: impl Future<Output = ()> + Send + 'static) -> Self {
+        self.stop = Some(Box::pin(signal));
+        self
+    }
+
+    /// Whether the [`stop_on`](Self::stop_on) signal ended the run
+    pub fn stopped(&self) -> bool {
+        self.stopped
+    }
+
+    /// Call `callback` for every event, in order, while the run is driven
+    pub fn on_event(mut self, callback: impl FnMut(&GidEvent) + Send + 'static) -> Self {
+        self.on_event = Some(Box::new(callback));
+        self
+    }
+
+    /// A stream of the run's events, for consumers on another task
+    pub fn subscribe(&self) -> broadcast::Receiver<GidEvent> {
+        self.app.events.subscribe()
+    }
+
+    /// Drive the graph until every task that can run has finished or the
+    /// run deadlocks, then stop, save the session and summarize
+    ///
+    /// Fails only if a task couldn't be spawned. Dropping the future
+    /// before it resolves kills the running tasks.
+    pub async fn run_to_completion(mut self) -> Result<RunSummary> {
+        self.run().await
+    }
+
+    /// Like [`run_to_completion`](Self::run_to_completion), but keeps the
+    /// engine so the finished app can still be inspected
+    pub async fn run(&mut self) -> Result<RunSummary> {
+        self.drive().await?;
+        Ok(self.finish())
+    }
+
+    async fn drive(&mut self) -> Result<()> {
+        let mut events = self.app.events.subscribe();
+        self.app.start_ready_tasks().await?;
+
+        loop {
+            let backlog = self.app.process_events();
+            if let Some(commands) = self.commands.as_mut() {
+                self.app.handle_command_requests(commands).await;
+            }
+            if let Some((watcher, targets)) = self.watcher.as_mut() {
+                self.app.apply_graph_changes(watcher, targets);
+            }
+            self.app.start_ready_tasks().await?;
+            self.dispatch(&mut events);
+
+            let idle = !backlog
+                && self.app.scheduler.get_running().is_empty()
+                && self.app.scheduler.schedule_next().is_empty();
+            if self.app.should_quit || idle {
+                return Ok(());
+            }
+
+            let tick = if backlog { Duration::ZERO } else { self.app.tick.interval(Instant::now()) };
+            let stop = async {
+                match self.stop.as_mut() {
+                    Some(signal) => signal.await,
+                    None => std::future::pending().await,
+                }
+            };
+            tokio::select! {
+                _ = tokio::time::sleep(tick) => {}
+                _ = stop => {
+                    log::warn!("Interrupted, stopping tasks");
+                    self.stopped = true;
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+
+    /// Hand queued events to the callback
+    fn dispatch(&mut self, events: &mut broadcast::Receiver<GidEvent>) {
+        let Some(callback) = self.on_event.as_mut() else {
+            return;
+        };
+
+        loop {
+            match events.try_recv() {
+                Ok(event) => callback(&event),
+                Err(TryRecvError::Lagged(missed)) => {
+                    log::warn!("Event callback too slow, skipped {} event(s)", missed)
+                }
+                Err(TryRecvError::Empty) | Err(TryRecvError::Closed) => break,
+            }
+        }
+    }
+
+    fn finish(&mut self) -> RunSummary {
+        log::info!("Shutting down...");
+        self.shutdown();
+        self.app.session.end();
+        self.app.session_saver.mark_dirty();
+        if let Err(e) = self.app.session_saver.flush(&self.app.session) {
+            log::warn!("Failed to save final session: {}", e);
+        }
+        self.app.summary()
+    }
+
+    /// Kill running tasks and release their ports
+    fn shutdown(&mut self) {
+        self.app.executor.stop_all();
+        match self.app.port_manager.release_all() {
+            Ok(released) if released > 0 => log::info!("Released {} port allocation(s)", released),
+            Ok(_) => {}
+            Err(e) => log::warn!("Failed to release ports: {}", e),
+        }
+    }
+}
+
+impl Drop for GidTermEngine {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
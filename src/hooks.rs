@@ -0,0 +1,135 @@
+//! Lifecycle hooks - shell commands run when a task succeeds or fails
+//!
+//! Hooks are fire-and-forget: nothing waits for them, and their output goes
+//! to the log rather than to a task pane.
+
+use crate::core::{find_shell, shell_flags, DEFAULT_SHELL};
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{BufRead, BufReader, Read};
+use std::process::{Command, Stdio};
+
+/// Which hook of a task to run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookTrigger {
+    Success,
+    Failure,
+}
+
+impl fmt::Display for HookTrigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HookTrigger::Success => write!(f, "on_success"),
+            HookTrigger::Failure => write!(f, "on_failure"),
+        }
+    }
+}
+
+/// Start a hook in the background
+///
+/// Runs `command` under the task's `shell` like the task itself (the
+/// default shell, `sh -c` or `cmd /C` on Windows, if `None`) with the task's
+/// environment, plus `GIDTERM_TASK` (the task ID) and `GIDTERM_HOOK`
+/// (`on_success` or `on_failure`).
+pub fn spawn_hook(
+    task_id: &str,
+    trigger: HookTrigger,
+    shell: Option<&str>,
+    command: &str,
+    env: &HashMap<String, String>,
+) -> Result<()> {
+    log::info!("Running {} hook for {}: {}", trigger, task_id, command);
+
+    let shell = match shell {
+        Some(shell) => {
+            find_shell(shell).with_context(|| format!("Cannot run {} hook for {}", trigger, task_id))?;
+            shell
+        }
+        None => DEFAULT_SHELL,
+    };
+    let mut child = Command::new(shell)
+        .args(shell_flags(shell))
+        .arg(command)
+        .envs(env)
+        .env("GIDTERM_TASK", task_id)
+        .env("GIDTERM_HOOK", trigger.to_string())
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to start {} hook for {}", trigger, task_id))?;
+
+    let label = format!("{} {}", task_id, trigger);
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+
+    std::thread::spawn(move || {
+        let stderr_label = label.clone();
+        let stderr_reader = stderr.map(|err| std::thread::spawn(move || log_lines(&stderr_label, err)));
+        if let Some(out) = stdout {
+            log_lines(&label, out);
+        }
+        if let Some(reader) = stderr_reader {
+            let _ = reader.join();
+        }
+
+        match child.wait() {
+            Ok(status) if status.success() => log::info!("Hook {} finished", label),
+            Ok(status) => log::warn!("Hook {} failed: {}", label, status),
+            Err(e) => log::warn!("Hook {} could not be waited on: {}", label, e),
+        }
+    });
+
+    Ok(())
+}
+
+fn log_lines(label: &str, output: impl Read) {
+    for line in BufReader::new(output).lines().map_while(Result::ok) {
+        log::info!("[hook {}] {}", label, line);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+    use std::time::{Duration, Instant};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_spawn_hook_env() {
+        let dir = TempDir::new().unwrap();
+        let out = dir.path().join("hook.txt");
+        let env = HashMap::from([("PORT".to_string(), "3001".to_string())]);
+
+        let command = format!("echo \"$GIDTERM_HOOK $GIDTERM_TASK $PORT\" > {}", out.display());
+        spawn_hook("build", HookTrigger::Failure, None, &command, &env).unwrap();
+        assert_eq!(wait_for_line(&out), "on_failure build 3001\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_spawn_hook_uses_task_shell() {
+        let dir = TempDir::new().unwrap();
+        let out = dir.path().join("hook.txt");
+
+        // `[[ ]]` is bash-only, so this only writes the file under bash
+        let command = format!("[[ -n $BASH_VERSION ]] && echo bash > {}", out.display());
+        spawn_hook("build", HookTrigger::Success, Some("bash"), &command, &HashMap::new()).unwrap();
+        assert_eq!(wait_for_line(&out), "bash\n");
+
+        let err = spawn_hook("build", HookTrigger::Success, Some("gidterm-no-such-shell"), "true", &HashMap::new())
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Cannot run on_success hook for build");
+    }
+
+    /// Contents of `path` once the detached hook has written a full line
+    fn wait_for_line(path: &Path) -> String {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !std::fs::read_to_string(path).is_ok_and(|s| s.ends_with('\n')) && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        std::fs::read_to_string(path).unwrap()
+    }
+}
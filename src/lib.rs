@@ -6,7 +6,11 @@
 pub mod agents;
 pub mod ai;
 pub mod app;
+pub mod config;
 pub mod core;
+pub mod engine;
+pub mod hooks;
+pub mod logs;
 pub mod notifications;
 pub mod ports;
 pub mod semantic;
@@ -19,11 +23,16 @@ pub use agents::{
     AgentDetector, AgentManager, AgentProcess, AgentRuntimeStatus, AgentState, AgentStatusParser,
     AgentTask, AgentTaskStatus, AgentType,
 };
-pub use app::App;
-pub use core::{Executor, Graph, GraphTaskStatus, PTYHandle, Scheduler, TaskEvent};
+pub use app::{App, RunSummary};
+pub use config::Config;
+pub use core::{
+    CommandVars, Executor, FailurePolicy, Graph, GraphTaskStatus, GraphWatcher, PTYHandle, ReconcileReport, RingBuffer, Scheduler,
+    TaskEvent, EVENT_CHANNEL_CAPACITY,
+};
+pub use engine::GidTermEngine;
 pub use notifications::{NotificationConfig, NotificationEvent, NotificationManager};
 pub use ports::{PortEntry, PortManager, PortRegistry, PortStatus};
-pub use session::{Session, TaskHistory, TaskRun, TaskStatus};
+pub use session::{best_run, BestRun, Session, SessionDiff, SessionSaver, TaskDiff, TaskHistory, TaskRun, TaskStatus, UiState};
 pub use workspace::{Project, Workspace};
 
 /// Result type alias
@@ -0,0 +1,127 @@
+//! Task output logs - keep output on disk beyond the in-memory buffer
+
+use crate::core::task_file_stem;
+use anyhow::Result;
+use serde::Deserialize;
+use std::fs::{self, File, OpenOptions};
+use std::io::{LineWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// Output lines kept in memory per task unless configured otherwise
+pub const DEFAULT_BUFFER_LINES: usize = 2000;
+
+const LOGS_DIR: &str = ".gidterm/logs";
+
+/// Output settings, the `[output]` table of config.toml (see [`Config`](crate::config::Config))
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct OutputConfig {
+    /// Lines of output kept in memory per task
+    pub buffer_lines: Option<usize>,
+    /// Write every task's output to `.gidterm/logs/<session>/<task>.log`
+    #[serde(default)]
+    pub spill: bool,
+    /// Lines of output kept per task run in session files
+    pub session_lines: Option<usize>,
+    /// Mirror each task's output to `<dir>/<task>.fifo` while it runs (Unix)
+    pub task_fifo_dir: Option<PathBuf>,
+}
+
+/// Directory holding a session's task logs
+pub fn session_log_dir(session_id: &str) -> PathBuf {
+    Path::new(LOGS_DIR).join(session_id)
+}
+
+/// Log file for a task within a session's log directory, named by
+/// [`task_file_stem`]
+pub fn log_path(dir: &Path, task_id: &str) -> PathBuf {
+    dir.join(format!("{}.log", task_file_stem(task_id)))
+}
+
+/// Append-only log of one task's output lines
+///
+/// The latest line is held back until the next one arrives, since a `\r`
+/// redraw may still replace it; it is written when the log is dropped.
+pub struct TaskLog {
+    writer: LineWriter<File>,
+    pending: Option<String>,
+}
+
+impl TaskLog {
+    /// Open (or continue, for a rerun) a task's log in `dir`
+    pub fn open(dir: &Path, task_id: &str) -> Result<Self> {
+        fs::create_dir_all(dir)?;
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(log_path(dir, task_id))?;
+        Ok(Self {
+            writer: LineWriter::new(file),
+            pending: None,
+        })
+    }
+
+    /// Record a new line, writing out the one before it
+    pub fn push(&mut self, line: &str) -> Result<()> {
+        if let Some(previous) = self.pending.replace(line.to_string()) {
+            writeln!(self.writer, "{}", previous)?;
+        }
+        Ok(())
+    }
+
+    /// Redraw the latest line in place
+    pub fn replace(&mut self, line: &str) {
+        self.pending = Some(line.to_string());
+    }
+}
+
+impl Drop for TaskLog {
+    fn drop(&mut self) {
+        if let Some(last) = self.pending.take() {
+            let _ = writeln!(self.writer, "{}", last);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_task_log_writes_final_lines() {
+        let dir = TempDir::new().unwrap();
+        {
+            let mut log = TaskLog::open(dir.path(), "api:build").unwrap();
+            log.push("start").unwrap();
+            log.push(" 10%").unwrap();
+            log.replace("100%");
+            log.push("done").unwrap();
+        }
+
+        let path = log_path(dir.path(), "api:build");
+        assert_eq!(path.file_name().unwrap(), "api%3Abuild.log");
+        assert_eq!(fs::read_to_string(&path).unwrap(), "start\n100%\ndone\n");
+
+        // A rerun appends to the same file
+        TaskLog::open(dir.path(), "api:build").unwrap().push("again").unwrap();
+        assert!(fs::read_to_string(&path).unwrap().ends_with("done\nagain\n"));
+    }
+
+    #[test]
+    fn test_output_config() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "[output]\nbuffer_lines = 50000\nspill = true\nsession_lines = 200\n").unwrap();
+
+        let none = dir.path().join("none.toml");
+        let config = Config::load_layers(&path, &none).unwrap().output;
+        assert_eq!(config.buffer_lines, Some(50000));
+        assert!(config.spill);
+        assert_eq!(config.session_lines, Some(200));
+
+        let missing = Config::load_layers(&none, &none).unwrap().output;
+        assert_eq!(missing.buffer_lines, None);
+        assert!(!missing.spill);
+    }
+}
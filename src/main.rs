@@ -1,17 +1,30 @@
 //! GidTerm CLI - Graph-Driven Semantic Terminal Controller
 
-use anyhow::Result;
-use clap::{Parser, Subcommand};
-use gidterm::app::{App, ViewMode};
-use gidterm::core::Graph;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use clap::{ArgGroup, Args, Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+use gidterm::ai::{CommandRequest, ControlHandle, ControlMode, GidEvent};
+use gidterm::app::{App, RunSummary, ViewMode};
+use gidterm::core::{
+    find_shell, shell_flags, Diagnostic, FailurePolicy, Graph, GraphCache, GraphTaskStatus, GraphWatcher, Task,
+    DEFAULT_SHELL,
+};
+use gidterm::config::Config;
+use gidterm::GidTermEngine;
 use gidterm::ports::PortRegistry;
+use gidterm::logs;
+use gidterm::session::{self, Session, TaskStatus};
 use gidterm::ui::{
-    render_comparison_view, render_graph_view, render_live_dashboard, render_project_overview,
-    render_terminal_view, TUI,
+    color_diagnostics, display_width, pad_to_width, render_comparison_view, render_graph_view, render_live_dashboard,
+    render_project_overview, render_terminal_view, render_toast, Theme, TUI,
 };
-use gidterm::workspace::Workspace;
-use std::path::PathBuf;
-use std::time::Duration;
+use gidterm::workspace::{Workspace, DEFAULT_DISCOVERY_DEPTH};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
 
 #[derive(Parser)]
 #[command(name = "gidterm", version, about = "Graph-Driven Semantic Terminal Controller")]
@@ -20,21 +33,127 @@ struct Cli {
     command: Option<Commands>,
 }
 
+#[derive(Args, Default)]
+struct RunArgs {
+    /// Only run these tasks and their dependencies (default: all tasks)
+    targets: Vec<String>,
+
+    /// Path to graph YAML file (auto-detects if not specified)
+    #[arg(short, long)]
+    graph: Option<PathBuf>,
+
+    /// Workspace mode: discover and run all projects
+    #[arg(short, long)]
+    workspace: bool,
+
+    /// Workspace mode: only load these projects (names or globs like `svc-*`)
+    #[arg(long, value_name = "LIST", value_delimiter = ',')]
+    projects: Vec<String>,
+
+    /// How many directory levels workspace mode searches for projects
+    /// (default: 1, immediate subdirectories)
+    #[arg(long, value_name = "N")]
+    depth: Option<usize>,
+
+    /// Skip tasks that completed in the previous session
+    #[arg(long)]
+    resume: bool,
+
+    /// Serve the control API over HTTP (e.g. 127.0.0.1:7777)
+    #[arg(long, value_name = "ADDR")]
+    serve: Option<SocketAddr>,
+
+    /// Let --serve listen on a non-loopback address. The API has no
+    /// authentication: anyone who can reach it can run commands in your tasks
+    #[arg(long, requires = "serve")]
+    allow_remote: bool,
+
+    /// Accept control commands on a Unix domain socket
+    #[arg(long, value_name = "PATH")]
+    control_sock: Option<PathBuf>,
+
+    /// Print the exit summary as JSON
+    #[arg(long)]
+    json: bool,
+
+    /// Run at most N tasks at once; higher-priority tasks start first
+    #[arg(short, long, value_name = "N")]
+    jobs: Option<usize>,
+
+    /// Stop starting new tasks after the first failure (headless runs also
+    /// stop the tasks already running)
+    #[arg(long)]
+    fail_fast: bool,
+
+    /// Agent mode: run advisory auto-actions without asking (needs
+    /// `auto_actions = true` under `[advisor]` in ~/.gidterm/config.toml)
+    #[arg(long)]
+    agent: bool,
+
+    /// Write task statuses back to the graph file on exit
+    #[arg(long)]
+    save_status: bool,
+
+    /// Start paused in step mode: each `s` (or `step` command) starts the
+    /// next wave of ready tasks
+    #[arg(long)]
+    step: bool,
+
+    /// Reload the graph file when it changes, adding and updating tasks
+    /// without disturbing running ones
+    #[arg(long)]
+    watch: bool,
+
+    /// Output lines kept in memory per task (default: 2000)
+    #[arg(long, value_name = "N")]
+    buffer_lines: Option<usize>,
+
+    /// Also write task output to .gidterm/logs/<session>/<task>.log
+    #[arg(long)]
+    spill_logs: bool,
+
+    /// Mirror each task's output to <DIR>/<task>.fifo (Unix)
+    #[arg(long, value_name = "DIR")]
+    task_fifo_dir: Option<PathBuf>,
+
+    /// Color theme: dark (default) or light; overrides the preset in
+    /// ~/.gidterm/theme.toml
+    #[arg(long, value_name = "NAME")]
+    theme: Option<String>,
+
+    /// Main loop tick in milliseconds while tasks produce output (default:
+    /// 30); quiet runs tick every 250ms
+    #[arg(long, value_name = "MS")]
+    tick_ms: Option<u64>,
+
+    /// Quit on `q` right away, without asking when tasks are still running
+    #[arg(long)]
+    force_quit: bool,
+
+    /// Run to completion without the TUI; exit non-zero if any task fails
+    /// (tasks with `allow_failure` excepted)
+    #[arg(long)]
+    headless: bool,
+}
+
 #[derive(Subcommand)]
 enum Commands {
     /// Run tasks from a graph file (default behavior)
-    Run {
-        /// Path to graph YAML file (auto-detects if not specified)
+    Run(RunArgs),
+
+    /// Show status of tasks in a graph
+    Status {
+        /// Path to graph YAML file
         #[arg(short, long)]
         graph: Option<PathBuf>,
 
-        /// Workspace mode: discover and run all projects
-        #[arg(short, long)]
-        workspace: bool,
+        /// Print the status as JSON
+        #[arg(long)]
+        json: bool,
     },
 
-    /// Show status of tasks in a graph
-    Status {
+    /// Check a graph for cycles, unknown dependencies and other mistakes
+    Validate {
         /// Path to graph YAML file
         #[arg(short, long)]
         graph: Option<PathBuf>,
@@ -47,17 +166,93 @@ enum Commands {
         output: PathBuf,
     },
 
-    /// Show session history
+    /// Show session history with per-task runs, successes and average duration
     History {
         /// Number of recent sessions to show
         #[arg(short, long, default_value = "5")]
         count: usize,
+
+        /// Delete all but the newest N sessions
+        #[arg(long, value_name = "N")]
+        prune: Option<usize>,
     },
 
-    /// Start a single task by ID
+    /// Print a task's output from a previous or running session
+    Logs {
+        /// Task ID
+        task_id: String,
+
+        /// Session ID (default: the latest session)
+        #[arg(long, value_name = "ID")]
+        session: Option<String>,
+
+        /// Only print the last N lines
+        #[arg(long, value_name = "N")]
+        tail: Option<usize>,
+
+        /// Keep printing new output until the task finishes
+        #[arg(short, long)]
+        follow: bool,
+    },
+
+    /// Compare task outcomes and runtimes between two sessions
+    Diff {
+        /// Older session ID (`latest` for the latest session)
+        session_a: String,
+
+        /// Newer session ID (or `latest`)
+        session_b: String,
+
+        /// Print the differences as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Find the run with the best final value of a metric across sessions
+    #[command(group(ArgGroup::new("direction").required(true).args(["maximize", "minimize"])))]
+    Best {
+        /// Metric name, as parsed from task output (e.g. val_accuracy)
+        #[arg(long)]
+        metric: String,
+
+        /// Higher is better (accuracy, throughput)
+        #[arg(long)]
+        maximize: bool,
+
+        /// Lower is better (loss, error rate)
+        #[arg(long)]
+        minimize: bool,
+    },
+
+    /// Report on a finished run
+    #[command(group(ArgGroup::new("kind").required(true).args(["estimates"])))]
+    Report {
+        /// Compare each task's `estimated_hours` with how long it actually took
+        #[arg(long)]
+        estimates: bool,
+
+        /// Path to graph YAML file
+        #[arg(short, long)]
+        graph: Option<PathBuf>,
+
+        /// Session ID (default: the latest session)
+        #[arg(long, value_name = "ID")]
+        session: Option<String>,
+
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Start a single task by ID, or every task with a tag
     Start {
         /// Task ID to start
-        task_id: String,
+        #[arg(required_unless_present = "tag")]
+        task_id: Option<String>,
+
+        /// Start every task with this tag instead, in execution order
+        #[arg(long, conflicts_with = "task_id")]
+        tag: Option<String>,
 
         /// Path to graph YAML file
         #[arg(short, long)]
@@ -70,61 +265,84 @@ enum Commands {
         #[arg(long)]
         cleanup: bool,
     },
+
+    /// Print the task DAG as Graphviz DOT or a Mermaid flowchart
+    Graph {
+        /// Output format
+        #[arg(long, value_enum, default_value_t = GraphFormat::Dot)]
+        format: GraphFormat,
+
+        /// Path to graph YAML file
+        #[arg(short, long, conflicts_with = "workspace")]
+        graph: Option<PathBuf>,
+
+        /// Export every project under the current directory, clustered by project
+        #[arg(short, long)]
+        workspace: bool,
+    },
+
+    /// Print MCP tool definitions for the control commands, plus the
+    /// state snapshot and event schemas, as one JSON document
+    McpSchema,
+}
+
+/// Output format of `gidterm graph`
+#[derive(Clone, Copy, ValueEnum)]
+enum GraphFormat {
+    /// Graphviz DOT (`dot -Tsvg`)
+    Dot,
+    /// Mermaid `flowchart`, for Markdown docs
+    Mermaid,
 }
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> Result<ExitCode> {
     env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
 
     let cli = Cli::parse();
 
     match cli.command {
-        None | Some(Commands::Run { .. }) => {
-            let (graph_path, workspace) = match &cli.command {
-                Some(Commands::Run { graph, workspace }) => (graph.clone(), *workspace),
-                _ => (None, false),
-            };
-            run_tui(graph_path, workspace).await
+        None => run_tui(RunArgs::default()).await?,
+        Some(Commands::Run(args)) if args.headless => return run_headless(args).await,
+        Some(Commands::Run(args)) => run_tui(args).await?,
+        Some(Commands::Status { graph, json }) => cmd_status(graph, json)?,
+        Some(Commands::Validate { graph }) => return cmd_validate(graph),
+        Some(Commands::Init { output }) => cmd_init(&output)?,
+        Some(Commands::History { count, prune }) => cmd_history(count, prune)?,
+        Some(Commands::Logs { task_id, session, tail, follow }) => {
+            cmd_logs(&task_id, session.as_deref(), tail, follow).await?
         }
-        Some(Commands::Status { graph }) => cmd_status(graph),
-        Some(Commands::Init { output }) => cmd_init(&output),
-        Some(Commands::History { count }) => cmd_history(count),
-        Some(Commands::Start { task_id, graph }) => cmd_start(&task_id, graph).await,
-        Some(Commands::Ports { cleanup }) => cmd_ports(cleanup),
+        Some(Commands::Diff { session_a, session_b, json }) => cmd_diff(&session_a, &session_b, json)?,
+        Some(Commands::Best { metric, maximize, .. }) => cmd_best(&metric, maximize)?,
+        Some(Commands::Report { graph, session, json, .. }) => cmd_report_estimates(graph, session.as_deref(), json)?,
+        Some(Commands::Start { task_id, tag, graph }) => cmd_start(task_id, tag, graph).await?,
+        Some(Commands::Ports { cleanup }) => cmd_ports(cleanup)?,
+        Some(Commands::Graph { format, graph, workspace }) => cmd_graph(format, graph, workspace)?,
+        Some(Commands::McpSchema) => cmd_mcp_schema()?,
     }
+
+    Ok(ExitCode::SUCCESS)
 }
 
-async fn run_tui(graph_path: Option<PathBuf>, workspace: bool) -> Result<()> {
+async fn run_tui(args: RunArgs) -> Result<()> {
     log::info!("🚀 GidTerm v{} (Live Mode)", env!("CARGO_PKG_VERSION"));
 
-    let mut app = if workspace {
-        let root = std::env::current_dir()?;
-        log::info!("🌐 Workspace mode: discovering projects in {}", root.display());
-        let workspace = Workspace::discover(&root)?;
-        log::info!(
-            "Found {} projects with {} total tasks",
-            workspace.project_count(),
-            workspace.total_task_count()
-        );
-        App::from_workspace(&workspace)
-    } else {
-        let graph = if let Some(path) = graph_path {
-            log::info!("Loading graph from: {}", path.display());
-            Graph::from_file(&path)?
-        } else {
-            log::info!("Auto-detecting graph file...");
-            Graph::auto_load()?
-        };
-        log::info!("Loaded {} nodes, {} tasks", graph.nodes.len(), graph.tasks.len());
-        App::new(graph)
-    };
+    let mut app = load_app(&args)?;
+    let mut commands = start_control(&app, &args).await?;
+    let mut watcher = start_watch(&args)?;
 
     app.start_ready_tasks().await?;
 
     let mut tui = TUI::new()?;
 
     loop {
-        app.process_events();
+        let backlog = app.process_events();
+        if let Some(commands) = commands.as_mut() {
+            app.handle_command_requests(commands).await;
+        }
+        if let Some(watcher) = watcher.as_mut() {
+            app.apply_graph_changes(watcher, &args.targets);
+        }
         app.start_ready_tasks().await?;
 
         tui.terminal().draw(|f| {
@@ -135,47 +353,361 @@ async fn run_tui(graph_path: Option<PathBuf>, workspace: bool) -> Result<()> {
                 ViewMode::Comparison => render_comparison_view(f, &app),
                 ViewMode::ProjectOverview => render_project_overview(f, &app),
             }
+            render_toast(f, &app);
         })?;
 
-        if App::should_poll_input()? {
+        // Waiting for input is the tick, so keys are handled right away;
+        // a backlog of task events only gets a quick look for input
+        let timeout = if backlog { Duration::ZERO } else { app.tick.interval(Instant::now()) };
+        if App::should_poll_input(timeout)? {
             let event = App::read_event()?;
-            if let crossterm::event::Event::Key(key) = event {
-                app.handle_key(key);
+            match event {
+                crossterm::event::Event::Key(key) => app.handle_key(key),
+                crossterm::event::Event::Resize(cols, rows) => app.executor.resize_all(rows, cols),
+                _ => {}
             }
         }
 
         if app.should_quit {
             break;
         }
+    }
+
+    // Leave the alternate screen so the summary lands in the normal scrollback
+    drop(tui);
+
+    if let Err(e) = app.ui_state().save() {
+        log::warn!("Failed to save UI state: {}", e);
+    }
 
-        tokio::time::sleep(Duration::from_millis(50)).await;
+    finish_run(&mut app, &args)?;
+    Ok(())
+}
+
+/// Run the graph to completion without the TUI, streaming output to stdout
+async fn run_headless(args: RunArgs) -> Result<ExitCode> {
+    log::info!("🚀 GidTerm v{} (Headless Mode)", env!("CARGO_PKG_VERSION"));
+    if args.step && args.serve.is_none() && args.control_sock.is_none() {
+        // Nothing could ever trigger the next wave
+        anyhow::bail!("--step in headless mode needs --serve or --control-sock");
     }
 
+    let mut app = load_app(&args)?;
+    app.quit_on_failure = args.fail_fast;
+    let mut engine = GidTermEngine::from_app(app)
+        .on_event(print_headless_event)
+        .stop_on(async {
+            let _ = tokio::signal::ctrl_c().await;
+        });
+    // After `from_app`, which gives the app its headless event stream
+    if let Some(commands) = start_control(engine.app(), &args).await? {
+        engine = engine.with_commands(commands);
+    }
+    if let Some(watcher) = start_watch(&args)? {
+        engine = engine.with_watcher(watcher, args.targets.clone());
+    }
+
+    let summary = engine.run().await?;
+    report_run(engine.app(), &args, &summary)?;
+    let deadlocked = engine.app().scheduler.is_deadlocked();
+    if engine.stopped() || deadlocked || summary.failed > summary.allowed_failures {
+        Ok(ExitCode::FAILURE)
+    } else {
+        Ok(ExitCode::SUCCESS)
+    }
+}
+
+/// Print task output and status changes as `[task] ...` lines
+fn print_headless_event(event: &GidEvent) {
+    match event {
+        GidEvent::TaskOutput { task_id, line, .. } => println!("[{}] {}", task_id, line),
+        GidEvent::TaskCompleted { task_id, exit_code } => println!("[{}] => done (exit {})", task_id, exit_code),
+        GidEvent::TaskFailed { task_id, error } => println!("[{}] => failed: {}", task_id, error),
+        _ => {}
+    }
+}
+
+/// Load the graph or workspace and reconcile the previous session if asked
+fn load_app(args: &RunArgs) -> Result<App> {
+    let config = load_config(args);
+    let mut app = if args.workspace {
+        let root = std::env::current_dir()?;
+        log::info!("🌐 Workspace mode: discovering projects in {}", root.display());
+        let mut workspace = Workspace::discover_with_depth(&root, args.depth.unwrap_or(DEFAULT_DISCOVERY_DEPTH))?;
+        if !args.projects.is_empty() {
+            workspace.retain_projects(&args.projects)?;
+        }
+        workspace.check_dependencies()?;
+        log::info!(
+            "Found {} projects with {} total tasks",
+            workspace.project_count(),
+            workspace.total_task_count()
+        );
+        App::from_workspace_with_config(&workspace, &config)
+    } else {
+        match &args.graph {
+            Some(path) => log::info!("Loading graph from: {}", path.display()),
+            None => log::info!("Auto-detecting graph file..."),
+        }
+        let graph = load_graph(args.graph.as_deref(), &config)?;
+        log::info!("Loaded {} nodes, {} tasks", graph.nodes.len(), graph.tasks.len());
+        App::with_config(graph, &config)
+    };
+
+    if args.fail_fast {
+        app.scheduler.set_failure_policy(FailurePolicy::FailFast);
+    }
+    if let Some(jobs) = args.jobs {
+        app.scheduler.set_max_parallel(Some(jobs.max(1)));
+    }
+    if args.agent {
+        app.control_mode = ControlMode::Agent;
+    }
+    app.set_step_mode(args.step);
+    app.force_quit = args.force_quit;
+    if let Some(name) = &args.theme {
+        app.theme = Theme::load(Some(name))?;
+    }
+
+    if !args.targets.is_empty() {
+        app.select_targets(&args.targets)?;
+    }
+
+    if args.resume {
+        match Session::load_latest() {
+            Ok(previous) if previous.project == app.session.project => {
+                app.resume_from_session(&previous);
+            }
+            Ok(previous) => log::warn!(
+                "Latest session belongs to project '{}', not resuming",
+                previous.project
+            ),
+            Err(e) => log::warn!("No session to resume: {}", e),
+        }
+    }
+
+    Ok(app)
+}
+
+/// The graph at `path`, or the auto-detected one, read through the parse
+/// cache when `[graph] cache` is on
+fn load_graph(path: Option<&Path>, config: &Config) -> Result<Graph> {
+    let cache = GraphCache::from_config(config);
+    match path {
+        Some(path) => Graph::from_file_with_cache(path, cache.as_ref()),
+        None => Graph::auto_load_with_cache(cache.as_ref()),
+    }
+}
+
+/// The user and project config files, overridden by command-line flags
+fn load_config(args: &RunArgs) -> Config {
+    let mut config = Config::load_or_default();
+    if let Some(lines) = args.buffer_lines {
+        config.output.buffer_lines = Some(lines);
+    }
+    if args.spill_logs {
+        config.output.spill = true;
+    }
+    if let Some(dir) = &args.task_fifo_dir {
+        config.output.task_fifo_dir = Some(dir.clone());
+    }
+    if let Some(ms) = args.tick_ms {
+        config.ui.tick_ms = Some(ms);
+    }
+    config
+}
+
+/// Start the requested control transports, returning their shared command queue
+async fn start_control(app: &App, args: &RunArgs) -> Result<Option<mpsc::Receiver<CommandRequest>>> {
+    if args.serve.is_none() && args.control_sock.is_none() {
+        return Ok(None);
+    }
+
+    let (handle, rx) = ControlHandle::new(app.events.clone());
+    if let Some(addr) = args.serve {
+        start_control_server(handle.clone(), addr, args.allow_remote).await?;
+    }
+    if let Some(path) = &args.control_sock {
+        start_control_socket(handle, path)?;
+    }
+
+    Ok(Some(rx))
+}
+
+/// Watch the graph file if `--watch` was given
+fn start_watch(args: &RunArgs) -> Result<Option<GraphWatcher>> {
+    if !args.watch {
+        return Ok(None);
+    }
+    if args.workspace {
+        log::warn!("--watch is not supported in workspace mode");
+        return Ok(None);
+    }
+    let path = args
+        .graph
+        .clone()
+        .or_else(Graph::default_path)
+        .context("No graph file to watch")?;
+
+    log::info!("Watching {} for changes", path.display());
+    GraphWatcher::new(&path).map(Some)
+}
+
+/// Stop tasks, release resources, save the session and print the summary
+fn finish_run(app: &mut App, args: &RunArgs) -> Result<()> {
     log::info!("Shutting down...");
     app.executor.stop_all();
+    match app.port_manager.release_all() {
+        Ok(released) if released > 0 => log::info!("Released {} port allocation(s)", released),
+        Ok(_) => {}
+        Err(e) => log::warn!("Failed to release ports: {}", e),
+    }
     app.session.end();
-    if let Err(e) = app.session.save() {
+    app.session_saver.mark_dirty();
+    if let Err(e) = app.session_saver.flush(&app.session) {
         log::warn!("Failed to save final session: {}", e);
     }
 
-    Ok(())
+    report_run(app, args, &app.summary())
 }
 
-fn cmd_status(graph_path: Option<PathBuf>) -> Result<()> {
-    let graph = if let Some(path) = graph_path {
-        Graph::from_file(&path)?
+/// Clean up the control socket, write `--save-status` and print the summary
+/// of a finished run
+fn report_run(app: &App, args: &RunArgs, summary: &RunSummary) -> Result<()> {
+    #[cfg(unix)]
+    if let Some(path) = &args.control_sock {
+        gidterm::ai::socket::cleanup(path);
+    }
+    if args.save_status {
+        save_graph_status(app, args);
+    }
+
+    if args.json {
+        println!("{}", serde_json::to_string(summary)?);
     } else {
-        Graph::auto_load()?
+        print!("{}", summary);
+    }
+    Ok(())
+}
+
+/// Write the run's task statuses back to the graph file (`--save-status`)
+fn save_graph_status(app: &App, args: &RunArgs) {
+    if args.workspace {
+        log::warn!("--save-status is not supported in workspace mode");
+        return;
+    }
+    let Some(path) = args.graph.clone().or_else(Graph::default_path) else {
+        return;
     };
 
-    if let Some(meta) = &graph.metadata {
-        println!("Project: {}", meta.project);
+    match app.scheduler.graph().save_to(&path) {
+        Ok(()) => log::info!("Saved task status to {}", path.display()),
+        Err(e) => log::warn!("Failed to save task status to {}: {}", path.display(), e),
     }
+}
+
+/// Bind the HTTP control server and serve it in the background
+#[cfg(feature = "server")]
+async fn start_control_server(handle: ControlHandle, addr: SocketAddr, allow_remote: bool) -> Result<()> {
+    let server = gidterm::ai::server::ControlServer::new(handle).allow_remote(allow_remote);
+    let listener = server.bind(addr).await?;
+
+    tokio::spawn(async move {
+        if let Err(e) = server.serve_on(listener).await {
+            log::error!("Control server stopped: {}", e);
+        }
+    });
+
+    Ok(())
+}
+
+#[cfg(not(feature = "server"))]
+async fn start_control_server(_handle: ControlHandle, _addr: SocketAddr, _allow_remote: bool) -> Result<()> {
+    anyhow::bail!("--serve requires gidterm to be built with the `server` feature")
+}
+
+/// Bind the Unix control socket and serve it in the background
+#[cfg(unix)]
+fn start_control_socket(handle: ControlHandle, path: &std::path::Path) -> Result<()> {
+    let listener = gidterm::ai::socket::bind(path)?;
+    log::info!("Control socket listening on {}", path.display());
+
+    tokio::spawn(async move {
+        if let Err(e) = gidterm::ai::socket::serve(listener, handle).await {
+            log::error!("Control socket stopped: {}", e);
+        }
+    });
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn start_control_socket(_handle: ControlHandle, _path: &std::path::Path) -> Result<()> {
+    anyhow::bail!("--control-sock is only supported on Unix")
+}
+
+/// `gidterm status --json` output
+#[derive(Serialize)]
+struct StatusReport<'a> {
+    project: Option<&'a str>,
+    tasks: Vec<TaskStatusEntry<'a>>,
+    progress: StatusProgress,
+}
+
+#[derive(Serialize)]
+struct TaskStatusEntry<'a> {
+    id: &'a str,
+    status: String,
+    description: &'a str,
+    depends_on: &'a [String],
+}
+
+#[derive(Serialize)]
+struct StatusProgress {
+    done: usize,
+    total: usize,
+    pct: f64,
+}
+
+impl StatusProgress {
+    fn of<'a>(tasks: impl ExactSizeIterator<Item = &'a Task>) -> Self {
+        let total = tasks.len();
+        let done = tasks.filter(|t| t.status == GraphTaskStatus::Done).count();
+        let pct = if total > 0 { done as f64 / total as f64 * 100.0 } else { 0.0 };
+        Self { done, total, pct }
+    }
+}
+
+fn cmd_status(graph_path: Option<PathBuf>, json: bool) -> Result<()> {
+    let graph = load_graph(graph_path.as_deref(), &Config::load_or_default())?;
 
-    println!("\nTasks ({}):", graph.tasks.len());
     let mut tasks: Vec<_> = graph.tasks.iter().collect();
     tasks.sort_by_key(|(id, _)| (*id).clone());
+    let progress = StatusProgress::of(graph.tasks.values());
+
+    if json {
+        let report = StatusReport {
+            project: graph.metadata.as_ref().map(|m| m.project.as_str()),
+            tasks: tasks
+                .iter()
+                .map(|(id, task)| TaskStatusEntry {
+                    id,
+                    status: task.status.to_string(),
+                    description: &task.description,
+                    depends_on: task.depends_on.as_deref().unwrap_or_default(),
+                })
+                .collect(),
+            progress,
+        };
+        println!("{}", serde_json::to_string(&report)?);
+        return Ok(());
+    }
 
+    if let Some(meta) = &graph.metadata {
+        println!("Project: {}", meta.project);
+    }
+
+    println!("\nTasks ({}):", graph.tasks.len());
     for (id, task) in &tasks {
         let deps = match &task.depends_on {
             Some(d) if !d.is_empty() => format!(" (depends: {})", d.join(", ")),
@@ -184,13 +716,55 @@ fn cmd_status(graph_path: Option<PathBuf>) -> Result<()> {
         println!("  {} [{}]{} - {}", task.status, id, deps, task.description);
     }
 
-    let done = tasks.iter().filter(|(_, t)| t.status == gidterm::core::GraphTaskStatus::Done).count();
-    let total = tasks.len();
-    println!("\nProgress: {}/{} ({:.0}%)", done, total, if total > 0 { done as f64 / total as f64 * 100.0 } else { 0.0 });
+    println!("\nProgress: {}/{} ({:.0}%)", progress.done, progress.total, progress.pct);
+
+    let diagnostics = check_graph(&graph);
+    if !diagnostics.is_empty() {
+        println!("\n{} (run `gidterm validate` for details)", problem_count(&diagnostics));
+    }
 
     Ok(())
 }
 
+fn cmd_validate(graph_path: Option<PathBuf>) -> Result<ExitCode> {
+    let graph = load_graph(graph_path.as_deref(), &Config::load_or_default())?;
+
+    let project = graph.metadata.as_ref().map(|m| m.project.as_str()).unwrap_or("unnamed");
+    println!("Validating {} ({} tasks)", project, graph.tasks.len());
+
+    let diagnostics = check_graph(&graph);
+    for diagnostic in &diagnostics {
+        println!("  {}", diagnostic);
+    }
+
+    if diagnostics.is_empty() {
+        println!("No problems found.");
+    } else {
+        println!("\n{}", problem_count(&diagnostics));
+    }
+
+    if diagnostics.iter().any(|d| d.is_error()) {
+        Ok(ExitCode::FAILURE)
+    } else {
+        Ok(ExitCode::SUCCESS)
+    }
+}
+
+/// Everything `gidterm validate` reports: the graph's own checks plus
+/// task colors the theme can't show, sorted by task ID
+fn check_graph(graph: &Graph) -> Vec<Diagnostic> {
+    let mut diagnostics = graph.validate();
+    diagnostics.extend(color_diagnostics(graph));
+    diagnostics.sort_by(|a, b| a.task_id.cmp(&b.task_id));
+    diagnostics
+}
+
+/// "N error(s), M warning(s)"
+fn problem_count(diagnostics: &[Diagnostic]) -> String {
+    let errors = diagnostics.iter().filter(|d| d.is_error()).count();
+    format!("{} error(s), {} warning(s)", errors, diagnostics.len() - errors)
+}
+
 fn cmd_init(output: &PathBuf) -> Result<()> {
     if output.exists() {
         anyhow::bail!("File already exists: {}. Use --output to specify a different path.", output.display());
@@ -214,51 +788,335 @@ tasks:
     depends_on: [build]
 "#;
 
+    // Guard against the template drifting into something invalid
+    let graph: Graph = serde_yaml::from_str(template)?;
+    if let Some(problem) = graph.validate().into_iter().find(|d| d.is_error()) {
+        anyhow::bail!("Built-in template is invalid: {}", problem);
+    }
+
     std::fs::write(output, template)?;
     println!("Created task graph: {}", output.display());
     println!("Run `gidterm run` to start executing tasks.");
     Ok(())
 }
 
-fn cmd_history(count: usize) -> Result<()> {
-    let session_dir = PathBuf::from(".gidterm/sessions");
-    if !session_dir.exists() {
+fn cmd_history(count: usize, prune: Option<usize>) -> Result<()> {
+    if let Some(keep) = prune {
+        let removed = Session::prune(keep)?;
+        println!("Pruned {} session(s), kept the newest {}.", removed, keep);
+        return Ok(());
+    }
+
+    let mut ids = Session::list_all()?;
+    ids.truncate(count);
+
+    if ids.is_empty() {
         println!("No session history found.");
         return Ok(());
     }
 
-    let mut entries: Vec<_> = std::fs::read_dir(&session_dir)?
-        .filter_map(|e| e.ok())
-        .filter(|e| e.path().extension().map(|ext| ext == "json").unwrap_or(false))
+    println!("Recent sessions:");
+    // IDs are the file names' timestamps (2026-01-31-17-34-21.json[.gz])
+    for id in &ids {
+        // Sessions that no longer parse (older formats) are listed by name only
+        let Ok(session) = Session::load(id) else {
+            println!("  {}", id);
+            continue;
+        };
+        println!("  {} ({})", id, session.project);
+
+        let mut tasks: Vec<_> = session.tasks.values().collect();
+        tasks.sort_by(|a, b| a.task_id.cmp(&b.task_id));
+        for history in tasks {
+            println!("    {}: {}", history.task_id, history.summary());
+        }
+    }
+
+    Ok(())
+}
+
+/// How often `logs --follow` checks for new output
+const LOGS_FOLLOW_INTERVAL: Duration = Duration::from_millis(500);
+
+async fn cmd_logs(task_id: &str, session_id: Option<&str>, tail: Option<usize>, follow: bool) -> Result<()> {
+    let load = || match session_id {
+        Some(id) => Session::load(id).with_context(|| format!("Session '{}' not found", id)),
+        None => Session::load_latest().context("No session history found"),
+    };
+
+    let log = task_log(&load()?, task_id)?;
+    let start = tail.map_or(0, |n| log.lines.len().saturating_sub(n));
+    for line in &log.lines[start..] {
+        println!("{}", line);
+    }
+    let mut run = log.run;
+    let mut printed = log.first + log.lines.len();
+    let mut running = log.running;
+
+    while follow && running {
+        tokio::select! {
+            _ = tokio::time::sleep(LOGS_FOLLOW_INTERVAL) => {}
+            _ = tokio::signal::ctrl_c() => break,
+        }
+
+        // The session may be between saves; look again next time
+        let log = match load().and_then(|session| task_log(&session, task_id)) {
+            Ok(log) => log,
+            Err(e) => {
+                log::debug!("Cannot read {} yet: {:#}", task_id, e);
+                continue;
+            }
+        };
+        if log.run != run {
+            // The task was restarted; follow the new run from its start
+            run = log.run;
+            printed = 0;
+        }
+        if log.first > printed {
+            eprintln!(
+                "[{} lines no longer in the session; run with --spill-logs to keep them all]",
+                log.first - printed
+            );
+            printed = log.first;
+        }
+        for line in log.lines.iter().skip(printed - log.first) {
+            println!("{}", line);
+        }
+        printed = printed.max(log.first + log.lines.len());
+        running = log.running;
+    }
+
+    Ok(())
+}
+
+/// A task's finished output lines in a session
+struct TaskLog {
+    lines: Vec<String>,
+    /// Position of `lines[0]` in everything the run (or spill log) holds,
+    /// as the session keeps only a run's last lines
+    first: usize,
+    /// Start of the run the lines come from; `None` for a spill log, which
+    /// spans every run
+    run: Option<DateTime<Utc>>,
+    running: bool,
+}
+
+/// A task's output in a session
+///
+/// The spill log (`run --spill-logs`) is preferred since it keeps every
+/// run's output; otherwise the session's record of the latest run is used.
+fn task_log(session: &Session, task_id: &str) -> Result<TaskLog> {
+    let running = session.last_status(task_id) == Some(&TaskStatus::Running);
+
+    let log_path = logs::log_path(&logs::session_log_dir(&session.id), task_id);
+    if log_path.exists() {
+        let content = String::from_utf8_lossy(&std::fs::read(&log_path)?).into_owned();
+        // While the task runs, the last line may be half written
+        let complete = match content.rfind('\n') {
+            Some(end) if running => &content[..end],
+            None if running => "",
+            _ => &content,
+        };
+        return Ok(TaskLog {
+            lines: complete.lines().map(str::to_string).collect(),
+            first: 0,
+            run: None,
+            running,
+        });
+    }
+
+    let run = session
+        .tasks
+        .get(task_id)
+        .and_then(|history| history.runs.last())
+        .ok_or_else(|| anyhow::anyhow!("Task '{}' has no output in session {}", task_id, session.id))?;
+    let mut lines = run.output.clone();
+    let first = run.output_lines.saturating_sub(lines.len());
+    // While the task runs, its last line may still be redrawn in place
+    if running {
+        lines.pop();
+    }
+    Ok(TaskLog {
+        lines,
+        first,
+        run: Some(run.started),
+        running,
+    })
+}
+
+fn cmd_diff(session_a: &str, session_b: &str, json: bool) -> Result<()> {
+    let load = |id: &str| match id {
+        "latest" => Session::load_latest().context("No session history found"),
+        _ => Session::load(id).with_context(|| format!("Session '{}' not found", id)),
+    };
+    let diff = load(session_a)?.diff(&load(session_b)?);
+
+    if json {
+        println!("{}", serde_json::to_string(&diff)?);
+        return Ok(());
+    }
+
+    println!("Comparing {} → {}\n", diff.from, diff.to);
+    if diff.tasks.is_empty() && diff.added.is_empty() && diff.removed.is_empty() {
+        println!("No tasks ran in either session.");
+        return Ok(());
+    }
+
+    let mut rows: Vec<[String; 3]> = diff
+        .tasks
+        .iter()
+        .map(|task| {
+            let status = if task.status_changed() {
+                format!("{:?}→{:?}", task.before, task.after)
+            } else {
+                format!("{:?}", task.after)
+            };
+            let runtime = format!("{} → {}", format_secs(task.before_secs), format_secs(task.after_secs));
+            let runtime = match task.runtime_delta_secs() {
+                Some(delta) => format!("{} ({:+.1}s)", runtime, delta),
+                None => runtime,
+            };
+            [task.task_id.clone(), status, runtime]
+        })
         .collect();
+    rows.extend(diff.added.iter().map(|id| [id.clone(), "new".to_string(), String::new()]));
+    rows.extend(diff.removed.iter().map(|id| [id.clone(), "removed".to_string(), String::new()]));
 
-    entries.sort_by_key(|e| std::cmp::Reverse(e.file_name()));
-    entries.truncate(count);
+    let header = ["TASK".to_string(), "STATUS".to_string(), "RUNTIME".to_string()];
+    let width = |col: usize| {
+        std::iter::once(&header)
+            .chain(&rows)
+            .map(|row| display_width(&row[col]))
+            .max()
+            .unwrap_or(0)
+    };
+    let (task_width, status_width) = (width(0), width(1));
+    for [task, status, runtime] in std::iter::once(&header).chain(&rows) {
+        let line = format!(
+            "{}  {}  {}",
+            pad_to_width(task, task_width),
+            pad_to_width(status, status_width),
+            runtime
+        );
+        println!("{}", line.trim_end());
+    }
 
-    if entries.is_empty() {
-        println!("No session history found.");
+    Ok(())
+}
+
+/// Seconds as `12.3s`, or `-` for a run that never ended
+fn format_secs(secs: Option<f64>) -> String {
+    secs.map_or_else(|| "-".to_string(), |secs| format!("{:.1}s", secs))
+}
+
+fn cmd_best(metric: &str, maximize: bool) -> Result<()> {
+    // Sessions that no longer parse (older formats) are skipped
+    let sessions: Vec<Session> = Session::list_all()?
+        .iter()
+        .filter_map(|id| Session::load(id).ok())
+        .collect();
+
+    let Some(best) = session::best_run(&sessions, metric, maximize) else {
+        anyhow::bail!("No run in {} session(s) recorded '{}'", sessions.len(), metric);
+    };
+
+    println!(
+        "Best {} ({}): {} - task {} in session {}",
+        metric,
+        if maximize { "max" } else { "min" },
+        best.value,
+        best.task_id,
+        best.session_id
+    );
+    Ok(())
+}
+
+fn cmd_report_estimates(graph_path: Option<PathBuf>, session_id: Option<&str>, json: bool) -> Result<()> {
+    let graph = load_graph(graph_path.as_deref(), &Config::load_or_default())?;
+    let session = match session_id {
+        Some(id) => Session::load(id).with_context(|| format!("Session '{}' not found", id))?,
+        None => Session::load_latest().context("No session history found")?,
+    };
+    let report = session::estimate_report(&graph, &session);
+
+    if json {
+        println!("{}", serde_json::to_string(&report)?);
         return Ok(());
     }
 
-    println!("Recent sessions:");
-    for entry in &entries {
-        let name = entry.file_name();
-        let name_str = name.to_string_lossy();
-        // Parse timestamp from filename (2026-01-31-17-34-21.json)
-        let display = name_str.trim_end_matches(".json");
-        println!("  {}", display);
+    println!("Estimates vs. session {}\n", session.id);
+    if report.is_empty() {
+        println!("No task with estimated_hours finished a run in this session.");
+        return Ok(());
     }
 
+    let mut rows = vec![["TASK", "ESTIMATE", "ACTUAL", "VARIANCE"].map(String::from)];
+    rows.extend(report.iter().map(|v| {
+        let status = if v.status == TaskStatus::Done { String::new() } else { format!(" ({:?})", v.status) };
+        [
+            v.task_id.clone(),
+            format!("{}h", v.estimated_hours),
+            format!("{}{}", format_hours(v.actual_secs), status),
+            format!("{:+.0}%{}", v.variance_pct, if v.overrun { "  ⚠ overrun" } else { "" }),
+        ]
+    }));
+    let width = |col: usize| rows.iter().map(|row| display_width(&row[col])).max().unwrap_or(0);
+    let widths = [width(0), width(1), width(2)];
+    for [task, estimate, actual, variance] in &rows {
+        let line = format!(
+            "{}  {}  {}  {}",
+            pad_to_width(task, widths[0]),
+            pad_to_width(estimate, widths[1]),
+            pad_to_width(actual, widths[2]),
+            variance
+        );
+        println!("{}", line.trim_end());
+    }
+
+    let overruns = report.iter().filter(|v| v.overrun).count();
+    if overruns > 0 {
+        println!(
+            "\n{} task(s) took at least {}x their estimate",
+            overruns,
+            session::OVERRUN_RATIO
+        );
+    }
     Ok(())
 }
 
-async fn cmd_start(task_id: &str, graph_path: Option<PathBuf>) -> Result<()> {
-    let graph = if let Some(path) = graph_path {
-        Graph::from_file(&path)?
+/// Seconds as `45s`, `12m` or `1.5h`
+fn format_hours(secs: f64) -> String {
+    if secs < 60.0 {
+        format!("{:.0}s", secs)
+    } else if secs < 3600.0 {
+        format!("{:.0}m", secs / 60.0)
     } else {
-        Graph::auto_load()?
+        format!("{:.1}h", secs / 3600.0)
+    }
+}
+
+async fn cmd_start(task_id: Option<String>, tag: Option<String>, graph_path: Option<PathBuf>) -> Result<()> {
+    let graph = load_graph(graph_path.as_deref(), &Config::load_or_default())?;
+
+    let Some(tag) = tag else {
+        return run_graph_task(&graph, task_id.as_deref().unwrap_or_default()).await;
     };
 
+    let tagged = graph.tasks_with_tag(&tag);
+    if tagged.is_empty() {
+        anyhow::bail!("No tasks tagged '{}'", tag);
+    }
+    for task_id in &tagged {
+        if graph.get_task(task_id).is_some_and(|t| t.command.is_some()) {
+            run_graph_task(&graph, task_id).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Run one task's command in the foreground
+async fn run_graph_task(graph: &Graph, task_id: &str) -> Result<()> {
     let task = graph.get_task(task_id)
         .ok_or_else(|| anyhow::anyhow!("Task '{}' not found", task_id))?;
 
@@ -268,8 +1126,15 @@ async fn cmd_start(task_id: &str, graph_path: Option<PathBuf>) -> Result<()> {
     println!("Starting task: {} ({})", task_id, command);
     println!("  {}", task.description);
 
-    let status = tokio::process::Command::new("sh")
-        .arg("-c")
+    let shell = match task.shell.as_deref() {
+        Some(shell) => {
+            find_shell(shell)?;
+            shell
+        }
+        None => DEFAULT_SHELL,
+    };
+    let status = tokio::process::Command::new(shell)
+        .args(shell_flags(shell))
         .arg(command)
         .status()
         .await?;
@@ -283,8 +1148,29 @@ async fn cmd_start(task_id: &str, graph_path: Option<PathBuf>) -> Result<()> {
     Ok(())
 }
 
+fn cmd_graph(format: GraphFormat, graph_path: Option<PathBuf>, workspace: bool) -> Result<()> {
+    let graph = if workspace {
+        let root = std::env::current_dir()?;
+        Workspace::discover_with_depth(&root, DEFAULT_DISCOVERY_DEPTH)?.to_unified_graph()
+    } else {
+        load_graph(graph_path.as_deref(), &Config::load_or_default())?
+    };
+
+    let text = match format {
+        GraphFormat::Dot => graph.to_dot(workspace),
+        GraphFormat::Mermaid => graph.to_mermaid(workspace),
+    };
+    print!("{}", text);
+    Ok(())
+}
+
+fn cmd_mcp_schema() -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(&gidterm::ai::mcp::schema_document())?);
+    Ok(())
+}
+
 fn cmd_ports(cleanup: bool) -> Result<()> {
-    let mut registry = PortRegistry::load()?;
+    let mut registry = PortRegistry::load(&Config::load()?)?;
 
     if cleanup {
         let cleaned = registry.cleanup_stale()?;
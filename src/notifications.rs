@@ -9,25 +9,22 @@
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::process::Command;
+use std::time::{Duration, Instant};
 
 /// Notification priority/urgency
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub enum NotificationPriority {
     Low,
+    #[default]
     Normal,
     High,
     Critical,
 }
 
-impl Default for NotificationPriority {
-    fn default() -> Self {
-        Self::Normal
-    }
-}
-
 /// Notification event types
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum NotificationEvent {
     /// Task/agent completed successfully
     Complete,
@@ -64,6 +61,17 @@ impl NotificationEvent {
         }
     }
 
+    /// Summary for several events of this type, e.g. "3 tasks completed"
+    pub fn summary(&self, count: usize) -> String {
+        match self {
+            Self::Complete => format!("{} tasks completed", count),
+            Self::Error => format!("{} tasks failed", count),
+            Self::WaitingInput => format!("{} tasks waiting for input", count),
+            Self::Started => format!("{} tasks started", count),
+            Self::Warning => format!("{} warnings", count),
+        }
+    }
+
     /// Get sound name for event type
     pub fn sound(&self) -> Option<&'static str> {
         match self {
@@ -76,8 +84,10 @@ impl NotificationEvent {
     }
 }
 
-/// Notification configuration
+/// Notification configuration, the `[notifications]` table of config.toml (see
+/// [`Config`](crate::config::Config))
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct NotificationConfig {
     /// Enable notifications
     pub enabled: bool,
@@ -91,8 +101,19 @@ pub struct NotificationConfig {
     pub on_start: bool,
     /// Play sound with notifications
     pub sound: bool,
-    /// Suppress notifications during quiet hours (23:00-08:00)
+    /// Suppress notifications during quiet hours
     pub quiet_hours: bool,
+    /// Hour of day (0-23) quiet hours begin
+    pub quiet_start: u32,
+    /// Hour of day (0-23) quiet hours end; before `quiet_start` wraps past midnight
+    pub quiet_end: u32,
+    /// Only notify on completion of tasks that ran at least this long
+    pub min_duration_secs: u64,
+    /// Combine a project's notifications of one kind sent within this many
+    /// seconds into a single summary (0 = send each one)
+    pub batch_window_secs: u64,
+    /// Ring the terminal bell for each notification sent
+    pub terminal_bell: bool,
 }
 
 impl Default for NotificationConfig {
@@ -105,6 +126,11 @@ impl Default for NotificationConfig {
             on_start: false,
             sound: true,
             quiet_hours: true,
+            quiet_start: 23,
+            quiet_end: 8,
+            min_duration_secs: 0,
+            batch_window_secs: 0,
+            terminal_bell: false,
         }
     }
 }
@@ -112,12 +138,18 @@ impl Default for NotificationConfig {
 impl NotificationConfig {
     /// Check if notification should be sent for event type
     pub fn should_notify(&self, event: NotificationEvent) -> bool {
+        use chrono::Timelike;
+        self.should_notify_at(event, chrono::Local::now().hour())
+    }
+
+    /// Check if notification should be sent for event type at an hour of day
+    pub fn should_notify_at(&self, event: NotificationEvent, hour: u32) -> bool {
         if !self.enabled {
             return false;
         }
 
         // Check quiet hours
-        if self.quiet_hours && is_quiet_hours() {
+        if self.quiet_hours && self.is_quiet_hour(hour) {
             // Only allow high priority during quiet hours
             if event.default_priority() != NotificationPriority::High
                 && event.default_priority() != NotificationPriority::Critical
@@ -134,14 +166,26 @@ impl NotificationConfig {
             NotificationEvent::Warning => self.on_complete, // Group with complete
         }
     }
-}
 
-/// Check if current time is within quiet hours (23:00-08:00)
-fn is_quiet_hours() -> bool {
-    use chrono::Timelike;
-    let now = chrono::Local::now();
-    let hour = now.hour();
-    hour >= 23 || hour < 8
+    /// Check if a finished task ran long enough to notify about
+    ///
+    /// Tasks with an unknown duration always qualify.
+    pub fn ran_long_enough(&self, duration: Option<std::time::Duration>) -> bool {
+        duration.is_none_or(|d| d.as_secs() >= self.min_duration_secs)
+    }
+
+    /// Check if an hour of day falls within quiet hours
+    ///
+    /// The window is `[quiet_start, quiet_end)`, wrapping past midnight when
+    /// it ends before it starts (23-8). Equal hours mean no quiet time.
+    pub fn is_quiet_hour(&self, hour: u32) -> bool {
+        let (start, end) = (self.quiet_start, self.quiet_end);
+        if start <= end {
+            (start..end).contains(&hour)
+        } else {
+            hour >= start || hour < end
+        }
+    }
 }
 
 /// Notification payload
@@ -189,13 +233,24 @@ impl Notification {
     }
 }
 
+/// Notifications held back to be sent as one summary
+struct Batch {
+    /// When the first notification arrived; the batch is sent a window later
+    started: Instant,
+    notifications: Vec<Notification>,
+}
+
 /// Notification manager - sends system notifications
 pub struct NotificationManager {
     config: NotificationConfig,
     /// Track recent notifications to avoid spam
-    recent: Vec<(String, std::time::Instant)>,
+    recent: Vec<(String, Instant)>,
     /// Minimum interval between duplicate notifications (seconds)
     dedup_interval: u64,
+    /// Batched notifications by (project, event)
+    batches: HashMap<(String, NotificationEvent), Batch>,
+    /// Most recently sent notification, for in-app display
+    last_sent: Option<Notification>,
 }
 
 impl NotificationManager {
@@ -205,6 +260,8 @@ impl NotificationManager {
             config: NotificationConfig::default(),
             recent: Vec::new(),
             dedup_interval: 30,
+            batches: HashMap::new(),
+            last_sent: None,
         }
     }
 
@@ -214,6 +271,8 @@ impl NotificationManager {
             config,
             recent: Vec::new(),
             dedup_interval: 30,
+            batches: HashMap::new(),
+            last_sent: None,
         }
     }
 
@@ -223,6 +282,8 @@ impl NotificationManager {
     }
 
     /// Send a notification
+    ///
+    /// With batching enabled, project notifications are held until `flush`.
     pub fn send(&mut self, notification: &Notification) -> Result<()> {
         // Check if we should notify for this event
         if !self.config.should_notify(notification.event) {
@@ -232,7 +293,7 @@ impl NotificationManager {
 
         // Deduplicate
         let key = format!("{}:{}", notification.title, notification.message);
-        let now = std::time::Instant::now();
+        let now = Instant::now();
         
         // Clean old entries
         self.recent.retain(|(_, t)| now.duration_since(*t).as_secs() < self.dedup_interval);
@@ -244,10 +305,84 @@ impl NotificationManager {
         }
         self.recent.push((key, now));
 
+        if self.config.batch_window_secs > 0 {
+            if let Some(project) = &notification.subtitle {
+                self.batches
+                    .entry((project.clone(), notification.event))
+                    .or_insert_with(|| Batch {
+                        started: now,
+                        notifications: Vec::new(),
+                    })
+                    .notifications
+                    .push(notification.clone());
+                return Ok(());
+            }
+        }
+
         // Send the notification
+        self.deliver(notification)
+    }
+
+    /// Take the most recently sent notification, if there is a new one
+    pub fn take_last_sent(&mut self) -> Option<Notification> {
+        self.last_sent.take()
+    }
+
+    /// Send a notification through every configured channel
+    ///
+    /// The bell and `last_sent` work even where the desktop notification
+    /// can't be shown (e.g. a remote box without osascript).
+    fn deliver(&mut self, notification: &Notification) -> Result<()> {
+        self.last_sent = Some(notification.clone());
+        if self.config.terminal_bell {
+            ring_bell();
+        }
         self.send_macos_notification(notification)
     }
 
+    /// Send batches whose window has passed; call on every event tick
+    pub fn flush(&mut self, now: Instant) -> Result<()> {
+        let mut result = Ok(());
+        for notification in self.take_due(now) {
+            if let Err(e) = self.deliver(&notification) {
+                result = Err(e);
+            }
+        }
+        result
+    }
+
+    /// Remove batches whose window has passed, as one notification each
+    ///
+    /// A batch of one is sent unchanged; larger ones become a summary like
+    /// "3 tasks completed in web".
+    pub fn take_due(&mut self, now: Instant) -> Vec<Notification> {
+        let window = Duration::from_secs(self.config.batch_window_secs);
+        let mut due: Vec<(String, NotificationEvent)> = self
+            .batches
+            .iter()
+            .filter(|(_, batch)| now.saturating_duration_since(batch.started) >= window)
+            .map(|(key, _)| key.clone())
+            .collect();
+        due.sort_by_key(|(project, event)| (project.clone(), *event as u8));
+
+        due.into_iter()
+            .filter_map(|key| self.batches.remove(&key))
+            .map(|mut batch| {
+                if batch.notifications.len() == 1 {
+                    return batch.notifications.remove(0);
+                }
+                let first = &batch.notifications[0];
+                let project = first.subtitle.clone().unwrap_or_default();
+                Notification::new(
+                    first.title.clone(),
+                    format!("{} in {}", first.event.summary(batch.notifications.len()), project),
+                    first.event,
+                )
+                .with_subtitle(project)
+            })
+            .collect()
+    }
+
     /// Send macOS notification via osascript
     fn send_macos_notification(&self, notification: &Notification) -> Result<()> {
         let title = notification.formatted_title();
@@ -298,7 +433,14 @@ impl NotificationManager {
     }
 
     /// Send task completed notification
+    ///
+    /// Tasks that finished faster than `min_duration_secs` are skipped.
     pub fn notify_complete(&mut self, project: &str, task: &str, duration: Option<std::time::Duration>) -> Result<()> {
+        if !self.config.ran_long_enough(duration) {
+            log::debug!("Notification skipped: {} finished quickly", task);
+            return Ok(());
+        }
+
         let duration_str = duration
             .map(|d| format!(" ({})", format_duration(d)))
             .unwrap_or_default();
@@ -368,6 +510,16 @@ impl Default for NotificationManager {
     }
 }
 
+/// Write BEL to the terminal; skipped when stdout isn't one (e.g. CI logs)
+fn ring_bell() {
+    use std::io::{IsTerminal, Write};
+    let mut stdout = std::io::stdout();
+    if stdout.is_terminal() {
+        let _ = stdout.write_all(b"\x07");
+        let _ = stdout.flush();
+    }
+}
+
 /// Escape string for AppleScript
 fn escape_applescript(s: &str) -> String {
     s.replace('\\', "\\\\")
@@ -399,6 +551,7 @@ fn truncate(s: &str, max_len: usize) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::Config;
 
     #[test]
     fn test_escape_applescript() {
@@ -422,6 +575,119 @@ mod tests {
         assert!(!config.should_notify(NotificationEvent::Started));
     }
 
+    #[test]
+    fn test_quiet_hours_wrap_around() {
+        let config = NotificationConfig::default();
+        for hour in [23, 0, 3, 7] {
+            assert!(config.is_quiet_hour(hour), "{} should be quiet", hour);
+        }
+        for hour in [8, 12, 22] {
+            assert!(!config.is_quiet_hour(hour), "{} should not be quiet", hour);
+        }
+
+        // Quiet hours suppress normal events, not urgent ones
+        assert!(!config.should_notify_at(NotificationEvent::Complete, 2));
+        assert!(config.should_notify_at(NotificationEvent::Error, 2));
+        assert!(config.should_notify_at(NotificationEvent::Complete, 14));
+    }
+
+    #[test]
+    fn test_quiet_hours_same_day_and_empty() {
+        let config = NotificationConfig {
+            quiet_start: 12,
+            quiet_end: 14,
+            ..Default::default()
+        };
+        assert!(!config.is_quiet_hour(11));
+        assert!(config.is_quiet_hour(12));
+        assert!(config.is_quiet_hour(13));
+        assert!(!config.is_quiet_hour(14));
+
+        let never = NotificationConfig {
+            quiet_start: 9,
+            quiet_end: 9,
+            ..Default::default()
+        };
+        assert!((0..24).all(|hour| !never.is_quiet_hour(hour)));
+    }
+
+    #[test]
+    fn test_min_duration() {
+        use std::time::Duration;
+
+        assert!(NotificationConfig::default().ran_long_enough(Some(Duration::from_secs(1))));
+
+        let config = NotificationConfig {
+            min_duration_secs: 30,
+            ..Default::default()
+        };
+        assert!(!config.ran_long_enough(Some(Duration::from_secs(5))));
+        assert!(config.ran_long_enough(Some(Duration::from_secs(30))));
+        assert!(config.ran_long_enough(None));
+    }
+
+    #[test]
+    fn test_load_notification_config() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "[notifications]\nquiet_start = 22\nquiet_end = 7\nmin_duration_secs = 30\n").unwrap();
+
+        let config = Config::load_layers(&path, &dir.path().join("missing.toml")).unwrap().notifications;
+        assert_eq!((config.quiet_start, config.quiet_end), (22, 7));
+        assert_eq!(config.min_duration_secs, 30);
+        // Unset keys keep their defaults
+        assert!(config.on_complete);
+        assert!(!config.on_start);
+    }
+
+    #[test]
+    fn test_batching_per_project() {
+        let mut manager = NotificationManager::with_config(NotificationConfig {
+            quiet_hours: false,
+            batch_window_secs: 2,
+            ..Default::default()
+        });
+        let start = Instant::now();
+
+        for task in ["build", "test", "lint"] {
+            manager.notify_complete("web", task, None).unwrap();
+        }
+        manager.notify_complete("api", "server", None).unwrap();
+        manager.notify_error("api", "deploy", "exit 1").unwrap();
+
+        // Nothing goes out until the window has passed
+        assert!(manager.take_due(start).is_empty());
+
+        let sent = manager.take_due(start + Duration::from_secs(3));
+        let messages: Vec<&str> = sent.iter().map(|n| n.message.as_str()).collect();
+        assert_eq!(messages, vec!["server", "deploy: exit 1", "3 tasks completed in web"]);
+        assert_eq!(sent[2].subtitle.as_deref(), Some("web"));
+        assert!(manager.take_due(start + Duration::from_secs(10)).is_empty());
+    }
+
+    #[test]
+    fn test_last_sent_without_desktop_support() {
+        let mut manager = NotificationManager::with_config(NotificationConfig {
+            quiet_hours: false,
+            ..Default::default()
+        });
+        assert!(manager.take_last_sent().is_none());
+
+        // Recorded whether or not osascript exists on this machine
+        let _ = manager.notify_error("web", "build", "exit 2");
+        let sent = manager.take_last_sent().unwrap();
+        assert_eq!(sent.message, "build: exit 2");
+        assert!(manager.take_last_sent().is_none());
+
+        // Suppressed notifications don't show up
+        manager.set_config(NotificationConfig {
+            enabled: false,
+            ..Default::default()
+        });
+        let _ = manager.notify_error("web", "test", "exit 1");
+        assert!(manager.take_last_sent().is_none());
+    }
+
     #[test]
     fn test_notification_event_emoji() {
         assert_eq!(NotificationEvent::Complete.emoji(), "✅");
@@ -2,17 +2,47 @@
 //!
 //! Maintains a global registry at ~/.gidterm/ports.json to avoid conflicts.
 //! Supports automatic port allocation, $PORT env var injection, and status tracking.
-
+//!
+//! The allocation range can be overridden in config.toml (see [`Config`]):
+//! ```toml
+//! [ports]
+//! range = [8000, 8010]
+//! bind_hosts = ["0.0.0.0", "::"]
+//! ```
+
+use crate::config::Config;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::net::TcpListener;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 /// Default port range for auto-allocation
-const PORT_RANGE_START: u16 = 3000;
-const PORT_RANGE_END: u16 = 3999;
+pub const PORT_RANGE_START: u16 = 3000;
+pub const PORT_RANGE_END: u16 = 3999;
+
+/// Hosts probed when checking whether a port is free (IPv4 and IPv6 wildcards)
+pub const DEFAULT_BIND_HOSTS: [&str; 2] = ["0.0.0.0", "::"];
+
+fn default_range() -> (u16, u16) {
+    (PORT_RANGE_START, PORT_RANGE_END)
+}
+
+fn default_bind_hosts() -> Vec<String> {
+    DEFAULT_BIND_HOSTS.iter().map(|h| h.to_string()).collect()
+}
+
+/// Port settings, the `[ports]` table of config.toml (see [`Config`])
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PortConfig {
+    /// Inclusive port range for auto-allocation
+    pub range: Option<(u16, u16)>,
+    /// Hosts to probe when checking port availability
+    pub bind_hosts: Option<Vec<String>>,
+    /// Registry file (default ~/.gidterm/ports.json)
+    pub registry: Option<PathBuf>,
+}
 
 /// Port allocation entry
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -47,16 +77,66 @@ pub enum PortStatus {
 }
 
 /// Port registry - maintains global port assignments
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PortRegistry {
     /// Port allocations keyed by project name
     pub allocations: HashMap<String, PortEntry>,
+    /// Inclusive port range used for auto-allocation
+    #[serde(default = "default_range")]
+    pub range: (u16, u16),
+    /// Hosts that must all accept a bind for a port to count as available
+    #[serde(default = "default_bind_hosts")]
+    pub bind_hosts: Vec<String>,
     /// Port to project mapping for quick lookup
     #[serde(skip)]
     port_map: HashMap<u16, String>,
+    /// File the registry was loaded from and is saved back to
+    #[serde(skip)]
+    path: Option<PathBuf>,
+}
+
+/// An empty registry kept in memory; it is never saved
+impl Default for PortRegistry {
+    fn default() -> Self {
+        Self {
+            allocations: HashMap::new(),
+            range: default_range(),
+            bind_hosts: default_bind_hosts(),
+            port_map: HashMap::new(),
+            path: None,
+        }
+    }
 }
 
 impl PortRegistry {
+    /// Use a custom auto-allocation range (inclusive)
+    pub fn with_range(mut self, start: u16, end: u16) -> Result<Self> {
+        self.set_range(start, end)?;
+        Ok(self)
+    }
+
+    /// Set the auto-allocation range (inclusive)
+    pub fn set_range(&mut self, start: u16, end: u16) -> Result<()> {
+        if start > end {
+            anyhow::bail!("Invalid port range {}-{}", start, end);
+        }
+        self.range = (start, end);
+        Ok(())
+    }
+
+    /// Use custom hosts for availability checks
+    pub fn with_bind_hosts(mut self, hosts: Vec<String>) -> Self {
+        self.bind_hosts = hosts;
+        self
+    }
+
+    /// Check if a port is free on all configured bind hosts
+    fn is_available(&self, port: u16) -> bool {
+        self.bind_hosts
+            .iter()
+            .all(|host| is_port_available_on(host, port))
+    }
+
     /// Get the default registry path
     pub fn default_path() -> PathBuf {
         dirs::home_dir()
@@ -65,32 +145,46 @@ impl PortRegistry {
             .join("ports.json")
     }
 
-    /// Load registry from default location
-    pub fn load() -> Result<Self> {
-        let path = Self::default_path();
-        Self::load_from(&path)
+    /// Load the configured registry (or the default one), applying the
+    /// `[ports]` settings
+    pub fn load(config: &Config) -> Result<Self> {
+        let config = &config.ports;
+        let path = config.registry.clone().unwrap_or_else(Self::default_path);
+        let mut registry = Self::load_from(&path)?;
+        if let Some((start, end)) = config.range {
+            registry.set_range(start, end)?;
+        }
+        if let Some(hosts) = &config.bind_hosts {
+            registry.bind_hosts = hosts.clone();
+        }
+        Ok(registry)
     }
 
     /// Load registry from specific path
-    pub fn load_from(path: &PathBuf) -> Result<Self> {
-        if !path.exists() {
-            return Ok(Self::default());
-        }
-
-        let content = std::fs::read_to_string(path)?;
-        let mut registry: Self = serde_json::from_str(&content)?;
+    ///
+    /// Changes are saved back to `path`, which doesn't have to exist yet.
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let mut registry = if path.exists() {
+            let content = std::fs::read_to_string(path)?;
+            serde_json::from_str(&content)?
+        } else {
+            Self::default()
+        };
+        registry.path = Some(path.to_path_buf());
         registry.rebuild_port_map();
         Ok(registry)
     }
 
-    /// Save registry to default location
+    /// Save registry to the file it was loaded from (no-op in memory)
     pub fn save(&self) -> Result<()> {
-        let path = Self::default_path();
-        self.save_to(&path)
+        match &self.path {
+            Some(path) => self.save_to(path),
+            None => Ok(()),
+        }
     }
 
     /// Save registry to specific path
-    pub fn save_to(&self, path: &PathBuf) -> Result<()> {
+    pub fn save_to(&self, path: &Path) -> Result<()> {
         // Ensure directory exists
         if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent)?;
@@ -114,7 +208,7 @@ impl PortRegistry {
         // Check if already allocated
         if let Some(entry) = self.allocations.get(project) {
             // Verify it's still available (not taken by external process)
-            if is_port_available(entry.port) || entry.active {
+            if self.is_available(entry.port) || entry.active {
                 return Ok(entry.port);
             }
             // Port was taken externally, need to reallocate
@@ -125,9 +219,10 @@ impl PortRegistry {
             );
         }
 
-        // Try preferred port first
-        if let Some(pref) = preferred {
-            if is_port_available(pref) && !self.port_map.contains_key(&pref) {
+        // Try preferred port first, if it's in the allocation range
+        let (start, end) = self.range;
+        if let Some(pref) = preferred.filter(|p| (start..=end).contains(p)) {
+            if self.is_available(pref) && !self.port_map.contains_key(&pref) {
                 self.allocate(project, pref)?;
                 return Ok(pref);
             }
@@ -141,12 +236,13 @@ impl PortRegistry {
 
     /// Find an available port in the range
     fn find_available_port(&self) -> Result<u16> {
-        for port in PORT_RANGE_START..=PORT_RANGE_END {
-            if !self.port_map.contains_key(&port) && is_port_available(port) {
+        let (start, end) = self.range;
+        for port in start..=end {
+            if !self.port_map.contains_key(&port) && self.is_available(port) {
                 return Ok(port);
             }
         }
-        anyhow::bail!("No available ports in range {}-{}", PORT_RANGE_START, PORT_RANGE_END)
+        anyhow::bail!("No available ports in range {}-{}", start, end)
     }
 
     /// Allocate a specific port to a project
@@ -216,7 +312,7 @@ impl PortRegistry {
         if let Some(entry) = self.allocations.get(project) {
             if entry.active {
                 PortStatus::Active
-            } else if is_port_available(entry.port) {
+            } else if self.is_available(entry.port) {
                 PortStatus::Reserved
             } else {
                 PortStatus::ExternallyUsed
@@ -239,7 +335,7 @@ impl PortRegistry {
             .allocations
             .iter()
             .filter(|(_, entry)| {
-                !entry.active && !is_port_available(entry.port)
+                !entry.active && !self.is_available(entry.port)
             })
             .map(|(k, _)| k.clone())
             .collect();
@@ -271,9 +367,22 @@ impl PortRegistry {
     }
 }
 
-/// Check if a port is available for binding
+/// Check if a port is available for binding on both IPv4 and IPv6 wildcards
 pub fn is_port_available(port: u16) -> bool {
-    TcpListener::bind(("127.0.0.1", port)).is_ok()
+    DEFAULT_BIND_HOSTS
+        .iter()
+        .all(|host| is_port_available_on(host, port))
+}
+
+/// Check if a port is available for binding on a specific host
+///
+/// Hosts that cannot be bound on this machine at all (e.g. `::` with IPv6
+/// disabled) don't count against availability.
+pub fn is_port_available_on(host: &str, port: u16) -> bool {
+    match TcpListener::bind((host, port)) {
+        Ok(_) => true,
+        Err(e) => e.kind() == std::io::ErrorKind::AddrNotAvailable,
+    }
 }
 
 /// Check if a process is running
@@ -297,25 +406,54 @@ fn is_process_running(_pid: u32) -> bool {
 pub struct PortManager {
     registry: PortRegistry,
     project_ports: HashMap<String, u16>,
+    /// Projects whose allocation was created by this session
+    session_owned: HashSet<String>,
 }
 
 impl PortManager {
-    /// Create a new port manager
-    pub fn new() -> Result<Self> {
-        let registry = PortRegistry::load()?;
-        Ok(Self {
+    /// Create a port manager on the global registry with the given settings
+    pub fn new(config: &Config) -> Result<Self> {
+        Ok(Self::from_registry(PortRegistry::load(config)?))
+    }
+
+    /// Create a port manager on `registry`
+    pub fn from_registry(registry: PortRegistry) -> Self {
+        Self {
             registry,
             project_ports: HashMap::new(),
-        })
+            session_owned: HashSet::new(),
+        }
+    }
+
+    /// Inclusive range ports are allocated from
+    pub fn range(&self) -> (u16, u16) {
+        self.registry.range
     }
 
     /// Allocate port for a project and return it
     pub fn allocate(&mut self, project: &str, preferred: Option<u16>) -> Result<u16> {
+        let preexisting = self.registry.allocations.contains_key(project);
         let port = self.registry.get_or_allocate(project, preferred)?;
         self.project_ports.insert(project.to_string(), port);
+        if !preexisting {
+            self.session_owned.insert(project.to_string());
+        }
         Ok(port)
     }
 
+    /// Release every allocation created by this session
+    ///
+    /// Allocations that already existed in the registry are kept.
+    /// Returns the number of released allocations.
+    pub fn release_all(&mut self) -> Result<usize> {
+        let owned: Vec<String> = self.session_owned.drain().collect();
+        for project in &owned {
+            self.registry.release(project)?;
+            self.project_ports.remove(project);
+        }
+        Ok(owned.len())
+    }
+
     /// Get environment variables for a project (includes $PORT)
     pub fn get_env(&self, project: &str) -> HashMap<String, String> {
         let mut env = HashMap::new();
@@ -352,12 +490,10 @@ impl PortManager {
     }
 }
 
+/// A port manager on an in-memory registry
 impl Default for PortManager {
     fn default() -> Self {
-        Self::new().unwrap_or_else(|_| Self {
-            registry: PortRegistry::default(),
-            project_ports: HashMap::new(),
-        })
+        Self::from_registry(PortRegistry::default())
     }
 }
 
@@ -373,23 +509,43 @@ mod tests {
         let _ = is_port_available(65534);
     }
 
+    #[test]
+    fn test_bound_port_unavailable() {
+        let listener = TcpListener::bind(("0.0.0.0", 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        assert!(!is_port_available(port));
+        assert!(!is_port_available_on("0.0.0.0", port));
+        drop(listener);
+    }
+
+    /// A registry saved in `dir` rather than ~/.gidterm
+    fn temp_registry(dir: &TempDir) -> PortRegistry {
+        PortRegistry::load_from(&dir.path().join("ports.json")).unwrap()
+    }
+
     #[test]
     fn test_registry_save_load() {
         let temp_dir = TempDir::new().unwrap();
         let path = temp_dir.path().join("ports.json");
 
-        let mut registry = PortRegistry::default();
+        // Allocations are saved to the file the registry came from
+        let mut registry = PortRegistry::load_from(&path).unwrap();
         registry.allocate("test-project", 3000).unwrap();
-        registry.save_to(&path).unwrap();
 
         let loaded = PortRegistry::load_from(&path).unwrap();
         assert!(loaded.allocations.contains_key("test-project"));
         assert_eq!(loaded.allocations["test-project"].port, 3000);
+
+        let copy = temp_dir.path().join("copy.json");
+        loaded.save_to(&copy).unwrap();
+        assert_eq!(PortRegistry::load_from(&copy).unwrap().allocations.len(), 1);
     }
 
     #[test]
     fn test_port_allocation() {
-        let mut registry = PortRegistry::default();
+        let temp_dir = TempDir::new().unwrap();
+        let mut registry = temp_registry(&temp_dir);
         
         // First allocation should get preferred port
         let port1 = registry.get_or_allocate("project1", Some(3000)).unwrap();
@@ -403,4 +559,65 @@ mod tests {
         let port2 = registry.get_or_allocate("project2", Some(3000)).unwrap();
         assert_ne!(port2, 3000); // 3000 is taken
     }
+
+    #[test]
+    fn test_custom_port_range() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut registry = temp_registry(&temp_dir).with_range(8000, 8010).unwrap();
+
+        let port = registry.get_or_allocate("range-project", None).unwrap();
+        assert!((8000..=8010).contains(&port));
+
+        // A preferred port outside the range is ignored
+        let port = registry.get_or_allocate("preferred-project", Some(3000)).unwrap();
+        assert!((8000..=8010).contains(&port));
+
+        assert!(PortRegistry::default().with_range(9000, 8000).is_err());
+    }
+
+    #[test]
+    fn test_release_all_keeps_preexisting() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("ports.json");
+        let mut registry = PortRegistry::load_from(&path).unwrap().with_range(8100, 8199).unwrap();
+        registry.get_or_allocate("persistent-project", None).unwrap();
+
+        let mut manager = PortManager::from_registry(PortRegistry::load_from(&path).unwrap());
+        manager.allocate("persistent-project", None).unwrap();
+        manager.allocate("session-project", None).unwrap();
+
+        assert_eq!(manager.release_all().unwrap(), 1);
+        assert!(manager.registry.allocations.contains_key("persistent-project"));
+        assert!(!manager.registry.allocations.contains_key("session-project"));
+        assert_eq!(manager.get_port("session-project"), None);
+
+        let saved = PortRegistry::load_from(&path).unwrap();
+        assert!(saved.allocations.contains_key("persistent-project"));
+        assert!(!saved.allocations.contains_key("session-project"));
+    }
+
+    #[test]
+    fn test_port_config_load() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("config.toml");
+        let registry = temp_dir.path().join("ports.json");
+        std::fs::write(
+            &path,
+            format!("[ports]\nrange = [8000, 8010]\nregistry = {:?}\n", registry.display().to_string()),
+        )
+        .unwrap();
+
+        let missing = temp_dir.path().join("missing.toml");
+        let config = Config::load_layers(&path, &missing).unwrap();
+        assert_eq!(config.ports.range, Some((8000, 8010)));
+
+        // Allocations go to the configured registry file
+        let mut loaded = PortRegistry::load(&config).unwrap();
+        let port = loaded.get_or_allocate("config-project", None).unwrap();
+        assert!((8000..=8010).contains(&port));
+        assert!(PortRegistry::load_from(&registry).unwrap().allocations.contains_key("config-project"));
+
+        let defaults = Config::load_layers(&missing, &missing).unwrap().ports;
+        assert!(defaults.range.is_none());
+    }
 }
@@ -2,7 +2,9 @@
 //!
 //! Detects anomalies and provides actionable suggestions:
 //! - Loss NaN (training diverged)
+//! - Loss divergence (loss blowing up before it turns NaN)
 //! - Loss plateau (not improving)
+//! - Overfitting (validation loss rising while training loss falls)
 //! - High loss after significant progress
 //! - Accuracy saturation
 //! - Error spikes
@@ -10,9 +12,35 @@
 
 use super::history::TaskMetricHistory;
 use super::TaskMetrics;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// Repeats of an advisory within this window don't count as new
+pub const ADVISORY_DEDUP_WINDOW: Duration = Duration::from_secs(30);
+
+/// Max distinct advisories kept per task
+const MAX_ADVISORY_HISTORY: usize = 50;
+
+/// Default seconds without output before a running task counts as stalled
+pub const DEFAULT_STALL_SECS: u64 = 120;
+
+/// Advisor settings, the `[advisor]` table of config.toml (see [`Config`](crate::config::Config))
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AdvisorConfig {
+    /// Offer (or, in agent mode, run) an advisory's `auto_action` when the
+    /// task defines a matching semantic command. Off unless set.
+    #[serde(default)]
+    pub auto_actions: bool,
+    /// Seconds a running task may go without output before it is reported
+    /// as stalled (default [`DEFAULT_STALL_SECS`]; tasks can override it)
+    #[serde(default)]
+    pub stall_secs: Option<u64>,
+}
 
 /// Severity of an advisory
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
 pub enum Severity {
     Info,
     Warning,
@@ -39,6 +67,98 @@ pub struct Advisory {
     pub auto_action: Option<String>,
 }
 
+/// An advisory as seen over time
+#[derive(Debug, Clone)]
+pub struct AdvisoryRecord {
+    /// Latest occurrence (its message may carry updated numbers)
+    pub advisory: Advisory,
+    pub first_seen: Instant,
+    pub last_seen: Instant,
+    /// Times the advisory fired, including repeats within the dedup window
+    pub count: u32,
+    /// Dismissed by the user; stays hidden even if it fires again
+    pub acknowledged: bool,
+}
+
+impl AdvisoryRecord {
+    /// Rules produce the same severity and suggestion on every firing,
+    /// while messages often embed the current loss or progress
+    fn is_same_rule(&self, advisory: &Advisory) -> bool {
+        self.advisory.severity == advisory.severity && self.advisory.suggestion == advisory.suggestion
+    }
+}
+
+/// Distinct advisories for one task, oldest first
+#[derive(Debug, Clone, Default)]
+pub struct AdvisoryHistory {
+    pub records: Vec<AdvisoryRecord>,
+}
+
+impl AdvisoryHistory {
+    /// Record advisories that fired at `now`, returning the ones that are new
+    ///
+    /// An advisory from the same rule as an existing record updates that
+    /// record instead. It only counts as new again once it has been quiet
+    /// for `ADVISORY_DEDUP_WINDOW`, and never while acknowledged.
+    pub fn record(&mut self, advisories: &[Advisory], now: Instant) -> Vec<Advisory> {
+        let mut fresh = Vec::new();
+
+        for advisory in advisories {
+            match self.records.iter_mut().find(|r| r.is_same_rule(advisory)) {
+                Some(record) => {
+                    let quiet = now.duration_since(record.last_seen) >= ADVISORY_DEDUP_WINDOW;
+                    if quiet && !record.acknowledged {
+                        fresh.push(advisory.clone());
+                    }
+                    record.advisory = advisory.clone();
+                    record.last_seen = now;
+                    record.count += 1;
+                }
+                None => {
+                    self.records.push(AdvisoryRecord {
+                        advisory: advisory.clone(),
+                        first_seen: now,
+                        last_seen: now,
+                        count: 1,
+                        acknowledged: false,
+                    });
+                    fresh.push(advisory.clone());
+                }
+            }
+        }
+
+        if self.records.len() > MAX_ADVISORY_HISTORY {
+            let excess = self.records.len() - MAX_ADVISORY_HISTORY;
+            self.records.drain(0..excess);
+        }
+
+        fresh
+    }
+
+    /// Acknowledge the record at `index`; false if out of range
+    pub fn acknowledge(&mut self, index: usize) -> bool {
+        match self.records.get_mut(index) {
+            Some(record) => {
+                record.acknowledged = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Unacknowledged records with their indices, most recently seen first
+    pub fn active(&self) -> Vec<(usize, &AdvisoryRecord)> {
+        let mut active: Vec<_> = self
+            .records
+            .iter()
+            .enumerate()
+            .filter(|(_, r)| !r.acknowledged)
+            .collect();
+        active.sort_by_key(|(_, r)| std::cmp::Reverse(r.last_seen));
+        active
+    }
+}
+
 /// Smart advisor that analyzes metrics and emits suggestions
 pub struct SmartAdvisor {
     rules: Vec<Box<dyn AdvisoryRule + Send + Sync>>,
@@ -58,7 +178,9 @@ impl SmartAdvisor {
     pub fn new() -> Self {
         let rules: Vec<Box<dyn AdvisoryRule + Send + Sync>> = vec![
             Box::new(LossNaNRule),
+            Box::new(LossDivergenceRule),
             Box::new(LossPlateauRule),
+            Box::new(OverfittingRule),
             Box::new(HighLossRule),
             Box::new(AccuracySaturationRule),
             Box::new(ErrorSpikeRule),
@@ -106,6 +228,51 @@ impl AdvisoryRule for LossNaNRule {
     }
 }
 
+/// Loss snapshots needed before divergence is judged
+const DIVERGENCE_MIN_HISTORY: usize = 10;
+/// Loss this many times the best seen so far has diverged
+const DIVERGENCE_FACTOR: f64 = 10.0;
+/// Snapshots used for the recent loss trend
+const DIVERGENCE_TREND_WINDOW: usize = 5;
+/// Recent rise per snapshot, as a fraction of the best loss, that counts as sharp
+const DIVERGENCE_SHARP_RISE: f64 = 0.2;
+
+struct LossDivergenceRule;
+impl AdvisoryRule for LossDivergenceRule {
+    fn evaluate(&self, _metrics: &TaskMetrics, history: Option<&TaskMetricHistory>) -> Option<Advisory> {
+        let history = history?;
+        let losses = history.metric_values("loss", history.snapshots.len());
+        if losses.len() < DIVERGENCE_MIN_HISTORY {
+            return None;
+        }
+
+        let latest = *losses.last()?;
+        let best = losses.iter().cloned().fold(f64::INFINITY, f64::min);
+        if !latest.is_finite() || best <= 0.0 {
+            return None; // NaN is LossNaNRule's job; zero loss gives no scale
+        }
+
+        let message = if latest > best * DIVERGENCE_FACTOR {
+            format!("Loss is diverging ({:.3e}, {:.0}x the best {:.4})", latest, latest / best, best)
+        } else {
+            // A sharp upturn only matters once the loss had been coming down
+            let had_decreased = losses[0] > best;
+            let trend = history.trend("loss", DIVERGENCE_TREND_WINDOW)?;
+            if !had_decreased || trend <= best * DIVERGENCE_SHARP_RISE {
+                return None;
+            }
+            format!("Loss is rising sharply ({:.4}, best {:.4}) after decreasing", latest, best)
+        };
+
+        Some(Advisory {
+            severity: Severity::Critical,
+            message,
+            suggestion: "Training is diverging: lower the learning rate or restore the last good checkpoint".to_string(),
+            auto_action: Some("early_stop".to_string()),
+        })
+    }
+}
+
 struct LossPlateauRule;
 impl AdvisoryRule for LossPlateauRule {
     fn evaluate(&self, _metrics: &TaskMetrics, history: Option<&TaskMetricHistory>) -> Option<Advisory> {
@@ -126,6 +293,40 @@ impl AdvisoryRule for LossPlateauRule {
     }
 }
 
+/// Snapshots over which validation loss must keep rising
+const OVERFIT_WINDOW: usize = 5;
+
+struct OverfittingRule;
+impl AdvisoryRule for OverfittingRule {
+    fn evaluate(&self, _metrics: &TaskMetrics, history: Option<&TaskMetricHistory>) -> Option<Advisory> {
+        let history = history?;
+        let val_losses = history.metric_values("val_loss", OVERFIT_WINDOW);
+        let losses = history.metric_values("loss", OVERFIT_WINDOW);
+        if val_losses.len() < OVERFIT_WINDOW || losses.len() < OVERFIT_WINDOW {
+            return None;
+        }
+
+        // Validation loss is only reported once per epoch, so repeats are fine
+        let val_rising = val_losses.windows(2).all(|w| w[1] >= w[0])
+            && val_losses[OVERFIT_WINDOW - 1] > val_losses[0];
+        let loss_falling = history.trend("loss", OVERFIT_WINDOW)? < 0.0;
+
+        if val_rising && loss_falling {
+            return Some(Advisory {
+                severity: Severity::Warning,
+                message: format!(
+                    "Validation loss rising ({:.4} -> {:.4}) while training loss falls - likely overfitting",
+                    val_losses[0],
+                    val_losses[OVERFIT_WINDOW - 1]
+                ),
+                suggestion: "Consider early stopping, or add regularization (dropout, weight decay, augmentation)".to_string(),
+                auto_action: Some("save_checkpoint".to_string()),
+            });
+        }
+        None
+    }
+}
+
 struct HighLossRule;
 impl AdvisoryRule for HighLossRule {
     fn evaluate(&self, metrics: &TaskMetrics, _history: Option<&TaskMetricHistory>) -> Option<Advisory> {
@@ -289,6 +490,54 @@ mod tests {
         assert!(advisories.iter().any(|a| a.severity == Severity::Critical));
     }
 
+    fn advisory(message: &str) -> Advisory {
+        Advisory {
+            severity: Severity::Warning,
+            message: message.to_string(),
+            suggestion: "Reduce learning rate".to_string(),
+            auto_action: None,
+        }
+    }
+
+    #[test]
+    fn test_history_dedups_within_window() {
+        let mut history = AdvisoryHistory::default();
+        let start = Instant::now();
+
+        assert_eq!(history.record(&[advisory("Loss 1.50")], start).len(), 1);
+        // Same rule, new numbers, inside the window: updated, not new
+        let fresh = history.record(&[advisory("Loss 1.40")], start + Duration::from_secs(5));
+        assert!(fresh.is_empty());
+        assert_eq!(history.records.len(), 1);
+        assert_eq!(history.records[0].count, 2);
+        assert_eq!(history.records[0].advisory.message, "Loss 1.40");
+
+        // Quiet for the whole window: fires again
+        let later = start + Duration::from_secs(5) + ADVISORY_DEDUP_WINDOW;
+        assert_eq!(history.record(&[advisory("Loss 1.30")], later).len(), 1);
+    }
+
+    #[test]
+    fn test_acknowledged_advisories_stay_hidden() {
+        let mut history = AdvisoryHistory::default();
+        let start = Instant::now();
+        let mut other = advisory("Errors");
+        other.severity = Severity::Critical;
+
+        history.record(&[advisory("Loss 1.50"), other], start);
+        assert_eq!(history.active().len(), 2);
+
+        assert!(history.acknowledge(0));
+        assert!(!history.acknowledge(5));
+        let active = history.active();
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].0, 1);
+
+        let later = start + ADVISORY_DEDUP_WINDOW * 2;
+        assert_eq!(history.record(&[advisory("Loss 1.20")], later).len(), 0);
+        assert!(history.records[0].acknowledged);
+    }
+
     #[test]
     fn test_no_false_positives_early() {
         let advisor = SmartAdvisor::new();
@@ -298,4 +547,86 @@ mod tests {
         let advisories = advisor.evaluate(&metrics, None);
         assert!(advisories.iter().all(|a| !a.message.contains("still high")));
     }
+
+    fn loss_history(losses: &[f64]) -> TaskMetricHistory {
+        let mut history = TaskMetricHistory::new();
+        let base = Instant::now();
+        for (i, &loss) in losses.iter().enumerate() {
+            let mut m = HashMap::new();
+            m.insert("loss".to_string(), loss);
+            history.record_at(i as f32 * 0.01, m, base + Duration::from_secs(i as u64));
+        }
+        history
+    }
+
+    fn divergence(losses: &[f64]) -> Option<Advisory> {
+        let metrics = make_metrics(0.5, *losses.last().unwrap(), vec![]);
+        LossDivergenceRule.evaluate(&metrics, Some(&loss_history(losses)))
+    }
+
+    #[test]
+    fn test_loss_divergence_explodes() {
+        let mut losses = vec![2.0, 1.5, 1.1, 0.8, 0.6, 0.45, 0.35, 0.3, 0.28, 0.27];
+        assert!(divergence(&losses).is_none());
+
+        losses.extend([0.9, 40.0, 1e9]);
+        let advisory = divergence(&losses).unwrap();
+        assert_eq!(advisory.severity, Severity::Critical);
+        assert!(advisory.message.contains("diverging"), "{}", advisory.message);
+        assert_eq!(advisory.auto_action, Some("early_stop".to_string()));
+    }
+
+    #[test]
+    fn test_loss_divergence_sharp_upturn() {
+        // Rises well short of 10x, but quickly after a steady decline
+        let losses = [2.0, 1.6, 1.2, 0.9, 0.7, 0.5, 0.4, 0.45, 0.6, 0.8, 1.1];
+        let advisory = divergence(&losses).unwrap();
+        assert!(advisory.message.contains("rising sharply"), "{}", advisory.message);
+
+        // Ordinary noise around a decreasing loss is fine
+        let noisy = [2.0, 1.6, 1.2, 0.9, 0.7, 0.5, 0.42, 0.45, 0.41, 0.43, 0.40];
+        assert!(divergence(&noisy).is_none());
+    }
+
+    #[test]
+    fn test_loss_divergence_needs_history() {
+        // Early spikes during warm-up are not divergence
+        assert!(divergence(&[0.5, 0.1, 5.0]).is_none());
+    }
+
+    fn train_val_history(pairs: &[(f64, f64)]) -> TaskMetricHistory {
+        let mut history = TaskMetricHistory::new();
+        let base = Instant::now();
+        for (i, &(loss, val_loss)) in pairs.iter().enumerate() {
+            let mut m = HashMap::new();
+            m.insert("loss".to_string(), loss);
+            m.insert("val_loss".to_string(), val_loss);
+            history.record_at(i as f32 * 0.05, m, base + Duration::from_secs(i as u64));
+        }
+        history
+    }
+
+    #[test]
+    fn test_overfitting_detection() {
+        let metrics = make_metrics(0.5, 0.2, vec![]);
+
+        // Both falling: healthy
+        let healthy = train_val_history(&[(0.9, 1.0), (0.7, 0.85), (0.5, 0.7), (0.4, 0.62), (0.3, 0.58), (0.25, 0.55)]);
+        assert!(OverfittingRule.evaluate(&metrics, Some(&healthy)).is_none());
+
+        // Curves diverge: train keeps falling, validation turns up
+        let diverging = train_val_history(&[
+            (0.9, 1.0),
+            (0.6, 0.7),
+            (0.4, 0.55),
+            (0.3, 0.56),
+            (0.22, 0.56),
+            (0.15, 0.61),
+            (0.1, 0.68),
+        ]);
+        let advisory = OverfittingRule.evaluate(&metrics, Some(&diverging)).unwrap();
+        assert_eq!(advisory.severity, Severity::Warning);
+        assert_eq!(advisory.auto_action, Some("save_checkpoint".to_string()));
+        assert!(advisory.message.contains("overfitting"));
+    }
 }
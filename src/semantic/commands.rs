@@ -7,11 +7,75 @@
 //!     command: python train.py
 //!     semantic_commands:
 //!       save_checkpoint: "model.save('checkpoint.pth')"
-//!       adjust_lr: "optimizer.param_groups[0]['lr'] = {value}"
+//!       adjust_lr: "optimizer.param_groups[0]['lr'] = {value:float}"
 //!       early_stop: "trainer.should_stop = True"
+//!       interrupt: 'raw:\x03'
 //! ```
+//!
+//! A placeholder may declare a type (`{value:float}`, `{epochs:int}`); values
+//! that don't parse as that type are rejected before the command is sent.
+//!
+//! Commands are sent as a line of input. With the `raw:` prefix the rest of
+//! the template is sent byte for byte with no newline, after decoding `\xNN`,
+//! `\n`, `\r`, `\t`, `\e` and `\\` escapes - for Ctrl-C (`\x03`), Ctrl-D
+//! (`\x04`) and other control sequences.
 
+use anyhow::Result;
+use regex::Regex;
 use std::collections::HashMap;
+use std::fmt;
+use std::sync::OnceLock;
+
+/// Template prefix for commands sent as raw bytes
+const RAW_PREFIX: &str = "raw:";
+
+/// `{name}` or `{name:kind}`
+fn placeholder_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\{(\w+)(?::(int|float))?\}").unwrap())
+}
+
+/// Expected type of a template parameter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ParamKind {
+    /// Any text (`{name}`)
+    #[default]
+    Text,
+    /// An integer (`{name:int}`)
+    Int,
+    /// A finite number (`{name:float}`)
+    Float,
+}
+
+impl ParamKind {
+    fn from_suffix(suffix: Option<&str>) -> Self {
+        match suffix {
+            Some("int") => ParamKind::Int,
+            Some("float") => ParamKind::Float,
+            _ => ParamKind::Text,
+        }
+    }
+
+    /// Whether `value` is acceptable for this kind
+    pub fn accepts(&self, value: &str) -> bool {
+        let value = value.trim();
+        match self {
+            ParamKind::Text => true,
+            ParamKind::Int => value.parse::<i64>().is_ok(),
+            ParamKind::Float => value.parse::<f64>().is_ok_and(f64::is_finite),
+        }
+    }
+}
+
+impl fmt::Display for ParamKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamKind::Text => write!(f, "text"),
+            ParamKind::Int => write!(f, "int"),
+            ParamKind::Float => write!(f, "float"),
+        }
+    }
+}
 
 /// A semantic command definition
 #[derive(Debug, Clone)]
@@ -22,43 +86,125 @@ pub struct SemanticCommand {
     pub template: String,
     /// Extracted parameter names from template
     pub params: Vec<String>,
+    /// Declared type of each parameter (`Text` when untyped)
+    pub kinds: HashMap<String, ParamKind>,
+    /// Send the rendered template as raw bytes, without a newline
+    pub raw: bool,
 }
 
 impl SemanticCommand {
     /// Create from a label and template string
     pub fn new(label: impl Into<String>, template: impl Into<String>) -> Self {
-        let template = template.into();
-        let params = Self::extract_params(&template);
+        let template: String = template.into();
+        let (template, raw) = match template.strip_prefix(RAW_PREFIX) {
+            Some(rest) => (rest.to_string(), true),
+            None => (template, false),
+        };
+        let extracted = Self::extract_params(&template);
+        let mut kinds = HashMap::new();
+        for (name, kind) in &extracted {
+            // The first typed occurrence decides
+            let entry = kinds.entry(name.clone()).or_insert(*kind);
+            if *entry == ParamKind::Text {
+                *entry = *kind;
+            }
+        }
         Self {
             label: label.into(),
             template,
-            params,
+            params: extracted.into_iter().map(|(name, _)| name).collect(),
+            kinds,
+            raw,
         }
     }
 
-    /// Extract {param} placeholders from template
-    fn extract_params(template: &str) -> Vec<String> {
-        let re = regex::Regex::new(r"\{(\w+)\}").unwrap();
-        re.captures_iter(template)
-            .map(|cap| cap[1].to_string())
+    /// Extract {param} and {param:kind} placeholders from template
+    fn extract_params(template: &str) -> Vec<(String, ParamKind)> {
+        placeholder_re()
+            .captures_iter(template)
+            .map(|cap| {
+                let kind = ParamKind::from_suffix(cap.get(2).map(|m| m.as_str()));
+                (cap[1].to_string(), kind)
+            })
             .collect()
     }
 
+    /// Declared type of a parameter
+    pub fn kind(&self, param: &str) -> ParamKind {
+        self.kinds.get(param).copied().unwrap_or_default()
+    }
+
+    /// Check provided values against the parameters' declared types
+    pub fn validate(&self, params: &HashMap<String, String>) -> Result<()> {
+        for (name, value) in params {
+            let kind = self.kind(name);
+            if !kind.accepts(value) {
+                anyhow::bail!("'{}' expects {}, got '{}'", name, kind, value);
+            }
+        }
+        Ok(())
+    }
+
     /// Check if this command requires parameters
     pub fn needs_params(&self) -> bool {
         !self.params.is_empty()
     }
 
     /// Render the template with provided parameter values
+    ///
+    /// Placeholders without a value are left as-is; call `validate` first
+    /// to reject values of the wrong type.
     pub fn render(&self, params: &HashMap<String, String>) -> String {
-        let mut result = self.template.clone();
-        for (key, value) in params {
-            result = result.replace(&format!("{{{}}}", key), value);
+        placeholder_re()
+            .replace_all(&self.template, |cap: &regex::Captures| match params.get(&cap[1]) {
+                Some(value) => value.clone(),
+                None => cap[0].to_string(),
+            })
+            .into_owned()
+    }
+
+    /// Bytes to write to the task's PTY for these parameter values
+    pub fn render_input(&self, params: &HashMap<String, String>) -> Vec<u8> {
+        let rendered = self.render(params);
+        if self.raw {
+            decode_escapes(&rendered)
+        } else {
+            format!("{}\n", rendered).into_bytes()
         }
-        result
     }
 }
 
+/// Decode `\xNN`, `\n`, `\r`, `\t`, `\e` and `\\`; anything else is kept as-is
+fn decode_escapes(text: &str) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(pos) = rest.find('\\') {
+        bytes.extend_from_slice(&rest.as_bytes()[..pos]);
+        let escape = &rest[pos + 1..];
+        let (decoded, len) = match escape.as_bytes().first() {
+            Some(b'x') => match escape.get(1..3).and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                Some(byte) => (Some(byte), 3),
+                None => (None, 0),
+            },
+            Some(b'n') => (Some(b'\n'), 1),
+            Some(b'r') => (Some(b'\r'), 1),
+            Some(b't') => (Some(b'\t'), 1),
+            Some(b'e') => (Some(0x1b), 1),
+            Some(b'\\') => (Some(b'\\'), 1),
+            _ => (None, 0),
+        };
+        match decoded {
+            Some(byte) => bytes.push(byte),
+            None => bytes.push(b'\\'),
+        }
+        rest = &escape[len..];
+    }
+
+    bytes.extend_from_slice(rest.as_bytes());
+    bytes
+}
+
 /// Registry of semantic commands for a task
 #[derive(Debug, Clone, Default)]
 pub struct TaskCommands {
@@ -67,11 +213,14 @@ pub struct TaskCommands {
 
 impl TaskCommands {
     /// Build from the semantic_commands HashMap in a Task
+    ///
+    /// Commands are sorted by label so F-key bindings stay stable.
     pub fn from_map(map: &HashMap<String, String>) -> Self {
-        let commands = map
+        let mut commands: Vec<SemanticCommand> = map
             .iter()
             .map(|(label, template)| SemanticCommand::new(label.clone(), template.clone()))
             .collect();
+        commands.sort_by(|a, b| a.label.cmp(&b.label));
         Self { commands }
     }
 
@@ -116,6 +265,51 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_typed_params() {
+        let cmd = SemanticCommand::new("adjust_lr", "set_lr({value:float}, epochs={epochs:int}, tag={tag})");
+        assert_eq!(cmd.params, vec!["value", "epochs", "tag"]);
+        assert_eq!(cmd.kind("value"), ParamKind::Float);
+        assert_eq!(cmd.kind("epochs"), ParamKind::Int);
+        assert_eq!(cmd.kind("tag"), ParamKind::Text);
+
+        let params = |value: &str| {
+            HashMap::from([
+                ("value".to_string(), value.to_string()),
+                ("epochs".to_string(), "3".to_string()),
+                ("tag".to_string(), "warm restart".to_string()),
+            ])
+        };
+        assert!(cmd.validate(&params("0.01")).is_ok());
+        assert_eq!(cmd.render(&params("0.01")), "set_lr(0.01, epochs=3, tag=warm restart)");
+
+        let err = cmd.validate(&params("abc")).unwrap_err();
+        assert_eq!(err.to_string(), "'value' expects float, got 'abc'");
+        assert!(cmd.validate(&params("inf")).is_err());
+
+        let mut bad_int = params("1e-4");
+        bad_int.insert("epochs".to_string(), "2.5".to_string());
+        assert!(cmd.validate(&bad_int).is_err());
+    }
+
+    #[test]
+    fn test_raw_command() {
+        let cmd = SemanticCommand::new("interrupt", "raw:\\x03");
+        assert!(cmd.raw);
+        assert_eq!(cmd.template, "\\x03");
+        assert_eq!(cmd.render_input(&HashMap::new()), vec![0x03]);
+
+        // Escapes, parameters and untouched backslashes
+        let cmd = SemanticCommand::new("quit", "raw:\\e:q{bang}\\r\\\\n \\q \\xZZ");
+        let params = HashMap::from([("bang".to_string(), "!".to_string())]);
+        assert_eq!(cmd.render_input(&params), b"\x1b:q!\r\\n \\q \\xZZ".to_vec());
+
+        // Plain commands are a line of input
+        let cmd = SemanticCommand::new("save", "model.save()");
+        assert!(!cmd.raw);
+        assert_eq!(cmd.render_input(&HashMap::new()), b"model.save()\n".to_vec());
+    }
+
     #[test]
     fn test_task_commands_from_map() {
         let mut map = HashMap::new();
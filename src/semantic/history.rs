@@ -11,12 +11,24 @@ pub struct MetricSnapshot {
     pub metrics: HashMap<String, f64>,
 }
 
+/// Default EMA weight given to one second of new progress data
+pub const DEFAULT_EMA_ALPHA: f64 = 0.05;
+
+/// Below this many snapshots the EMA is too young; ETA uses the window rate
+const EMA_MIN_SNAPSHOTS: usize = 5;
+
 /// History of metrics for a single task
 #[derive(Debug, Clone)]
 pub struct TaskMetricHistory {
     pub snapshots: Vec<MetricSnapshot>,
     pub max_snapshots: usize,
     pub started_at: Instant,
+    /// Smoothing factor for the progress rate EMA, in (0, 1]; higher reacts faster
+    pub ema_alpha: f64,
+    // Decayed sums of weighted rates and of weights; their ratio is the
+    // bias-corrected EMA, so early estimates aren't dragged toward zero
+    ema_sum: f64,
+    ema_weight: f64,
 }
 
 impl TaskMetricHistory {
@@ -25,22 +37,47 @@ impl TaskMetricHistory {
             snapshots: Vec::new(),
             max_snapshots: 500,
             started_at: Instant::now(),
+            ema_alpha: DEFAULT_EMA_ALPHA,
+            ema_sum: 0.0,
+            ema_weight: 0.0,
+        }
+    }
+
+    /// Create a history with a custom EMA smoothing factor
+    pub fn with_ema_alpha(alpha: f64) -> Self {
+        Self {
+            ema_alpha: alpha.clamp(f64::EPSILON, 1.0),
+            ..Self::new()
         }
     }
 
     /// Record a new metric snapshot
     pub fn record(&mut self, progress: f32, metrics: HashMap<String, f64>) {
+        self.record_at(progress, metrics, Instant::now());
+    }
+
+    /// Record a new metric snapshot taken at `timestamp`
+    pub fn record_at(&mut self, progress: f32, metrics: HashMap<String, f64>, timestamp: Instant) {
         // Deduplicate: skip if progress hasn't changed and it's been < 1s
         if let Some(last) = self.snapshots.last() {
-            if (last.progress - progress).abs() < 0.001
-                && last.timestamp.elapsed() < Duration::from_secs(1)
-            {
+            let since_last = timestamp.saturating_duration_since(last.timestamp);
+            if (last.progress - progress).abs() < 0.001 && since_last < Duration::from_secs(1) {
                 return;
             }
+
+            let dt = since_last.as_secs_f64();
+            if dt > 0.0 {
+                let rate = (progress - last.progress) as f64 / dt;
+                // Weight by elapsed time so a burst of quick snapshots counts
+                // for its progress, not for its (huge) instantaneous rate
+                let decay = (1.0 - self.ema_alpha).powf(dt);
+                self.ema_sum = self.ema_sum * decay + (1.0 - decay) * rate;
+                self.ema_weight = self.ema_weight * decay + (1.0 - decay);
+            }
         }
 
         self.snapshots.push(MetricSnapshot {
-            timestamp: Instant::now(),
+            timestamp,
             progress,
             metrics,
         });
@@ -58,32 +95,26 @@ impl TaskMetricHistory {
     }
 
     /// Estimate time remaining based on progress rate
+    ///
+    /// Uses the EMA-smoothed rate once enough snapshots exist, so bursty
+    /// progress doesn't make the ETA jump around.
     pub fn estimate_remaining(&self) -> Option<Duration> {
-        if self.snapshots.len() < 2 {
-            return None;
-        }
-
-        let latest = self.snapshots.last()?;
-        let progress = latest.progress;
-
-        if progress <= 0.0 || progress >= 1.0 {
-            return None;
-        }
-
-        // Use recent progress rate (last 10 snapshots or all if fewer)
-        let window = self.snapshots.len().min(10);
-        let start_idx = self.snapshots.len() - window;
-        let start = &self.snapshots[start_idx];
-        let end = latest;
+        let rate = if self.snapshots.len() >= EMA_MIN_SNAPSHOTS {
+            self.smoothed_rate()
+        } else {
+            self.progress_rate()
+        };
+        self.remaining_at(rate?)
+    }
 
-        let progress_delta = end.progress - start.progress;
-        let time_delta = end.timestamp.duration_since(start.timestamp);
+    /// Time to finish from the latest progress at `rate` (progress/second)
+    fn remaining_at(&self, rate: f64) -> Option<Duration> {
+        let progress = self.snapshots.last()?.progress;
 
-        if progress_delta <= 0.0 || time_delta.as_secs_f64() <= 0.0 {
+        if progress <= 0.0 || progress >= 1.0 || rate <= 0.0 {
             return None;
         }
 
-        let rate = progress_delta as f64 / time_delta.as_secs_f64(); // progress per second
         let remaining_progress = (1.0 - progress) as f64;
         let remaining_secs = remaining_progress / rate;
 
@@ -116,6 +147,11 @@ impl TaskMetricHistory {
         }
     }
 
+    /// Get the EMA-smoothed progress rate (progress/second)
+    pub fn smoothed_rate(&self) -> Option<f64> {
+        (self.ema_weight > 0.0).then(|| self.ema_sum / self.ema_weight)
+    }
+
     /// Get the last N values of a named metric (for sparklines)
     pub fn metric_values(&self, name: &str, last_n: usize) -> Vec<f64> {
         self.snapshots
@@ -310,4 +346,43 @@ mod tests {
         // Should be roughly 50ms (allow wide tolerance for CI)
         assert!(eta.as_millis() < 500, "ETA should be reasonable: {:?}", eta);
     }
+
+    #[test]
+    fn test_smoothed_eta_is_steadier() {
+        let mut history = TaskMetricHistory::new();
+        let base = Instant::now();
+
+        // Bursty progress: big jumps every few seconds, idle in between
+        let mut progress = 0.0;
+        let mut raw = Vec::new();
+        let mut smoothed = Vec::new();
+        for i in 0..80u64 {
+            progress += match i % 7 {
+                0 => 0.03,
+                3 => 0.012,
+                _ => 0.0,
+            };
+            history.record_at(progress, HashMap::new(), base + Duration::from_secs(i));
+
+            // Give the EMA time to settle before comparing
+            if i >= 20 {
+                let secs = |rate| history.remaining_at(rate).unwrap().as_secs_f64();
+                raw.push(secs(history.progress_rate().unwrap()));
+                smoothed.push(secs(history.smoothed_rate().unwrap()));
+            }
+        }
+
+        // Jitter: how far the ETA moves between consecutive snapshots
+        let jitter = |etas: &[f64]| etas.windows(2).map(|w| (w[1] - w[0]).abs()).sum::<f64>();
+        assert!(
+            jitter(&smoothed) < jitter(&raw) / 2.0,
+            "smoothed jitter {:.1}s should be well below raw {:.1}s",
+            jitter(&smoothed),
+            jitter(&raw)
+        );
+
+        // Enough snapshots: the estimate follows the smoothed rate
+        let expected = history.remaining_at(history.smoothed_rate().unwrap());
+        assert_eq!(history.estimate_remaining(), expected);
+    }
 }
@@ -0,0 +1,101 @@
+//! Log levels - tags each output line as an error, a warning or plain info
+//!
+//! Only common shapes are recognized: a leading level word (`ERROR`,
+//! `[WARN]`, after an optional timestamp), compiler diagnostics, `npm ERR!`,
+//! panics and exception lines. Anything else is info.
+
+use super::parsers::build::{ERROR_PATTERN, NPM_ERR_PATTERN, NPM_WARN_PATTERN, WARNING_PATTERN};
+use regex::RegexSet;
+
+/// Level of one output line, from least to most severe
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LogLevel {
+    #[default]
+    Info,
+    Warn,
+    Error,
+}
+
+/// A leading level word, after an optional timestamp: `ERROR`, `[ERROR]`,
+/// `2024-05-01 12:00:00,123 ERROR` or `<error>`
+fn leading_level(words: &str) -> String {
+    format!(r"(?i)^[\s\[]*(?:[\d\-:.,TZ+/]+\s+)*[\[<]?(?:{})\b", words)
+}
+
+/// Assigns a `LogLevel` to output lines
+pub struct LevelClassifier {
+    errors: RegexSet,
+    warnings: RegexSet,
+}
+
+impl LevelClassifier {
+    pub fn new() -> Self {
+        Self {
+            errors: RegexSet::new([
+                leading_level("error|err|fatal|critical|crit|panic"),
+                ERROR_PATTERN.to_string(),
+                NPM_ERR_PATTERN.to_string(),
+                r"panicked at".to_string(),
+                r"^Traceback \(most recent call last\)".to_string(),
+                r"^[A-Z]\w*(?:Error|Exception): ".to_string(),
+                r"^(?:--- )?FAIL\b".to_string(),
+            ])
+            .unwrap(),
+            warnings: RegexSet::new([
+                leading_level("warn|warning"),
+                WARNING_PATTERN.to_string(),
+                format!("(?i){}", NPM_WARN_PATTERN),
+                r"^[A-Z]\w*Warning: ".to_string(),
+            ])
+            .unwrap(),
+        }
+    }
+
+    /// Level of a single line of output
+    pub fn classify(&self, line: &str) -> LogLevel {
+        if self.errors.is_match(line) {
+            LogLevel::Error
+        } else if self.warnings.is_match(line) {
+            LogLevel::Warn
+        } else {
+            LogLevel::Info
+        }
+    }
+}
+
+impl Default for LevelClassifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_common_lines() {
+        let classifier = LevelClassifier::new();
+        let cases = [
+            ("error[E0308]: mismatched types", LogLevel::Error),
+            ("error: could not compile `gidterm`", LogLevel::Error),
+            ("npm ERR! code ELIFECYCLE", LogLevel::Error),
+            ("2024-05-01 12:00:00,123 ERROR db: connection refused", LogLevel::Error),
+            ("[FATAL] out of memory", LogLevel::Error),
+            ("thread 'main' panicked at src/main.rs:3:5:", LogLevel::Error),
+            ("ValueError: invalid literal for int()", LogLevel::Error),
+            ("--- FAIL: TestParse (0.00s)", LogLevel::Error),
+            ("warning: unused variable: `x`", LogLevel::Warn),
+            ("npm WARN deprecated request@2.88.2", LogLevel::Warn),
+            ("[WARN] disk almost full", LogLevel::Warn),
+            ("DeprecationWarning: use load() instead", LogLevel::Warn),
+            ("   Compiling gidterm v0.5.0", LogLevel::Info),
+            ("INFO server listening on :8080", LogLevel::Info),
+            ("test result: ok. 3 passed; 0 failed", LogLevel::Info),
+            ("Errors found: none", LogLevel::Info),
+        ];
+        for (line, level) in cases {
+            assert_eq!(classifier.classify(line), level, "{}", line);
+        }
+    }
+}
@@ -3,10 +3,14 @@
 pub mod advisor;
 pub mod commands;
 pub mod history;
+pub mod levels;
 pub mod parsers;
 pub mod registry;
+pub mod throttle;
 
-pub use registry::{OutputParser, ParsedMetrics, ParserRegistry};
+pub use levels::{LevelClassifier, LogLevel};
+pub use registry::{OutputParser, ParsedMetrics, ParserConfig, ParserRegistry};
+pub use throttle::ParseThrottle;
 
 use std::collections::HashMap;
 
@@ -58,4 +62,14 @@ impl MetricValue {
             _ => None,
         }
     }
+
+    /// Convert to a JSON value for AI/automation consumers
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            MetricValue::Float(f) => serde_json::json!(f),
+            MetricValue::Int(i) => serde_json::json!(i),
+            MetricValue::String(s) => serde_json::json!(s),
+            MetricValue::Bool(b) => serde_json::json!(b),
+        }
+    }
 }
@@ -5,6 +5,13 @@ use anyhow::Result;
 use regex::Regex;
 use std::collections::HashMap;
 
+/// Cargo/rustc warning, e.g. `warning: unused variable` or `warning[E0170]:`
+pub(crate) const WARNING_PATTERN: &str = r"warning(?:\[[\w]+\])?:";
+/// Cargo/rustc error at the start of a line, e.g. `error[E0308]: mismatched types`
+pub(crate) const ERROR_PATTERN: &str = r"(?i)^error(?:\[[\w]+\])?:";
+pub(crate) const NPM_WARN_PATTERN: &str = r"npm warn";
+pub(crate) const NPM_ERR_PATTERN: &str = r"npm ERR!";
+
 /// Parser for build tool output
 pub struct BuildParser {
     // Cargo patterns
@@ -24,12 +31,12 @@ impl BuildParser {
     pub fn new() -> Self {
         Self {
             compiling_re: Regex::new(r"Compiling\s+(\S+)\s+v").unwrap(),
-            warning_re: Regex::new(r"warning(?:\[[\w]+\])?:").unwrap(),
-            error_re: Regex::new(r"(?i)^error(?:\[[\w]+\])?:").unwrap(),
+            warning_re: Regex::new(WARNING_PATTERN).unwrap(),
+            error_re: Regex::new(ERROR_PATTERN).unwrap(),
             finished_re: Regex::new(r"Finished\s+`?(\w+)`?\s+.*in\s+([\d.]+)s").unwrap(),
             test_result_re: Regex::new(r"test result:.*?(\d+) passed.*?(\d+) failed").unwrap(),
-            npm_warn_re: Regex::new(r"npm warn").unwrap(),
-            npm_err_re: Regex::new(r"npm ERR!").unwrap(),
+            npm_warn_re: Regex::new(NPM_WARN_PATTERN).unwrap(),
+            npm_err_re: Regex::new(NPM_ERR_PATTERN).unwrap(),
             step_re: Regex::new(r"\[(\d+)/(\d+)\]").unwrap(),
         }
     }
@@ -0,0 +1,278 @@
+//! Docker output parser - BuildKit builds and compose up
+
+use crate::semantic::{MetricValue, OutputParser, ParsedMetrics, TaskMetrics};
+use anyhow::Result;
+use regex::Regex;
+use std::collections::HashMap;
+
+/// Parser for `docker build` (BuildKit plain progress) and `docker compose up`
+pub struct DockerParser {
+    // BuildKit patterns
+    step_re: Regex,
+    done_re: Regex,
+    cached_re: Regex,
+    step_error_re: Regex,
+    error_re: Regex,
+    layer_re: Regex,
+    export_re: Regex,
+    // Compose patterns
+    container_re: Regex,
+    // Detection
+    marker_re: Regex,
+}
+
+/// A numbered BuildKit step, e.g. `#5 [builder 2/8] RUN make`
+struct Step {
+    stage: String,
+    total: u32,
+}
+
+impl DockerParser {
+    pub fn new() -> Self {
+        Self {
+            step_re: Regex::new(r"^#(\d+) \[(?:(\S+) )?(\d+)/(\d+)\]").unwrap(),
+            done_re: Regex::new(r"^#(\d+) DONE ([\d.]+)s").unwrap(),
+            cached_re: Regex::new(r"^#(\d+) CACHED").unwrap(),
+            step_error_re: Regex::new(r"^#\d+ ERROR:? (.*)").unwrap(),
+            error_re: Regex::new(r"^(?:ERROR:|Error response from daemon:)\s*(.*)").unwrap(),
+            layer_re: Regex::new(r"^#\d+ sha256:[0-9a-f]+ .*\bdone$").unwrap(),
+            export_re: Regex::new(r"^#\d+ (exporting|writing image|naming to)").unwrap(),
+            container_re: Regex::new(r"Container (\S+)\s+(Created|Starting|Started|Running|Waiting|Healthy|Error)\b")
+                .unwrap(),
+            marker_re: Regex::new(r"(?m)^#\d+ \[|\[\+\] (?:Building|Running)").unwrap(),
+        }
+    }
+}
+
+impl Default for DockerParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OutputParser for DockerParser {
+    fn name(&self) -> &str {
+        "docker"
+    }
+
+    fn parse(&self, output: &str) -> Result<ParsedMetrics> {
+        let mut metrics = HashMap::new();
+        let mut errors = Vec::new();
+
+        // BuildKit numbers every vertex; steps are the ones with [k/n]
+        let mut steps: HashMap<u32, Step> = HashMap::new();
+        let mut finished: HashMap<u32, bool> = HashMap::new(); // vertex -> cached
+        let mut slowest_step: Option<f64> = None;
+        let mut layers_done = 0;
+        let mut exporting = false;
+        // Latest status per compose container
+        let mut containers: HashMap<String, String> = HashMap::new();
+
+        for line in output.lines() {
+            let line = line.trim();
+
+            if let Some(caps) = self.step_re.captures(line) {
+                let vertex: u32 = caps[1].parse().unwrap_or(0);
+                steps.insert(
+                    vertex,
+                    Step {
+                        stage: caps.get(2).map(|m| m.as_str().to_string()).unwrap_or_default(),
+                        total: caps[4].parse().unwrap_or(0),
+                    },
+                );
+            } else if let Some(caps) = self.done_re.captures(line) {
+                finished.insert(caps[1].parse().unwrap_or(0), false);
+                if let Ok(secs) = caps[2].parse::<f64>() {
+                    slowest_step = Some(slowest_step.map_or(secs, |s: f64| s.max(secs)));
+                }
+            } else if let Some(caps) = self.cached_re.captures(line) {
+                finished.insert(caps[1].parse().unwrap_or(0), true);
+            } else if let Some(caps) = self.step_error_re.captures(line) {
+                errors.push(caps[1].to_string());
+            } else if let Some(caps) = self.error_re.captures(line) {
+                errors.push(caps[1].to_string());
+            } else if self.layer_re.is_match(line) {
+                layers_done += 1;
+            } else if self.export_re.is_match(line) {
+                exporting = true;
+            } else if let Some(caps) = self.container_re.captures(line) {
+                containers.insert(caps[1].to_string(), caps[2].to_string());
+            }
+        }
+
+        // Per stage, the highest step seen gives its total; done steps count
+        let mut stage_totals: HashMap<&str, u32> = HashMap::new();
+        for step in steps.values() {
+            let total = stage_totals.entry(step.stage.as_str()).or_insert(0);
+            *total = (*total).max(step.total);
+        }
+        let steps_total: u32 = stage_totals.values().sum();
+        let steps_done = steps.keys().filter(|v| finished.contains_key(v)).count() as u32;
+        let cached_steps = steps
+            .keys()
+            .filter(|v| finished.get(v) == Some(&true))
+            .count() as i64;
+
+        let ready = containers
+            .values()
+            .filter(|s| matches!(s.as_str(), "Started" | "Running" | "Healthy"))
+            .count() as i64;
+        let healthy = containers.values().filter(|s| s.as_str() == "Healthy").count() as i64;
+
+        if steps_total > 0 {
+            metrics.insert("steps_done".to_string(), MetricValue::Int(steps_done as i64));
+            metrics.insert("steps_total".to_string(), MetricValue::Int(steps_total as i64));
+        }
+        if cached_steps > 0 {
+            metrics.insert("cached_steps".to_string(), MetricValue::Int(cached_steps));
+        }
+        if let Some(secs) = slowest_step {
+            metrics.insert("slowest_step_secs".to_string(), MetricValue::Float(secs));
+        }
+        if layers_done > 0 {
+            metrics.insert("layers_done".to_string(), MetricValue::Int(layers_done));
+        }
+        if !containers.is_empty() {
+            metrics.insert("services_started".to_string(), MetricValue::Int(ready));
+        }
+        if healthy > 0 {
+            metrics.insert("services_healthy".to_string(), MetricValue::Int(healthy));
+        }
+        if !errors.is_empty() {
+            metrics.insert("errors".to_string(), MetricValue::Int(errors.len() as i64));
+        }
+
+        // Progress from build steps, else from compose services coming up
+        let progress = if steps_total > 0 {
+            (steps_done as f32 / steps_total as f32).min(1.0)
+        } else if !containers.is_empty() {
+            ready as f32 / containers.len() as f32
+        } else {
+            0.0
+        };
+
+        let phase = if !errors.is_empty() {
+            Some("Failed".to_string())
+        } else if exporting {
+            Some("Exporting".to_string())
+        } else if steps_total > 0 {
+            Some("Building".to_string())
+        } else if !containers.is_empty() && ready as usize == containers.len() {
+            Some("Running".to_string())
+        } else if !containers.is_empty() {
+            Some("Starting".to_string())
+        } else {
+            None
+        };
+
+        Ok(TaskMetrics {
+            progress,
+            metrics,
+            phase,
+            errors,
+        })
+    }
+
+    fn can_parse(&self, output: &str) -> bool {
+        self.marker_re.is_match(output)
+    }
+
+    fn supported_types(&self) -> Vec<&str> {
+        vec!["docker", "Docker", "container", "compose"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BUILDKIT_OUTPUT: &str = r#"#0 building with "default" instance using docker driver
+#1 [internal] load build definition from Dockerfile
+#1 transferring dockerfile: 412B done
+#1 DONE 0.0s
+#2 [internal] load metadata for docker.io/library/rust:1.80
+#2 DONE 1.1s
+#3 [1/4] FROM docker.io/library/rust:1.80@sha256:29fe4376919e
+#3 sha256:6a9a8b7f0e1d 24.05MB / 24.05MB 1.3s done
+#3 sha256:bc21d6c1e2f0 64.39MB / 64.39MB 3.8s done
+#3 DONE 6.2s
+#4 [2/4] WORKDIR /app
+#4 CACHED
+#5 [3/4] COPY . .
+#5 DONE 0.3s
+#6 [4/4] RUN cargo build --release
+#6 12.40    Compiling serde v1.0.204"#;
+
+    #[test]
+    fn test_buildkit_progress() {
+        let parser = DockerParser::new();
+        assert!(parser.can_parse(BUILDKIT_OUTPUT));
+
+        let metrics = parser.parse(BUILDKIT_OUTPUT).unwrap();
+        assert_eq!(metrics.metrics["steps_done"].as_int(), Some(3));
+        assert_eq!(metrics.metrics["steps_total"].as_int(), Some(4));
+        assert_eq!(metrics.metrics["cached_steps"].as_int(), Some(1));
+        assert_eq!(metrics.metrics["layers_done"].as_int(), Some(2));
+        assert_eq!(metrics.metrics["slowest_step_secs"].as_float(), Some(6.2));
+        assert_eq!(metrics.progress, 0.75);
+        assert_eq!(metrics.phase, Some("Building".to_string()));
+        assert!(metrics.errors.is_empty());
+    }
+
+    #[test]
+    fn test_buildkit_error() {
+        let parser = DockerParser::new();
+
+        let output = r#"#6 [4/4] RUN cargo build --release
+#6 3.21 error: could not find `Cargo.toml`
+#6 ERROR: process "/bin/sh -c cargo build --release" did not complete successfully: exit code: 101
+------
+ > [4/4] RUN cargo build --release:
+------
+ERROR: failed to solve: process "/bin/sh -c cargo build --release" did not complete successfully: exit code: 101"#;
+
+        let metrics = parser.parse(output).unwrap();
+        assert_eq!(metrics.errors.len(), 2);
+        assert!(metrics.errors[1].starts_with("failed to solve"));
+        assert_eq!(metrics.metrics["errors"].as_int(), Some(2));
+        assert_eq!(metrics.phase, Some("Failed".to_string()));
+    }
+
+    #[test]
+    fn test_multi_stage_totals() {
+        let parser = DockerParser::new();
+
+        let output = "#4 [builder 1/2] FROM rust\n#4 DONE 0.1s\n#5 [builder 2/2] RUN make\n#5 DONE 9.0s\n#6 [stage-1 1/2] FROM debian\n#6 DONE 0.1s\n#7 [stage-1 2/2] COPY --from=builder /app /app";
+
+        let metrics = parser.parse(output).unwrap();
+        assert_eq!(metrics.metrics["steps_total"].as_int(), Some(4));
+        assert_eq!(metrics.progress, 0.75);
+    }
+
+    #[test]
+    fn test_compose_readiness() {
+        let parser = DockerParser::new();
+
+        let output = r#"[+] Running 3/3
+ ✔ Network app_default  Created
+ ✔ Container app-db-1   Healthy
+ ✔ Container app-cache-1  Started
+   Container app-web-1  Starting"#;
+
+        assert!(parser.can_parse(output));
+        let metrics = parser.parse(output).unwrap();
+        assert_eq!(metrics.metrics["services_started"].as_int(), Some(2));
+        assert_eq!(metrics.metrics["services_healthy"].as_int(), Some(1));
+        assert!((metrics.progress - 2.0 / 3.0).abs() < 1e-6);
+        assert_eq!(metrics.phase, Some("Starting".to_string()));
+    }
+
+    #[test]
+    fn test_ignores_unrelated_output() {
+        let parser = DockerParser::new();
+        assert!(!parser.can_parse("   Compiling serde v1.0.204\n[3/10] Building"));
+        // Mentioning docker isn't docker output
+        assert!(!parser.can_parse("$ docker compose up -d\nREADME.md:12: run `docker build .` first"));
+        assert!(!parser.can_parse("#1 is the issue number"));
+    }
+}
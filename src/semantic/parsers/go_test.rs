@@ -0,0 +1,203 @@
+//! Go test output parser - `go test ./...`
+
+use crate::semantic::{MetricValue, OutputParser, ParsedMetrics, TaskMetrics};
+use anyhow::Result;
+use regex::Regex;
+use std::collections::HashMap;
+
+/// Parser for `go test` output
+pub struct GoTestParser {
+    // Package result lines: `ok  	pkg	0.5s`, `FAIL	pkg	0.1s`
+    package_ok_re: Regex,
+    package_fail_re: Regex,
+    // Test case lines: `--- FAIL: TestSub (0.01s)`
+    case_re: Regex,
+    marker_re: Regex,
+}
+
+impl GoTestParser {
+    pub fn new() -> Self {
+        Self {
+            package_ok_re: Regex::new(r"^ok\s+(\S+)\s+(?:([\d.]+)s|\(cached\))").unwrap(),
+            package_fail_re: Regex::new(r"^FAIL\s+(\S+)\s+(?:([\d.]+)s|\[)").unwrap(),
+            case_re: Regex::new(r"^--- (PASS|FAIL|SKIP): (\S+)").unwrap(),
+            marker_re: Regex::new(
+                r"(?m)^(?:ok\s+\S+\s+(?:[\d.]+s|\(cached\))|FAIL\s+\S+\s+(?:[\d.]+s|\[)|--- (?:PASS|FAIL|SKIP):)",
+            )
+            .unwrap(),
+        }
+    }
+}
+
+impl Default for GoTestParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OutputParser for GoTestParser {
+    fn name(&self) -> &str {
+        "go_test"
+    }
+
+    fn parse(&self, output: &str) -> Result<ParsedMetrics> {
+        let mut metrics = HashMap::new();
+        let mut errors = Vec::new();
+
+        let mut packages_passed = 0;
+        let mut packages_failed = 0;
+        let mut tests_passed = 0;
+        let mut tests_failed = 0;
+        let mut tests_skipped = 0;
+        let mut duration: Option<f64> = None;
+
+        for line in output.lines() {
+            // Subtests are indented; only top-level cases are counted
+            if let Some(caps) = self.case_re.captures(line) {
+                match &caps[1] {
+                    "PASS" => tests_passed += 1,
+                    "FAIL" => {
+                        tests_failed += 1;
+                        errors.push(format!("--- FAIL: {}", &caps[2]));
+                    }
+                    _ => tests_skipped += 1,
+                }
+                continue;
+            }
+
+            let (caps, passed) = if let Some(caps) = self.package_ok_re.captures(line) {
+                (caps, true)
+            } else if let Some(caps) = self.package_fail_re.captures(line) {
+                (caps, false)
+            } else {
+                continue;
+            };
+
+            if passed {
+                packages_passed += 1;
+            } else {
+                packages_failed += 1;
+                errors.push(format!("FAIL {}", &caps[1]));
+            }
+            if let Some(secs) = caps.get(2).and_then(|m| m.as_str().parse::<f64>().ok()) {
+                *duration.get_or_insert(0.0) += secs;
+            }
+        }
+
+        if packages_passed + packages_failed > 0 {
+            metrics.insert("packages_passed".to_string(), MetricValue::Int(packages_passed));
+            metrics.insert("packages_failed".to_string(), MetricValue::Int(packages_failed));
+        }
+        if tests_passed + tests_failed + tests_skipped > 0 {
+            metrics.insert("tests_passed".to_string(), MetricValue::Int(tests_passed));
+            metrics.insert("tests_failed".to_string(), MetricValue::Int(tests_failed));
+        }
+        if tests_skipped > 0 {
+            metrics.insert("tests_skipped".to_string(), MetricValue::Int(tests_skipped));
+        }
+        if let Some(secs) = duration {
+            metrics.insert("test_time_secs".to_string(), MetricValue::Float(secs));
+        }
+
+        let phase = if packages_failed > 0 || tests_failed > 0 {
+            Some("Failed".to_string())
+        } else if packages_passed > 0 {
+            Some("Passed".to_string())
+        } else if output.contains("=== RUN") || tests_passed > 0 {
+            Some("Testing".to_string())
+        } else {
+            None
+        };
+
+        Ok(TaskMetrics {
+            // `go test ./...` doesn't announce how many packages it will run
+            progress: 0.0,
+            metrics,
+            phase,
+            errors,
+        })
+    }
+
+    fn can_parse(&self, output: &str) -> bool {
+        self.marker_re.is_match(output)
+    }
+
+    fn supported_types(&self) -> Vec<&str> {
+        vec!["go_test", "gotest"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIXED_OUTPUT: &str = "=== RUN   TestAdd
+--- PASS: TestAdd (0.00s)
+=== RUN   TestSub
+--- FAIL: TestSub (0.01s)
+    math_test.go:12: expected 1, got 2
+=== RUN   TestDiv
+=== RUN   TestDiv/by_zero
+    --- PASS: TestDiv/by_zero (0.00s)
+--- PASS: TestDiv (0.00s)
+=== RUN   TestSlow
+--- SKIP: TestSlow (0.00s)
+FAIL
+FAIL\texample.com/calc/math\t0.012s
+ok  \texample.com/calc/strings\t0.250s
+ok  \texample.com/calc/parse\t(cached)
+?   \texample.com/calc/cmd\t[no test files]
+FAIL\texample.com/calc/broken [build failed]";
+
+    #[test]
+    fn test_mixed_transcript() {
+        let parser = GoTestParser::new();
+        assert!(parser.can_parse(MIXED_OUTPUT));
+
+        let metrics = parser.parse(MIXED_OUTPUT).unwrap();
+        assert_eq!(metrics.metrics["packages_passed"].as_int(), Some(2));
+        assert_eq!(metrics.metrics["packages_failed"].as_int(), Some(2));
+        assert_eq!(metrics.metrics["tests_passed"].as_int(), Some(2));
+        assert_eq!(metrics.metrics["tests_failed"].as_int(), Some(1));
+        assert_eq!(metrics.metrics["tests_skipped"].as_int(), Some(1));
+
+        let secs = metrics.metrics["test_time_secs"].as_float().unwrap();
+        assert!((secs - 0.262).abs() < 1e-9);
+
+        assert_eq!(
+            metrics.errors,
+            vec![
+                "--- FAIL: TestSub",
+                "FAIL example.com/calc/math",
+                "FAIL example.com/calc/broken",
+            ]
+        );
+        assert_eq!(metrics.phase, Some("Failed".to_string()));
+    }
+
+    #[test]
+    fn test_all_passing() {
+        let parser = GoTestParser::new();
+
+        let metrics = parser.parse("ok  \texample.com/calc\t0.5s").unwrap();
+        assert_eq!(metrics.metrics["packages_passed"].as_int(), Some(1));
+        assert_eq!(metrics.metrics["packages_failed"].as_int(), Some(0));
+        assert_eq!(metrics.phase, Some("Passed".to_string()));
+        assert!(metrics.errors.is_empty());
+    }
+
+    #[test]
+    fn test_can_parse() {
+        let parser = GoTestParser::new();
+        assert!(parser.can_parse("--- FAIL: TestSub (0.01s)"));
+        assert!(!parser.can_parse("ok, starting server"));
+        assert!(!parser.can_parse("   Compiling serde v1.0.204"));
+        assert!(parser.can_parse("FAIL\texample.com/api\t0.012s"));
+        assert!(parser.can_parse("FAIL\texample.com/api [build failed]"));
+
+        // Jest reports failing files with a bare FAIL too
+        let jest = "FAIL src/foo.test.js\n  ● adds numbers\n\nTests:       1 failed, 3 passed, 4 total";
+        assert!(!parser.can_parse(jest));
+        assert!(!parser.can_parse("FAIL src/bar.test.ts (5.2 s)"));
+    }
+}
@@ -8,8 +8,11 @@ use std::collections::HashMap;
 /// Parser for ML training output
 pub struct MLTrainingParser {
     epoch_regex: Regex,
+    batch_regex: Regex,
     loss_regex: Regex,
+    val_loss_regex: Regex,
     accuracy_regex: Regex,
+    val_accuracy_regex: Regex,
     lr_regex: Regex,
 }
 
@@ -18,8 +21,13 @@ impl MLTrainingParser {
     pub fn new() -> Self {
         Self {
             epoch_regex: Regex::new(r"(?i)epoch\s*(\d+)/(\d+)").unwrap(),
-            loss_regex: Regex::new(r"(?i)loss:\s*([\d.]+)").unwrap(),
-            accuracy_regex: Regex::new(r"(?i)(?:acc|accuracy):\s*([\d.]+)").unwrap(),
+            // Keras progress bar: `1234/1500 [=====>....] - ETA: 5s - loss: ...`
+            batch_regex: Regex::new(r"^\s*(\d+)/(\d+) \[[=>.]*\]").unwrap(),
+            // `\b` keeps `val_loss:` from being read as the training loss
+            loss_regex: Regex::new(r"(?i)\bloss:\s*([\d.]+)").unwrap(),
+            val_loss_regex: Regex::new(r"(?i)\bval(?:id|idation)?[_ ]loss:\s*([\d.]+)").unwrap(),
+            accuracy_regex: Regex::new(r"(?i)\b(?:acc|accuracy):\s*([\d.]+)").unwrap(),
+            val_accuracy_regex: Regex::new(r"(?i)\bval(?:id|idation)?[_ ]acc(?:uracy)?:\s*([\d.]+)").unwrap(),
             lr_regex: Regex::new(r"(?i)(?:lr|learning.?rate):\s*([\d.e-]+)").unwrap(),
         }
     }
@@ -36,6 +44,24 @@ impl MLTrainingParser {
         None
     }
     
+    /// Extract batch progress within the current epoch
+    ///
+    /// Only a progress bar after the latest `Epoch m/n` line counts; the
+    /// previous epoch's bar says nothing about the current one.
+    fn extract_batch(&self, output: &str) -> Option<(i64, i64)> {
+        for line in output.lines().rev() {
+            if let Some(captures) = self.batch_regex.captures(line) {
+                let current = captures.get(1)?.as_str().parse::<i64>().ok()?;
+                let total = captures.get(2)?.as_str().parse::<i64>().ok()?;
+                return Some((current, total));
+            }
+            if self.epoch_regex.is_match(line) {
+                return None;
+            }
+        }
+        None
+    }
+    
     /// Extract loss value
     fn extract_loss(&self, output: &str) -> Option<f64> {
         for line in output.lines().rev() {
@@ -46,6 +72,16 @@ impl MLTrainingParser {
         None
     }
     
+    /// Extract validation loss value
+    fn extract_val_loss(&self, output: &str) -> Option<f64> {
+        for line in output.lines().rev() {
+            if let Some(captures) = self.val_loss_regex.captures(line) {
+                return captures.get(1)?.as_str().parse::<f64>().ok();
+            }
+        }
+        None
+    }
+    
     /// Extract accuracy value
     fn extract_accuracy(&self, output: &str) -> Option<f64> {
         for line in output.lines().rev() {
@@ -56,6 +92,16 @@ impl MLTrainingParser {
         None
     }
     
+    /// Extract validation accuracy value
+    fn extract_val_accuracy(&self, output: &str) -> Option<f64> {
+        for line in output.lines().rev() {
+            if let Some(captures) = self.val_accuracy_regex.captures(line) {
+                return captures.get(1)?.as_str().parse::<f64>().ok();
+            }
+        }
+        None
+    }
+    
     /// Extract learning rate
     fn extract_lr(&self, output: &str) -> Option<f64> {
         for line in output.lines().rev() {
@@ -86,10 +132,18 @@ impl OutputParser for MLTrainingParser {
             metrics.insert("epoch".to_string(), MetricValue::Int(current));
             metrics.insert("total_epochs".to_string(), MetricValue::Int(total));
             
-            if total > 0 {
-                current as f32 / total as f32
-            } else {
+            if total <= 0 {
                 0.0
+            } else if let Some((batch, batches)) = self.extract_batch(output) {
+                metrics.insert("batch".to_string(), MetricValue::Int(batch));
+                metrics.insert("total_batches".to_string(), MetricValue::Int(batches));
+
+                // Keras announces an epoch as it starts: blend in how far
+                // through it the batches are
+                let within = if batches > 0 { batch as f32 / batches as f32 } else { 0.0 };
+                (((current - 1) as f32 + within.min(1.0)) / total as f32).clamp(0.0, 1.0)
+            } else {
+                current as f32 / total as f32
             }
         } else {
             0.0
@@ -100,11 +154,21 @@ impl OutputParser for MLTrainingParser {
             metrics.insert("loss".to_string(), MetricValue::Float(loss));
         }
         
+        // Extract validation loss
+        if let Some(val_loss) = self.extract_val_loss(output) {
+            metrics.insert("val_loss".to_string(), MetricValue::Float(val_loss));
+        }
+        
         // Extract accuracy
         if let Some(acc) = self.extract_accuracy(output) {
             metrics.insert("accuracy".to_string(), MetricValue::Float(acc));
         }
         
+        // Extract validation accuracy
+        if let Some(val_acc) = self.extract_val_accuracy(output) {
+            metrics.insert("val_accuracy".to_string(), MetricValue::Float(val_acc));
+        }
+        
         // Extract learning rate
         if let Some(lr) = self.extract_lr(output) {
             metrics.insert("learning_rate".to_string(), MetricValue::Float(lr));
@@ -194,6 +258,55 @@ mod tests {
         assert_eq!(metrics.metrics["accuracy"].as_float(), Some(0.789));
     }
     
+    #[test]
+    fn test_val_loss_extraction() {
+        let parser = MLTrainingParser::new();
+        
+        let output = "Epoch 3/10 - loss: 0.412 - acc: 0.850 - val_loss: 0.530 - val_acc: 0.801";
+        
+        let metrics = parser.parse(output).unwrap();
+        
+        assert_eq!(metrics.metrics["loss"].as_float(), Some(0.412));
+        assert_eq!(metrics.metrics["val_loss"].as_float(), Some(0.530));
+        assert_eq!(metrics.metrics["accuracy"].as_float(), Some(0.850));
+        
+        // A line with only validation loss doesn't count as training loss
+        let metrics = parser.parse("loss: 0.3\nval_loss: 0.6").unwrap();
+        assert_eq!(metrics.metrics["loss"].as_float(), Some(0.3));
+        assert_eq!(metrics.metrics["val_loss"].as_float(), Some(0.6));
+    }
+    
+    #[test]
+    fn test_keras_progress_bar() {
+        let parser = MLTrainingParser::new();
+        
+        let output = "Epoch 1/10
+1500/1500 [==============================] - 14s 9ms/step - loss: 0.5123 - accuracy: 0.8201 - val_loss: 0.4012 - val_accuracy: 0.8555
+Epoch 2/10
+1500/1500 [==============================] - 12s 8ms/step - loss: 0.3340 - accuracy: 0.8790 - val_loss: 0.3521 - val_accuracy: 0.8702
+Epoch 3/10
+1125/1500 [=====================>........] - ETA: 3s - loss: 0.2301 - accuracy: 0.9104";
+        
+        let metrics = parser.parse(output).unwrap();
+        
+        assert_eq!(metrics.metrics["epoch"].as_int(), Some(3));
+        assert_eq!(metrics.metrics["batch"].as_int(), Some(1125));
+        assert_eq!(metrics.metrics["total_batches"].as_int(), Some(1500));
+        // Two epochs done plus 3/4 of the third
+        assert!((metrics.progress - 0.275).abs() < 1e-6);
+        
+        assert_eq!(metrics.metrics["loss"].as_float(), Some(0.2301));
+        assert_eq!(metrics.metrics["accuracy"].as_float(), Some(0.9104));
+        // Validation metrics come from the last finished epoch
+        assert_eq!(metrics.metrics["val_loss"].as_float(), Some(0.3521));
+        assert_eq!(metrics.metrics["val_accuracy"].as_float(), Some(0.8702));
+        
+        // Without a bar for the current epoch, progress is the epoch fraction
+        let metrics = parser.parse("Epoch 1/10\n1500/1500 [====] - loss: 0.5\nEpoch 2/10").unwrap();
+        assert!(!metrics.metrics.contains_key("batch"));
+        assert_eq!(metrics.progress, 0.2);
+    }
+    
     #[test]
     fn test_error_detection() {
         let parser = MLTrainingParser::new();
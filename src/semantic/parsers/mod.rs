@@ -1,9 +1,15 @@
 //! Output parsers for different task types
 
 pub mod build;
+pub mod docker;
+pub mod go_test;
 pub mod regex;
 pub mod ml_training;
+pub mod tqdm;
 
 pub use build::BuildParser;
+pub use docker::DockerParser;
+pub use go_test::GoTestParser;
 pub use regex::RegexParser;
 pub use ml_training::MLTrainingParser;
+pub use tqdm::TqdmParser;
@@ -0,0 +1,185 @@
+//! tqdm progress bar parser - tqdm, PyTorch Lightning, Hugging Face
+
+use crate::semantic::{MetricValue, OutputParser, ParsedMetrics, TaskMetrics};
+use anyhow::Result;
+use regex::Regex;
+use std::collections::HashMap;
+
+/// Parser for tqdm-style progress bars, e.g.
+/// `Epoch 2: 45%|████▌     | 450/1000 [00:10<00:12, 44.3it/s, loss=0.23]`
+pub struct TqdmParser {
+    bar_re: Regex,
+    epoch_re: Regex,
+}
+
+impl TqdmParser {
+    pub fn new() -> Self {
+        Self {
+            // The bar itself is any run of non-`|` characters (unicode blocks, spaces, `#`)
+            bar_re: Regex::new(
+                r"(?m)^\s*(?:(?P<desc>.*?):\s*)?(?P<pct>\d{1,3})%\|[^|]*\|\s*(?P<n>\d+)/(?P<total>\d+)\s*\[(?P<elapsed>[\d:]+)<(?P<remaining>[\d:]+|\?)(?:,\s*(?P<rate>[\d.]+|\?)(?P<unit>it/s|s/it))?(?:,\s*(?P<postfix>[^\]]*))?\]",
+            )
+            .unwrap(),
+            epoch_re: Regex::new(r"(?i)epoch\s*(\d+)").unwrap(),
+        }
+    }
+}
+
+impl Default for TqdmParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Parse a tqdm time like `00:12` or `1:02:03` into seconds
+fn parse_clock(clock: &str) -> Option<u64> {
+    clock
+        .split(':')
+        .try_fold(0u64, |secs, part| Some(secs * 60 + part.parse::<u64>().ok()?))
+}
+
+impl OutputParser for TqdmParser {
+    fn name(&self) -> &str {
+        "tqdm"
+    }
+
+    fn parse(&self, output: &str) -> Result<ParsedMetrics> {
+        let mut metrics = HashMap::new();
+
+        // Bars redraw in place; only the latest one matters
+        let Some(caps) = output.lines().rev().find_map(|line| self.bar_re.captures(line)) else {
+            return Ok(TaskMetrics {
+                progress: 0.0,
+                metrics,
+                phase: None,
+                errors: Vec::new(),
+            });
+        };
+
+        let n: i64 = caps["n"].parse().unwrap_or(0);
+        let total: i64 = caps["total"].parse().unwrap_or(0);
+        metrics.insert("current".to_string(), MetricValue::Int(n));
+        metrics.insert("total".to_string(), MetricValue::Int(total));
+
+        // n/total is finer-grained than the rounded percentage
+        let progress = if total > 0 {
+            (n as f32 / total as f32).min(1.0)
+        } else {
+            caps["pct"].parse::<f32>().unwrap_or(0.0) / 100.0
+        };
+
+        if let Some(rate) = caps.name("rate").and_then(|m| m.as_str().parse::<f64>().ok()) {
+            // Slow loops report seconds per iteration instead
+            let throughput = match caps.name("unit").map(|m| m.as_str()) {
+                Some("s/it") if rate > 0.0 => 1.0 / rate,
+                _ => rate,
+            };
+            metrics.insert("throughput".to_string(), MetricValue::Float(throughput));
+        }
+
+        if let Some(secs) = parse_clock(&caps["elapsed"]) {
+            metrics.insert("elapsed_secs".to_string(), MetricValue::Int(secs as i64));
+        }
+        if let Some(secs) = parse_clock(&caps["remaining"]) {
+            metrics.insert("remaining_secs".to_string(), MetricValue::Int(secs as i64));
+        }
+
+        // Postfix: `loss=0.23, v_num=1`; non-numeric values are skipped
+        if let Some(postfix) = caps.name("postfix") {
+            for pair in postfix.as_str().split(',') {
+                if let Some((key, value)) = pair.split_once('=') {
+                    if let Ok(value) = value.trim().parse::<f64>() {
+                        metrics.insert(key.trim().to_string(), MetricValue::Float(value));
+                    }
+                }
+            }
+        }
+
+        let desc = caps.name("desc").map(|m| m.as_str()).unwrap_or("");
+        if let Some(epoch) = self.epoch_re.captures(desc).and_then(|c| c[1].parse::<i64>().ok()) {
+            metrics.insert("epoch".to_string(), MetricValue::Int(epoch));
+        }
+
+        let phase = if desc.to_lowercase().contains("valid") {
+            Some("Validation".to_string())
+        } else if !desc.is_empty() {
+            Some(desc.trim().to_string())
+        } else {
+            None
+        };
+
+        Ok(TaskMetrics {
+            progress,
+            metrics,
+            phase,
+            errors: Vec::new(),
+        })
+    }
+
+    fn can_parse(&self, output: &str) -> bool {
+        self.bar_re.is_match(output)
+    }
+
+    fn supported_types(&self) -> Vec<&str> {
+        vec!["tqdm", "lightning"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lightning_bar() {
+        let parser = TqdmParser::new();
+        let output = "Epoch 2:  45%|████▌     | 450/1000 [00:10<00:12, 44.3it/s, loss=0.23, v_num=1]";
+
+        assert!(parser.can_parse(output));
+        let metrics = parser.parse(output).unwrap();
+        assert_eq!(metrics.progress, 0.45);
+        assert_eq!(metrics.metrics["current"].as_int(), Some(450));
+        assert_eq!(metrics.metrics["total"].as_int(), Some(1000));
+        assert_eq!(metrics.metrics["throughput"].as_float(), Some(44.3));
+        assert_eq!(metrics.metrics["elapsed_secs"].as_int(), Some(10));
+        assert_eq!(metrics.metrics["remaining_secs"].as_int(), Some(12));
+        assert_eq!(metrics.metrics["loss"].as_float(), Some(0.23));
+        assert_eq!(metrics.metrics["v_num"].as_float(), Some(1.0));
+        assert_eq!(metrics.metrics["epoch"].as_int(), Some(2));
+        assert_eq!(metrics.phase, Some("Epoch 2".to_string()));
+    }
+
+    #[test]
+    fn test_plain_bar_and_slow_rate() {
+        let parser = TqdmParser::new();
+
+        // Bare tqdm: no description, no postfix, hours in the clock
+        let metrics = parser
+            .parse(" 10%|▉         | 1/10 [1:00:05<?, 120.00s/it]")
+            .unwrap();
+        assert_eq!(metrics.progress, 0.1);
+        assert_eq!(metrics.metrics["elapsed_secs"].as_int(), Some(3605));
+        assert!(!metrics.metrics.contains_key("remaining_secs"));
+        let throughput = metrics.metrics["throughput"].as_float().unwrap();
+        assert!((throughput - 1.0 / 120.0).abs() < 1e-9);
+        assert_eq!(metrics.phase, None);
+    }
+
+    #[test]
+    fn test_latest_redraw_wins() {
+        let parser = TqdmParser::new();
+        let output = "Validation DataLoader 0:  20%|██        | 2/10 [00:01<00:04, 1.95it/s]\n\
+                      Validation DataLoader 0:  90%|█████████ | 9/10 [00:04<00:00, 2.01it/s]";
+
+        let metrics = parser.parse(output).unwrap();
+        assert_eq!(metrics.progress, 0.9);
+        assert_eq!(metrics.phase, Some("Validation".to_string()));
+    }
+
+    #[test]
+    fn test_ignores_non_bars() {
+        let parser = TqdmParser::new();
+        assert!(!parser.can_parse("Epoch 3/10 - loss: 0.23"));
+        assert!(!parser.can_parse("[3/10] Building 45%"));
+        assert!(!parser.can_parse("1234/1500 [=====>....] - ETA: 5s"));
+    }
+}
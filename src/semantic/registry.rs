@@ -2,11 +2,20 @@
 
 use super::TaskMetrics;
 use anyhow::Result;
+use serde::Deserialize;
 use std::collections::HashMap;
 
 /// Parsed metrics from output
 pub type ParsedMetrics = TaskMetrics;
 
+/// Parser settings, the `[parsers]` table of config.toml (see [`Config`](crate::config::Config))
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ParserConfig {
+    /// Merge the metrics of every matching parser instead of picking one
+    #[serde(default)]
+    pub merge: bool,
+}
+
 /// Trait for output parsers
 pub trait OutputParser: Send + Sync {
     /// Parser name/identifier
@@ -17,6 +26,19 @@ pub trait OutputParser: Send + Sync {
     
     /// Check if this parser can handle the output
     fn can_parse(&self, output: &str) -> bool;
+
+    /// How well this parser fits the output, from 0.0 to 1.0
+    ///
+    /// Defaults to the fraction of non-empty lines `can_parse` accepts, so
+    /// a parser whose patterns match more of the output scores higher.
+    fn confidence(&self, output: &str) -> f32 {
+        let lines: Vec<&str> = output.lines().filter(|l| !l.trim().is_empty()).collect();
+        if lines.is_empty() {
+            return 0.0;
+        }
+        let matched = lines.iter().filter(|l| self.can_parse(l)).count();
+        matched as f32 / lines.len() as f32
+    }
     
     /// Get supported task types
     fn supported_types(&self) -> Vec<&str>;
@@ -26,6 +48,7 @@ pub trait OutputParser: Send + Sync {
 pub struct ParserRegistry {
     parsers: HashMap<String, Box<dyn OutputParser>>,
     type_mappings: HashMap<String, String>, // task_type -> parser_name
+    order: Vec<String>, // parser names in registration order
 }
 
 impl ParserRegistry {
@@ -34,6 +57,7 @@ impl ParserRegistry {
         Self {
             parsers: HashMap::new(),
             type_mappings: HashMap::new(),
+            order: Vec::new(),
         }
     }
     
@@ -46,6 +70,9 @@ impl ParserRegistry {
             self.type_mappings.insert(task_type.to_string(), name.clone());
         }
         
+        if !self.order.contains(&name) {
+            self.order.push(name.clone());
+        }
         self.parsers.insert(name, parser);
     }
     
@@ -60,42 +87,103 @@ impl ParserRegistry {
         self.get(parser_name)
     }
     
-    /// Find a parser that can handle the output
+    /// Find the parser most confident it can handle the output
+    ///
+    /// Only parsers whose `can_parse` accepts the output are considered.
+    /// Ties go to the parser registered first.
     pub fn find_parser(&self, output: &str) -> Option<&dyn OutputParser> {
-        for parser in self.parsers.values() {
-            if parser.can_parse(output) {
-                return Some(parser.as_ref());
+        let mut best: Option<(&dyn OutputParser, f32)> = None;
+
+        for name in &self.order {
+            let parser = self.parsers[name].as_ref();
+            if !parser.can_parse(output) {
+                continue;
+            }
+            let confidence = parser.confidence(output);
+            if best.is_none_or(|(_, c)| confidence > c) {
+                best = Some((parser, confidence));
             }
         }
-        None
+
+        best.map(|(parser, _)| parser)
     }
     
+    /// Select the parser `parse` would use: the task type's mapping, else
+    /// the most confident match
+    pub fn select(&self, task_type: Option<&str>, output: &str) -> Option<&dyn OutputParser> {
+        task_type
+            .and_then(|t| self.get_for_type(t))
+            .or_else(|| self.find_parser(output))
+    }
+
     /// Parse output with appropriate parser
     pub fn parse(&self, task_type: Option<&str>, output: &str) -> Result<ParsedMetrics> {
-        // Try task type mapping first
-        if let Some(task_type) = task_type {
-            if let Some(parser) = self.get_for_type(task_type) {
-                return parser.parse(output);
-            }
+        match self.select(task_type, output) {
+            Some(parser) => parser.parse(output),
+            None => Ok(Self::no_parser_metrics()),
         }
-        
-        // Fall back to auto-detection
-        if let Some(parser) = self.find_parser(output) {
-            return parser.parse(output);
+    }
+
+    /// Parse output with every applicable parser and merge the results
+    ///
+    /// The task type's parser comes first, then every parser whose
+    /// `can_parse` matches, by descending confidence. Metric keys are
+    /// unioned (earlier parsers win on conflicts), progress is the maximum,
+    /// the phase is the first one reported and errors are concatenated.
+    pub fn parse_all(&self, task_type: Option<&str>, output: &str) -> Result<ParsedMetrics> {
+        let typed = task_type.and_then(|t| self.get_for_type(t));
+
+        let mut matching: Vec<(&dyn OutputParser, f32)> = self
+            .order
+            .iter()
+            .map(|name| self.parsers[name].as_ref())
+            .filter(|p| typed.is_none_or(|t| t.name() != p.name()) && p.can_parse(output))
+            .map(|p| (p, p.confidence(output)))
+            .collect();
+        // Stable sort keeps registration order on ties
+        matching.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        let parsers: Vec<&dyn OutputParser> = typed
+            .into_iter()
+            .chain(matching.into_iter().map(|(p, _)| p))
+            .collect();
+        if parsers.is_empty() {
+            return Ok(Self::no_parser_metrics());
         }
-        
-        // No parser found, return default
-        Ok(ParsedMetrics {
+
+        let mut merged = ParsedMetrics {
+            progress: 0.0,
+            metrics: HashMap::new(),
+            phase: None,
+            errors: Vec::new(),
+        };
+        for parser in parsers {
+            let metrics = parser.parse(output)?;
+            merged.progress = merged.progress.max(metrics.progress);
+            for (key, value) in metrics.metrics {
+                merged.metrics.entry(key).or_insert(value);
+            }
+            if merged.phase.is_none() {
+                merged.phase = metrics.phase;
+            }
+            merged.errors.extend(metrics.errors);
+        }
+
+        Ok(merged)
+    }
+
+    fn no_parser_metrics() -> ParsedMetrics {
+        ParsedMetrics {
             progress: 0.0,
             metrics: HashMap::new(),
             phase: None,
             errors: vec!["No suitable parser found".to_string()],
-        })
+        }
     }
     
     /// List all registered parsers
     pub fn list_parsers(&self) -> Vec<&str> {
-        self.parsers.keys().map(|s| s.as_str()).collect()
+        self.order.iter().map(|s| s.as_str()).collect()
     }
 }
 
@@ -108,6 +196,7 @@ impl Default for ParserRegistry {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::Config;
     
     struct TestParser;
     
@@ -144,6 +233,82 @@ mod tests {
         assert!(registry.get_for_type("test_task").is_some());
     }
     
+    #[test]
+    fn test_build_output_beats_regex_on_confidence() {
+        use crate::semantic::parsers::{BuildParser, RegexParser};
+
+        // Generic parser registered first, so a plain first-match would pick it
+        let mut registry = ParserRegistry::new();
+        registry.register(Box::new(RegexParser::default_parser()));
+        registry.register(Box::new(BuildParser::new()));
+
+        let output = "   Compiling serde v1.0.204\n   Compiling tokio v1.40.0\n[3/10] Building gidterm";
+        let regex = registry.get("regex").unwrap();
+        let build = registry.get("build").unwrap();
+        assert!(regex.can_parse(output));
+        assert!(build.confidence(output) > regex.confidence(output));
+
+        assert_eq!(registry.find_parser(output).unwrap().name(), "build");
+    }
+
+    #[test]
+    fn test_confidence_tie_goes_to_first_registered() {
+        struct Named(&'static str);
+        impl OutputParser for Named {
+            fn name(&self) -> &str {
+                self.0
+            }
+            fn parse(&self, _output: &str) -> Result<ParsedMetrics> {
+                TestParser.parse("")
+            }
+            fn can_parse(&self, output: &str) -> bool {
+                output.contains("test")
+            }
+            fn supported_types(&self) -> Vec<&str> {
+                vec![]
+            }
+        }
+
+        let mut registry = ParserRegistry::new();
+        registry.register(Box::new(Named("zeta")));
+        registry.register(Box::new(Named("alpha")));
+
+        assert_eq!(registry.list_parsers(), vec!["zeta", "alpha"]);
+        assert_eq!(registry.find_parser("test output").unwrap().name(), "zeta");
+    }
+
+    #[test]
+    fn test_parse_all_merges_build_and_regex() {
+        use crate::semantic::parsers::{BuildParser, RegexParser};
+
+        let mut registry = ParserRegistry::new();
+        registry.register(Box::new(BuildParser::new()));
+        registry.register(Box::new(RegexParser::default_parser()));
+
+        let output = "   Compiling serde v1.0.204\nwarning: unused variable\nDownloading assets 75%";
+
+        // Single-parser mode picks build and loses the percentage
+        assert_eq!(registry.select(None, output).unwrap().name(), "build");
+        assert_eq!(registry.parse(None, output).unwrap().progress, 0.0);
+
+        let merged = registry.parse_all(None, output).unwrap();
+        assert_eq!(merged.progress, 0.75);
+        assert_eq!(merged.metrics["crates_compiled"].as_int(), Some(1));
+        assert_eq!(merged.metrics["warnings"].as_int(), Some(1));
+        assert_eq!(merged.phase, Some("Compiling".to_string()));
+    }
+
+    #[test]
+    fn test_parser_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "[ports]\nrange = [4000, 4100]\n\n[parsers]\nmerge = true\n").unwrap();
+
+        let missing = dir.path().join("missing.toml");
+        assert!(Config::load_layers(&path, &missing).unwrap().parsers.merge);
+        assert!(!Config::load_layers(&missing, &missing).unwrap().parsers.merge);
+    }
+
     #[test]
     fn test_parse() {
         let mut registry = ParserRegistry::new();
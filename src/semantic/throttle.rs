@@ -0,0 +1,114 @@
+//! Parse throttling - chatty tasks are run through the parsers a few times
+//! per second rather than on every line
+
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+/// Shortest gap between two parses of the same task's output
+pub const METRIC_PARSE_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Tracks which tasks have unparsed output and when each was last parsed
+#[derive(Debug, Clone)]
+pub struct ParseThrottle {
+    interval: Duration,
+    pending: HashSet<String>,
+    last_parse: HashMap<String, Instant>,
+    parses: usize,
+}
+
+impl ParseThrottle {
+    /// Parse each task at most once per `interval`
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            pending: HashSet::new(),
+            last_parse: HashMap::new(),
+            parses: 0,
+        }
+    }
+
+    /// New output arrived for a task
+    pub fn mark(&mut self, task_id: &str) {
+        if !self.pending.contains(task_id) {
+            self.pending.insert(task_id.to_string());
+        }
+    }
+
+    /// Tasks with new output whose interval has passed, in ID order
+    ///
+    /// They count as parsed at `now`.
+    pub fn take_due(&mut self, now: Instant) -> Vec<String> {
+        let mut due: Vec<String> = self
+            .pending
+            .iter()
+            .filter(|id| {
+                self.last_parse
+                    .get(*id)
+                    .is_none_or(|last| now.saturating_duration_since(*last) >= self.interval)
+            })
+            .cloned()
+            .collect();
+        due.sort();
+
+        for task_id in &due {
+            self.pending.remove(task_id);
+            self.last_parse.insert(task_id.clone(), now);
+        }
+        self.parses += due.len();
+        due
+    }
+
+    /// Whether a task has output left to parse, regardless of the interval
+    ///
+    /// Used when the task exits so its final output is always parsed.
+    pub fn take_now(&mut self, task_id: &str, now: Instant) -> bool {
+        if !self.pending.remove(task_id) {
+            return false;
+        }
+        self.last_parse.insert(task_id.to_string(), now);
+        self.parses += 1;
+        true
+    }
+
+    /// How many parses have been handed out
+    pub fn parses(&self) -> usize {
+        self.parses
+    }
+}
+
+impl Default for ParseThrottle {
+    fn default() -> Self {
+        Self::new(METRIC_PARSE_INTERVAL)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_throttle_interval() {
+        let start = Instant::now();
+        let mut throttle = ParseThrottle::new(Duration::from_millis(250));
+        assert!(throttle.take_due(start).is_empty());
+
+        // The first output is parsed straight away
+        throttle.mark("build");
+        assert_eq!(throttle.take_due(start), vec!["build"]);
+
+        // More output within the interval waits
+        for _ in 0..100 {
+            throttle.mark("build");
+        }
+        throttle.mark("test");
+        assert_eq!(throttle.take_due(start + Duration::from_millis(100)), vec!["test"]);
+        assert_eq!(throttle.take_due(start + Duration::from_millis(250)), vec!["build"]);
+        assert!(throttle.take_due(start + Duration::from_secs(5)).is_empty());
+
+        // An exiting task is parsed at once, but only if it has new output
+        throttle.mark("build");
+        assert!(throttle.take_now("build", start + Duration::from_millis(300)));
+        assert!(!throttle.take_now("build", start + Duration::from_millis(300)));
+        assert_eq!(throttle.parses(), 4);
+    }
+}
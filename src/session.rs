@@ -1,39 +1,69 @@
 //! Session persistence - track task history across runs
 
+use crate::app::ViewMode;
+use crate::core::Graph;
 use anyhow::Result;
 use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
+use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 const SESSIONS_DIR: &str = ".gidterm/sessions";
+const UI_STATE_PATH: &str = ".gidterm/ui-state.json";
+
+/// Sessions with more JSON than this are saved gzip-compressed
+const COMPRESS_ABOVE_BYTES: usize = 256 * 1024;
+
+/// Runs taking this many times their estimate count as a big overrun
+pub const OVERRUN_RATIO: f64 = 1.5;
+
+/// Output lines kept per task run in the session unless configured otherwise
+pub const DEFAULT_SESSION_OUTPUT_LINES: usize = 1000;
 
 /// A session represents one gidterm run
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Session {
     pub id: String,
     pub project: String,
     pub started_at: DateTime<Utc>,
     pub ended_at: Option<DateTime<Utc>>,
     pub tasks: HashMap<String, TaskHistory>,
+    /// Output lines kept per run; older lines are dropped
+    #[serde(skip, default = "default_output_limit")]
+    output_limit: usize,
+}
+
+fn default_output_limit() -> usize {
+    DEFAULT_SESSION_OUTPUT_LINES
 }
 
 /// History of a single task across multiple runs
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TaskHistory {
     pub task_id: String,
     pub runs: Vec<TaskRun>,
 }
 
 /// A single run of a task
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TaskRun {
     pub started: DateTime<Utc>,
     pub ended: Option<DateTime<Utc>>,
     pub status: TaskStatus,
     pub output: Vec<String>,
+    /// Lines the run has produced, including those dropped from `output`
+    #[serde(default)]
+    pub output_lines: usize,
     pub exit_code: Option<i32>,
+    /// Latest value of each numeric metric parsed from the output
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub metrics: HashMap<String, f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -44,6 +74,206 @@ pub enum TaskStatus {
     Failed,
 }
 
+impl TaskRun {
+    /// Wall time of the run, once it has ended
+    pub fn duration(&self) -> Option<Duration> {
+        self.ended.and_then(|ended| (ended - self.started).to_std().ok())
+    }
+
+    /// Wall time of the run in seconds, once it has ended
+    pub fn duration_secs(&self) -> Option<f64> {
+        self.ended
+            .map(|ended| (ended - self.started).num_milliseconds() as f64 / 1000.0)
+    }
+}
+
+impl TaskHistory {
+    /// Runs that ended `Done`
+    pub fn success_count(&self) -> usize {
+        self.runs.iter().filter(|run| run.status == TaskStatus::Done).count()
+    }
+
+    /// Wall time of all runs that have ended
+    pub fn total_duration(&self) -> Duration {
+        self.runs.iter().filter_map(TaskRun::duration).sum()
+    }
+
+    /// Mean wall time of the runs that have ended
+    pub fn average_duration(&self) -> Option<Duration> {
+        let durations: Vec<Duration> = self.runs.iter().filter_map(TaskRun::duration).collect();
+        let count = u32::try_from(durations.len()).ok().filter(|&n| n > 0)?;
+        Some(durations.iter().sum::<Duration>() / count)
+    }
+
+    /// Share of ended runs that succeeded, from 0.0 to 1.0
+    pub fn success_rate(&self) -> Option<f64> {
+        let ended = self.runs.iter().filter(|run| run.ended.is_some()).count();
+        (ended > 0).then(|| self.success_count() as f64 / ended as f64)
+    }
+
+    /// One line for `gidterm history`, e.g. "3 runs, 2 ok, avg 4.2s"
+    pub fn summary(&self) -> String {
+        let average = self
+            .average_duration()
+            .map_or_else(|| "-".to_string(), |d| format!("{:.1}s", d.as_secs_f64()));
+        let runs = self.runs.len();
+        let plural = if runs == 1 { "" } else { "s" };
+        format!("{} run{}, {} ok, avg {}", runs, plural, self.success_count(), average)
+    }
+}
+
+/// The run with the best final value of a metric
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BestRun {
+    pub session_id: String,
+    pub task_id: String,
+    pub started: DateTime<Utc>,
+    pub value: f64,
+}
+
+/// Find the run whose final `metric` is highest (or lowest) across sessions
+///
+/// Ties go to the most recent run.
+pub fn best_run(sessions: &[Session], metric: &str, maximize: bool) -> Option<BestRun> {
+    sessions
+        .iter()
+        .flat_map(|session| {
+            session.tasks.values().flat_map(move |history| {
+                history.runs.iter().filter_map(move |run| {
+                    let value = *run.metrics.get(metric)?;
+                    (!value.is_nan()).then(|| BestRun {
+                        session_id: session.id.clone(),
+                        task_id: history.task_id.clone(),
+                        started: run.started,
+                        value,
+                    })
+                })
+            })
+        })
+        .max_by(|a, b| {
+            let by_value = if maximize {
+                a.value.total_cmp(&b.value)
+            } else {
+                b.value.total_cmp(&a.value)
+            };
+            by_value.then(a.started.cmp(&b.started))
+        })
+}
+
+/// How a task's actual runtime compares with its `estimated_hours`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EstimateVariance {
+    pub task_id: String,
+    pub estimated_hours: u32,
+    pub actual_secs: f64,
+    pub status: TaskStatus,
+    /// Percent over (positive) or under (negative) the estimate
+    pub variance_pct: f64,
+    /// Took at least `OVERRUN_RATIO` times the estimate
+    pub overrun: bool,
+}
+
+/// Join each estimated task with its latest ended run in `session`
+///
+/// Tasks without an estimate (or with `0`), or that never finished a run,
+/// are skipped. Sorted by variance, biggest overrun first.
+pub fn estimate_report(graph: &Graph, session: &Session) -> Vec<EstimateVariance> {
+    let mut report: Vec<EstimateVariance> = graph
+        .all_tasks()
+        .iter()
+        .filter_map(|(id, task)| {
+            let estimated_hours = task.estimated_hours.filter(|h| *h > 0)?;
+            let run = session.tasks.get(id)?.runs.iter().rev().find(|run| run.ended.is_some())?;
+            let actual_secs = run.duration_secs()?;
+            let ratio = actual_secs / (estimated_hours as f64 * 3600.0);
+            Some(EstimateVariance {
+                task_id: id.clone(),
+                estimated_hours,
+                actual_secs,
+                status: run.status.clone(),
+                variance_pct: (ratio - 1.0) * 100.0,
+                overrun: ratio >= OVERRUN_RATIO,
+            })
+        })
+        .collect();
+    report.sort_by(|a, b| b.variance_pct.total_cmp(&a.variance_pct).then(a.task_id.cmp(&b.task_id)));
+    report
+}
+
+/// Differences in task outcomes between two sessions
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SessionDiff {
+    pub from: String,
+    pub to: String,
+    /// Tasks that ran in both sessions, sorted by ID
+    pub tasks: Vec<TaskDiff>,
+    /// Tasks that only ran in the newer session
+    pub added: Vec<String>,
+    /// Tasks that only ran in the older session
+    pub removed: Vec<String>,
+}
+
+/// How one task's latest run compares between two sessions
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TaskDiff {
+    pub task_id: String,
+    pub before: TaskStatus,
+    pub after: TaskStatus,
+    pub before_secs: Option<f64>,
+    pub after_secs: Option<f64>,
+}
+
+impl TaskDiff {
+    pub fn status_changed(&self) -> bool {
+        self.before != self.after
+    }
+
+    /// Change in runtime (positive = slower), when both runs ended
+    pub fn runtime_delta_secs(&self) -> Option<f64> {
+        Some(self.after_secs? - self.before_secs?)
+    }
+}
+
+/// Dashboard selection and view, restored when the same graph is reopened
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UiState {
+    /// Project name, or `workspace:` and the workspace's project names
+    pub source: String,
+    pub selected_project: usize,
+    pub selected_task: usize,
+    pub view_mode: ViewMode,
+}
+
+impl UiState {
+    /// Load the saved UI state, if any
+    pub fn load() -> Result<Option<Self>> {
+        Self::load_from(Path::new(UI_STATE_PATH))
+    }
+
+    /// Load UI state from a file; a missing file is `None`
+    pub fn load_from(path: &Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(path)?;
+        Ok(Some(serde_json::from_str(&content)?))
+    }
+
+    /// Save the UI state for the next run
+    pub fn save(&self) -> Result<()> {
+        self.save_to(Path::new(UI_STATE_PATH))
+    }
+
+    /// Save UI state to a file, creating its directory
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
 impl Session {
     /// Create a new session
     pub fn new(project: String) -> Self {
@@ -54,79 +284,209 @@ impl Session {
             started_at: Utc::now(),
             ended_at: None,
             tasks: HashMap::new(),
+            output_limit: DEFAULT_SESSION_OUTPUT_LINES,
         }
     }
 
-    /// Get session file path
-    fn session_path(&self) -> PathBuf {
-        Path::new(SESSIONS_DIR).join(format!("{}.json", self.id))
+    /// Keep only the last `lines` output lines of each run
+    pub fn set_output_limit(&mut self, lines: usize) {
+        self.output_limit = lines.max(1);
     }
 
     /// Save session to disk
     pub fn save(&self) -> Result<()> {
-        // Ensure sessions directory exists
-        fs::create_dir_all(SESSIONS_DIR)?;
+        self.save_in(Path::new(SESSIONS_DIR))
+    }
+
+    /// Save session to a sessions directory
+    ///
+    /// Large sessions are written as `<id>.json.gz`, others as `<id>.json`;
+    /// `latest.json` links to whichever was written. Files are replaced by
+    /// renaming, so a crash mid-save leaves the previous save readable.
+    pub fn save_in(&self, dir: &Path) -> Result<()> {
+        fs::create_dir_all(dir)?;
 
-        let path = self.session_path();
         let json = serde_json::to_string_pretty(self)?;
-        fs::write(&path, json)?;
+        let plain = dir.join(format!("{}.json", self.id));
+        let compressed = dir.join(format!("{}.json.gz", self.id));
+        let (path, stale) = if json.len() > COMPRESS_ABOVE_BYTES {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(json.as_bytes())?;
+            write_atomic(&compressed, &encoder.finish()?)?;
+            (compressed, plain)
+        } else {
+            write_atomic(&plain, json.as_bytes())?;
+            (plain, compressed)
+        };
 
-        // Update latest symlink
-        let latest_path = Path::new(SESSIONS_DIR).join("latest.json");
+        // Update latest symlink (target is relative to the sessions dir)
+        let latest_path = dir.join("latest.json");
         #[cfg(unix)]
         {
             use std::os::unix::fs as unix_fs;
-            let _ = fs::remove_file(&latest_path); // Ignore if doesn't exist
-            unix_fs::symlink(&path, &latest_path)?;
+            if let Some(name) = path.file_name() {
+                let tmp = tmp_path(&latest_path);
+                let _ = fs::remove_file(&tmp); // Left over from an interrupted save
+                unix_fs::symlink(name, &tmp)?;
+                fs::rename(&tmp, &latest_path)?;
+            }
         }
 
+        // The session may have just grown past the threshold
+        let _ = fs::remove_file(stale);
         Ok(())
     }
 
     /// Load session from disk
     pub fn load(id: &str) -> Result<Self> {
-        let path = Path::new(SESSIONS_DIR).join(format!("{}.json", id));
-        let content = fs::read_to_string(&path)?;
-        let session: Session = serde_json::from_str(&content)?;
-        Ok(session)
+        Self::load_in(Path::new(SESSIONS_DIR), id)
+    }
+
+    /// Load a session from a sessions directory, compressed or not
+    pub fn load_in(dir: &Path, id: &str) -> Result<Self> {
+        let compressed = dir.join(format!("{}.json.gz", id));
+        if compressed.exists() {
+            return read_session_file(&compressed);
+        }
+        read_session_file(&dir.join(format!("{}.json", id)))
     }
 
     /// Load the latest session
+    ///
+    /// Follows the `latest.json` symlink, falling back to the newest
+    /// session file when the link is missing (e.g. on non-Unix).
     pub fn load_latest() -> Result<Self> {
         let latest_path = Path::new(SESSIONS_DIR).join("latest.json");
-        if !latest_path.exists() {
-            anyhow::bail!("No latest session found");
+        if latest_path.exists() {
+            return read_session_file(&latest_path);
         }
 
-        let content = fs::read_to_string(&latest_path)?;
-        let session: Session = serde_json::from_str(&content)?;
-        Ok(session)
+        match Self::list_all()?.first() {
+            Some(id) => Self::load(id),
+            None => anyhow::bail!("No latest session found"),
+        }
+    }
+
+    /// Status of the most recent run of a task, if it ever ran
+    pub fn last_status(&self, task_id: &str) -> Option<&TaskStatus> {
+        self.tasks
+            .get(task_id)
+            .and_then(|history| history.runs.last())
+            .map(|run| &run.status)
+    }
+
+    /// Output of the most recent run of a task, if it ever ran
+    pub fn last_output(&self, task_id: &str) -> Option<&[String]> {
+        self.tasks
+            .get(task_id)
+            .and_then(|history| history.runs.last())
+            .map(|run| run.output.as_slice())
+    }
+
+    /// Compare each task's latest run in this session with `other`
+    pub fn diff(&self, other: &Session) -> SessionDiff {
+        let latest = |session: &Session| -> HashMap<String, TaskRun> {
+            session
+                .tasks
+                .iter()
+                .filter_map(|(id, history)| Some((id.clone(), history.runs.last()?.clone())))
+                .collect()
+        };
+        let before = latest(self);
+        let after = latest(other);
+
+        let mut tasks: Vec<TaskDiff> = before
+            .iter()
+            .filter_map(|(id, old)| {
+                let new = after.get(id)?;
+                Some(TaskDiff {
+                    task_id: id.clone(),
+                    before: old.status.clone(),
+                    after: new.status.clone(),
+                    before_secs: old.duration_secs(),
+                    after_secs: new.duration_secs(),
+                })
+            })
+            .collect();
+        tasks.sort_by(|a, b| a.task_id.cmp(&b.task_id));
+
+        let mut added: Vec<String> = after.keys().filter(|id| !before.contains_key(*id)).cloned().collect();
+        let mut removed: Vec<String> = before.keys().filter(|id| !after.contains_key(*id)).cloned().collect();
+        added.sort();
+        removed.sort();
+
+        SessionDiff {
+            from: self.id.clone(),
+            to: other.id.clone(),
+            tasks,
+            added,
+            removed,
+        }
     }
 
     /// List all sessions
     pub fn list_all() -> Result<Vec<String>> {
-        if !Path::new(SESSIONS_DIR).exists() {
+        Self::list_in(Path::new(SESSIONS_DIR))
+    }
+
+    /// List session IDs in a directory, most recent first
+    pub fn list_in(dir: &Path) -> Result<Vec<String>> {
+        if !dir.exists() {
             return Ok(Vec::new());
         }
 
         let mut sessions = Vec::new();
-        for entry in fs::read_dir(SESSIONS_DIR)? {
-            let entry = entry?;
-            let path = entry.path();
-            if path.extension().and_then(|s| s.to_str()) == Some("json") {
-                if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
-                    if stem != "latest" {
-                        sessions.push(stem.to_string());
-                    }
+        for entry in fs::read_dir(dir)? {
+            let name = entry?.file_name();
+            if let Some(id) = name.to_str().and_then(session_id) {
+                if id != "latest" {
+                    sessions.push(id.to_string());
                 }
             }
         }
 
         sessions.sort();
+        sessions.dedup();
         sessions.reverse(); // Most recent first
         Ok(sessions)
     }
 
+    /// Delete all but the newest `keep` sessions, returning how many were removed
+    pub fn prune(keep: usize) -> Result<usize> {
+        Self::prune_in(Path::new(SESSIONS_DIR), keep)
+    }
+
+    /// Prune sessions in a directory, keeping the newest `keep`
+    ///
+    /// The session that `latest.json` points at is always kept.
+    pub fn prune_in(dir: &Path, keep: usize) -> Result<usize> {
+        let latest_id = fs::read_link(dir.join("latest.json"))
+            .ok()
+            .and_then(|target| {
+                target
+                    .file_name()
+                    .and_then(|s| s.to_str())
+                    .and_then(session_id)
+                    .map(|s| s.to_string())
+            });
+
+        let mut removed = 0;
+        for id in Self::list_in(dir)?.into_iter().skip(keep) {
+            if latest_id.as_deref() == Some(id.as_str()) {
+                continue;
+            }
+            for name in [format!("{}.json", id), format!("{}.json.gz", id)] {
+                let path = dir.join(name);
+                if path.exists() {
+                    fs::remove_file(path)?;
+                }
+            }
+            removed += 1;
+        }
+
+        Ok(removed)
+    }
+
     /// Start tracking a task
     pub fn start_task(&mut self, task_id: String) {
         let task_history = self.tasks.entry(task_id.clone()).or_insert(TaskHistory {
@@ -139,7 +499,9 @@ impl Session {
             ended: None,
             status: TaskStatus::Running,
             output: Vec::new(),
+            output_lines: 0,
             exit_code: None,
+            metrics: HashMap::new(),
         });
     }
 
@@ -154,11 +516,41 @@ impl Session {
         }
     }
 
-    /// Add output line to current task run
+    /// Record the latest metric values of the current task run
+    pub fn record_metrics(&mut self, task_id: &str, metrics: &HashMap<String, f64>) {
+        if let Some(task_history) = self.tasks.get_mut(task_id) {
+            if let Some(last_run) = task_history.runs.last_mut() {
+                last_run
+                    .metrics
+                    .extend(metrics.iter().map(|(name, value)| (name.clone(), *value)));
+            }
+        }
+    }
+
+    /// Add output line to current task run, dropping its oldest line once
+    /// the run holds more than the output limit
     pub fn add_output(&mut self, task_id: &str, line: String) {
         if let Some(task_history) = self.tasks.get_mut(task_id) {
             if let Some(last_run) = task_history.runs.last_mut() {
                 last_run.output.push(line);
+                last_run.output_lines += 1;
+                let excess = last_run.output.len().saturating_sub(self.output_limit);
+                last_run.output.drain(..excess);
+            }
+        }
+    }
+
+    /// Replace the last output line of the current run (in-place progress updates)
+    pub fn replace_last_output(&mut self, task_id: &str, line: String) {
+        if let Some(task_history) = self.tasks.get_mut(task_id) {
+            if let Some(last_run) = task_history.runs.last_mut() {
+                match last_run.output.last_mut() {
+                    Some(last) => *last = line,
+                    None => {
+                        last_run.output.push(line);
+                        last_run.output_lines += 1;
+                    }
+                }
             }
         }
     }
@@ -169,6 +561,109 @@ impl Session {
     }
 }
 
+/// Debounced session writes
+///
+/// Changes only mark the session dirty; it is written when at least
+/// `interval` has passed since the previous write, and once more on exit.
+#[derive(Debug)]
+pub struct SessionSaver {
+    dir: PathBuf,
+    interval: Duration,
+    dirty: bool,
+    last_save: Option<Instant>,
+    writes: usize,
+}
+
+impl SessionSaver {
+    /// Save to `.gidterm/sessions` at most once per `interval`
+    pub fn new(interval: Duration) -> Self {
+        Self::in_dir(Path::new(SESSIONS_DIR), interval)
+    }
+
+    /// Save to a specific sessions directory
+    pub fn in_dir(dir: &Path, interval: Duration) -> Self {
+        Self {
+            dir: dir.to_path_buf(),
+            interval,
+            dirty: false,
+            last_save: None,
+            writes: 0,
+        }
+    }
+
+    /// Record that the session changed
+    pub fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    /// Whether there are changes not yet written
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// How many times the session has been written
+    pub fn writes(&self) -> usize {
+        self.writes
+    }
+
+    /// Write the session if it changed and the interval has passed
+    pub fn save_if_due(&mut self, session: &Session, now: Instant) -> Result<bool> {
+        let due = self
+            .last_save
+            .is_none_or(|last| now.saturating_duration_since(last) >= self.interval);
+        if !self.dirty || !due {
+            return Ok(false);
+        }
+        self.last_save = Some(now);
+        self.flush(session)
+    }
+
+    /// Write the session now if it changed (e.g. on exit)
+    pub fn flush(&mut self, session: &Session) -> Result<bool> {
+        if !self.dirty {
+            return Ok(false);
+        }
+        self.dirty = false;
+        self.writes += 1;
+        session.save_in(&self.dir)?;
+        Ok(true)
+    }
+}
+
+/// Session ID from a session file name (`<id>.json` or `<id>.json.gz`)
+fn session_id(file_name: &str) -> Option<&str> {
+    file_name
+        .strip_suffix(".json.gz")
+        .or_else(|| file_name.strip_suffix(".json"))
+}
+
+/// `path` with `.tmp` appended, which `session_id` doesn't recognize
+fn tmp_path(path: &Path) -> PathBuf {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    PathBuf::from(tmp)
+}
+
+/// Write `contents` aside and rename it over `path`, so readers see either
+/// the old file or the new one, never a truncated one
+fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    let tmp = tmp_path(path);
+    fs::write(&tmp, contents)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// Read a session file, gzip-compressed or plain JSON
+fn read_session_file(path: &Path) -> Result<Session> {
+    let bytes = fs::read(path)?;
+    let session = if bytes.starts_with(&[0x1f, 0x8b]) {
+        serde_json::from_reader(GzDecoder::new(bytes.as_slice()))?
+    } else {
+        serde_json::from_slice(&bytes)?
+    };
+    Ok(session)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -196,4 +691,344 @@ mod tests {
         assert_eq!(task.runs[0].status, TaskStatus::Done);
         assert_eq!(task.runs[0].exit_code, Some(0));
     }
+
+    #[test]
+    fn test_output_capped_per_run() {
+        let mut session = Session::new("test".to_string());
+        assert_eq!(session.output_limit, DEFAULT_SESSION_OUTPUT_LINES);
+        session.set_output_limit(3);
+
+        session.start_task("build".to_string());
+        for i in 0..10 {
+            session.add_output("build", format!("line {}", i));
+        }
+        session.replace_last_output("build", "line 9 (done)".to_string());
+        assert_eq!(session.tasks["build"].runs[0].output, vec!["line 7", "line 8", "line 9 (done)"]);
+
+        // Each run has its own budget
+        session.start_task("build".to_string());
+        session.add_output("build", "rerun".to_string());
+        assert_eq!(session.tasks["build"].runs[0].output.len(), 3);
+        assert_eq!(session.tasks["build"].runs[1].output, vec!["rerun"]);
+    }
+
+    #[test]
+    fn test_task_history_stats() {
+        let run = |status: TaskStatus, millis: Option<i64>| {
+            let started = Utc::now();
+            TaskRun {
+                started,
+                ended: millis.map(|ms| started + chrono::Duration::milliseconds(ms)),
+                status,
+                output: Vec::new(),
+                output_lines: 0,
+                exit_code: None,
+                metrics: HashMap::new(),
+            }
+        };
+        let history = TaskHistory {
+            task_id: "build".to_string(),
+            runs: vec![
+                run(TaskStatus::Done, Some(3000)),
+                run(TaskStatus::Failed, Some(1500)),
+                run(TaskStatus::Done, Some(8100)),
+                run(TaskStatus::Running, None),
+            ],
+        };
+
+        assert_eq!(history.runs[0].duration(), Some(Duration::from_secs(3)));
+        assert_eq!(history.runs[3].duration(), None);
+        assert_eq!(history.success_count(), 2);
+        assert_eq!(history.total_duration(), Duration::from_millis(12600));
+        assert_eq!(history.average_duration(), Some(Duration::from_millis(4200)));
+        assert_eq!(history.success_rate(), Some(2.0 / 3.0));
+        assert_eq!(history.summary(), "4 runs, 2 ok, avg 4.2s");
+
+        // Nothing has finished yet
+        let running = TaskHistory {
+            task_id: "serve".to_string(),
+            runs: vec![run(TaskStatus::Running, None)],
+        };
+        assert_eq!(running.total_duration(), Duration::ZERO);
+        assert_eq!(running.average_duration(), None);
+        assert_eq!(running.success_rate(), None);
+        assert_eq!(running.summary(), "1 run, 0 ok, avg -");
+    }
+
+    #[test]
+    fn test_session_diff() {
+        let run = |status: TaskStatus, secs: i64| {
+            let started = Utc::now();
+            TaskRun {
+                started,
+                ended: Some(started + chrono::Duration::seconds(secs)),
+                status,
+                output: Vec::new(),
+                output_lines: 0,
+                exit_code: None,
+                metrics: HashMap::new(),
+            }
+        };
+        let session = |id: &str, runs: Vec<(&str, TaskRun)>| {
+            let mut session = Session::new("diff".to_string());
+            session.id = id.to_string();
+            for (task_id, run) in runs {
+                session.tasks.insert(
+                    task_id.to_string(),
+                    TaskHistory {
+                        task_id: task_id.to_string(),
+                        runs: vec![run],
+                    },
+                );
+            }
+            session
+        };
+
+        let a = session(
+            "a",
+            vec![
+                ("build", run(TaskStatus::Done, 12)),
+                ("test", run(TaskStatus::Done, 3)),
+                ("deploy", run(TaskStatus::Done, 1)),
+            ],
+        );
+        let b = session(
+            "b",
+            vec![
+                ("build", run(TaskStatus::Failed, 4)),
+                ("test", run(TaskStatus::Done, 5)),
+                ("lint", run(TaskStatus::Done, 2)),
+            ],
+        );
+
+        let diff = a.diff(&b);
+        assert_eq!((diff.from.as_str(), diff.to.as_str()), ("a", "b"));
+        assert_eq!(diff.added, vec!["lint"]);
+        assert_eq!(diff.removed, vec!["deploy"]);
+
+        let ids: Vec<_> = diff.tasks.iter().map(|t| t.task_id.as_str()).collect();
+        assert_eq!(ids, vec!["build", "test"]);
+        assert!(diff.tasks[0].status_changed());
+        assert_eq!(diff.tasks[0].after, TaskStatus::Failed);
+        assert_eq!(diff.tasks[0].runtime_delta_secs(), Some(-8.0));
+        assert!(!diff.tasks[1].status_changed());
+        assert_eq!(diff.tasks[1].runtime_delta_secs(), Some(2.0));
+    }
+
+    #[test]
+    fn test_estimate_report() {
+        let graph: Graph = serde_yaml::from_str(
+            "tasks:\n  build:\n    description: b\n    estimated_hours: 1\n  test:\n    description: t\n    estimated_hours: 2\n  lint:\n    description: l\n  docs:\n    description: d\n    estimated_hours: 0\n  deploy:\n    description: d\n    estimated_hours: 1\n",
+        )
+        .unwrap();
+        let mut session = Session::new("estimates".to_string());
+        let started = Utc::now();
+        for (task_id, hours) in [("build", 2.0), ("test", 1.0), ("lint", 1.0), ("docs", 1.0)] {
+            let secs = (hours * 3600.0) as i64;
+            session.tasks.insert(
+                task_id.to_string(),
+                TaskHistory {
+                    task_id: task_id.to_string(),
+                    runs: vec![TaskRun {
+                        started,
+                        ended: Some(started + chrono::Duration::seconds(secs)),
+                        status: TaskStatus::Done,
+                        output: Vec::new(),
+                        output_lines: 0,
+                        exit_code: Some(0),
+                        metrics: HashMap::new(),
+                    }],
+                },
+            );
+        }
+        // deploy is still running, so it has no actual duration yet
+        session.start_task("deploy".to_string());
+
+        let report = estimate_report(&graph, &session);
+        let ids: Vec<_> = report.iter().map(|v| v.task_id.as_str()).collect();
+        assert_eq!(ids, vec!["build", "test"]);
+        assert_eq!(report[0].variance_pct, 100.0);
+        assert!(report[0].overrun);
+        assert_eq!(report[1].variance_pct, -50.0);
+        assert!(!report[1].overrun);
+    }
+
+    #[test]
+    fn test_best_run() {
+        let mut sessions = Vec::new();
+        for (id, results) in [
+            ("2026-01-01-00-00-00", [("train_a", 0.91), ("train_b", 0.93)]),
+            ("2026-01-02-00-00-00", [("train_a", 0.93), ("train_b", 0.88)]),
+        ] {
+            let mut session = Session::new("ml".to_string());
+            session.id = id.to_string();
+            session.started_at += chrono::Duration::days(sessions.len() as i64);
+            for (task_id, accuracy) in results {
+                session.start_task(task_id.to_string());
+                session.tasks.get_mut(task_id).unwrap().runs[0].started = session.started_at;
+                session.record_metrics(task_id, &HashMap::from([("val_accuracy".to_string(), 0.5)]));
+                session.record_metrics(task_id, &HashMap::from([("val_accuracy".to_string(), accuracy)]));
+            }
+            sessions.push(session);
+        }
+
+        // 0.93 twice: the more recent run wins
+        let best = best_run(&sessions, "val_accuracy", true).unwrap();
+        assert_eq!(best.value, 0.93);
+        assert_eq!((best.session_id.as_str(), best.task_id.as_str()), ("2026-01-02-00-00-00", "train_a"));
+
+        let worst = best_run(&sessions, "val_accuracy", false).unwrap();
+        assert_eq!((worst.task_id.as_str(), worst.value), ("train_b", 0.88));
+
+        assert_eq!(best_run(&sessions, "loss", false), None);
+    }
+
+    #[test]
+    fn test_ui_state_roundtrip() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join(".gidterm").join("ui-state.json");
+        assert_eq!(UiState::load_from(&path).unwrap(), None);
+
+        let state = UiState {
+            source: "my-app".to_string(),
+            selected_project: 0,
+            selected_task: 3,
+            view_mode: ViewMode::Terminal,
+        };
+        state.save_to(&path).unwrap();
+        assert!(fs::read_to_string(&path).unwrap().contains("\"terminal\""));
+        assert_eq!(UiState::load_from(&path).unwrap(), Some(state));
+    }
+
+    #[test]
+    fn test_compressed_session_roundtrip() {
+        let dir = tempfile::TempDir::new().unwrap();
+
+        let mut small = Session::new("small".to_string());
+        small.id = "2026-01-01-12-00-00".to_string();
+        small.start_task("build".to_string());
+        small.add_output("build", "ok".to_string());
+        small.save_in(dir.path()).unwrap();
+        assert!(dir.path().join("2026-01-01-12-00-00.json").exists());
+
+        let mut large = Session::new("large".to_string());
+        large.id = "2026-01-02-12-00-00".to_string();
+        for run in 0..10 {
+            let task_id = format!("train-{}", run);
+            large.start_task(task_id.clone());
+            for i in 0..DEFAULT_SESSION_OUTPUT_LINES {
+                large.add_output(&task_id, format!("epoch {} loss {:.4}", i, 1.0 / (i + 1) as f64));
+            }
+            large.end_task(&task_id, TaskStatus::Done, Some(0));
+        }
+        large.save_in(dir.path()).unwrap();
+
+        let compressed = dir.path().join("2026-01-02-12-00-00.json.gz");
+        assert!(compressed.exists());
+        assert!(!dir.path().join("2026-01-02-12-00-00.json").exists());
+        assert!(fs::metadata(&compressed).unwrap().len() < COMPRESS_ABOVE_BYTES as u64);
+
+        assert_eq!(Session::load_in(dir.path(), &large.id).unwrap(), large);
+        assert_eq!(Session::load_in(dir.path(), &small.id).unwrap(), small);
+        assert_eq!(
+            Session::list_in(dir.path()).unwrap(),
+            vec!["2026-01-02-12-00-00", "2026-01-01-12-00-00"]
+        );
+
+        // The latest link can point at a compressed file too
+        #[cfg(unix)]
+        assert_eq!(read_session_file(&dir.path().join("latest.json")).unwrap(), large);
+
+        Session::prune_in(dir.path(), 0).unwrap();
+        assert_eq!(Session::list_in(dir.path()).unwrap(), vec!["2026-01-02-12-00-00"]);
+    }
+
+    #[test]
+    fn test_interrupted_save_keeps_previous_session() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut session = Session::new("resume".to_string());
+        session.start_task("build".to_string());
+        session.save_in(dir.path()).unwrap();
+
+        // A save killed halfway leaves only a partial temp file behind
+        let plain = dir.path().join(format!("{}.json", session.id));
+        fs::write(tmp_path(&plain), "{\"id\": \"trunc").unwrap();
+        assert_eq!(Session::load_in(dir.path(), &session.id).unwrap(), session);
+        assert_eq!(Session::list_in(dir.path()).unwrap(), vec![session.id.clone()]);
+
+        session.add_output("build", "done".to_string());
+        session.save_in(dir.path()).unwrap();
+        assert_eq!(Session::load_in(dir.path(), &session.id).unwrap(), session);
+        assert!(!tmp_path(&plain).exists());
+        #[cfg(unix)]
+        assert_eq!(read_session_file(&dir.path().join("latest.json")).unwrap(), session);
+    }
+
+    #[test]
+    fn test_session_saver_debounces() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut saver = SessionSaver::in_dir(dir.path(), Duration::from_millis(500));
+        let mut session = Session::new("debounce".to_string());
+        session.start_task("spam".to_string());
+
+        // 1000 lines over 600ms: written on the first change and once the
+        // interval has passed
+        let start = Instant::now();
+        for i in 0..1000 {
+            session.add_output("spam", format!("line {}", i));
+            saver.mark_dirty();
+            saver
+                .save_if_due(&session, start + Duration::from_micros(600 * i))
+                .unwrap();
+        }
+        assert_eq!(saver.writes(), 2);
+        assert!(saver.is_dirty());
+
+        // Nothing new: no write, however much time passes
+        assert!(saver.flush(&session).unwrap());
+        assert!(!saver.save_if_due(&session, start + Duration::from_secs(60)).unwrap());
+        assert_eq!(saver.writes(), 3);
+
+        let saved = Session::load_in(dir.path(), &session.id).unwrap();
+        assert_eq!(saved.tasks["spam"].runs[0].output.last().unwrap(), "line 999");
+    }
+
+    #[test]
+    fn test_prune_keeps_newest() {
+        let dir = tempfile::TempDir::new().unwrap();
+        for i in 0..10 {
+            let path = dir.path().join(format!("2026-01-{:02}-12-00-00.json", i + 1));
+            fs::write(path, "{}").unwrap();
+        }
+
+        let removed = Session::prune_in(dir.path(), 3).unwrap();
+        assert_eq!(removed, 7);
+
+        let remaining = Session::list_in(dir.path()).unwrap();
+        assert_eq!(
+            remaining,
+            vec!["2026-01-10-12-00-00", "2026-01-09-12-00-00", "2026-01-08-12-00-00"]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_prune_keeps_latest_target() {
+        let dir = tempfile::TempDir::new().unwrap();
+        for i in 0..5 {
+            let path = dir.path().join(format!("2026-01-{:02}-12-00-00.json", i + 1));
+            fs::write(path, "{}").unwrap();
+        }
+        std::os::unix::fs::symlink(
+            "2026-01-01-12-00-00.json",
+            dir.path().join("latest.json"),
+        )
+        .unwrap();
+
+        Session::prune_in(dir.path(), 1).unwrap();
+
+        let remaining = Session::list_in(dir.path()).unwrap();
+        assert_eq!(remaining, vec!["2026-01-05-12-00-00", "2026-01-01-12-00-00"]);
+        assert!(dir.path().join("latest.json").exists());
+    }
 }
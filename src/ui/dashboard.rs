@@ -1,9 +1,10 @@
 //! Dashboard view - Unified task status display
 
 use crate::core::{Graph, GraphTaskStatus};
+use crate::ui::Theme;
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, List, ListItem, Paragraph},
     Frame,
@@ -14,7 +15,7 @@ pub struct DashboardView;
 
 impl DashboardView {
     /// Render the dashboard
-    pub fn render(f: &mut Frame, graph: &Graph, area: Rect) {
+    pub fn render(f: &mut Frame, graph: &Graph, theme: &Theme, area: Rect) {
         // Split into header and content
         let chunks = Layout::default()
             .direction(Direction::Vertical)
@@ -25,13 +26,13 @@ impl DashboardView {
             .split(area);
 
         // Render header
-        Self::render_header(f, graph, chunks[0]);
+        Self::render_header(f, graph, theme, chunks[0]);
 
         // Render task list
-        Self::render_tasks(f, graph, chunks[1]);
+        Self::render_tasks(f, graph, theme, chunks[1]);
     }
 
-    fn render_header(f: &mut Frame, graph: &Graph, area: Rect) {
+    fn render_header(f: &mut Frame, graph: &Graph, theme: &Theme, area: Rect) {
         let title = if let Some(metadata) = &graph.metadata {
             format!("📊 {} - GidTerm", metadata.project)
         } else {
@@ -40,12 +41,12 @@ impl DashboardView {
 
         let header = Paragraph::new(title)
             .block(Block::default().borders(Borders::ALL))
-            .style(Style::default().fg(Color::Cyan));
+            .style(Style::default().fg(theme.accent));
 
         f.render_widget(header, area);
     }
 
-    fn render_tasks(f: &mut Frame, graph: &Graph, area: Rect) {
+    fn render_tasks(f: &mut Frame, graph: &Graph, theme: &Theme, area: Rect) {
         let tasks: Vec<ListItem> = graph
             .all_tasks()
             .iter()
@@ -58,13 +59,7 @@ impl DashboardView {
                     GraphTaskStatus::Planned => "○",
                 };
 
-                let status_color = match task.status {
-                    GraphTaskStatus::Done => Color::Green,
-                    GraphTaskStatus::InProgress => Color::Yellow,
-                    GraphTaskStatus::Failed => Color::Red,
-                    GraphTaskStatus::Pending => Color::Gray,
-                    GraphTaskStatus::Planned => Color::DarkGray,
-                };
+                let status_color = theme.status(&task.status);
 
                 let priority_badge = task.priority.as_ref().map(|p| match p.as_str() {
                     "critical" => "🔴",
@@ -85,13 +80,13 @@ impl DashboardView {
 
                 let line = Line::from(vec![
                     Span::raw(format!("{} ", status_icon)),
-                    Span::styled(id, Style::default().fg(Color::White).add_modifier(Modifier::BOLD)),
+                    Span::styled(id, Style::default().fg(theme.text).add_modifier(Modifier::BOLD)),
                     Span::raw(format!(" {}", priority_badge)),
                     Span::styled(
                         format!(" [{}]", task.status),
                         Style::default().fg(status_color),
                     ),
-                    Span::styled(deps_info, Style::default().fg(Color::DarkGray)),
+                    Span::styled(deps_info, Style::default().fg(theme.muted)),
                 ]);
 
                 ListItem::new(line)
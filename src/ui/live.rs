@@ -1,11 +1,12 @@
 //! Live dashboard with real-time updates and semantic metrics
 
-use crate::app::App;
+use crate::app::{App, StepMode};
 use crate::core::GraphTaskStatus;
+use crate::semantic::history;
 use crate::semantic::MetricValue;
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, Gauge, List, ListItem, Paragraph, Wrap},
     Frame,
@@ -26,7 +27,7 @@ pub fn render_live_dashboard(f: &mut Frame, app: &App) {
     render_header(f, app, chunks[0]);
     render_task_list(f, app, chunks[1]);
     render_task_detail(f, app, chunks[2]);
-    render_footer(f, chunks[3]);
+    render_footer(f, app, chunks[3]);
 }
 
 fn render_header(f: &mut Frame, app: &App, area: Rect) {
@@ -57,14 +58,34 @@ fn render_header(f: &mut Frame, app: &App, area: Rect) {
         .filter(|t| t.status == GraphTaskStatus::Failed)
         .count();
 
-    let status_text = format!(
-        "{} | Running: {} | Done: {} | Failed: {} | Total: {}",
-        title, running, done, failed, total
+    let eta = app
+        .project_names
+        .iter()
+        .filter_map(|project| app.project_eta(project))
+        .max()
+        .map(|eta| format!(" | ETA: {}", history::format_eta(eta)))
+        .unwrap_or_default();
+
+    let mut status_text = format!(
+        "{} | Running: {} | Done: {} | Failed: {} | Total: {}{}",
+        title, running, done, failed, total, eta
     );
 
+    status_text.push_str(&app.search_indicator());
+
+    if app.step_mode != StepMode::Off {
+        status_text.push_str(" | STEP (s: next wave)");
+    }
+
+    let deadlock = app.deadlock_message();
+    if let Some(message) = &deadlock {
+        status_text.push_str(&format!(" | ⚠ {}", message));
+    }
+
+    let color = if deadlock.is_some() { app.theme.failed } else { app.theme.accent };
     let header = Paragraph::new(status_text)
         .block(Block::default().borders(Borders::ALL))
-        .style(Style::default().fg(Color::Cyan));
+        .style(Style::default().fg(color));
 
     f.render_widget(header, area);
 }
@@ -73,7 +94,37 @@ fn render_task_list(f: &mut Frame, app: &App, area: Rect) {
     let mut items: Vec<ListItem> = Vec::new();
     let mut flat_idx = 0usize;
 
-    if app.workspace_mode {
+    if app.group_by_component {
+        let graph = app.scheduler.graph();
+        for (component, task_ids) in app.get_tasks_by_component() {
+            let done = task_ids
+                .iter()
+                .filter(|id| graph.get_task(id).is_some_and(|t| t.status == GraphTaskStatus::Done))
+                .count();
+            let component_header = Line::from(vec![
+                Span::styled(
+                    format!("🧩 {} ", component),
+                    Style::default()
+                        .fg(app.theme.heading)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    progress_bar(done, task_ids.len(), 10),
+                    Style::default().fg(if done == task_ids.len() { app.theme.done } else { app.theme.running }),
+                ),
+                Span::styled(format!(" {}/{}", done, task_ids.len()), Style::default().fg(app.theme.muted)),
+            ]);
+            items.push(ListItem::new(component_header));
+
+            for task_id in &task_ids {
+                items.push(render_task_item(app, task_id, flat_idx));
+                flat_idx += 1;
+            }
+
+            // Spacer
+            items.push(ListItem::new(Line::from("")));
+        }
+    } else if app.workspace_mode {
         let tasks_by_project = app.get_tasks_by_project();
 
         for project_name in &app.project_names {
@@ -81,7 +132,7 @@ fn render_task_list(f: &mut Frame, app: &App, area: Rect) {
             let project_header = Line::from(vec![Span::styled(
                 format!("📁 {}", project_name),
                 Style::default()
-                    .fg(Color::Magenta)
+                    .fg(app.theme.heading)
                     .add_modifier(Modifier::BOLD),
             )]);
             items.push(ListItem::new(project_header));
@@ -114,24 +165,24 @@ fn render_task_list(f: &mut Frame, app: &App, area: Rect) {
     f.render_widget(task_list, area);
 }
 
+/// A bar of `width` cells, filled in proportion to `done` out of `total`
+fn progress_bar(done: usize, total: usize, width: usize) -> String {
+    let filled = (done * width).checked_div(total).unwrap_or(0);
+    format!("{}{}", "█".repeat(filled), "░".repeat(width - filled))
+}
+
 fn render_task_item<'a>(app: &'a App, task_id: &str, idx: usize) -> ListItem<'a> {
     let task = app.scheduler.graph().get_task(task_id).unwrap();
 
-    let status_icon = match task.status {
+    let status_icon = task.icon.as_deref().unwrap_or(match task.status {
         GraphTaskStatus::Done => "✓",
         GraphTaskStatus::InProgress => "⚙",
         GraphTaskStatus::Failed => "✗",
         GraphTaskStatus::Pending => "□",
         GraphTaskStatus::Planned => "○",
-    };
+    });
 
-    let status_color = match task.status {
-        GraphTaskStatus::Done => Color::Green,
-        GraphTaskStatus::InProgress => Color::Yellow,
-        GraphTaskStatus::Failed => Color::Red,
-        GraphTaskStatus::Pending => Color::Gray,
-        GraphTaskStatus::Planned => Color::DarkGray,
-    };
+    let status_color = app.theme.status(&task.status);
 
     let priority_badge = task
         .priority
@@ -151,8 +202,9 @@ fn render_task_item<'a>(app: &'a App, task_id: &str, idx: usize) -> ListItem<'a>
         .map(|lines| format!(" ({}L)", lines.len()))
         .unwrap_or_default();
 
-    // Display name — strip project prefix in workspace mode
-    let display_name = if app.workspace_mode {
+    // Display name — strip project prefix in workspace mode, unless
+    // tasks from several projects share a component group
+    let display_name = if app.workspace_mode && !app.group_by_component {
         task_id.split(':').nth(1).unwrap_or(task_id)
     } else {
         task_id
@@ -168,16 +220,14 @@ fn render_task_item<'a>(app: &'a App, task_id: &str, idx: usize) -> ListItem<'a>
 
         for (key, value) in &metrics.metrics {
             match value {
-                MetricValue::Float(v) => {
-                    if key == "loss" || key == "accuracy" || key == "learning_rate" {
-                        parts.push(format!("{}: {:.4}", key, v));
-                    }
+                MetricValue::Float(v)
+                    if key == "loss" || key == "accuracy" || key == "learning_rate" =>
+                {
+                    parts.push(format!("{}: {:.4}", key, v));
                 }
-                MetricValue::Int(v) => {
-                    if key == "epoch" {
-                        if let Some(MetricValue::Int(total)) = metrics.metrics.get("total_epochs") {
-                            parts.push(format!("ep {}/{}", v, total));
-                        }
+                MetricValue::Int(v) if key == "epoch" => {
+                    if let Some(MetricValue::Int(total)) = metrics.metrics.get("total_epochs") {
+                        parts.push(format!("ep {}/{}", v, total));
                     }
                 }
                 _ => {}
@@ -202,9 +252,15 @@ fn render_task_item<'a>(app: &'a App, task_id: &str, idx: usize) -> ListItem<'a>
         String::new()
     };
 
+    let waiting = if app.is_waiting_input(task_id) {
+        " ⏳ waiting for input (i: answer)"
+    } else {
+        ""
+    };
+
     // Highlight selected task
     let style = if idx == app.selected_task {
-        Style::default().bg(Color::DarkGray)
+        Style::default().bg(app.theme.selection_bg)
     } else {
         Style::default()
     };
@@ -215,13 +271,14 @@ fn render_task_item<'a>(app: &'a App, task_id: &str, idx: usize) -> ListItem<'a>
         Span::styled(
             display_name.to_string(),
             Style::default()
-                .fg(Color::White)
+                .fg(app.theme.task_color(task).unwrap_or(app.theme.text))
                 .add_modifier(Modifier::BOLD),
         ),
         Span::raw(priority_badge.to_string()),
-        Span::styled(format!(" [{}]", task.status.to_string()), Style::default().fg(status_color)),
-        Span::styled(output_count, Style::default().fg(Color::DarkGray)),
-        Span::styled(metrics_summary, Style::default().fg(Color::Cyan)),
+        Span::styled(format!(" [{}]", task.status), Style::default().fg(status_color)),
+        Span::styled(output_count, Style::default().fg(app.theme.muted)),
+        Span::styled(waiting, Style::default().fg(app.theme.waiting)),
+        Span::styled(metrics_summary, Style::default().fg(app.theme.accent)),
     ]);
 
     ListItem::new(line).style(style)
@@ -273,8 +330,8 @@ fn render_task_detail(f: &mut Frame, app: &App, area: Rect) {
             )
             .gauge_style(
                 Style::default()
-                    .fg(Color::Green)
-                    .bg(Color::DarkGray),
+                    .fg(app.theme.done)
+                    .bg(app.theme.selection_bg),
             )
             .percent(pct)
             .label(label);
@@ -288,13 +345,19 @@ fn render_task_detail(f: &mut Frame, app: &App, area: Rect) {
 
 fn render_output_panel(f: &mut Frame, app: &App, task_id: &str, area: Rect) {
     let height = area.height.saturating_sub(2) as usize; // minus borders
-    let output_lines = app.get_task_output(task_id, height);
-
-    let text = if output_lines.is_empty() {
-        "(no output yet)".to_string()
-    } else {
-        output_lines.join("\n")
-    };
+    let mut text = app.with_tail(task_id, height, |lines| {
+        let mut text = String::new();
+        for line in lines {
+            if !text.is_empty() {
+                text.push('\n');
+            }
+            text.push_str(line);
+        }
+        text
+    });
+    if text.is_empty() {
+        text = "(no output yet)".to_string();
+    }
 
     let output = Paragraph::new(text)
         .block(
@@ -303,17 +366,36 @@ fn render_output_panel(f: &mut Frame, app: &App, task_id: &str, area: Rect) {
                 .title(format!("Output: {}", task_id)),
         )
         .wrap(Wrap { trim: false })
-        .style(Style::default().fg(Color::White));
+        .style(Style::default().fg(app.theme.text));
 
     f.render_widget(output, area);
 }
 
-fn render_footer(f: &mut Frame, area: Rect) {
-    let help_text = "q: Quit │ k: Kill │ ↑↓: Select │ Enter: Terminal │ Tab: Cycle │ 1-3: Views";
+fn render_footer(f: &mut Frame, app: &App, area: Rect) {
+    let help_text = "q: Quit │ k: Kill │ X: Abort all │ ↑↓: Select │ Enter: Terminal │ Tab: Cycle │ f: Filter tags │ G: Group by component │ 1-3: Views";
+
+    let text = if let Some(prompt) = &app.input_prompt {
+        format!("Input for {}: {}_ │ Enter: Send │ Esc: Cancel", prompt.task_id, prompt.input)
+    } else if let Some(action) = &app.pending_action {
+        format!(
+            "{} │ Run '{}' on {}? y: Yes │ n: No",
+            action.message, action.label, action.task_id
+        )
+    } else if app.search_mode {
+        app.search_help().to_string()
+    } else {
+        match &app.tag_filter {
+            Some(filter) if app.tag_filter_mode => {
+                format!("Filter by tag: {}_ │ Enter: Apply │ Esc: Clear", filter)
+            }
+            Some(filter) => format!("Tag: {} (Esc clears) │ {}", filter, help_text),
+            None => help_text.to_string(),
+        }
+    };
 
-    let footer = Paragraph::new(help_text)
+    let footer = Paragraph::new(text)
         .block(Block::default().borders(Borders::ALL))
-        .style(Style::default().fg(Color::DarkGray));
+        .style(Style::default().fg(app.theme.muted));
 
     f.render_widget(footer, area);
 }
@@ -2,10 +2,16 @@
 
 mod dashboard;
 mod live;
+mod text;
+mod theme;
+mod toast;
 pub mod views;
 
 pub use dashboard::DashboardView;
 pub use live::render_live_dashboard;
+pub use text::{display_width, pad_to_width, truncate_to_width, wrapped_rows};
+pub use theme::{color_diagnostics, parse_color, Theme};
+pub use toast::render_toast;
 pub use views::comparison::render_comparison_view;
 pub use views::graph::render_graph_view;
 pub use views::project_overview::render_project_overview;
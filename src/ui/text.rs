@@ -0,0 +1,70 @@
+//! Text measurement in terminal columns rather than chars or bytes
+//!
+//! CJK characters and most emoji take two columns, so `{:<16}` padding and
+//! `chars().count()` widths misalign them.
+
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Terminal columns `s` occupies
+pub fn display_width(s: &str) -> usize {
+    UnicodeWidthStr::width(s)
+}
+
+/// Pad `s` with spaces to `width` columns (`{:<width}` by display width)
+pub fn pad_to_width(s: &str, width: usize) -> String {
+    let padding = width.saturating_sub(display_width(s));
+    format!("{}{}", s, " ".repeat(padding))
+}
+
+/// Cut `s` to at most `width` columns, ending in `...` when shortened
+pub fn truncate_to_width(s: &str, width: usize) -> String {
+    if display_width(s) <= width {
+        return s.to_string();
+    }
+
+    let budget = width.saturating_sub(3);
+    let mut used = 0;
+    let mut cut = String::new();
+    for c in s.chars() {
+        let w = c.width().unwrap_or(0);
+        if used + w > budget {
+            break;
+        }
+        used += w;
+        cut.push(c);
+    }
+    format!("{}...", cut)
+}
+
+/// Rows a line takes when wrapped at `width` columns (at least one)
+pub fn wrapped_rows(s: &str, width: usize) -> usize {
+    if width == 0 {
+        return 1;
+    }
+    display_width(s).div_ceil(width).max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wide_characters() {
+        assert_eq!(display_width("build"), 5);
+        assert_eq!(display_width("前端"), 4);
+        assert_eq!(display_width("🚀 deploy"), 9);
+
+        assert_eq!(pad_to_width("前端", 6), "前端  ");
+        assert_eq!(pad_to_width("api", 6), "api   ");
+        assert_eq!(pad_to_width("toolongname", 4), "toolongname");
+
+        // Never splits a wide character, even if that leaves a column spare
+        assert_eq!(truncate_to_width("数据库迁移任务", 8), "数据...");
+        assert_eq!(truncate_to_width("🚀🚀🚀🚀", 7), "🚀🚀...");
+        assert_eq!(truncate_to_width("short", 10), "short");
+
+        assert_eq!(wrapped_rows("", 10), 1);
+        assert_eq!(wrapped_rows("日本語のログ", 10), 2);
+        assert_eq!(wrapped_rows("0123456789", 10), 1);
+    }
+}
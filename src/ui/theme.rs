@@ -0,0 +1,296 @@
+//! Color themes - every view takes its colors from `App::theme`
+//!
+//! `~/.gidterm/theme.toml` picks a preset and overrides single colors:
+//! ```toml
+//! preset = "light"
+//!
+//! [colors]
+//! running = "blue"
+//! accent = "#005f87"
+//! ```
+//! Colors are ratatui names (`green`, `dark gray`), `#rrggbb` or a 0-255 index.
+
+use crate::core::{Diagnostic, Graph, GraphTaskStatus, Task};
+use crate::semantic::advisor::Severity;
+use crate::semantic::LogLevel;
+use anyhow::{Context, Result};
+use ratatui::style::Color;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Colors used by the views
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Theme {
+    // Task status
+    pub done: Color,
+    pub running: Color,
+    pub failed: Color,
+    pub pending: Color,
+    pub planned: Color,
+    /// Agents waiting for input
+    pub waiting: Color,
+
+    // Advisory severity
+    pub critical: Color,
+    pub warning: Color,
+    pub info: Color,
+
+    /// Headers, metrics and other highlights
+    pub accent: Color,
+    /// Project headings in workspace mode
+    pub heading: Color,
+    /// Task names and output
+    pub text: Color,
+    /// Footers, dependencies and other secondary text
+    pub muted: Color,
+    /// Background of the selected row (and unfilled gauges)
+    pub selection_bg: Color,
+    /// Search matches and toasts: text on `highlight_bg`
+    pub highlight_fg: Color,
+    pub highlight_bg: Color,
+    /// The search match in view
+    pub current_match_bg: Color,
+}
+
+/// Contents of `theme.toml`
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ThemeFile {
+    preset: Option<String>,
+    #[serde(default)]
+    colors: HashMap<String, String>,
+}
+
+impl Theme {
+    /// For dark terminals (the default)
+    pub fn dark() -> Self {
+        Self {
+            done: Color::Green,
+            running: Color::Yellow,
+            failed: Color::Red,
+            pending: Color::Gray,
+            planned: Color::DarkGray,
+            waiting: Color::Blue,
+            critical: Color::Red,
+            warning: Color::Yellow,
+            info: Color::Cyan,
+            accent: Color::Cyan,
+            heading: Color::Magenta,
+            text: Color::White,
+            muted: Color::DarkGray,
+            selection_bg: Color::DarkGray,
+            highlight_fg: Color::Black,
+            highlight_bg: Color::Yellow,
+            current_match_bg: Color::LightYellow,
+        }
+    }
+
+    /// For light terminals: no white or yellow text
+    pub fn light() -> Self {
+        Self {
+            running: Color::Blue,
+            pending: Color::DarkGray,
+            planned: Color::Gray,
+            warning: Color::Magenta,
+            info: Color::Blue,
+            accent: Color::Blue,
+            text: Color::Black,
+            selection_bg: Color::Gray,
+            ..Self::dark()
+        }
+    }
+
+    /// A built-in theme by name
+    pub fn preset(name: &str) -> Result<Self> {
+        match name {
+            "dark" => Ok(Self::dark()),
+            "light" => Ok(Self::light()),
+            _ => anyhow::bail!("Unknown theme '{}' (expected dark or light)", name),
+        }
+    }
+
+    /// Load `~/.gidterm/theme.toml`; `preset` (from `--theme`) replaces its preset
+    pub fn load(preset: Option<&str>) -> Result<Self> {
+        let path = dirs::home_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join(".gidterm")
+            .join("theme.toml");
+        Self::load_from(&path, preset)
+    }
+
+    /// Load a theme file (missing means the preset as-is)
+    pub fn load_from(path: &Path, preset: Option<&str>) -> Result<Self> {
+        let file: ThemeFile = if path.exists() {
+            toml::from_str(&fs::read_to_string(path)?)
+                .with_context(|| format!("Invalid theme file {}", path.display()))?
+        } else {
+            ThemeFile::default()
+        };
+
+        let mut theme = Self::preset(preset.or(file.preset.as_deref()).unwrap_or("dark"))?;
+        for (name, value) in &file.colors {
+            let color = parse_color(value)
+                .ok_or_else(|| anyhow::anyhow!("Invalid color '{}' for '{}' in {}", value, name, path.display()))?;
+            theme.set(name, color)?;
+        }
+        Ok(theme)
+    }
+
+    fn set(&mut self, name: &str, color: Color) -> Result<()> {
+        let slot = match name {
+            "done" => &mut self.done,
+            "running" => &mut self.running,
+            "failed" => &mut self.failed,
+            "pending" => &mut self.pending,
+            "planned" => &mut self.planned,
+            "waiting" => &mut self.waiting,
+            "critical" => &mut self.critical,
+            "warning" => &mut self.warning,
+            "info" => &mut self.info,
+            "accent" => &mut self.accent,
+            "heading" => &mut self.heading,
+            "text" => &mut self.text,
+            "muted" => &mut self.muted,
+            "selection_bg" => &mut self.selection_bg,
+            "highlight_fg" => &mut self.highlight_fg,
+            "highlight_bg" => &mut self.highlight_bg,
+            "current_match_bg" => &mut self.current_match_bg,
+            _ => anyhow::bail!("Unknown theme color '{}'", name),
+        };
+        *slot = color;
+        Ok(())
+    }
+
+    /// Color a task's YAML asks for, if it is set and valid
+    pub fn task_color(&self, task: &Task) -> Option<Color> {
+        task.color.as_deref().and_then(parse_color)
+    }
+
+    /// Color for a task status
+    pub fn status(&self, status: &GraphTaskStatus) -> Color {
+        match status {
+            GraphTaskStatus::Done => self.done,
+            GraphTaskStatus::InProgress => self.running,
+            GraphTaskStatus::Failed => self.failed,
+            GraphTaskStatus::Pending => self.pending,
+            GraphTaskStatus::Planned => self.planned,
+        }
+    }
+
+    /// Color for an advisory severity
+    pub fn severity(&self, severity: &Severity) -> Color {
+        match severity {
+            Severity::Critical => self.critical,
+            Severity::Warning => self.warning,
+            Severity::Info => self.info,
+        }
+    }
+
+    /// Color for a line of task output
+    pub fn log_level(&self, level: LogLevel) -> Color {
+        match level {
+            LogLevel::Error => self.failed,
+            LogLevel::Warn => self.warning,
+            LogLevel::Info => self.text,
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+/// A color name (`green`, `dark gray`), `#rrggbb` or 0-255 index, as
+/// written in `theme.toml` or a task's `color`
+pub fn parse_color(value: &str) -> Option<Color> {
+    Color::from_str(value).ok()
+}
+
+/// Warnings for task `color`s that [`parse_color`] doesn't know, which the
+/// views replace with the default (for `gidterm validate`)
+pub fn color_diagnostics(graph: &Graph) -> Vec<Diagnostic> {
+    let mut task_ids: Vec<&String> = graph.tasks.keys().collect();
+    task_ids.sort();
+    task_ids
+        .into_iter()
+        .filter_map(|id| {
+            let color = graph.tasks[id].color.as_deref()?;
+            parse_color(color)
+                .is_none()
+                .then(|| Diagnostic::warning(id, format!("unknown color '{}'; the default is used", color)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_theme_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("theme.toml");
+
+        // No file: the default palette
+        assert_eq!(Theme::load_from(&path, None).unwrap(), Theme::dark());
+        assert_eq!(Theme::load_from(&path, Some("light")).unwrap(), Theme::light());
+
+        fs::write(&path, "preset = \"light\"\n\n[colors]\ndone = \"#00aa00\"\nmuted = \"8\"\n").unwrap();
+        let theme = Theme::load_from(&path, None).unwrap();
+        assert_eq!(theme.done, Color::Rgb(0, 0xaa, 0));
+        assert_eq!(theme.muted, Color::Indexed(8));
+        assert_eq!(theme.text, Color::Black);
+
+        // --theme replaces the preset; the overrides still apply
+        let theme = Theme::load_from(&path, Some("dark")).unwrap();
+        assert_eq!(theme.text, Color::White);
+        assert_eq!(theme.done, Color::Rgb(0, 0xaa, 0));
+    }
+
+    #[test]
+    fn test_task_color() {
+        let theme = Theme::dark();
+        let task = |yaml: &str| -> Task { serde_yaml::from_str(yaml).unwrap() };
+
+        assert_eq!(theme.task_color(&task("description: t\ncolor: magenta\n")), Some(Color::Magenta));
+        assert_eq!(theme.task_color(&task("description: t\ncolor: \"#ff8800\"\n")), Some(Color::Rgb(0xff, 0x88, 0)));
+        assert_eq!(theme.task_color(&task("description: t\ncolor: \"208\"\n")), Some(Color::Indexed(208)));
+        // Unset or unknown: the views fall back to the default text color
+        assert_eq!(theme.task_color(&task("description: t\n")), None);
+        assert_eq!(theme.task_color(&task("description: t\ncolor: purpel\n")), None);
+
+        let with_icon = task("description: t\nicon: \"🚀\"\n");
+        assert_eq!(with_icon.icon.as_deref(), Some("🚀"));
+    }
+
+    #[test]
+    fn test_color_diagnostics() {
+        let graph: Graph = serde_yaml::from_str(
+            "tasks:\n  web:\n    description: w\n    color: cyan\n  serve:\n    description: s\n    color: purpel\n",
+        )
+        .unwrap();
+        let rendered: Vec<String> = color_diagnostics(&graph).iter().map(|d| d.to_string()).collect();
+        assert_eq!(rendered, vec!["warning: serve: unknown color 'purpel'; the default is used"]);
+    }
+
+    #[test]
+    fn test_theme_errors() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("theme.toml");
+        assert!(Theme::load_from(&path, Some("solarized")).is_err());
+
+        fs::write(&path, "[colors]\nsucess = \"green\"\n").unwrap();
+        let err = Theme::load_from(&path, None).unwrap_err();
+        assert_eq!(err.to_string(), "Unknown theme color 'sucess'");
+
+        fs::write(&path, "[colors]\ndone = \"greeen\"\n").unwrap();
+        let err = Theme::load_from(&path, None).unwrap_err();
+        assert!(err.to_string().starts_with("Invalid color 'greeen' for 'done'"));
+    }
+}
@@ -0,0 +1,58 @@
+//! Toast - the latest notification, or a pending confirmation, as an overlay line
+
+use crate::app::App;
+use crate::ui::display_width;
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    widgets::{Clear, Paragraph},
+    Frame,
+};
+use std::time::Instant;
+
+/// Draw the active toast over the top-right of the current view
+///
+/// The abort-all and quit confirmations take its place while they wait
+/// for a key.
+pub fn render_toast(f: &mut Frame, app: &App) {
+    let (text, bg) = if app.confirm_abort {
+        let running = app.scheduler.get_running().len();
+        (
+            format!(" Abort {} running task(s)? y: Yes │ any other key: No ", running),
+            app.theme.critical,
+        )
+    } else if app.confirm_quit {
+        let running = app.scheduler.get_running().len();
+        (
+            format!(" {} task(s) running — quit anyway? y: Yes │ any other key: No ", running),
+            app.theme.critical,
+        )
+    } else if let Some(message) = app.active_toast(Instant::now()) {
+        (format!(" {} ", message), app.theme.highlight_bg)
+    } else {
+        return;
+    };
+
+    let area = f.area();
+    let width = (display_width(&text) as u16).min(area.width.saturating_sub(2));
+    if width == 0 || area.height < 2 {
+        return;
+    }
+
+    // Sits on the top border of the view's header block
+    let toast_area = Rect {
+        x: area.x + area.width - width - 1,
+        y: area.y,
+        width,
+        height: 1,
+    };
+
+    let toast = Paragraph::new(text).style(
+        Style::default()
+            .fg(app.theme.highlight_fg)
+            .bg(bg)
+            .add_modifier(Modifier::BOLD),
+    );
+    f.render_widget(Clear, toast_area);
+    f.render_widget(toast, toast_area);
+}
@@ -5,7 +5,7 @@ use crate::core::GraphTaskStatus;
 use crate::semantic::MetricValue;
 use ratatui::{
     layout::{Constraint, Direction, Layout},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, Cell, Paragraph, Row, Table},
     Frame,
@@ -26,13 +26,13 @@ pub fn render_comparison_view(f: &mut Frame, app: &App) {
     render_header(f, app, chunks[0]);
     render_comparison_table(f, app, chunks[1]);
     render_summary(f, app, chunks[2]);
-    render_footer(f, chunks[3]);
+    render_footer(f, app, chunks[3]);
 }
 
-fn render_header(f: &mut Frame, _app: &App, area: ratatui::layout::Rect) {
+fn render_header(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
     let header = Paragraph::new("Cross-Task Comparison")
         .block(Block::default().borders(Borders::ALL))
-        .style(Style::default().fg(Color::Cyan));
+        .style(Style::default().fg(app.theme.accent));
     f.render_widget(header, area);
 }
 
@@ -72,7 +72,10 @@ fn render_comparison_table(f: &mut Frame, app: &App, area: ratatui::layout::Rect
         if let Some(metrics) = app.get_task_metrics(task_id) {
             for (key, value) in &metrics.metrics {
                 if let Some(v) = value.as_float() {
-                    let lower_is_better = key == "loss" || key == "errors" || key == "warnings";
+                    let lower_is_better = matches!(
+                        key.as_str(),
+                        "loss" | "errors" | "warnings" | "peak_mem_mb" | "cpu_secs"
+                    );
                     let entry = best_values.entry(key.clone()).or_insert((v, lower_is_better));
                     if lower_is_better {
                         if v < entry.0 {
@@ -115,10 +118,8 @@ fn render_comparison_table(f: &mut Frame, app: &App, area: ratatui::layout::Rect
             let mut cells = vec![
                 Cell::from(display_name.to_string()),
                 Cell::from(status_str).style(Style::default().fg(match task.status {
-                    GraphTaskStatus::Done => Color::Green,
-                    GraphTaskStatus::InProgress => Color::Yellow,
-                    GraphTaskStatus::Failed => Color::Red,
-                    _ => Color::Gray,
+                    GraphTaskStatus::Planned => app.theme.pending,
+                    ref status => app.theme.status(status),
                 })),
                 Cell::from(progress_str),
                 Cell::from(eta_str),
@@ -143,7 +144,7 @@ fn render_comparison_table(f: &mut Frame, app: &App, area: ratatui::layout::Rect
 
                         if is_best {
                             Cell::from(format!("{} *", text))
-                                .style(Style::default().fg(Color::Green).add_modifier(Modifier::BOLD))
+                                .style(Style::default().fg(app.theme.done).add_modifier(Modifier::BOLD))
                         } else {
                             Cell::from(text)
                         }
@@ -210,7 +211,7 @@ fn render_summary(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
             Span::raw("  Best Loss: "),
             Span::styled(
                 format!("{:.4} ({})", loss, task),
-                Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
+                Style::default().fg(app.theme.done).add_modifier(Modifier::BOLD),
             ),
         ]));
     }
@@ -219,7 +220,7 @@ fn render_summary(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
             Span::raw("  Best Accuracy: "),
             Span::styled(
                 format!("{:.2}% ({})", acc * 100.0, task),
-                Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
+                Style::default().fg(app.theme.done).add_modifier(Modifier::BOLD),
             ),
         ]));
     }
@@ -232,10 +233,10 @@ fn render_summary(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
     f.render_widget(summary, area);
 }
 
-fn render_footer(f: &mut Frame, area: ratatui::layout::Rect) {
+fn render_footer(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
     let footer_text = "Esc: Back | Tab: Cycle view | 1: Dashboard | 2: Terminal | 3: Graph | 4: Compare";
     let footer = Paragraph::new(footer_text)
         .block(Block::default().borders(Borders::ALL))
-        .style(Style::default().fg(Color::DarkGray));
+        .style(Style::default().fg(app.theme.muted));
     f.render_widget(footer, area);
 }
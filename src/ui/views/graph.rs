@@ -4,12 +4,11 @@ use crate::app::App;
 use crate::core::GraphTaskStatus;
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, List, ListItem, Paragraph},
     Frame,
 };
-use std::collections::HashMap;
 
 /// Render a visual DAG view of task dependencies
 pub fn render_graph_view(f: &mut Frame, app: &App) {
@@ -23,8 +22,17 @@ pub fn render_graph_view(f: &mut Frame, app: &App) {
         .split(f.area());
 
     render_header(f, app, chunks[0]);
-    render_dag(f, app, chunks[1]);
-    render_footer(f, chunks[2]);
+    if app.show_nodes && !app.scheduler.graph().nodes.is_empty() {
+        let body = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(65), Constraint::Percentage(35)])
+            .split(chunks[1]);
+        render_dag(f, app, body[0]);
+        render_nodes(f, app, body[1]);
+    } else {
+        render_dag(f, app, chunks[1]);
+    }
+    render_footer(f, app, chunks[2]);
 }
 
 fn render_header(f: &mut Frame, app: &App, area: Rect) {
@@ -42,7 +50,7 @@ fn render_header(f: &mut Frame, app: &App, area: Rect) {
 
     let header = Paragraph::new(title)
         .block(Block::default().borders(Borders::ALL))
-        .style(Style::default().fg(Color::Cyan));
+        .style(Style::default().fg(app.theme.accent));
     f.render_widget(header, area);
 }
 
@@ -50,8 +58,10 @@ fn render_dag(f: &mut Frame, app: &App, area: Rect) {
     let graph = app.scheduler.graph();
     let tasks = graph.all_tasks();
 
-    // Build layers: tasks grouped by dependency depth
-    let layers = build_layers(tasks);
+    // Build layers: tasks grouped by dependency depth (single layer if cyclic)
+    let layers = graph
+        .layers()
+        .unwrap_or_else(|| vec![graph.alphabetical_order()]);
     let mut items: Vec<ListItem> = Vec::new();
 
     for (depth, layer_tasks) in layers.iter().enumerate() {
@@ -61,7 +71,7 @@ fn render_dag(f: &mut Frame, app: &App, area: Rect) {
             Span::styled(
                 format!("{}Layer {} ────", indent, depth),
                 Style::default()
-                    .fg(Color::DarkGray)
+                    .fg(app.theme.muted)
                     .add_modifier(Modifier::BOLD),
             ),
         ]);
@@ -69,21 +79,15 @@ fn render_dag(f: &mut Frame, app: &App, area: Rect) {
 
         for task_id in layer_tasks {
             if let Some(task) = tasks.get(task_id) {
-                let status_icon = match task.status {
+                let status_icon = task.icon.as_deref().unwrap_or(match task.status {
                     GraphTaskStatus::Done => "✓",
                     GraphTaskStatus::InProgress => "⚙",
                     GraphTaskStatus::Failed => "✗",
                     GraphTaskStatus::Pending => "□",
                     GraphTaskStatus::Planned => "○",
-                };
+                });
 
-                let status_color = match task.status {
-                    GraphTaskStatus::Done => Color::Green,
-                    GraphTaskStatus::InProgress => Color::Yellow,
-                    GraphTaskStatus::Failed => Color::Red,
-                    GraphTaskStatus::Pending => Color::Gray,
-                    GraphTaskStatus::Planned => Color::DarkGray,
-                };
+                let status_color = app.theme.status(&task.status);
 
                 // Show dependency arrows
                 let deps_str = task.depends_on.as_ref()
@@ -110,10 +114,10 @@ fn render_dag(f: &mut Frame, app: &App, area: Rect) {
                     Span::styled(
                         task_id.to_string(),
                         Style::default()
-                            .fg(Color::White)
+                            .fg(app.theme.task_color(task).unwrap_or(app.theme.text))
                             .add_modifier(Modifier::BOLD),
                     ),
-                    Span::styled(deps_str, Style::default().fg(Color::DarkGray)),
+                    Span::styled(deps_str, Style::default().fg(app.theme.muted)),
                 ]);
 
                 items.push(ListItem::new(line));
@@ -132,59 +136,63 @@ fn render_dag(f: &mut Frame, app: &App, area: Rect) {
     f.render_widget(list, area);
 }
 
-/// Build layers: group tasks by their dependency depth
-fn build_layers(tasks: &HashMap<String, crate::core::Task>) -> Vec<Vec<String>> {
-    let mut depths: HashMap<String, usize> = HashMap::new();
-
-    // Calculate depth for each task
-    for task_id in tasks.keys() {
-        calculate_depth(task_id, tasks, &mut depths);
-    }
-
-    // Group by depth
-    let max_depth = depths.values().copied().max().unwrap_or(0);
-    let mut layers: Vec<Vec<String>> = vec![Vec::new(); max_depth + 1];
-
-    let mut sorted_tasks: Vec<(String, usize)> = depths.into_iter().collect();
-    sorted_tasks.sort_by(|a, b| a.1.cmp(&b.1).then(a.0.cmp(&b.0)));
+/// Project structure: the graph's nodes grouped by architecture layer
+fn render_nodes(f: &mut Frame, app: &App, area: Rect) {
+    let graph = app.scheduler.graph();
+    let mut items: Vec<ListItem> = Vec::new();
 
-    for (task_id, depth) in sorted_tasks {
-        layers[depth].push(task_id);
+    for (layer, node_ids) in graph.nodes_by_layer() {
+        items.push(ListItem::new(Line::from(Span::styled(
+            format!("{} ────", layer.as_deref().unwrap_or("(no layer)")),
+            Style::default()
+                .fg(app.theme.heading)
+                .add_modifier(Modifier::BOLD),
+        ))));
+
+        for node_id in node_ids {
+            let node = &graph.nodes[&node_id];
+            // Node statuses are free-form; color the common ones
+            let (icon, color) = match node.status.to_lowercase().as_str() {
+                "done" | "complete" | "completed" => ("✓", app.theme.done),
+                "active" | "in-progress" | "in_progress" => ("●", app.theme.running),
+                "failed" | "blocked" => ("✗", app.theme.failed),
+                "planned" => ("○", app.theme.planned),
+                _ => ("□", app.theme.pending),
+            };
+            let tasks = graph
+                .all_tasks()
+                .values()
+                .filter(|t| t.component.as_deref() == Some(node_id.as_str()))
+                .count();
+            let tasks_str = if tasks > 0 { format!(" · {} tasks", tasks) } else { String::new() };
+
+            items.push(ListItem::new(Line::from(vec![
+                Span::styled(format!("  {} ", icon), Style::default().fg(color)),
+                Span::styled(node_id.clone(), Style::default().fg(app.theme.text)),
+                Span::styled(
+                    format!(" ({}){}", node.node_type, tasks_str),
+                    Style::default().fg(app.theme.muted),
+                ),
+            ])));
+        }
     }
 
-    layers
+    let list = List::new(items).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Project Structure (by layer)"),
+    );
+    f.render_widget(list, area);
 }
 
-fn calculate_depth(
-    task_id: &str,
-    tasks: &HashMap<String, crate::core::Task>,
-    depths: &mut HashMap<String, usize>,
-) -> usize {
-    if let Some(&depth) = depths.get(task_id) {
-        return depth;
-    }
-
-    let depth = if let Some(task) = tasks.get(task_id) {
-        if let Some(deps) = &task.depends_on {
-            deps.iter()
-                .map(|dep| calculate_depth(dep, tasks, depths) + 1)
-                .max()
-                .unwrap_or(0)
-        } else {
-            0
-        }
+fn render_footer(f: &mut Frame, app: &App, area: Rect) {
+    let footer_text = if app.scheduler.graph().nodes.is_empty() {
+        "Esc: Back | Tab: Cycle view | 1: Dashboard | 2: Terminal | 3: Graph"
     } else {
-        0
+        "Esc: Back | Tab: Cycle view | 1: Dashboard | 2: Terminal | 3: Graph | n: Nodes"
     };
-
-    depths.insert(task_id.to_string(), depth);
-    depth
-}
-
-fn render_footer(f: &mut Frame, area: Rect) {
-    let footer_text = "Esc: Back | Tab: Cycle view | 1: Dashboard | 2: Terminal | 3: Graph";
     let footer = Paragraph::new(footer_text)
         .block(Block::default().borders(Borders::ALL))
-        .style(Style::default().fg(Color::DarkGray));
+        .style(Style::default().fg(app.theme.muted));
     f.render_widget(footer, area);
 }
@@ -11,9 +11,11 @@
 
 use crate::agents::AgentRuntimeStatus;
 use crate::app::App;
+use crate::semantic::history;
+use crate::ui::{pad_to_width, truncate_to_width};
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, List, ListItem, Paragraph, Wrap},
     Frame,
@@ -59,11 +61,7 @@ fn render_header(f: &mut Frame, app: &App, area: Rect) {
     let completed = summaries.iter().filter(|s| s.tasks_done == s.task_count && s.task_count > 0).count();
     let errors = summaries.iter().filter(|s| s.tasks_failed > 0).count();
     
-    let search_indicator = if app.is_search_mode() {
-        format!(" | Search: {}_", app.get_search_query())
-    } else {
-        String::new()
-    };
+    let search_indicator = app.search_indicator();
     
     // Build status string with agent indicators
     let agent_status = if agents_running + agents_thinking + agents_waiting > 0 {
@@ -82,7 +80,7 @@ fn render_header(f: &mut Frame, app: &App, area: Rect) {
     
     let header = Paragraph::new(title)
         .block(Block::default().borders(Borders::ALL))
-        .style(Style::default().fg(Color::Cyan));
+        .style(Style::default().fg(app.theme.accent));
     
     f.render_widget(header, area);
 }
@@ -91,7 +89,10 @@ fn render_project_list(f: &mut Frame, app: &App, area: Rect) {
     let summaries = app.get_project_summaries();
     let mut items: Vec<ListItem> = Vec::new();
     
-    for (idx, summary) in summaries.iter().enumerate() {
+    for idx in app.visible_project_indices() {
+        let Some(summary) = summaries.get(idx) else {
+            continue;
+        };
         let is_selected = idx == app.selected_project;
         
         // Port display
@@ -102,15 +103,15 @@ fn render_project_list(f: &mut Frame, app: &App, area: Rect) {
         // Get agent runtime status for more detailed emoji (Phase 2)
         let agent_runtime = app.get_agent_status(&summary.name);
         let (status_emoji, status_color, status_text) = match agent_runtime {
-            AgentRuntimeStatus::Running => ("🤖", Color::Green, "running"),
-            AgentRuntimeStatus::Thinking => ("💭", Color::Yellow, "thinking"),
-            AgentRuntimeStatus::WaitingInput => ("⏳", Color::Blue, "waiting"),
-            AgentRuntimeStatus::Completed => ("✅", Color::Gray, "done"),
-            AgentRuntimeStatus::Error => ("❌", Color::Red, "error"),
+            AgentRuntimeStatus::Running => ("🤖", app.theme.done, "running"),
+            AgentRuntimeStatus::Thinking => ("💭", app.theme.running, "thinking"),
+            AgentRuntimeStatus::WaitingInput => ("⏳", app.theme.waiting, "waiting"),
+            AgentRuntimeStatus::Completed => ("✅", app.theme.pending, "done"),
+            AgentRuntimeStatus::Error => ("❌", app.theme.failed, "error"),
             AgentRuntimeStatus::NotRunning => {
                 // Fall back to task-based display
                 let emoji = summary.agent_status.emoji();
-                let color = summary.agent_status.color();
+                let color = summary.agent_status.color(&app.theme);
                 (emoji, color, "idle")
             }
         };
@@ -130,29 +131,34 @@ fn render_project_list(f: &mut Frame, app: &App, area: Rect) {
             0
         };
         
+        let eta = app
+            .project_eta(&summary.name)
+            .map(|eta| format!("ETA {}", history::format_eta(eta)))
+            .unwrap_or_else(|| "—".to_string());
+
         // Build the line
         let line = Line::from(vec![
             // Selection indicator and project number
             Span::styled(
                 format!(" {} ", if is_selected { "▶" } else { " " }),
-                Style::default().fg(if is_selected { Color::Yellow } else { Color::DarkGray }),
+                Style::default().fg(if is_selected { app.theme.running } else { app.theme.muted }),
             ),
             Span::styled(
                 format!("[{}] ", idx + 1),
-                Style::default().fg(Color::DarkGray),
+                Style::default().fg(app.theme.muted),
             ),
             // Project icon and name
             Span::raw("📁 "),
             Span::styled(
-                format!("{:<16}", summary.name),
+                pad_to_width(&summary.name, 16),
                 Style::default()
-                    .fg(Color::White)
+                    .fg(app.theme.text)
                     .add_modifier(if is_selected { Modifier::BOLD } else { Modifier::empty() }),
             ),
             // Port
             Span::styled(
                 format!("{:<6}", port_str),
-                Style::default().fg(Color::Green),
+                Style::default().fg(app.theme.done),
             ),
             // Agent Status (Phase 2: detailed status)
             Span::styled(
@@ -165,14 +171,15 @@ fn render_project_list(f: &mut Frame, app: &App, area: Rect) {
             ),
             // Pipeline
             Span::styled(
-                format!("{:<12}", pipeline),
-                Style::default().fg(Color::Gray),
+                pad_to_width(&pipeline, 12),
+                Style::default().fg(app.theme.pending),
             ),
             // Progress
             Span::styled(
                 format!(" {:>3}%", progress_pct),
-                Style::default().fg(if progress_pct == 100 { Color::Green } else { Color::Yellow }),
+                Style::default().fg(if progress_pct == 100 { app.theme.done } else { app.theme.running }),
             ),
+            Span::styled(format!("  {}", eta), Style::default().fg(app.theme.accent)),
         ]);
         
         // Recent event (second line)
@@ -180,8 +187,8 @@ fn render_project_list(f: &mut Frame, app: &App, area: Rect) {
             Line::from(vec![
                 Span::raw("      └─ "),
                 Span::styled(
-                    truncate(event, 60),
-                    Style::default().fg(Color::DarkGray),
+                    truncate_to_width(event, 60),
+                    Style::default().fg(app.theme.muted),
                 ),
             ])
         } else {
@@ -189,7 +196,7 @@ fn render_project_list(f: &mut Frame, app: &App, area: Rect) {
         };
         
         let style = if is_selected {
-            Style::default().bg(Color::DarkGray)
+            Style::default().bg(app.theme.selection_bg)
         } else {
             Style::default()
         };
@@ -224,14 +231,14 @@ fn render_recent_events(f: &mut Frame, app: &App, area: Rect) {
     let events_widget = Paragraph::new(text)
         .block(Block::default().borders(Borders::ALL).title("Recent Events"))
         .wrap(Wrap { trim: false })
-        .style(Style::default().fg(Color::Gray));
+        .style(Style::default().fg(app.theme.pending));
     
     f.render_widget(events_widget, area);
 }
 
 fn render_footer(f: &mut Frame, app: &App, area: Rect) {
     let help = if app.is_search_mode() {
-        "Type to search │ Enter: Jump │ Esc: Cancel".to_string()
+        app.search_help().to_string()
     } else {
         // Include agent status legend (Phase 2)
         "1-9: Switch │ /: Search │ Enter: Focus │ Tab: Views │ q: Quit │ 🤖running 💭thinking ⏳waiting ✅done ❌error".to_string()
@@ -239,16 +246,7 @@ fn render_footer(f: &mut Frame, app: &App, area: Rect) {
     
     let footer = Paragraph::new(help)
         .block(Block::default().borders(Borders::ALL))
-        .style(Style::default().fg(Color::DarkGray));
+        .style(Style::default().fg(app.theme.muted));
     
     f.render_widget(footer, area);
 }
-
-/// Truncate string with ellipsis
-fn truncate(s: &str, max_len: usize) -> String {
-    if s.len() <= max_len {
-        s.to_string()
-    } else {
-        format!("{}...", &s[..max_len.saturating_sub(3)])
-    }
-}
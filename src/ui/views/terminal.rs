@@ -1,9 +1,12 @@
 //! Terminal View - Full-screen terminal output for a single task
 
 use crate::app::App;
-use crate::core::GraphTaskStatus;
+use crate::core::{GraphTaskStatus, RingBuffer};
 use crate::semantic::MetricValue;
 use crate::semantic::advisor::Severity;
+use crate::semantic::commands::ParamKind;
+use crate::ui::{wrapped_rows, Theme};
+use regex::Regex;
 use ratatui::{
     layout::{Constraint, Direction, Layout},
     style::{Color, Modifier, Style},
@@ -29,7 +32,7 @@ pub fn render_terminal_view(f: &mut Frame, app: &App) {
     let has_metrics = app.get_task_metrics(task_id).is_some();
     let has_commands = app.get_semantic_commands(task_id).is_some();
     let has_advisories = app.get_advisories(task_id)
-        .map(|a| !a.is_empty())
+        .map(|h| !h.active().is_empty())
         .unwrap_or(false);
     let has_history = app.get_metric_history(task_id)
         .map(|h| h.snapshots.len() >= 3)
@@ -69,17 +72,15 @@ pub fn render_terminal_view(f: &mut Frame, app: &App) {
     };
 
     let status_color = match task.status {
-        GraphTaskStatus::Done => Color::Green,
-        GraphTaskStatus::InProgress => Color::Yellow,
-        GraphTaskStatus::Failed => Color::Red,
-        _ => Color::Gray,
+        GraphTaskStatus::Planned => app.theme.pending,
+        ref status => app.theme.status(status),
     };
 
-    let header_text = Line::from(vec![
+    let mut header_spans = vec![
         Span::styled(
             format!(" {} {} ", status_icon, task_id),
             Style::default()
-                .fg(Color::White)
+                .fg(app.theme.text)
                 .add_modifier(Modifier::BOLD),
         ),
         Span::styled(
@@ -89,9 +90,20 @@ pub fn render_terminal_view(f: &mut Frame, app: &App) {
         Span::raw("  "),
         Span::styled(
             &task.description,
-            Style::default().fg(Color::DarkGray),
+            Style::default().fg(app.theme.muted),
         ),
-    ]);
+    ];
+    if app.interact_task.as_ref() == Some(task_id) {
+        header_spans.push(Span::raw("  "));
+        header_spans.push(Span::styled(
+            " INTERACT ",
+            Style::default()
+                .fg(app.theme.highlight_fg)
+                .bg(app.theme.accent)
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+    let header_text = Line::from(header_spans);
 
     let header = Paragraph::new(header_text)
         .block(Block::default().borders(Borders::ALL).title("Task"));
@@ -120,7 +132,7 @@ pub fn render_terminal_view(f: &mut Frame, app: &App) {
 
         let gauge = Gauge::default()
             .block(Block::default().borders(Borders::ALL).title("Progress"))
-            .gauge_style(Style::default().fg(Color::Green).bg(Color::DarkGray))
+            .gauge_style(Style::default().fg(app.theme.done).bg(app.theme.selection_bg))
             .percent(pct.min(100))
             .label(label_parts.join(" | "));
 
@@ -141,14 +153,14 @@ pub fn render_terminal_view(f: &mut Frame, app: &App) {
         if loss_vals.len() >= 2 {
             // Scale to u64 (multiply by 1000 for precision)
             let scaled: Vec<u64> = loss_vals.iter().map(|v| (v * 1000.0) as u64).collect();
-            spark_charts.push(("Loss", scaled, Color::Red));
+            spark_charts.push(("Loss", scaled, app.theme.failed));
         }
 
         // Accuracy sparkline
         let acc_vals = history.metric_values("accuracy", 50);
         if acc_vals.len() >= 2 {
             let scaled: Vec<u64> = acc_vals.iter().map(|v| (v * 1000.0) as u64).collect();
-            spark_charts.push(("Accuracy", scaled, Color::Green));
+            spark_charts.push(("Accuracy", scaled, app.theme.done));
         }
 
         // Progress sparkline (if no loss/acc, show progress)
@@ -156,7 +168,7 @@ pub fn render_terminal_view(f: &mut Frame, app: &App) {
             let prog_vals = history.progress_values(50);
             if prog_vals.len() >= 2 {
                 let scaled: Vec<u64> = prog_vals.iter().map(|v| (v * 1000.0) as u64).collect();
-                spark_charts.push(("Progress", scaled, Color::Cyan));
+                spark_charts.push(("Progress", scaled, app.theme.accent));
             }
         }
 
@@ -183,22 +195,52 @@ pub fn render_terminal_view(f: &mut Frame, app: &App) {
         chunk_idx += 1;
     }
 
-    // Output panel (full height)
+    // Output panel (full height), `scroll_offset` lines up from the tail.
+    // With errors only, positions count error lines, mapped to line indices.
     let output_area = chunks[chunk_idx];
     let output_height = output_area.height.saturating_sub(2) as usize;
-    let output_lines = app.get_task_output(task_id, output_height + app.scroll_offset);
-
-    let visible_lines = if output_lines.len() > output_height {
-        let start = output_lines.len().saturating_sub(output_height);
-        output_lines[start..].to_vec()
-    } else {
-        output_lines
-    };
+    let output_width = output_area.width.saturating_sub(2) as usize;
+    let no_output = RingBuffer::new(0);
+    let output_lines = app.task_outputs.get(task_id.as_str()).unwrap_or(&no_output);
+    let errors = app.errors_only.then(|| app.error_lines(task_id));
+    let line_index = |pos: usize| errors.as_ref().map_or(pos, |e| e[pos]);
+    let total = errors.as_ref().map_or(output_lines.len(), Vec::len);
+    let end = total - app.scroll_offset.min(total.saturating_sub(output_height));
+    // Long lines wrap, so fill the panel by rows (display columns) rather than lines
+    let mut start = end;
+    let mut rows = 0;
+    while start > 0 {
+        rows += wrapped_rows(&output_lines[line_index(start - 1)], output_width);
+        if rows > output_height && start < end {
+            break;
+        }
+        start -= 1;
+    }
 
-    let text = if visible_lines.is_empty() {
-        "(waiting for output...)".to_string()
+    let search_re = app.output_search.as_ref().and_then(|s| s.regex());
+    let matches = app.output_matches(task_id);
+    let current_line = app
+        .output_search
+        .as_ref()
+        .and_then(|s| matches.get(s.current.min(matches.len().saturating_sub(1))))
+        .copied();
+
+    let text: Vec<Line> = if total == 0 && app.errors_only {
+        vec![Line::from("(no errors)")]
+    } else if total == 0 {
+        vec![Line::from("(waiting for output...)")]
     } else {
-        visible_lines.join("\n")
+        (start..end)
+            .map(line_index)
+            .map(|index| {
+                let line = &output_lines[index];
+                let level = Style::default().fg(app.theme.log_level(app.output_level(task_id, index)));
+                match &search_re {
+                    Some(re) => highlight_matches(line, re, current_line == Some(index), &app.theme).style(level),
+                    None => Line::styled(line.as_str(), level),
+                }
+            })
+            .collect()
     };
 
     let cmd_display = task
@@ -210,36 +252,50 @@ pub fn render_terminal_view(f: &mut Frame, app: &App) {
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title(format!("Output: {}", cmd_display)),
+                .title(if app.errors_only {
+                    format!("Output (errors only): {}", cmd_display)
+                } else {
+                    format!("Output: {}", cmd_display)
+                }),
         )
         .wrap(Wrap { trim: false })
-        .style(Style::default().fg(Color::White));
+        .style(Style::default().fg(app.theme.text));
 
     f.render_widget(output, output_area);
     chunk_idx += 1;
 
     // Advisories panel
     if has_advisories {
-        let advisories = app.get_advisories(task_id).unwrap();
-        let advisory_lines: Vec<Line> = advisories
+        // Only unacknowledged advisories are shown, newest first
+        let active = app.get_advisories(task_id).unwrap().active();
+        let advisory_lines: Vec<Line> = active
             .iter()
             .take(3)
-            .map(|a| {
-                let (icon, color) = match a.severity {
-                    Severity::Critical => ("!!", Color::Red),
-                    Severity::Warning => ("!", Color::Yellow),
-                    Severity::Info => ("i", Color::Cyan),
+            .map(|(_, record)| {
+                let a = &record.advisory;
+                let icon = match a.severity {
+                    Severity::Critical => "!!",
+                    Severity::Warning => "!",
+                    Severity::Info => "i",
+                };
+                let color = app.theme.severity(&a.severity);
+                let repeats = if record.count > 1 {
+                    format!(" (x{})", record.count)
+                } else {
+                    String::new()
                 };
                 Line::from(vec![
                     Span::styled(format!(" [{}] ", icon), Style::default().fg(color).add_modifier(Modifier::BOLD)),
-                    Span::raw(&a.message),
-                    Span::styled(format!(" -> {}", a.suggestion), Style::default().fg(Color::DarkGray)),
+                    Span::styled(&a.message, Style::default().add_modifier(Modifier::BOLD)),
+                    Span::styled(repeats, Style::default().fg(color)),
+                    Span::styled(format!(" -> {}", a.suggestion), Style::default().fg(app.theme.muted)),
                 ])
             })
             .collect();
 
+        let title = format!("Advisories ({}) - a: Acknowledge", active.len());
         let advisories_widget = Paragraph::new(advisory_lines)
-            .block(Block::default().borders(Borders::ALL).title("Advisories"))
+            .block(Block::default().borders(Borders::ALL).title(title))
             .wrap(Wrap { trim: true });
 
         f.render_widget(advisories_widget, chunks[chunk_idx]);
@@ -256,23 +312,94 @@ pub fn render_terminal_view(f: &mut Frame, app: &App) {
             .map(|(i, label)| format!("[F{}] {}", i + 1, label))
             .collect();
 
-        let commands_bar = Paragraph::new(cmd_labels.join("  "))
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title("Semantic Commands"),
-            )
-            .style(Style::default().fg(Color::Cyan));
+        // While collecting parameters, the bar becomes the input prompt
+        let (text, title) = match &app.command_prompt {
+            Some(prompt) if &prompt.task_id == task_id => {
+                let param = match prompt.current_kind() {
+                    ParamKind::Text => prompt.current_param().unwrap_or("").to_string(),
+                    kind => format!("{} ({})", prompt.current_param().unwrap_or(""), kind),
+                };
+                let title = match &prompt.error {
+                    Some(error) => format!("{} - {} (Esc: Cancel)", prompt.label, error),
+                    None => format!("{} (Enter: Next | Esc: Cancel)", prompt.label),
+                };
+                (format!("{}: {}_", param, prompt.input), title)
+            }
+            _ => (cmd_labels.join("  "), "Semantic Commands".to_string()),
+        };
+
+        let commands_bar = Paragraph::new(text)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .style(Style::default().fg(app.theme.accent));
 
         f.render_widget(commands_bar, chunks[chunk_idx]);
         chunk_idx += 1;
     }
 
     // Footer
-    let footer_text = "Esc: Back | ↑↓: Switch task | k: Kill | Tab: Cycle view";
+    let footer_text = if has_commands {
+        "Esc: Back | ↑↓: Switch task | k: Kill | X: Abort all | F1-F12: Run command | Ctrl-A i: Interact | Tab: Cycle view"
+    } else {
+        "Esc: Back | ↑↓: Switch task | k: Kill | X: Abort all | Ctrl-A i: Interact | Tab: Cycle view"
+    };
+    let follow = if app.follow { "FOLLOW" } else { "PINNED" };
+    let footer_text = match (&app.input_prompt, &app.pending_action, &app.output_search) {
+        (Some(prompt), _, _) => {
+            format!("Input for {}: {}_ │ Enter: Send │ Esc: Cancel", prompt.task_id, prompt.input)
+        }
+        (None, Some(action), _) => format!(
+            "{} │ Run '{}' on {}? y: Yes │ n: No",
+            action.message, action.label, action.task_id
+        ),
+        (None, None, Some(search)) if search.editing => {
+            format!("/{}_ │ Enter: Search │ Esc: Cancel", search.query)
+        }
+        (None, None, Some(search)) if matches.is_empty() => {
+            format!("'{}': no matches │ /: New search │ Esc: Clear", search.query)
+        }
+        (None, None, Some(search)) => format!(
+            "'{}': {}/{} │ n: Next │ N: Previous │ Esc: Clear",
+            search.query,
+            search.current.min(matches.len() - 1) + 1,
+            matches.len()
+        ),
+        (None, None, None) => format!("{} | /: Search | F: Follow | E: Errors", footer_text),
+    };
+    let (footer_text, footer_style) = match &app.interact_task {
+        Some(task) => (
+            format!("[INTERACT] Keys go to {} │ Esc: Leave", task),
+            Style::default().fg(app.theme.accent).add_modifier(Modifier::BOLD),
+        ),
+        None if app.pending_action.is_some() || app.input_prompt.is_some() => {
+            (footer_text, Style::default().fg(app.theme.muted))
+        }
+        None => (format!("[{}] {}", follow, footer_text), Style::default().fg(app.theme.muted)),
+    };
     let footer = Paragraph::new(footer_text)
         .block(Block::default().borders(Borders::ALL))
-        .style(Style::default().fg(Color::DarkGray));
+        .style(footer_style);
 
     f.render_widget(footer, chunks[chunk_idx]);
 }
+
+/// Highlight every match of `re` in `line`; the current match is emphasized
+fn highlight_matches<'a>(line: &'a str, re: &Regex, current: bool, theme: &Theme) -> Line<'a> {
+    let style = if current {
+        Style::default()
+            .fg(theme.highlight_fg)
+            .bg(theme.current_match_bg)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(theme.highlight_fg).bg(theme.highlight_bg)
+    };
+
+    let mut spans = Vec::new();
+    let mut last = 0;
+    for m in re.find_iter(line) {
+        spans.push(Span::raw(&line[last..m.start()]));
+        spans.push(Span::styled(m.as_str(), style));
+        last = m.end();
+    }
+    spans.push(Span::raw(&line[last..]));
+    Line::from(spans)
+}
@@ -1,12 +1,65 @@
 //! Multi-project workspace management
 
 use anyhow::Result;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::thread;
 
 use crate::core::Graph;
 
+/// How deep `Workspace::discover` looks: immediate subdirectories only
+pub const DEFAULT_DISCOVERY_DEPTH: usize = 1;
+
+/// Parse the graphs of discovered projects on all cores
+///
+/// Projects whose graph fails to load are logged and left out.
+fn load_projects_parallel(found: Vec<(String, PathBuf)>) -> HashMap<String, Project> {
+    if found.is_empty() {
+        return HashMap::new();
+    }
+    let threads = thread::available_parallelism().map_or(1, |n| n.get()).min(found.len());
+    let chunk_size = found.len().div_ceil(threads);
+
+    thread::scope(|scope| {
+        let workers: Vec<_> = found
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .filter_map(|(name, path)| load_project(name, path))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic)))
+            .map(|project| (project.name.clone(), project))
+            .collect()
+    })
+}
+
+fn load_project(name: &str, path: &Path) -> Option<Project> {
+    let graph_path = path.join(".gid/graph.yml");
+    match Graph::from_file(&graph_path) {
+        Ok(graph) => Some(Project {
+            name: name.to_string(),
+            path: path.to_path_buf(),
+            graph,
+        }),
+        Err(e) => {
+            log::warn!("Failed to load graph from {:?}: {}", graph_path, e);
+            None
+        }
+    }
+}
+
+/// Directories never searched for projects
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target", ".git", ".gid"];
+
 /// A workspace containing multiple projects
 #[derive(Debug, Clone)]
 pub struct Workspace {
@@ -23,42 +76,20 @@ pub struct Project {
 }
 
 impl Workspace {
-    /// Discover and load all projects in a directory
+    /// Discover and load all projects in the immediate subdirectories of `root`
     pub fn discover(root: &Path) -> Result<Self> {
-        let mut projects = HashMap::new();
+        Self::discover_with_depth(root, DEFAULT_DISCOVERY_DEPTH)
+    }
 
-        // Walk subdirectories looking for .gid/graph.yml
-        for entry in fs::read_dir(root)? {
-            let entry = entry?;
-            let path = entry.path();
-
-            if path.is_dir() {
-                let graph_path = path.join(".gid/graph.yml");
-                if graph_path.exists() {
-                    let name = path
-                        .file_name()
-                        .and_then(|n| n.to_str())
-                        .unwrap_or("unknown")
-                        .to_string();
-
-                    match Graph::from_file(&graph_path) {
-                        Ok(graph) => {
-                            projects.insert(
-                                name.clone(),
-                                Project {
-                                    name,
-                                    path,
-                                    graph,
-                                },
-                            );
-                        }
-                        Err(e) => {
-                            log::warn!("Failed to load graph from {:?}: {}", graph_path, e);
-                        }
-                    }
-                }
-            }
-        }
+    /// Discover projects up to `max_depth` directories below `root`
+    ///
+    /// Projects are named by their path relative to `root` (e.g.
+    /// `services/api`), so same-named directories in different places
+    /// don't collide. `node_modules`, `target`, `.git` and `.gid` are skipped.
+    pub fn discover_with_depth(root: &Path, max_depth: usize) -> Result<Self> {
+        let mut found = Vec::new();
+        Self::discover_in(root, root, max_depth, &mut found)?;
+        let projects = load_projects_parallel(found);
 
         if projects.is_empty() {
             anyhow::bail!("No projects found with .gid/graph.yml in {}", root.display());
@@ -70,6 +101,78 @@ impl Workspace {
         })
     }
 
+    /// Walk subdirectories of `dir` collecting (name, path) of every
+    /// directory with a .gid/graph.yml
+    fn discover_in(
+        root: &Path,
+        dir: &Path,
+        depth_left: usize,
+        found: &mut Vec<(String, PathBuf)>,
+    ) -> Result<()> {
+        if depth_left == 0 {
+            return Ok(());
+        }
+
+        let mut entries: Vec<PathBuf> = fs::read_dir(dir)?
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.is_dir())
+            .collect();
+        entries.sort();
+
+        for path in entries {
+            let skip = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .is_none_or(|n| SKIPPED_DIRS.contains(&n));
+            if skip {
+                continue;
+            }
+
+            if path.join(".gid/graph.yml").exists() {
+                let name = path
+                    .strip_prefix(root)
+                    .unwrap_or(&path)
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                found.push((name, path.clone()));
+            }
+
+            // Projects can nest inside other projects
+            if let Err(e) = Self::discover_in(root, &path, depth_left - 1, found) {
+                log::debug!("Skipping {:?}: {}", path, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Keep only projects matching one of `patterns`
+    ///
+    /// Patterns are project names or globs (`*` and `?`, e.g. `svc-*`).
+    /// Errors if any pattern matches no discovered project.
+    pub fn retain_projects(&mut self, patterns: &[String]) -> Result<()> {
+        let mut keep = HashSet::new();
+
+        for pattern in patterns {
+            let re = glob_regex(pattern)?;
+            let matched: Vec<&String> = self.projects.keys().filter(|name| re.is_match(name)).collect();
+            if matched.is_empty() {
+                anyhow::bail!(
+                    "No project matches '{}' (found: {})",
+                    pattern,
+                    self.project_names().join(", ")
+                );
+            }
+            keep.extend(matched.into_iter().cloned());
+        }
+
+        self.projects.retain(|name, _| keep.contains(name));
+        Ok(())
+    }
+
     /// Load specific projects by name
     pub fn load_projects(root: &Path, project_names: &[String]) -> Result<Self> {
         let mut projects = HashMap::new();
@@ -101,6 +204,9 @@ impl Workspace {
 
     /// Create a unified graph with namespaced task IDs
     /// Task IDs become: "project:task_id"
+    ///
+    /// Dependencies that are already namespaced (`other-project:task`) are
+    /// kept as-is, which is how one project depends on another's task.
     pub fn to_unified_graph(&self) -> Graph {
         let mut unified_tasks = HashMap::new();
         let mut unified_nodes = HashMap::new();
@@ -115,7 +221,7 @@ impl Workspace {
                 if let Some(deps) = &task.depends_on {
                     namespaced_task.depends_on = Some(
                         deps.iter()
-                            .map(|dep| format!("{}:{}", project_name, dep))
+                            .map(|dep| namespace_dep(project_name, dep))
                             .collect(),
                     );
                 }
@@ -131,7 +237,7 @@ impl Workspace {
                 if let Some(deps) = &node.depends_on {
                     namespaced_node.depends_on = Some(
                         deps.iter()
-                            .map(|dep| format!("{}:{}", project_name, dep))
+                            .map(|dep| namespace_dep(project_name, dep))
                             .collect(),
                     );
                 }
@@ -146,11 +252,31 @@ impl Workspace {
                 version: Some("1.0.0".to_string()),
                 description: Some(format!("{} projects", self.projects.len())),
             }),
+            includes: Vec::new(),
             nodes: unified_nodes,
             tasks: unified_tasks,
+            ready: Default::default(),
         }
     }
 
+    /// Check that every cross-project dependency names an existing task
+    pub fn check_dependencies(&self) -> Result<()> {
+        let unified = self.to_unified_graph();
+        let mut missing: Vec<String> = unified
+            .tasks
+            .iter()
+            .flat_map(|(id, task)| task.depends_on.iter().flatten().map(move |dep| (id, dep)))
+            .filter(|(_, dep)| !unified.tasks.contains_key(*dep))
+            .map(|(id, dep)| format!("{} depends on unknown task '{}'", id, dep))
+            .collect();
+
+        if missing.is_empty() {
+            return Ok(());
+        }
+        missing.sort();
+        anyhow::bail!("Unresolved dependencies in workspace:\n  {}", missing.join("\n  "))
+    }
+
     /// Get project count
     pub fn project_count(&self) -> usize {
         self.projects.len()
@@ -177,13 +303,161 @@ impl Workspace {
     }
 }
 
+/// Compile a `*`/`?` glob into an anchored regex
+fn glob_regex(pattern: &str) -> Result<regex::Regex> {
+    let escaped = regex::escape(pattern).replace(r"\*", ".*").replace(r"\?", ".");
+    Ok(regex::Regex::new(&format!("^{}$", escaped))?)
+}
+
+/// Namespace a dependency within `project`, unless it already names a project
+fn namespace_dep(project: &str, dep: &str) -> String {
+    if dep.contains(':') {
+        dep.to_string()
+    } else {
+        format!("{}:{}", project, dep)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    
+    use super::*;
+    use tempfile::TempDir;
+
+    fn add_project(root: &Path, rel: &str) {
+        let gid = root.join(rel).join(".gid");
+        fs::create_dir_all(&gid).unwrap();
+        fs::write(
+            gid.join("graph.yml"),
+            "tasks:\n  build:\n    description: Build\n    command: make\n",
+        )
+        .unwrap();
+    }
 
     #[test]
     fn test_workspace_creation() {
         // Test that workspace can be created
         // (Actual discovery would need real filesystem)
     }
+
+    #[test]
+    fn test_discover_nested_projects() {
+        let dir = TempDir::new().unwrap();
+        add_project(dir.path(), "web");
+        add_project(dir.path(), "services/api");
+        add_project(dir.path(), "services/worker");
+        add_project(dir.path(), "web/node_modules/some-dep");
+
+        // Default depth keeps the old one-level behavior
+        let shallow = Workspace::discover(dir.path()).unwrap();
+        assert_eq!(shallow.project_names(), vec!["web"]);
+
+        let deep = Workspace::discover_with_depth(dir.path(), 3).unwrap();
+        assert_eq!(deep.project_names(), vec!["services/api", "services/worker", "web"]);
+        assert_eq!(deep.projects["services/api"].path, dir.path().join("services/api"));
+        assert!(deep.to_unified_graph().tasks.contains_key("services/worker:build"));
+    }
+
+    #[test]
+    fn test_discover_many_projects_skips_broken() {
+        let dir = TempDir::new().unwrap();
+        for i in 0..24 {
+            add_project(dir.path(), &format!("svc-{:02}", i));
+        }
+        for broken in ["svc-03", "svc-17"] {
+            fs::write(dir.path().join(broken).join(".gid/graph.yml"), "tasks: [not, a, map").unwrap();
+        }
+
+        let workspace = Workspace::discover(dir.path()).unwrap();
+        assert_eq!(workspace.project_count(), 22);
+        assert!(!workspace.projects.contains_key("svc-03"));
+        assert!(!workspace.projects.contains_key("svc-17"));
+        assert_eq!(workspace.projects["svc-23"].path, dir.path().join("svc-23"));
+        assert!(workspace.to_unified_graph().tasks.contains_key("svc-00:build"));
+    }
+
+    #[test]
+    fn test_unified_graph_keeps_tags() {
+        let dir = TempDir::new().unwrap();
+        for (project, tags) in [("api", "[backend]"), ("db", "[Backend, infra]"), ("web", "[frontend]")] {
+            let gid = dir.path().join(project).join(".gid");
+            fs::create_dir_all(&gid).unwrap();
+            fs::write(
+                gid.join("graph.yml"),
+                format!("tasks:\n  build:\n    description: Build\n    command: make\n    tags: {}\n", tags),
+            )
+            .unwrap();
+        }
+
+        let graph = Workspace::discover(dir.path()).unwrap().to_unified_graph();
+        assert_eq!(graph.tasks_with_tag("backend"), vec!["api:build", "db:build"]);
+        assert_eq!(graph.tasks_with_tag("infra"), vec!["db:build"]);
+        assert!(graph.tasks_with_tag("back").is_empty());
+    }
+
+    #[test]
+    fn test_cross_project_dependency() {
+        let dir = TempDir::new().unwrap();
+        add_project(dir.path(), "lib");
+        let app_gid = dir.path().join("app/.gid");
+        fs::create_dir_all(&app_gid).unwrap();
+        fs::write(
+            app_gid.join("graph.yml"),
+            "tasks:\n  build:\n    description: Build\n    command: make\n    depends_on: [setup, \"lib:build\"]\n  setup:\n    description: Setup\n    command: \"true\"\n",
+        )
+        .unwrap();
+
+        let workspace = Workspace::discover(dir.path()).unwrap();
+        workspace.check_dependencies().unwrap();
+
+        let graph = workspace.to_unified_graph();
+        assert_eq!(
+            graph.get_task("app:build").unwrap().depends_on,
+            Some(vec!["app:setup".to_string(), "lib:build".to_string()])
+        );
+
+        // app:build waits for lib:build as well as its own setup
+        let mut scheduler = crate::core::Scheduler::new(graph);
+        assert_eq!(scheduler.schedule_next(), vec!["app:setup", "lib:build"]);
+        scheduler.mark_started("app:setup").unwrap();
+        scheduler.mark_done("app:setup").unwrap();
+        assert!(scheduler.schedule_next().contains(&"lib:build".to_string()));
+        assert!(!scheduler.schedule_next().contains(&"app:build".to_string()));
+
+        scheduler.mark_started("lib:build").unwrap();
+        scheduler.mark_done("lib:build").unwrap();
+        assert_eq!(scheduler.schedule_next(), vec!["app:build"]);
+    }
+
+    #[test]
+    fn test_cross_project_dependency_must_exist() {
+        let dir = TempDir::new().unwrap();
+        let gid = dir.path().join("app/.gid");
+        fs::create_dir_all(&gid).unwrap();
+        fs::write(
+            gid.join("graph.yml"),
+            "tasks:\n  build:\n    description: Build\n    depends_on: [\"lib:publish\"]\n",
+        )
+        .unwrap();
+
+        let workspace = Workspace::discover(dir.path()).unwrap();
+        let err = workspace.check_dependencies().unwrap_err();
+        assert!(err.to_string().contains("app:build depends on unknown task 'lib:publish'"));
+    }
+
+    #[test]
+    fn test_retain_projects() {
+        let dir = TempDir::new().unwrap();
+        for name in ["svc-auth", "svc-billing", "web", "docs"] {
+            add_project(dir.path(), name);
+        }
+
+        let mut workspace = Workspace::discover(dir.path()).unwrap();
+        workspace
+            .retain_projects(&["svc-*".to_string(), "web".to_string()])
+            .unwrap();
+        assert_eq!(workspace.project_names(), vec!["svc-auth", "svc-billing", "web"]);
+
+        let err = workspace.retain_projects(&["api".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("No project matches 'api'"));
+    }
 }
@@ -1,7 +1,60 @@
-use gidterm::{Graph, GraphTaskStatus, Scheduler, Session};
+use gidterm::{FailurePolicy, Graph, GraphTaskStatus, Scheduler, Session};
 use std::collections::HashMap;
+use std::ops::{Deref, DerefMut};
 use std::path::Path;
 
+// === Test Apps ===
+
+/// An app whose port registry and sessions are kept in `dir`
+fn app_in(dir: &Path, graph: Graph) -> gidterm::App {
+    let mut config = gidterm::config::Config::default();
+    config.ports.registry = Some(dir.join("ports.json"));
+    let mut app = gidterm::App::with_config(graph, &config);
+    app.session_saver = gidterm::SessionSaver::in_dir(dir, gidterm::app::SESSION_SAVE_INTERVAL);
+    app
+}
+
+/// An app in a temporary directory of its own (see `app_in`)
+///
+/// Dropping it releases the app's ports like a clean shutdown, even when
+/// the test fails halfway through.
+struct TestApp {
+    app: gidterm::App,
+    _dir: tempfile::TempDir,
+}
+
+impl TestApp {
+    fn new(graph: Graph) -> Self {
+        let dir = tempfile::tempdir().unwrap();
+        Self {
+            app: app_in(dir.path(), graph),
+            _dir: dir,
+        }
+    }
+}
+
+impl Deref for TestApp {
+    type Target = gidterm::App;
+
+    fn deref(&self) -> &Self::Target {
+        &self.app
+    }
+}
+
+impl DerefMut for TestApp {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.app
+    }
+}
+
+impl Drop for TestApp {
+    fn drop(&mut self) {
+        if let Err(e) = self.app.port_manager.release_all() {
+            eprintln!("Failed to release test ports: {}", e);
+        }
+    }
+}
+
 // === Graph Tests ===
 
 #[test]
@@ -135,6 +188,203 @@ fn test_scheduler_full_progression() {
     assert!(scheduler.all_done());
 }
 
+/// Two independent chains: a1 -> a2 and b1 -> b2
+fn two_chain_graph() -> Graph {
+    serde_yaml::from_str(
+        "tasks:\n  a1:\n    description: a1\n  a2:\n    description: a2\n    depends_on: [a1]\n  b1:\n    description: b1\n  b2:\n    description: b2\n    depends_on: [b1]\n",
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_scheduler_continue_others_runs_independent_chain() {
+    let mut scheduler = Scheduler::new(two_chain_graph());
+    assert_eq!(scheduler.failure_policy(), FailurePolicy::ContinueOthers);
+
+    scheduler.mark_started("a1").unwrap();
+    scheduler.mark_started("b1").unwrap();
+    scheduler.mark_failed("a1").unwrap();
+    scheduler.mark_done("b1").unwrap();
+
+    assert_eq!(scheduler.schedule_next(), vec!["b2"]);
+    scheduler.mark_started("b2").unwrap();
+    scheduler.mark_done("b2").unwrap();
+
+    let graph = scheduler.graph();
+    assert_eq!(graph.get_task("b2").unwrap().status, GraphTaskStatus::Done);
+    assert_eq!(graph.get_task("a2").unwrap().status, GraphTaskStatus::Pending);
+    assert!(!scheduler.is_halted());
+}
+
+#[test]
+fn test_scheduler_fail_fast_halts_scheduling() {
+    let mut scheduler = Scheduler::new(two_chain_graph());
+    scheduler.set_failure_policy(FailurePolicy::FailFast);
+
+    scheduler.mark_started("a1").unwrap();
+    scheduler.mark_started("b1").unwrap();
+    scheduler.mark_failed("a1").unwrap();
+    assert!(scheduler.is_halted());
+
+    // b1 was already running and may finish, but b2 never starts
+    scheduler.mark_done("b1").unwrap();
+    assert!(scheduler.schedule_next().is_empty());
+    assert!(scheduler.all_done());
+}
+
+#[test]
+fn test_scheduler_priority_under_parallel_limit() {
+    let graph: Graph = serde_yaml::from_str(
+        "tasks:\n  a_low:\n    description: l\n    priority: low\n  z_critical:\n    description: c\n    priority: critical\n  m_plain:\n    description: m\n",
+    )
+    .unwrap();
+    let mut scheduler = Scheduler::new(graph);
+    scheduler.set_max_parallel(Some(1));
+
+    // Critical beats alphabetical order
+    assert_eq!(scheduler.schedule_next(), vec!["z_critical"]);
+    scheduler.mark_started("z_critical").unwrap();
+    assert!(scheduler.schedule_next().is_empty());
+
+    // Unprioritized tasks count as medium, ahead of low
+    scheduler.mark_done("z_critical").unwrap();
+    assert_eq!(scheduler.schedule_next(), vec!["m_plain"]);
+
+    // Without a limit everything ready comes back, still in priority order
+    scheduler.set_max_parallel(None);
+    assert_eq!(scheduler.schedule_next(), vec!["m_plain", "a_low"]);
+}
+
+#[test]
+fn test_scheduler_order_is_reproducible() {
+    let mut yaml = String::from("tasks:\n");
+    for i in 0..20 {
+        let priority = ["low", "high", "medium"][i % 3];
+        yaml.push_str(&format!("  t{:02}:\n    description: t\n    priority: {}\n", i, priority));
+        if i >= 10 {
+            yaml.push_str(&format!("    depends_on: [t{:02}]\n", i - 10));
+        }
+    }
+
+    // Each parse gets a freshly seeded task map, so any iteration-order
+    // dependence would show up across runs
+    let run = || {
+        let mut scheduler = Scheduler::new(serde_yaml::from_str(&yaml).unwrap());
+        scheduler.set_max_parallel(Some(4));
+        let mut order = Vec::new();
+        loop {
+            let batch = scheduler.schedule_next();
+            if batch.is_empty() && scheduler.get_running().is_empty() {
+                break;
+            }
+            for id in &batch {
+                scheduler.mark_started(id).unwrap();
+            }
+            let running = scheduler.get_running();
+            scheduler.mark_done(&running[0]).unwrap();
+            order.push((batch, running));
+        }
+        order
+    };
+
+    let first = run();
+    assert_eq!(first[0].0, vec!["t01", "t04", "t07", "t02"]);
+    assert_eq!(first.len(), 20);
+    for _ in 0..10 {
+        assert_eq!(run(), first);
+    }
+}
+
+#[test]
+fn test_scheduler_reconcile_adds_and_removes_tasks() {
+    let mut scheduler = Scheduler::new(two_chain_graph());
+    scheduler.mark_started("a1").unwrap();
+    scheduler.mark_done("a1").unwrap();
+    scheduler.mark_started("b1").unwrap();
+
+    // a2 and b1 (running) are gone from the file, c1 is new
+    let edited: Graph = serde_yaml::from_str(
+        "tasks:\n  a1:\n    description: a1\n  b2:\n    description: b2\n    depends_on: [b1]\n  c1:\n    description: c1\n",
+    )
+    .unwrap();
+    let report = scheduler.reconcile(edited);
+
+    assert_eq!(report.added, vec!["c1"]);
+    assert_eq!(report.removed, vec!["a2"]);
+    assert!(report.changed.is_empty());
+    assert_eq!(report.kept_running, vec!["b1"]);
+
+    let graph = scheduler.graph();
+    assert!(graph.get_task("a2").is_none());
+    assert_eq!(graph.get_task("a1").unwrap().status, GraphTaskStatus::Done);
+    assert_eq!(graph.get_task("b1").unwrap().status, GraphTaskStatus::InProgress);
+    assert_eq!(scheduler.get_running(), vec!["b1"]);
+    assert_eq!(scheduler.schedule_next(), vec!["c1"]);
+
+    // Once b1 has finished, the next reload drops it
+    scheduler.mark_done("b1").unwrap();
+    let edited: Graph = serde_yaml::from_str(
+        "tasks:\n  a1:\n    description: a1\n  b2:\n    description: b2\n    depends_on: [b1]\n  c1:\n    description: c1\n",
+    )
+    .unwrap();
+    let report = scheduler.reconcile(edited);
+    assert_eq!(report.removed, vec!["b1"]);
+    assert!(report.kept_running.is_empty());
+}
+
+#[test]
+fn test_scheduler_reconcile_modified_tasks() {
+    let graph: Graph = serde_yaml::from_str(
+        "tasks:\n  build:\n    description: b\n    command: make\n  serve:\n    description: s\n    command: ./serve\n  lint:\n    description: l\n    command: lint\n",
+    )
+    .unwrap();
+    let mut scheduler = Scheduler::new(graph);
+    scheduler.mark_started("build").unwrap();
+    scheduler.mark_done("build").unwrap();
+    scheduler.mark_started("lint").unwrap();
+    scheduler.mark_done("lint").unwrap();
+    scheduler.mark_started("serve").unwrap();
+
+    // Identical reload changes nothing
+    let unchanged = scheduler.graph().clone();
+    assert!(scheduler.reconcile(unchanged).is_empty());
+
+    let edited: Graph = serde_yaml::from_str(
+        "tasks:\n  build:\n    description: b\n    command: make all\n  serve:\n    description: s\n    command: ./serve --dev\n  lint:\n    description: l\n    command: lint\n",
+    )
+    .unwrap();
+    let report = scheduler.reconcile(edited);
+    assert_eq!(report.changed, vec!["build", "serve"]);
+    assert_eq!(report.to_string(), "0 added, 2 changed, 0 removed");
+
+    let graph = scheduler.graph();
+    // Changed and idle: back to the file's status, so it runs again
+    assert_eq!(graph.get_task("build").unwrap().status, GraphTaskStatus::Pending);
+    assert_eq!(graph.get_task("build").unwrap().command.as_deref(), Some("make all"));
+    // Changed but running: untouched until it finishes
+    assert_eq!(graph.get_task("serve").unwrap().status, GraphTaskStatus::InProgress);
+    assert_eq!(graph.get_task("serve").unwrap().command.as_deref(), Some("./serve --dev"));
+    // Unchanged: keeps its status
+    assert_eq!(graph.get_task("lint").unwrap().status, GraphTaskStatus::Done);
+
+    assert_eq!(scheduler.schedule_next(), vec!["build"]);
+}
+
+#[test]
+fn test_app_rejects_invalid_reload() {
+    let mut app = TestApp::new(two_chain_graph());
+    let cyclic: Graph = serde_yaml::from_str(
+        "tasks:\n  a1:\n    description: a1\n    depends_on: [a2]\n  a2:\n    description: a2\n    depends_on: [a1]\n",
+    )
+    .unwrap();
+    assert!(app.reload_graph(cyclic).is_err());
+    assert_eq!(app.scheduler.graph().tasks.len(), 4);
+
+    let report = app.reload_graph(serde_yaml::from_str("tasks:\n  a1:\n    description: a1\n").unwrap()).unwrap();
+    assert_eq!(report.removed, vec!["a2", "b1", "b2"]);
+    assert!(app.recent_events.iter().any(|(_, _, m)| m == "Reloaded graph: 0 added, 0 changed, 3 removed"));
+}
+
 #[test]
 fn test_scheduler_failed_task_blocks_dependents() {
     let graph = Graph::from_file(Path::new("test-gid-integration.yml")).unwrap();
@@ -151,6 +401,50 @@ fn test_scheduler_failed_task_blocks_dependents() {
     assert!(!scheduler.all_done());
 }
 
+#[test]
+fn test_scheduler_deadlock_detection() {
+    let graph = Graph::from_file(Path::new("test-gid-integration.yml")).unwrap();
+    let mut scheduler = Scheduler::new(graph);
+    assert!(!scheduler.is_deadlocked());
+
+    scheduler.mark_started("hello").unwrap();
+    assert!(!scheduler.is_deadlocked());
+    scheduler.mark_failed("hello").unwrap();
+
+    // Every remaining task depends on "hello"
+    assert!(scheduler.schedule_next().is_empty());
+    assert!(!scheduler.all_done());
+    assert!(scheduler.is_deadlocked());
+    assert_eq!(scheduler.unfinished_count(), 4);
+
+    // A fail-fast halt is a finished run, not a deadlock
+    let graph = Graph::from_file(Path::new("test-gid-integration.yml")).unwrap();
+    let mut scheduler = Scheduler::new(graph);
+    scheduler.set_failure_policy(FailurePolicy::FailFast);
+    scheduler.mark_started("hello").unwrap();
+    scheduler.mark_failed("hello").unwrap();
+    assert!(scheduler.all_done());
+    assert!(!scheduler.is_deadlocked());
+}
+
+#[test]
+fn test_app_reports_deadlock() {
+    let graph = Graph::from_file(Path::new("test-gid-integration.yml")).unwrap();
+    let mut app = TestApp::new(graph);
+    app.quit_on_deadlock = true;
+    assert_eq!(app.deadlock_message(), None);
+
+    app.scheduler.mark_started("hello").unwrap();
+    app.scheduler.mark_failed("hello").unwrap();
+    assert_eq!(
+        app.deadlock_message().as_deref(),
+        Some("deadlocked: 4 tasks blocked by failures")
+    );
+
+    app.process_events();
+    assert!(app.should_quit);
+}
+
 // === Session Tests ===
 
 #[test]
@@ -232,6 +526,16 @@ fn test_semantic_commands_from_graph() {
             estimated_hours: None,
             tags: None,
             semantic_commands: Some(sem_cmds),
+            success_codes: vec![0],
+            allow_failure: false,
+            stall_secs: None,
+            ready_when: None,
+            complete_on: None,
+            on_success: None,
+            on_failure: None,
+            color: None,
+            icon: None,
+            shell: None,
         },
     );
 
@@ -251,3 +555,2618 @@ fn test_semantic_commands_from_graph() {
         "optimizer.lr = 0.001"
     );
 }
+
+// === Port Environment Tests ===
+
+#[tokio::test]
+async fn test_executor_passes_port_env() {
+    use gidterm::{Executor, PortManager, TaskEvent};
+    use std::time::Duration;
+
+    let mut port_manager = PortManager::default();
+    let port = port_manager.allocate("gidterm-port-env-test", None).unwrap();
+    let env = port_manager.get_env("gidterm-port-env-test");
+
+    let (executor, mut event_rx) = Executor::new();
+    let pid = executor
+        .start_task("echo_port", "echo \"port=$PORT\"", &env, &[0])
+        .await
+        .unwrap();
+    assert!(pid.is_some());
+
+    let expected = format!("port={}", port);
+    let mut found = false;
+    let _ = tokio::time::timeout(Duration::from_secs(5), async {
+        while let Some(event) = event_rx.recv().await {
+            match event {
+                TaskEvent::Output { line, .. } if line.contains(&expected) => found = true,
+                TaskEvent::Completed { .. } | TaskEvent::Failed { .. } => break,
+                _ => {}
+            }
+        }
+    })
+    .await;
+
+    assert!(found, "expected '{}' in task output", expected);
+}
+
+#[tokio::test]
+async fn test_executor_interpolates_command_vars() {
+    use gidterm::{CommandVars, Executor, PortManager, TaskEvent};
+    use std::time::Duration;
+
+    let mut port_manager = PortManager::default();
+    let port = port_manager.allocate("gidterm-vars-test", None).unwrap();
+    let vars = CommandVars::new("gidterm-vars-test", port_manager.get_port("gidterm-vars-test")).unwrap();
+
+    let (executor, mut event_rx) = Executor::new();
+    executor
+        .start_task_with_vars("echo_port", None, "echo ${project} on ${port}", &vars, &HashMap::new(), &[0])
+        .await
+        .unwrap();
+
+    let expected = format!("gidterm-vars-test on {}", port);
+    let mut found = false;
+    let _ = tokio::time::timeout(Duration::from_secs(5), async {
+        while let Some(event) = event_rx.recv().await {
+            match event {
+                TaskEvent::Output { line, .. } if line.contains(&expected) => found = true,
+                TaskEvent::Completed { .. } | TaskEvent::Failed { .. } => break,
+                _ => {}
+            }
+        }
+    })
+    .await;
+
+    // Unknown variables fail before anything is spawned
+    let err = executor
+        .start_task_with_vars("typo", None, "echo ${prot}", &vars, &HashMap::new(), &[0])
+        .await
+        .unwrap_err();
+    assert!(format!("{:#}", err).contains("Unknown variable '${prot}'"));
+    assert!(!executor.is_running("typo"));
+
+    assert!(found, "expected '{}' in task output", expected);
+}
+
+#[tokio::test]
+async fn test_executor_success_codes() {
+    use gidterm::{Executor, TaskEvent};
+    use std::time::Duration;
+
+    let (executor, mut event_rx) = Executor::new();
+    executor
+        .start_task("lint", "exit 1", &HashMap::new(), &[0, 1])
+        .await
+        .unwrap();
+    executor
+        .start_task("strict", "exit 1", &HashMap::new(), &[0])
+        .await
+        .unwrap();
+
+    let mut outcomes = HashMap::new();
+    let _ = tokio::time::timeout(Duration::from_secs(5), async {
+        while let Some(event) = event_rx.recv().await {
+            match event {
+                TaskEvent::Completed { task_id, exit_code } => {
+                    outcomes.insert(task_id, format!("completed {}", exit_code));
+                }
+                TaskEvent::Failed { task_id, .. } => {
+                    outcomes.insert(task_id, "failed".to_string());
+                }
+                _ => {}
+            }
+            if outcomes.len() == 2 {
+                break;
+            }
+        }
+    })
+    .await;
+
+    assert_eq!(outcomes["lint"], "completed 1");
+    assert_eq!(outcomes["strict"], "failed");
+}
+
+#[cfg(target_os = "linux")]
+#[tokio::test]
+async fn test_executor_samples_task_resources() {
+    use gidterm::{Executor, TaskEvent};
+    use std::time::Duration;
+
+    let (executor, mut event_rx) = Executor::new();
+    executor
+        .start_task("sleepy", "sleep 1", &HashMap::new(), &[0])
+        .await
+        .unwrap();
+
+    let usage = tokio::time::timeout(Duration::from_secs(5), async {
+        while let Some(event) = event_rx.recv().await {
+            if let TaskEvent::Resources { task_id, usage } = event {
+                assert_eq!(task_id, "sleepy");
+                return Some(usage);
+            }
+        }
+        None
+    })
+    .await
+    .unwrap()
+    .expect("no resource sample");
+
+    assert!(usage.peak_mem_mb > 0.0);
+    assert!(usage.cpu_secs >= 0.0);
+}
+
+// === PTY Size Tests ===
+
+#[tokio::test]
+async fn test_executor_resizes_running_ptys() {
+    use gidterm::{Executor, TaskEvent};
+    use std::time::Duration;
+
+    let (executor, mut event_rx) = Executor::new();
+    executor.resize_all(30, 100);
+    executor
+        .start_task("size", "stty size; sleep 0.5; stty size", &HashMap::new(), &[0])
+        .await
+        .unwrap();
+
+    // Resize once the first size has been reported
+    let mut sizes = Vec::new();
+    let _ = tokio::time::timeout(Duration::from_secs(5), async {
+        while let Some(event) = event_rx.recv().await {
+            match event {
+                TaskEvent::Output { line, .. } => {
+                    sizes.push(line);
+                    executor.resize_all(40, 132);
+                }
+                TaskEvent::Completed { .. } | TaskEvent::Failed { .. } => break,
+                _ => {}
+            }
+        }
+    })
+    .await;
+
+    assert_eq!(sizes, vec!["30 100", "40 132"]);
+    assert_eq!(executor.pty_size(), (40, 132));
+}
+
+#[test]
+fn test_many_quiet_tasks_share_few_threads() {
+    use gidterm::{Executor, TaskEvent};
+    use std::time::Duration;
+
+    // A reader parked per task would leave no blocking threads for the rest
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(2)
+        .max_blocking_threads(2)
+        .enable_all()
+        .build()
+        .unwrap();
+
+    runtime.block_on(async {
+        let (executor, mut event_rx) = Executor::new();
+        for i in 0..10 {
+            executor
+                .start_task(&format!("quiet-{}", i), "sleep 30", &HashMap::new(), &[0])
+                .await
+                .unwrap();
+        }
+        for i in 0..40 {
+            executor
+                .start_task(&format!("short-{}", i), "echo done", &HashMap::new(), &[0])
+                .await
+                .unwrap();
+        }
+
+        let mut completed = 0;
+        let mut outputs = 0;
+        let _ = tokio::time::timeout(Duration::from_secs(5), async {
+            while let Some(event) = event_rx.recv().await {
+                match event {
+                    TaskEvent::Output { line, .. } if line == "done" => outputs += 1,
+                    TaskEvent::Completed { .. } => {
+                        completed += 1;
+                        if completed == 40 {
+                            break;
+                        }
+                    }
+                    TaskEvent::Failed { task_id, error } => panic!("{} failed: {}", task_id, error),
+                    _ => {}
+                }
+            }
+        })
+        .await;
+
+        // Every short task finished while the quiet ones were still running
+        assert_eq!((completed, outputs), (40, 40));
+        assert!((0..10).all(|i| executor.is_running(&format!("quiet-{}", i))));
+        executor.stop_all();
+    });
+}
+
+#[tokio::test]
+async fn test_flooding_output_keeps_lifecycle_events() {
+    use gidterm::{Executor, TaskEvent, EVENT_CHANNEL_CAPACITY};
+    use std::time::{Duration, Instant};
+
+    // Nothing drains the channel until the task is done
+    let (executor, mut event_rx) = Executor::new();
+    executor
+        .start_task("flood", "seq 1 100000", &HashMap::new(), &[0])
+        .await
+        .unwrap();
+    let deadline = Instant::now() + Duration::from_secs(30);
+    while executor.is_running("flood") {
+        assert!(Instant::now() < deadline, "flood never finished");
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    let mut events = Vec::new();
+    while let Ok(event) = event_rx.try_recv() {
+        events.push(event);
+    }
+    assert!(events.len() <= EVENT_CHANNEL_CAPACITY);
+
+    // A last resource sample may trail the exit; the rest is in order
+    events.retain(|e| !matches!(e, TaskEvent::Resources { .. }));
+    assert!(matches!(events.first(), Some(TaskEvent::Started { .. })));
+    assert!(matches!(events.last(), Some(TaskEvent::Completed { exit_code: 0, .. })));
+
+    // The lines that didn't fit are accounted for right before completion
+    let lines: Vec<&str> = events
+        .iter()
+        .filter_map(|e| match e {
+            TaskEvent::Output { line, .. } => Some(line.as_str()),
+            _ => None,
+        })
+        .collect();
+    let marker = lines.last().unwrap();
+    let dropped: usize = marker
+        .strip_prefix("[gidterm] ")
+        .and_then(|m| m.strip_suffix(" output lines dropped"))
+        .and_then(|n| n.parse().ok())
+        .unwrap_or_else(|| panic!("no drop marker, last line {:?}", marker));
+    assert_eq!(lines.len() - 1 + dropped, 100_000);
+    assert_eq!(lines[0], "1");
+}
+
+// === Resume Tests ===
+
+#[test]
+fn test_resume_from_session() {
+    use gidterm::TaskStatus;
+
+    let graph = Graph::from_file(Path::new("test-gid-integration.yml")).unwrap();
+    let mut app = TestApp::new(graph);
+
+    let mut previous = Session::new("test-integration".to_string());
+    previous.start_task("hello".to_string());
+    previous.end_task("hello", TaskStatus::Done, Some(0));
+    previous.start_task("world".to_string());
+    previous.end_task("world", TaskStatus::Failed, Some(1));
+
+    assert_eq!(app.resume_from_session(&previous), 1);
+
+    let graph = app.scheduler.graph();
+    assert_eq!(graph.get_task("hello").unwrap().status, GraphTaskStatus::Done);
+    assert_eq!(graph.get_task("world").unwrap().status, GraphTaskStatus::Pending);
+    assert_eq!(app.scheduler.schedule_next(), vec!["world"]);
+}
+
+#[tokio::test]
+async fn test_app_handle_command() {
+    use gidterm::ai::control::{ControlCommand, ControlResponse};
+
+    let graph = Graph::from_file(Path::new("test-gid-integration.yml")).unwrap();
+    let mut app = TestApp::new(graph);
+
+    match app.handle_command(ControlCommand::GetState).await {
+        ControlResponse::Ok { data: Some(data) } => {
+            assert_eq!(data["total_count"], app.scheduler.graph().all_tasks().len());
+        }
+        other => panic!("unexpected response: {:?}", other),
+    }
+
+    let response = app
+        .handle_command(ControlCommand::StartTask { task_id: "missing".to_string() })
+        .await;
+    assert!(matches!(response, ControlResponse::Error { .. }));
+
+    app.handle_command(ControlCommand::Quit).await;
+    assert!(app.should_quit);
+}
+
+#[cfg(feature = "server")]
+#[tokio::test]
+async fn test_websocket_streams_snapshot_and_events() {
+    use futures_util::StreamExt;
+    use gidterm::ai::server::ControlServer;
+    use gidterm::ai::{ControlHandle, GidEvent};
+    use std::time::Duration;
+    use tokio_tungstenite::tungstenite::Message;
+
+    let graph = Graph::from_file(Path::new("test-gid-integration.yml")).unwrap();
+    let mut app = TestApp::new(graph);
+
+    let (handle, mut commands) = ControlHandle::new(app.events.clone());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}/ws", listener.local_addr().unwrap());
+    tokio::spawn(ControlServer::new(handle).serve_on(listener));
+
+    let client = tokio::spawn(async move {
+        let (mut ws, _) = tokio_tungstenite::connect_async(url).await.unwrap();
+        let mut frames = Vec::new();
+        while let Some(Ok(Message::Text(text))) = ws.next().await {
+            let done = text.contains("task_started");
+            frames.push(text);
+            if done {
+                break;
+            }
+        }
+        frames
+    });
+
+    // Drive the app loop: answer the snapshot request, then report a start
+    tokio::time::timeout(Duration::from_secs(10), async {
+        while !client.is_finished() {
+            while let Ok(request) = commands.try_recv() {
+                let response = app.handle_command(request.command).await;
+                let _ = request.reply.send(response);
+                app.events.emit(GidEvent::TaskStarted { task_id: "hello".to_string() });
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .expect("client never saw task_started");
+
+    let frames = client.await.unwrap();
+    assert!(frames[0].contains("total_count"));
+    assert!(frames.last().unwrap().contains(r#""type":"task_started""#));
+}
+
+#[test]
+fn test_run_summary() {
+    use gidterm::TaskStatus;
+
+    let graph = Graph::from_file(Path::new("test-gid-integration.yml")).unwrap();
+    let mut app = TestApp::new(graph);
+
+    app.scheduler.mark_done("hello").unwrap();
+    app.scheduler.mark_failed("world").unwrap();
+    app.session.start_task("hello".to_string());
+    app.session.end_task("hello", TaskStatus::Done, Some(0));
+
+    let summary = app.summary();
+    assert_eq!(summary.total, 5);
+    assert_eq!(summary.done, 1);
+    assert_eq!(summary.failed, 1);
+    assert_eq!(summary.slowest_task.as_ref().unwrap().task_id, "hello");
+    assert_eq!(summary.pending.len(), 3);
+    assert!(!summary.pending.contains(&"hello".to_string()));
+
+    let text = summary.to_string();
+    assert!(text.contains("5 tasks, 1 done, 1 failed"));
+    assert!(text.contains("Pending:"));
+}
+
+#[test]
+fn test_headless_run_exit_code() {
+    use assert_cmd::Command;
+    use predicates::str::contains;
+
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("graph.yml"),
+        r#"
+metadata:
+  project: headless-test
+tasks:
+  ok:
+    description: Succeeds
+    command: "echo fine"
+  broken:
+    description: Fails
+    command: "echo oops; exit 3"
+    depends_on: [ok]
+"#,
+    )
+    .unwrap();
+
+    Command::cargo_bin("gidterm")
+        .unwrap()
+        .current_dir(dir.path())
+        .env("HOME", dir.path())
+        .args(["run", "--headless", "--graph", "graph.yml"])
+        .assert()
+        .failure()
+        .stdout(contains("[ok] fine"))
+        .stdout(contains("[broken] => failed"))
+        .stdout(contains("2 tasks, 1 done, 1 failed"));
+}
+
+#[test]
+fn test_headless_jobs_limit_applies_to_targets() {
+    use assert_cmd::Command;
+
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("graph.yml"),
+        r#"
+tasks:
+  a:
+    description: A
+    command: "echo a+ >> order.log; sleep 0.3; echo a- >> order.log"
+  b:
+    description: B
+    command: "echo b+ >> order.log; sleep 0.3; echo b- >> order.log"
+  c:
+    description: Not a target
+    command: "echo c+ >> order.log"
+"#,
+    )
+    .unwrap();
+
+    Command::cargo_bin("gidterm")
+        .unwrap()
+        .current_dir(dir.path())
+        .env("HOME", dir.path())
+        .args(["run", "--headless", "--jobs", "1", "--graph", "graph.yml", "a", "b"])
+        .assert()
+        .success();
+
+    // One task at a time: each finishes before the next starts
+    let order = std::fs::read_to_string(dir.path().join("order.log")).unwrap();
+    let lines: Vec<&str> = order.lines().collect();
+    assert_eq!(lines.len(), 4, "{}", order);
+    for pair in lines.chunks(2) {
+        assert_eq!(pair[0].replace('+', "-"), pair[1], "{}", order);
+    }
+}
+
+/// Run a graph headless and return its stdout
+fn run_headless_graph(yaml: &str) -> String {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("graph.yml"), yaml).unwrap();
+
+    let output = assert_cmd::Command::cargo_bin("gidterm")
+        .unwrap()
+        .current_dir(dir.path())
+        .env("HOME", dir.path())
+        .args(["run", "--headless", "--graph", "graph.yml"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn test_headless_ready_when_log_match() {
+    let stdout = run_headless_graph(
+        r#"
+metadata:
+  project: ready-log-test
+tasks:
+  api:
+    description: Server
+    command: "echo 'Listening on :8080'; sleep 2; echo api-stopped"
+    ready_when: { log_match: "Listening on :\\d+" }
+  e2e:
+    description: Needs the server up
+    command: "echo e2e-ran"
+    depends_on: [api]
+"#,
+    );
+
+    // e2e starts while api is still running
+    let e2e = stdout.find("[e2e] e2e-ran").expect("e2e ran");
+    let stopped = stdout.find("[api] api-stopped").expect("api finished");
+    assert!(e2e < stopped, "e2e waited for api to exit:\n{}", stdout);
+}
+
+#[test]
+fn test_headless_ready_when_port() {
+    use std::net::TcpListener;
+    use std::time::Duration;
+
+    let port = TcpListener::bind(("0.0.0.0", 0)).unwrap().local_addr().unwrap().port();
+    // Stands in for the server: starts listening a little after the task starts
+    let listener = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(500));
+        let _listener = TcpListener::bind(("0.0.0.0", port)).unwrap();
+        std::thread::sleep(Duration::from_secs(3));
+    });
+
+    let stdout = run_headless_graph(&format!(
+        r#"
+metadata:
+  project: ready-port-test
+tasks:
+  api:
+    description: Server
+    command: "sleep 2; echo api-stopped"
+    ready_when: {{ port: {} }}
+  e2e:
+    description: Needs the server up
+    command: "echo e2e-ran"
+    depends_on: [api]
+"#,
+        port
+    ));
+    listener.join().unwrap();
+
+    let e2e = stdout.find("[e2e] e2e-ran").expect("e2e ran");
+    let stopped = stdout.find("[api] api-stopped").expect("api finished");
+    assert!(e2e < stopped, "e2e waited for api to exit:\n{}", stdout);
+}
+
+#[test]
+fn test_headless_complete_on_keeps_task_running() {
+    let stdout = run_headless_graph(
+        r#"
+metadata:
+  project: complete-on-test
+tasks:
+  watch:
+    description: Never exits
+    command: "echo 'Watching for changes'; sleep 1; echo still-watching; sleep 30"
+    complete_on: { log_match: "^Watching" }
+  deploy:
+    description: Needs the first build
+    command: "sleep 2; echo deployed"
+    depends_on: [watch]
+"#,
+    );
+
+    // deploy starts on the match, and the watcher carries on meanwhile
+    let watching = stdout.find("[watch] still-watching").expect("watcher kept running");
+    let deployed = stdout.find("[deploy] deployed").expect("deploy ran");
+    assert!(watching < deployed, "{}", stdout);
+    assert!(stdout.contains("2 tasks, 2 done"), "{}", stdout);
+}
+
+#[test]
+fn test_headless_complete_on_kill() {
+    let stdout = run_headless_graph(
+        r#"
+metadata:
+  project: complete-on-kill-test
+tasks:
+  watch:
+    description: Never exits
+    command: "echo 'Watching for changes'; sleep 1; echo still-watching; sleep 30"
+    complete_on: { log_match: "^Watching", kill: true }
+  deploy:
+    description: Needs the first build
+    command: "sleep 2; echo deployed"
+    depends_on: [watch]
+"#,
+    );
+
+    // Stopped on the match, yet still counted as done rather than killed
+    assert!(!stdout.contains("still-watching"), "{}", stdout);
+    assert!(stdout.contains("[deploy] deployed"), "{}", stdout);
+    assert!(stdout.contains("2 tasks, 2 done"), "{}", stdout);
+}
+
+#[test]
+fn test_headless_lifecycle_hooks() {
+    use std::time::{Duration, Instant};
+
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("graph.yml"),
+        r#"
+metadata:
+  project: hooks-test
+tasks:
+  ok:
+    description: Succeeds
+    command: "true"
+    on_success: "echo \"$GIDTERM_HOOK $GIDTERM_TASK\" > ok-hook.txt"
+    on_failure: "touch ok-wrong.txt"
+  broken:
+    description: Fails
+    command: "exit 2"
+    on_success: "touch broken-wrong.txt"
+    on_failure: "echo \"$GIDTERM_HOOK ${project}\" > broken-hook.txt"
+"#,
+    )
+    .unwrap();
+
+    let output = assert_cmd::Command::cargo_bin("gidterm")
+        .unwrap()
+        .current_dir(dir.path())
+        .env("HOME", dir.path())
+        .args(["run", "--headless", "--graph", "graph.yml"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    // Hooks aren't tasks
+    assert!(String::from_utf8_lossy(&output.stdout).contains("2 tasks"));
+
+    // Hooks run detached, so they may finish after gidterm exits
+    let read = |name: &str| {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            match std::fs::read_to_string(dir.path().join(name)) {
+                Ok(s) if s.ends_with('\n') => return s,
+                _ if Instant::now() > deadline => panic!("{} was not written", name),
+                _ => std::thread::sleep(Duration::from_millis(20)),
+            }
+        }
+    };
+    assert_eq!(read("ok-hook.txt"), "on_success ok\n");
+    assert_eq!(read("broken-hook.txt"), "on_failure hooks-test\n");
+    assert!(!dir.path().join("ok-wrong.txt").exists());
+    assert!(!dir.path().join("broken-wrong.txt").exists());
+}
+
+#[test]
+fn test_tag_filter() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use gidterm::App;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("graph.yml");
+    std::fs::write(
+        &path,
+        r#"
+metadata:
+  project: tag-filter-test
+tasks:
+  api:
+    description: API server
+    tags: [backend, server]
+  db:
+    description: Database
+    tags: [Backend]
+  web:
+    description: Web app
+    tags: [frontend]
+  docs:
+    description: Untagged
+"#,
+    )
+    .unwrap();
+
+    let mut app = TestApp::new(Graph::from_file(&path).unwrap());
+    let press = |app: &mut App, code| app.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+
+    press(&mut app, KeyCode::Char('f'));
+    for c in "backend".chars() {
+        press(&mut app, KeyCode::Char(c));
+    }
+    press(&mut app, KeyCode::Enter);
+
+    let mut visible = app.get_task_ids();
+    visible.sort();
+    assert_eq!(visible, vec!["api", "db"]);
+    assert_eq!(app.all_task_ids().len(), 4);
+
+    press(&mut app, KeyCode::Esc);
+    assert!(app.tag_filter.is_none());
+    assert_eq!(app.get_task_ids().len(), 4);
+}
+
+#[test]
+fn test_output_search() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use gidterm::app::ViewMode;
+    use gidterm::App;
+
+    let graph: Graph = serde_yaml::from_str(
+        r#"
+metadata:
+  project: search-test
+tasks:
+  build:
+    description: Build
+"#,
+    )
+    .unwrap();
+
+    let mut app = TestApp::new(graph);
+    app.view_mode = ViewMode::Terminal;
+    app.task_outputs.insert(
+        "build".to_string(),
+        ["ERROR: first", "ok", "warning", "an error again", "ok", "done"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+    );
+    let press = |app: &mut App, code| app.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+
+    press(&mut app, KeyCode::Char('/'));
+    for c in "error".chars() {
+        press(&mut app, KeyCode::Char(c));
+    }
+    // Typing doesn't leave the view or trigger other keys
+    assert_eq!(app.view_mode, ViewMode::Terminal);
+    press(&mut app, KeyCode::Enter);
+
+    // Case-insensitive; the newest match is current, scrolled to the bottom
+    assert_eq!(app.output_matches("build"), vec![0, 3]);
+    assert_eq!(app.output_search.as_ref().unwrap().current, 1);
+    assert_eq!(app.scroll_offset, 2);
+
+    // Jumping to a match pins the view
+    assert!(!app.follow);
+
+    // `n` wraps around to the oldest match, `N` goes back
+    press(&mut app, KeyCode::Char('n'));
+    assert_eq!(app.output_search.as_ref().unwrap().current, 0);
+    assert_eq!(app.scroll_offset, 5);
+    press(&mut app, KeyCode::Char('N'));
+    assert_eq!(app.scroll_offset, 2);
+
+    // Esc clears the search before leaving the view
+    press(&mut app, KeyCode::Esc);
+    assert!(app.output_search.is_none());
+    assert_eq!(app.view_mode, ViewMode::Terminal);
+    assert!(app.output_matches("build").is_empty());
+}
+
+#[test]
+fn test_output_spillover() {
+    let dir = tempfile::tempdir().unwrap();
+    let graph = Graph::from_file(Path::new("test-gid-integration.yml")).unwrap();
+    let mut app = TestApp::new(graph);
+    app.set_buffer_lines(3);
+    app.log_dir = Some(dir.path().to_path_buf());
+    assert_eq!(app.get_full_log_path("hello"), None);
+
+    for i in 1..=5 {
+        assert!(app.store_output("hello", &format!("line {}", i), false));
+    }
+    assert!(!app.store_output("hello", "line 5 (redrawn)", true));
+
+    // Memory keeps the tail; the evicted lines are on disk
+    assert_eq!(
+        app.get_task_output("hello", 10),
+        vec!["line 3", "line 4", "line 5 (redrawn)"]
+    );
+    let path = app.get_full_log_path("hello").unwrap();
+    let log = std::fs::read_to_string(&path).unwrap();
+    assert!(log.starts_with("line 1\nline 2\n"));
+
+    // The final line is written once the app lets go of the log
+    drop(app);
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "line 1\nline 2\nline 3\nline 4\nline 5 (redrawn)\n"
+    );
+}
+
+#[test]
+fn test_follow_toggle() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    let graph = Graph::from_file(Path::new("test-gid-integration.yml")).unwrap();
+    let mut app = TestApp::new(graph);
+    assert!(app.follow);
+
+    // Following: new output keeps the view at the tail
+    app.scroll_offset = 3;
+    app.output_appended();
+    assert_eq!(app.scroll_offset, 0);
+
+    // Pinned: the offset grows so the same lines stay in view
+    app.handle_key(KeyEvent::new(KeyCode::Char('F'), KeyModifiers::SHIFT));
+    assert!(!app.follow);
+    app.scroll_offset = 3;
+    app.output_appended();
+    app.output_appended();
+    assert_eq!(app.scroll_offset, 5);
+
+    // Following again snaps back to the tail
+    app.handle_key(KeyEvent::new(KeyCode::Char('F'), KeyModifiers::SHIFT));
+    assert!(app.follow);
+    assert_eq!(app.scroll_offset, 0);
+}
+
+#[test]
+fn test_toast_expires() {
+    use gidterm::app::{Toast, TOAST_DURATION};
+    use std::time::Instant;
+
+    let graph = Graph::from_file(Path::new("test-gid-integration.yml")).unwrap();
+    let mut app = TestApp::new(graph);
+    let now = Instant::now();
+    assert_eq!(app.active_toast(now), None);
+
+    app.toast = Some(Toast {
+        message: "Task Complete: build".to_string(),
+        expires: now + TOAST_DURATION,
+    });
+    assert_eq!(app.active_toast(now), Some("Task Complete: build"));
+    assert_eq!(app.active_toast(now + TOAST_DURATION), None);
+}
+
+#[test]
+fn test_apply_ui_state() {
+    use gidterm::app::ViewMode;
+    use gidterm::UiState;
+
+    let graph = Graph::from_file(Path::new("test-gid-integration.yml")).unwrap();
+    let mut app = TestApp::new(graph);
+    app.selected_task = 2;
+    app.view_mode = ViewMode::Graph;
+
+    let saved = app.ui_state();
+    assert_eq!(saved.source, "test-integration");
+
+    // A state saved for another graph is ignored
+    app.apply_ui_state(&UiState {
+        source: "other".to_string(),
+        ..saved.clone()
+    });
+    assert_eq!(app.selected_task, 2);
+
+    // Stale indices are clamped to the tasks that exist now
+    app.apply_ui_state(&UiState {
+        selected_task: 99,
+        view_mode: ViewMode::Terminal,
+        ..saved.clone()
+    });
+    assert_eq!(app.selected_task, 4);
+    assert_eq!(app.view_mode, ViewMode::Terminal);
+
+    // The project overview only exists in workspace mode
+    app.apply_ui_state(&UiState {
+        view_mode: ViewMode::ProjectOverview,
+        ..saved
+    });
+    assert_eq!(app.view_mode, ViewMode::Terminal);
+}
+
+#[tokio::test]
+async fn test_semantic_command_fkeys() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use gidterm::app::ViewMode;
+    use gidterm::App;
+    use std::time::Duration;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("graph.yml");
+    std::fs::write(
+        &path,
+        r#"
+metadata:
+  project: fkey-test
+tasks:
+  train:
+    description: Train
+    command: cat
+    semantic_commands:
+      adjust_lr: "lr={value:float}"
+      save: "checkpoint"
+"#,
+    )
+    .unwrap();
+
+    let mut app = TestApp::new(Graph::from_file(&path).unwrap());
+    app.view_mode = ViewMode::Terminal;
+    // Start through the executor so no session file is written
+    app.executor
+        .start_task("train", "cat", &HashMap::new(), &[0])
+        .await
+        .unwrap();
+    let press = |app: &mut App, code| app.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+
+    // F2 is `save` (labels are sorted) and needs no parameters
+    press(&mut app, KeyCode::F(2));
+    assert!(app.command_prompt.is_none());
+
+    // F1 is `adjust_lr`, which prompts for {value}
+    press(&mut app, KeyCode::F(1));
+    let prompt = app.command_prompt.as_ref().expect("prompt for parameters");
+    assert_eq!(prompt.current_param(), Some("value"));
+
+    // Not a float: the prompt stays open with an error
+    for c in "abc".chars() {
+        press(&mut app, KeyCode::Char(c));
+    }
+    press(&mut app, KeyCode::Enter);
+    let prompt = app.command_prompt.as_ref().expect("invalid value keeps the prompt open");
+    assert_eq!(prompt.error.as_deref(), Some("'abc' is not a valid float"));
+    for _ in 0..3 {
+        press(&mut app, KeyCode::Backspace);
+    }
+    for c in "0.5".chars() {
+        press(&mut app, KeyCode::Char(c));
+    }
+    press(&mut app, KeyCode::Enter);
+    assert!(app.command_prompt.is_none());
+
+    let sent = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            let output = app.executor.get_output("train").join("\n");
+            if output.contains("checkpoint") && output.contains("lr=0.5") {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await;
+    assert!(sent.is_ok(), "commands not sent to the task");
+
+    app.executor.stop_all();
+}
+
+#[tokio::test]
+async fn test_advisory_auto_actions() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use gidterm::ai::ControlMode;
+    use gidterm::semantic::advisor::{Advisory, Severity};
+    use std::time::Duration;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("graph.yml");
+    std::fs::write(
+        &path,
+        r#"
+metadata:
+  project: auto-action-test
+tasks:
+  train:
+    description: Train
+    command: cat
+    semantic_commands:
+      early_stop: "stopping"
+"#,
+    )
+    .unwrap();
+
+    let advisories = vec![Advisory {
+        severity: Severity::Critical,
+        message: "Loss is NaN".to_string(),
+        suggestion: "Stop".to_string(),
+        auto_action: Some("early_stop".to_string()),
+    }];
+
+    let mut app = TestApp::new(Graph::from_file(&path).unwrap());
+    app.executor
+        .start_task("train", "cat", &HashMap::new(), &[0])
+        .await
+        .unwrap();
+
+    // Off unless configured
+    app.auto_actions = false;
+    app.offer_auto_actions("train", &advisories);
+    assert!(app.pending_action.is_none());
+
+    // Manual mode asks first, and only once
+    app.auto_actions = true;
+    app.offer_auto_actions("train", &advisories);
+    assert_eq!(app.pending_action.as_ref().unwrap().label, "early_stop");
+
+    // Keys typed into a prompt don't answer it
+    app.search_mode = true;
+    app.handle_key(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE));
+    assert_eq!(app.search_query, "y");
+    assert!(app.pending_action.is_some());
+    app.handle_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+    assert!(app.pending_action.is_some());
+
+    app.handle_key(KeyEvent::new(KeyCode::Char('n'), KeyModifiers::NONE));
+    assert!(app.pending_action.is_none());
+    app.offer_auto_actions("train", &advisories);
+    assert!(app.pending_action.is_none());
+
+    // Agent mode runs it straight away
+    let mut agent = TestApp::new(Graph::from_file(&path).unwrap());
+    agent.auto_actions = true;
+    agent.control_mode = ControlMode::Agent;
+    agent
+        .executor
+        .start_task("train", "cat", &HashMap::new(), &[0])
+        .await
+        .unwrap();
+    agent.offer_auto_actions("train", &advisories);
+    assert!(agent.pending_action.is_none());
+
+    let sent = tokio::time::timeout(Duration::from_secs(5), async {
+        while !agent.executor.get_output("train").iter().any(|l| l.contains("stopping")) {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await;
+    assert!(sent.is_ok(), "auto-action not sent");
+
+    for app in [&app, &agent] {
+        app.executor.stop_all();
+    }
+    agent.port_manager.release_all().unwrap();
+}
+
+#[test]
+fn test_project_eta() {
+    use std::time::Duration;
+
+    let graph = Graph::from_file(Path::new("test-gid-integration.yml")).unwrap();
+    let mut app = TestApp::new(graph);
+    let project = app.session.project.clone();
+
+    assert!(app.project_eta(&project).is_none());
+
+    app.scheduler.mark_started("hello").unwrap();
+    let history = app.metric_history.entry("hello".to_string()).or_default();
+    history.record(0.1, HashMap::new());
+    std::thread::sleep(Duration::from_millis(20));
+    history.record(0.5, HashMap::new());
+
+    let eta = app.project_eta(&project).expect("ETA from running task");
+    assert!(eta < Duration::from_secs(5));
+    assert!(app.project_eta("other-project").is_none());
+
+    // Finished tasks no longer contribute
+    app.scheduler.mark_done("hello").unwrap();
+    assert!(app.project_eta(&project).is_none());
+}
+
+#[tokio::test]
+async fn test_stall_detection() {
+    use std::time::{Duration, Instant};
+
+    let graph: Graph = serde_yaml::from_str(
+        r#"
+metadata:
+  project: stall-test
+tasks:
+  quiet:
+    description: Quiet
+    command: cat
+    stall_secs: 5
+  patient:
+    description: Patient
+    command: cat
+"#,
+    )
+    .unwrap();
+    assert_eq!(graph.get_task("quiet").unwrap().stall_secs, Some(5));
+
+    let mut app = TestApp::new(graph);
+    app.stall_secs = 120;
+    for id in ["quiet", "patient"] {
+        app.executor
+            .start_task(id, "cat", &HashMap::new(), &[0])
+            .await
+            .unwrap();
+    }
+
+    // Wait for both Started events
+    for _ in 0..50 {
+        app.process_events();
+        if app.last_output_time.len() == 2 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert_eq!(app.last_output_time.len(), 2);
+
+    // Only the task with a 5s override is past its threshold
+    let later = Instant::now() + Duration::from_secs(10);
+    app.check_stalls(later);
+    let quiet = app.get_advisories("quiet").unwrap();
+    assert_eq!(quiet.records.len(), 1);
+    assert!(quiet.records[0].advisory.message.contains("appears stalled"));
+    assert!(app.get_advisories("patient").is_none());
+
+    // Reported once per stall
+    app.check_stalls(later + Duration::from_secs(60));
+    assert_eq!(app.get_advisories("quiet").unwrap().records[0].count, 1);
+
+    // The default threshold applies to everything else
+    app.check_stalls(Instant::now() + Duration::from_secs(121));
+    assert!(app.get_advisories("patient").is_some());
+
+    app.executor.stop_all();
+}
+
+#[tokio::test]
+async fn test_rapid_output_debounces_session_saves() {
+    use gidterm::SessionSaver;
+    use std::time::Duration;
+
+    let graph: Graph = serde_yaml::from_str(
+        "metadata:\n  project: debounce-test\ntasks:\n  spam:\n    description: Chatty\n    command: seq 1 2000\n",
+    )
+    .unwrap();
+    let dir = tempfile::tempdir().unwrap();
+
+    let mut app = app_in(dir.path(), graph);
+    app.session_saver = SessionSaver::in_dir(dir.path(), Duration::from_millis(500));
+    app.session.start_task("spam".to_string());
+    app.executor
+        .start_task("spam", "seq 1 2000", &HashMap::new(), &[0])
+        .await
+        .unwrap();
+
+    // Drain output until the task exits, without letting 500ms pass
+    let mut lines = 0;
+    for _ in 0..200 {
+        app.process_events();
+        lines = app.get_task_output("spam", usize::MAX).len();
+        if !app.executor.is_running("spam") && lines >= 2000 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(1)).await;
+    }
+    app.process_events();
+    assert_eq!(lines, 2000);
+    assert!(
+        app.session_saver.writes() <= 2,
+        "{} writes for {} lines",
+        app.session_saver.writes(),
+        lines
+    );
+
+    // The final flush writes whatever is left
+    app.session_saver.flush(&app.session).unwrap();
+    assert!(!app.session_saver.is_dirty());
+    let saved = gidterm::Session::load_in(dir.path(), &app.session.id).unwrap();
+    assert_eq!(saved.tasks["spam"].runs[0].output.last().map(String::as_str), Some("2000"));
+}
+
+#[tokio::test]
+async fn test_abort_all_needs_confirmation() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use gidterm::App;
+    use std::time::Duration;
+
+    let graph: Graph = serde_yaml::from_str(
+        r#"
+metadata:
+  project: abort-test
+tasks:
+  server:
+    description: Server
+    command: cat
+  worker:
+    description: Worker
+    command: cat
+  later:
+    description: Not started yet
+    command: "true"
+"#,
+    )
+    .unwrap();
+
+    let mut app = TestApp::new(graph);
+    // Start through the executor so no session file is written
+    for id in ["server", "worker"] {
+        app.executor.start_task(id, "cat", &HashMap::new(), &[0]).await.unwrap();
+        app.scheduler.mark_started(id).unwrap();
+    }
+    let press = |app: &mut App, code| app.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+
+    // Any key but `y` cancels
+    press(&mut app, KeyCode::Char('X'));
+    assert!(app.confirm_abort);
+    press(&mut app, KeyCode::Char('x'));
+    assert!(!app.confirm_abort);
+    assert_eq!(app.scheduler.get_running().len(), 2);
+
+    press(&mut app, KeyCode::Char('X'));
+    press(&mut app, KeyCode::Char('y'));
+    assert!(!app.confirm_abort);
+    assert!(!app.should_quit);
+
+    let graph = app.scheduler.graph();
+    assert_eq!(graph.get_task("server").unwrap().status, GraphTaskStatus::Failed);
+    assert_eq!(graph.get_task("worker").unwrap().status, GraphTaskStatus::Failed);
+    assert!(app.scheduler.get_running().is_empty());
+    // Nothing new starts, and the run counts as over
+    assert!(app.scheduler.schedule_next().is_empty());
+    assert!(app.scheduler.all_done());
+
+    for _ in 0..250 {
+        if !app.executor.is_running("server") && !app.executor.is_running("worker") {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert!(!app.executor.is_running("server"));
+    assert!(!app.executor.is_running("worker"));
+
+    // With nothing running, `X` doesn't ask
+    press(&mut app, KeyCode::Char('X'));
+    assert!(!app.confirm_abort);
+}
+
+#[test]
+fn test_task_eta_cross_checks_reported_remaining() {
+    use gidterm::semantic::{MetricValue, TaskMetrics};
+    use gidterm::App;
+    use std::time::{Duration, Instant};
+
+    fn report(app: &mut App, task_id: &str, secs: i64) {
+        let mut metrics = HashMap::new();
+        metrics.insert("remaining_secs".to_string(), MetricValue::Int(secs));
+        app.task_metrics.insert(
+            task_id.to_string(),
+            TaskMetrics {
+                progress: 0.1,
+                metrics,
+                phase: None,
+                errors: Vec::new(),
+            },
+        );
+    }
+
+    fn eta_secs(app: &App, task_id: &str) -> Option<u64> {
+        app.task_eta(task_id).map(|d| d.as_secs_f64().round() as u64)
+    }
+
+    let graph = Graph::from_file(Path::new("test-gid-integration.yml")).unwrap();
+    let mut app = TestApp::new(graph);
+
+    // History: 10% in 10s, so 90s to go
+    let base = Instant::now();
+    let history = app.metric_history.entry("hello".to_string()).or_default();
+    history.record_at(0.0, HashMap::new(), base);
+    history.record_at(0.1, HashMap::new(), base + Duration::from_secs(10));
+    assert_eq!(eta_secs(&app, "hello"), Some(90));
+
+    // Close enough: keep our (smoother) estimate
+    report(&mut app, "hello", 120);
+    assert_eq!(eta_secs(&app, "hello"), Some(90));
+
+    // Way off: trust the tool
+    report(&mut app, "hello", 900);
+    assert_eq!(eta_secs(&app, "hello"), Some(900));
+
+    // Without history, the tool's figure is used as is
+    assert_eq!(eta_secs(&app, "world"), None);
+    report(&mut app, "world", 30);
+    assert_eq!(eta_secs(&app, "world"), Some(30));
+}
+
+#[test]
+fn test_cli_validate() {
+    use assert_cmd::Command;
+    use predicates::str::contains;
+
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("bad.yml"),
+        r#"
+metadata:
+  project: validate-test
+tasks:
+  build:
+    description: Build
+    command: make
+    depends_on: [compile]
+"#,
+    )
+    .unwrap();
+
+    Command::cargo_bin("gidterm")
+        .unwrap()
+        .current_dir(dir.path())
+        .env("HOME", dir.path())
+        .args(["validate", "--graph", "bad.yml"])
+        .assert()
+        .failure()
+        .stdout(contains("error: build: depends on unknown task 'compile'"))
+        .stdout(contains("1 error(s), 0 warning(s)"));
+
+    Command::cargo_bin("gidterm")
+        .unwrap()
+        .current_dir(dir.path())
+        .env("HOME", dir.path())
+        .args(["validate", "--graph"])
+        .arg(Path::new(env!("CARGO_MANIFEST_DIR")).join("test-gid-integration.yml"))
+        .assert()
+        .success()
+        .stdout(contains("No problems found."));
+}
+
+#[test]
+fn test_cli_status_json() {
+    use assert_cmd::Command;
+
+    let output = Command::cargo_bin("gidterm")
+        .unwrap()
+        .args(["status", "--json", "--graph", "test-gid-integration.yml"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["project"], "test-integration");
+    assert_eq!(report["tasks"].as_array().unwrap().len(), 5);
+    assert_eq!(report["tasks"][0]["id"], "final");
+    assert_eq!(report["progress"]["total"], 5);
+    assert_eq!(report["progress"]["done"], 0);
+    assert_eq!(report["progress"]["pct"], 0.0);
+
+    let world = report["tasks"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|t| t["id"] == "world")
+        .unwrap();
+    assert_eq!(world["status"], "pending");
+    assert_eq!(world["depends_on"], serde_json::json!(["hello"]));
+}
+
+#[test]
+fn test_cli_history_summarizes_tasks() {
+    use assert_cmd::Command;
+    use gidterm::TaskStatus;
+    use predicates::prelude::*;
+
+    let dir = tempfile::tempdir().unwrap();
+    let sessions = dir.path().join(".gidterm").join("sessions");
+    std::fs::create_dir_all(&sessions).unwrap();
+
+    let mut session = Session::new("history-test".to_string());
+    session.id = "2026-03-01-12-00-00".to_string();
+    for status in [TaskStatus::Done, TaskStatus::Failed, TaskStatus::Done] {
+        session.start_task("build".to_string());
+        session.end_task("build", status, None);
+    }
+    std::fs::write(
+        sessions.join("2026-03-01-12-00-00.json"),
+        serde_json::to_string(&session).unwrap(),
+    )
+    .unwrap();
+
+    Command::cargo_bin("gidterm")
+        .unwrap()
+        .arg("history")
+        .current_dir(dir.path())
+        .env("HOME", dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("2026-03-01-12-00-00 (history-test)"))
+        .stdout(predicate::str::contains("build: 3 runs, 2 ok, avg 0.0s"));
+}
+
+#[test]
+fn test_cli_diff() {
+    use assert_cmd::Command;
+    use gidterm::TaskStatus;
+    use predicates::prelude::*;
+
+    let dir = tempfile::tempdir().unwrap();
+    let sessions = dir.path().join(".gidterm").join("sessions");
+    std::fs::create_dir_all(&sessions).unwrap();
+
+    let write = |id: &str, tasks: &[(&str, TaskStatus)]| {
+        let mut session = Session::new("diff-test".to_string());
+        session.id = id.to_string();
+        for (task_id, status) in tasks {
+            session.start_task(task_id.to_string());
+            session.end_task(task_id, status.clone(), None);
+        }
+        std::fs::write(
+            sessions.join(format!("{}.json", id)),
+            serde_json::to_string(&session).unwrap(),
+        )
+        .unwrap();
+    };
+    write("2026-01-01-00-00-00", &[("build", TaskStatus::Done), ("deploy", TaskStatus::Done)]);
+    write("2026-01-02-00-00-00", &[("build", TaskStatus::Failed), ("lint", TaskStatus::Done)]);
+
+    let diff = |args: &[&str]| {
+        let mut cmd = Command::cargo_bin("gidterm").unwrap();
+        cmd.arg("diff").args(args).current_dir(dir.path()).env("HOME", dir.path());
+        cmd
+    };
+
+    diff(&["2026-01-01-00-00-00", "2026-01-02-00-00-00"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Done→Failed"))
+        .stdout(predicate::str::contains("lint    new"))
+        .stdout(predicate::str::contains("deploy  removed"));
+
+    let output = diff(&["2026-01-01-00-00-00", "latest", "--json"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["tasks"][0]["task_id"], "build");
+    assert_eq!(json["tasks"][0]["after"], "Failed");
+    assert_eq!(json["added"], serde_json::json!(["lint"]));
+    assert_eq!(json["removed"], serde_json::json!(["deploy"]));
+
+    diff(&["2026-01-01-00-00-00", "1999-01-01-00-00-00"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Session '1999-01-01-00-00-00' not found"));
+}
+
+#[test]
+fn test_cli_best() {
+    use assert_cmd::Command;
+    use predicates::prelude::*;
+    use std::collections::HashMap;
+
+    let dir = tempfile::tempdir().unwrap();
+    let sessions = dir.path().join(".gidterm").join("sessions");
+    std::fs::create_dir_all(&sessions).unwrap();
+
+    for (id, loss) in [("2026-01-01-00-00-00", 0.4), ("2026-01-02-00-00-00", 0.2), ("2026-01-03-00-00-00", 0.3)] {
+        let mut session = Session::new("best-test".to_string());
+        session.id = id.to_string();
+        session.start_task("train".to_string());
+        session.record_metrics("train", &HashMap::from([("loss".to_string(), loss)]));
+        std::fs::write(
+            sessions.join(format!("{}.json", id)),
+            serde_json::to_string(&session).unwrap(),
+        )
+        .unwrap();
+    }
+
+    let best = |args: &[&str]| {
+        let mut cmd = Command::cargo_bin("gidterm").unwrap();
+        cmd.arg("best").args(args).current_dir(dir.path()).env("HOME", dir.path());
+        cmd
+    };
+
+    best(&["--metric", "loss", "--minimize"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("0.2 - task train in session 2026-01-02-00-00-00"));
+    best(&["--metric", "loss", "--maximize"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("session 2026-01-01-00-00-00"));
+    best(&["--metric", "accuracy", "--maximize"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No run in 3 session(s) recorded 'accuracy'"));
+    // A direction is required
+    best(&["--metric", "loss"]).assert().failure();
+}
+
+#[test]
+fn test_cli_logs() {
+    use assert_cmd::Command;
+    use predicates::prelude::*;
+
+    let dir = tempfile::tempdir().unwrap();
+    let sessions = dir.path().join(".gidterm").join("sessions");
+    std::fs::create_dir_all(&sessions).unwrap();
+
+    let mut session = Session::new("logs-test".to_string());
+    session.id = "2026-01-01-00-00-00".to_string();
+    session.start_task("build".to_string());
+    for line in ["compiling", "linking", "finished"] {
+        session.add_output("build", line.to_string());
+    }
+    session.end_task("build", gidterm::TaskStatus::Done, Some(0));
+    std::fs::write(
+        sessions.join("2026-01-01-00-00-00.json"),
+        serde_json::to_string(&session).unwrap(),
+    )
+    .unwrap();
+
+    let logs = |args: &[&str]| {
+        let mut cmd = Command::cargo_bin("gidterm").unwrap();
+        cmd.arg("logs").args(args).current_dir(dir.path()).env("HOME", dir.path());
+        cmd
+    };
+
+    // Latest session by default
+    logs(&["build"])
+        .assert()
+        .success()
+        .stdout("compiling\nlinking\nfinished\n");
+    logs(&["build", "--tail", "1", "--session", "2026-01-01-00-00-00"])
+        .assert()
+        .success()
+        .stdout("finished\n");
+
+    logs(&["deploy"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Task 'deploy' has no output"));
+    logs(&["build", "--session", "1999-01-01-00-00-00"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("not found"));
+
+    // The spill log, when present, has the whole output
+    let log_dir = dir.path().join(".gidterm").join("logs").join("2026-01-01-00-00-00");
+    std::fs::create_dir_all(&log_dir).unwrap();
+    std::fs::write(log_dir.join("build.log"), "first run\ncompiling\nlinking\nfinished\n").unwrap();
+    logs(&["build", "--tail", "4"])
+        .assert()
+        .success()
+        .stdout("first run\ncompiling\nlinking\nfinished\n");
+}
+
+#[test]
+fn test_cli_logs_follow_past_session_cap() {
+    use assert_cmd::cargo::CommandCargoExt;
+    use std::io::{BufRead, BufReader};
+    use std::process::{Command, Stdio};
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    let dir = tempfile::tempdir().unwrap();
+    let sessions = dir.path().join(".gidterm").join("sessions");
+    let mut session = Session::new("follow-test".to_string());
+    session.set_output_limit(5);
+    session.start_task("train".to_string());
+    for i in 1..=5 {
+        session.add_output("train", format!("line {}", i));
+    }
+    session.save_in(&sessions).unwrap();
+
+    let mut child = Command::cargo_bin("gidterm")
+        .unwrap()
+        .args(["logs", "train", "--follow"])
+        .current_dir(dir.path())
+        .env("HOME", dir.path())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let (tx, rx) = mpsc::channel();
+    let stdout = child.stdout.take().unwrap();
+    std::thread::spawn(move || {
+        for line in BufReader::new(stdout).lines() {
+            let _ = tx.send(line.unwrap());
+        }
+    });
+
+    // The last line of a running task may still change, so it waits
+    let mut seen = Vec::new();
+    while seen.last().map(String::as_str) != Some("line 4") {
+        seen.push(rx.recv_timeout(Duration::from_secs(10)).expect("initial output"));
+    }
+
+    // The session only keeps 5 lines, so its line count stops growing
+    for i in 6..=8 {
+        session.add_output("train", format!("line {}", i));
+    }
+    session.end_task("train", gidterm::TaskStatus::Done, Some(0));
+    session.save_in(&sessions).unwrap();
+
+    while seen.last().map(String::as_str) != Some("line 8") {
+        seen.push(rx.recv_timeout(Duration::from_secs(10)).expect("followed output"));
+    }
+    let expected: Vec<String> = (1..=8).map(|i| format!("line {}", i)).collect();
+    assert_eq!(seen, expected);
+    assert!(child.wait().unwrap().success());
+}
+
+#[test]
+fn test_headless_save_status() {
+    use assert_cmd::Command;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("graph.yml");
+    std::fs::write(
+        &path,
+        r#"
+metadata:
+  project: save-status-test
+tasks:
+  ok:
+    description: Succeeds
+    command: "true"
+    status: pending
+  broken:
+    description: Fails
+    command: "false"
+"#,
+    )
+    .unwrap();
+
+    Command::cargo_bin("gidterm")
+        .unwrap()
+        .current_dir(dir.path())
+        .env("HOME", dir.path())
+        .args(["run", "--headless", "--save-status", "--graph", "graph.yml"])
+        .assert()
+        .failure();
+
+    let graph = Graph::from_file(&path).unwrap();
+    assert_eq!(graph.get_task("ok").unwrap().status, GraphTaskStatus::Done);
+    assert_eq!(graph.get_task("broken").unwrap().status, GraphTaskStatus::Failed);
+}
+
+#[test]
+fn test_tick_rate_adapts_to_activity() {
+    use gidterm::app::{TickRate, ACTIVE_WINDOW, DEFAULT_ACTIVE_TICK, IDLE_TICK};
+    use std::time::{Duration, Instant};
+
+    let now = Instant::now();
+    let mut tick = TickRate::default();
+    assert_eq!(tick.interval(now), IDLE_TICK);
+
+    tick.record_activity(now);
+    assert_eq!(tick.interval(now + Duration::from_millis(100)), DEFAULT_ACTIVE_TICK);
+    assert_eq!(tick.interval(now + ACTIVE_WINDOW), IDLE_TICK);
+
+    // --tick-ms sets the busy tick; idle never ticks faster than busy
+    let mut slow = TickRate::new(Duration::from_millis(500));
+    slow.record_activity(now);
+    assert_eq!(slow.interval(now), Duration::from_millis(500));
+    assert_eq!(slow.interval(now + ACTIVE_WINDOW), Duration::from_millis(500));
+}
+
+#[tokio::test]
+async fn test_task_output_speeds_up_ticks() {
+    use gidterm::app::{DEFAULT_ACTIVE_TICK, IDLE_TICK};
+    use std::time::{Duration, Instant};
+
+    let graph: Graph = serde_yaml::from_str(
+        "metadata:\n  project: tick-test\ntasks:\n  hello:\n    description: Hi\n    command: echo hi\n",
+    )
+    .unwrap();
+    let mut app = TestApp::new(graph);
+    assert_eq!(app.tick.interval(Instant::now()), IDLE_TICK);
+
+    app.executor
+        .start_task("hello", "echo hi", &HashMap::new(), &[0])
+        .await
+        .unwrap();
+    for _ in 0..200 {
+        app.process_events();
+        if !app.get_task_output("hello", 1).is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(app.tick.interval(Instant::now()), DEFAULT_ACTIVE_TICK);
+}
+
+#[test]
+fn test_process_events_caps_each_tick() {
+    use gidterm::app::MAX_EVENTS_PER_TICK;
+    use gidterm::TaskEvent;
+
+    let graph: Graph = serde_yaml::from_str(
+        "metadata:\n  project: flood-test\ntasks:\n  flood:\n    description: Loud\n    command: yes\n",
+    )
+    .unwrap();
+    let mut app = TestApp::new(graph);
+    app.set_buffer_lines(20_000);
+    let (tx, rx) = tokio::sync::mpsc::channel(10_000);
+    app.event_rx = rx;
+
+    for i in 0..10_000 {
+        tx.try_send(TaskEvent::Output {
+            task_id: "flood".to_string(),
+            line: format!("line {}", i),
+            replace: false,
+        })
+        .unwrap();
+    }
+
+    // Each call returns after the cap, reporting the rest as pending
+    assert!(app.process_events());
+    assert_eq!(app.get_task_output("flood", usize::MAX).len(), MAX_EVENTS_PER_TICK);
+
+    let mut calls = 2;
+    while app.process_events() {
+        calls += 1;
+    }
+    assert_eq!(calls, 10_000usize.div_ceil(MAX_EVENTS_PER_TICK));
+    let output = app.get_task_output("flood", usize::MAX);
+    assert_eq!(output.len(), 10_000);
+    assert_eq!(output.last().map(String::as_str), Some("line 9999"));
+}
+
+#[test]
+fn test_metric_parsing_bounded_under_rapid_output() {
+    use gidterm::TaskEvent;
+
+    let graph: Graph = serde_yaml::from_str(
+        "metadata:\n  project: parse-test\ntasks:\n  build:\n    type: build\n    description: Loud build\n    command: make\n",
+    )
+    .unwrap();
+    let mut app = TestApp::new(graph);
+    let (tx, rx) = tokio::sync::mpsc::channel(10_000);
+    app.event_rx = rx;
+
+    for i in 1..=5000 {
+        tx.try_send(TaskEvent::Output {
+            task_id: "build".to_string(),
+            line: format!("[{}/5000] Compiling unit{}", i, i),
+            replace: false,
+        })
+        .unwrap();
+    }
+    while app.process_events() {}
+
+    // Thousands of lines, but only a handful of parses
+    let parses = app.metric_throttle.parses();
+    assert!((1..=3).contains(&parses), "{} parses for 5000 lines", parses);
+
+    // The final output is parsed as the task exits, whatever the interval
+    tx.try_send(TaskEvent::Completed {
+        task_id: "build".to_string(),
+        exit_code: 0,
+    })
+    .unwrap();
+    app.process_events();
+    assert_eq!(app.metric_throttle.parses(), parses + 1);
+    assert!(app.get_task_metrics("build").is_some_and(|m| m.progress > 0.99));
+}
+
+#[test]
+fn test_capped_buffers_keep_order() {
+    use gidterm::TaskEvent;
+
+    let mut app = TestApp::new(two_chain_graph());
+    let (tx, rx) = tokio::sync::mpsc::channel(100);
+    app.event_rx = rx;
+
+    // Recent events keep the newest 50, oldest first
+    for i in 0..60 {
+        tx.try_send(TaskEvent::Started { task_id: format!("t{}", i) }).unwrap();
+    }
+    app.process_events();
+    assert_eq!(app.recent_events.len(), 50);
+    assert_eq!(app.recent_events.front().map(|(_, _, m)| m.as_str()), Some("Started: t10"));
+    let newest: Vec<String> = app.get_recent_events(3).into_iter().map(|(_, m)| m).collect();
+    assert_eq!(newest, vec!["Started: t59", "Started: t58", "Started: t57"]);
+
+    // Output buffers drop from the front and still replace the newest line
+    app.set_buffer_lines(3);
+    for i in 0..10 {
+        app.store_output("a1", &format!("line {}", i), false);
+    }
+    app.store_output("a1", "line 9 (redrawn)", true);
+    assert_eq!(app.get_task_output("a1", 10), vec!["line 7", "line 8", "line 9 (redrawn)"]);
+    assert_eq!(app.get_task_output("a1", 2), vec!["line 8", "line 9 (redrawn)"]);
+
+    // The same tail, borrowed rather than cloned
+    let longest = app.with_tail("a1", 2, |lines| lines.map(String::len).max());
+    assert_eq!(longest, Some("line 9 (redrawn)".len()));
+    assert_eq!(app.with_tail("missing", 5, |lines| lines.count()), 0);
+}
+
+#[test]
+fn test_output_lines_tagged_with_level() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use gidterm::app::ViewMode;
+    use gidterm::semantic::LogLevel;
+
+    let mut app = TestApp::new(two_chain_graph());
+    for line in [
+        "   Compiling gidterm v0.5.0",
+        "warning: unused import: `std::fs`",
+        "error[E0425]: cannot find value `x` in this scope",
+        "error: could not compile `gidterm`",
+    ] {
+        app.store_output("a1", line, false);
+    }
+    assert_eq!(app.output_level("a1", 0), LogLevel::Info);
+    assert_eq!(app.output_level("a1", 1), LogLevel::Warn);
+    assert_eq!(app.error_lines("a1"), vec![2, 3]);
+
+    // A redrawn line takes the level of its new text
+    app.store_output("a1", "Finished in 2s", true);
+    assert_eq!(app.error_lines("a1"), vec![2]);
+    assert_eq!(app.output_level("missing", 0), LogLevel::Info);
+
+    app.view_mode = ViewMode::Terminal;
+    app.handle_key(KeyEvent::new(KeyCode::Char('E'), KeyModifiers::NONE));
+    assert!(app.errors_only);
+    app.handle_key(KeyEvent::new(KeyCode::Char('E'), KeyModifiers::NONE));
+    assert!(!app.errors_only);
+}
+
+#[tokio::test]
+async fn test_answer_waiting_task_from_dashboard() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use gidterm::app::App;
+    use std::time::Duration;
+
+    let graph: Graph = serde_yaml::from_str(
+        "metadata:\n  project: input-test\ntasks:\n  build:\n    description: b\n    command: \"true\"\n  greet:\n    description: Asks a question\n    command: greet\n",
+    )
+    .unwrap();
+    let mut app = TestApp::new(graph);
+    let script = "printf 'Enter your name: \\n'; read -r name; echo \"hello $name\"";
+    app.executor.start_task("greet", script, &HashMap::new(), &[0]).await.unwrap();
+    app.scheduler.mark_started("greet").unwrap();
+    let press = |app: &mut App, code| app.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+
+    // Nothing to answer yet
+    press(&mut app, KeyCode::Char('i'));
+    assert!(app.input_prompt.is_none());
+
+    for _ in 0..250 {
+        app.process_events();
+        if app.is_waiting_input("greet") {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert!(app.is_waiting_input("greet"));
+
+    // `build` is selected, so `i` answers the waiting task instead
+    assert_eq!(app.get_task_ids()[app.selected_task], "build");
+    press(&mut app, KeyCode::Char('i'));
+    assert_eq!(app.input_prompt.as_ref().map(|p| p.task_id.as_str()), Some("greet"));
+    for c in "Adaa".chars() {
+        press(&mut app, KeyCode::Char(c));
+    }
+    press(&mut app, KeyCode::Backspace);
+    press(&mut app, KeyCode::Enter);
+    assert!(app.input_prompt.is_none());
+    assert!(!app.is_waiting_input("greet"));
+
+    for _ in 0..250 {
+        app.process_events();
+        if app.get_task_output("greet", 10).iter().any(|l| l == "hello Ada") {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert!(app.get_task_output("greet", 10).iter().any(|l| l == "hello Ada"));
+}
+
+#[tokio::test]
+async fn test_interact_mode_forwards_keys() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use gidterm::app::{App, ViewMode};
+    use std::time::Duration;
+
+    let graph: Graph = serde_yaml::from_str(
+        "metadata:\n  project: interact-test\ntasks:\n  repl:\n    description: Echoes lines\n    command: cat\n",
+    )
+    .unwrap();
+    let mut app = TestApp::new(graph);
+    app.executor.start_task("repl", "cat", &HashMap::new(), &[0]).await.unwrap();
+    app.scheduler.mark_started("repl").unwrap();
+    app.view_mode = ViewMode::Terminal;
+    let press = |app: &mut App, code| app.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+    let ctrl = |app: &mut App, c| app.handle_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL));
+
+    // Ctrl-A then anything but `i` does nothing
+    ctrl(&mut app, 'a');
+    press(&mut app, KeyCode::Char('x'));
+    assert!(app.interact_task.is_none());
+
+    ctrl(&mut app, 'a');
+    press(&mut app, KeyCode::Char('i'));
+    assert_eq!(app.interact_task.as_deref(), Some("repl"));
+
+    // Keys that would normally switch views or quit go to the task instead
+    for c in "qdx".chars() {
+        press(&mut app, KeyCode::Char(c));
+    }
+    press(&mut app, KeyCode::Backspace);
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.view_mode, ViewMode::Terminal);
+    assert!(!app.should_quit);
+
+    // cat prints the line as edited by the Backspace
+    for _ in 0..250 {
+        app.process_events();
+        if app.get_task_output("repl", 10).iter().any(|l| l == "qd") {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert!(app.get_task_output("repl", 10).iter().any(|l| l == "qd"));
+
+    press(&mut app, KeyCode::Esc);
+    assert!(app.interact_task.is_none());
+    assert_eq!(app.view_mode, ViewMode::Terminal);
+
+    // Ctrl-D reaches cat as EOF
+    ctrl(&mut app, 'a');
+    press(&mut app, KeyCode::Char('i'));
+    ctrl(&mut app, 'd');
+    for _ in 0..250 {
+        if !app.executor.is_running("repl") {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert!(!app.executor.is_running("repl"));
+
+    // With the task gone the next key leaves interact mode, and it can't
+    // be entered again
+    press(&mut app, KeyCode::Char('z'));
+    assert!(app.interact_task.is_none());
+    ctrl(&mut app, 'a');
+    press(&mut app, KeyCode::Char('i'));
+    assert!(app.interact_task.is_none());
+}
+
+#[tokio::test]
+async fn test_step_mode_starts_one_wave_per_step() {
+    use gidterm::app::{App, StepMode};
+
+    // Tasks without a command finish as soon as they start, so each step
+    // finishes exactly one dependency layer
+    let graph: Graph = serde_yaml::from_str(
+        "metadata:\n  project: step-test\ntasks:\n  a:\n    description: a\n  b:\n    description: b\n  c:\n    description: c\n    depends_on: [a, b]\n  d:\n    description: d\n    depends_on: [c]\n",
+    )
+    .unwrap();
+    let mut app = TestApp::new(graph);
+    assert!(app.step().is_err());
+
+    app.set_step_mode(true);
+    let done = |app: &App| {
+        let mut ids: Vec<String> = app
+            .scheduler
+            .graph()
+            .tasks
+            .iter()
+            .filter(|(_, t)| t.status == GraphTaskStatus::Done)
+            .map(|(id, _)| id.clone())
+            .collect();
+        ids.sort();
+        ids
+    };
+
+    app.start_ready_tasks().await.unwrap();
+    assert!(done(&app).is_empty());
+
+    let waves: [&[&str]; 3] = [&["a", "b"], &["a", "b", "c"], &["a", "b", "c", "d"]];
+    for wave in waves {
+        app.step().unwrap();
+        app.start_ready_tasks().await.unwrap();
+        assert_eq!(done(&app), wave);
+        assert_eq!(app.step_mode, StepMode::Paused);
+        // Without another step nothing more starts
+        app.start_ready_tasks().await.unwrap();
+        assert_eq!(done(&app), wave);
+    }
+
+    // A step with nothing ready stays pending
+    app.step().unwrap();
+    app.start_ready_tasks().await.unwrap();
+    assert_eq!(app.step_mode, StepMode::Step);
+}
+
+#[test]
+fn test_mcp_schema_command() {
+    let output = assert_cmd::Command::cargo_bin("gidterm")
+        .unwrap()
+        .arg("mcp-schema")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let doc: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let tools = doc["tools"].as_array().unwrap();
+    let start_task = tools.iter().find(|t| t["name"] == "start_task").unwrap();
+    assert_eq!(start_task["inputSchema"]["required"], serde_json::json!(["task_id"]));
+    assert!(doc["schemas"]["StateSnapshot"].is_object());
+    assert!(doc["schemas"]["GidEvent"].is_object());
+}
+
+#[tokio::test]
+async fn test_safe_mode_rejects_disallowed_agent_commands() {
+    use gidterm::ai::control::{ControlCommand, ControlResponse};
+    use gidterm::ai::{AgentPolicy, ControlMode};
+    use gidterm::app::App;
+
+    let graph: Graph = serde_yaml::from_str(
+        "metadata:\n  project: safe-mode-test\ntasks:\n  test:\n    description: t\n  deploy:\n    description: d\n",
+    )
+    .unwrap();
+    let mut app = TestApp::new(graph);
+    app.control_mode = ControlMode::Agent;
+    app.agent_policy = AgentPolicy {
+        safe_mode: true,
+        allowed_tasks: vec!["test".to_string()],
+        ..Default::default()
+    };
+    let status = |app: &App, id: &str| app.scheduler.graph().get_task(id).unwrap().status.clone();
+
+    let response = app
+        .handle_command(ControlCommand::StartTask { task_id: "deploy".to_string() })
+        .await;
+    match response {
+        ControlResponse::Error { message } => assert!(message.contains("deploy"), "{}", message),
+        other => panic!("expected an error, got {:?}", other),
+    }
+    assert_eq!(status(&app, "deploy"), GraphTaskStatus::Pending);
+
+    let response = app.handle_command(ControlCommand::StartAll).await;
+    assert!(matches!(response, ControlResponse::Error { .. }));
+    assert_eq!(status(&app, "deploy"), GraphTaskStatus::Pending);
+
+    let response = app
+        .handle_command(ControlCommand::SendInput {
+            task_id: "test".to_string(),
+            input: "y".to_string(),
+        })
+        .await;
+    assert!(matches!(response, ControlResponse::Error { .. }));
+
+    let response = app
+        .handle_command(ControlCommand::StartTask { task_id: "test".to_string() })
+        .await;
+    assert!(matches!(response, ControlResponse::Ok { .. }));
+    assert_eq!(status(&app, "test"), GraphTaskStatus::Done);
+    assert!(matches!(app.handle_command(ControlCommand::GetState).await, ControlResponse::Ok { .. }));
+
+    // A human at the TUI isn't bound by the allowlist
+    app.start_task("deploy").await.unwrap();
+    assert_eq!(status(&app, "deploy"), GraphTaskStatus::Done);
+}
+
+#[tokio::test]
+async fn test_safe_mode_applies_to_control_clients_without_agent() {
+    use gidterm::ai::control::{ControlCommand, ControlResponse};
+    use gidterm::ai::{AgentPolicy, ControlHandle, ControlMode};
+
+    let graph: Graph = serde_yaml::from_str(
+        "metadata:\n  project: safe-client-test\ntasks:\n  test:\n    description: t\n  deploy:\n    description: d\n",
+    )
+    .unwrap();
+    let mut app = TestApp::new(graph);
+    app.agent_policy = AgentPolicy {
+        safe_mode: true,
+        allowed_tasks: vec!["test".to_string()],
+        ..Default::default()
+    };
+    // `--serve` / `--control-sock` without `--agent`
+    assert_eq!(app.control_mode, ControlMode::Manual);
+
+    let (handle, mut commands) = ControlHandle::new(app.events.clone());
+    let client = tokio::spawn(async move {
+        handle
+            .dispatch(ControlCommand::StartTask { task_id: "deploy".to_string() })
+            .await
+    });
+    let request = commands.recv().await.unwrap();
+    let response = app.handle_command(request.command).await;
+    let _ = request.reply.send(response);
+
+    match client.await.unwrap() {
+        ControlResponse::Error { message } => assert!(message.contains("deploy"), "{}", message),
+        other => panic!("expected an error, got {:?}", other),
+    }
+    let deploy = app.scheduler.graph().get_task("deploy").unwrap().status.clone();
+    assert_eq!(deploy, GraphTaskStatus::Pending);
+}
+
+#[tokio::test]
+async fn test_start_and_stop_tasks_by_tag() {
+    use std::time::Duration;
+
+    let graph: Graph = serde_yaml::from_str(
+        r#"
+metadata:
+  project: tag-test
+tasks:
+  api:
+    description: API server
+    command: sleep 30
+    tags: [backend]
+  worker:
+    description: Worker
+    command: sleep 30
+    tags: [Backend, queue]
+  migrate:
+    description: Needs the schema first
+    command: sleep 30
+    depends_on: [schema]
+    tags: [backend]
+  schema:
+    description: Schema
+    command: "true"
+  web:
+    description: Frontend
+    command: sleep 30
+    tags: [frontend]
+"#,
+    )
+    .unwrap();
+    let mut app = TestApp::new(graph);
+
+    assert!(app.start_tasks_by_tag("nope").await.is_err());
+
+    // `migrate` waits for its dependency
+    let started = app.start_tasks_by_tag("backend").await.unwrap();
+    assert_eq!(started, vec!["api", "worker"]);
+    assert!(!app.executor.is_running("web"));
+    assert!(!app.executor.is_running("migrate"));
+
+    // Already running tasks aren't started twice
+    assert!(app.start_tasks_by_tag("backend").await.unwrap().is_empty());
+
+    let stopped = app.stop_tasks_by_tag("backend").unwrap();
+    assert_eq!(stopped, vec!["api", "worker"]);
+    for _ in 0..250 {
+        if !app.executor.is_running("api") && !app.executor.is_running("worker") {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert!(!app.executor.is_running("api"));
+    assert!(!app.executor.is_running("worker"));
+}
+
+#[test]
+fn test_report_estimates_after_run() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("graph.yml"),
+        "metadata:\n  project: estimate-test\ntasks:\n  build:\n    description: b\n    command: \"true\"\n    estimated_hours: 2\n  lint:\n    description: l\n    command: \"true\"\n",
+    )
+    .unwrap();
+    let gidterm = |args: &[&str]| {
+        let output = assert_cmd::Command::cargo_bin("gidterm")
+            .unwrap()
+            .current_dir(dir.path())
+            .env("HOME", dir.path())
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    gidterm(&["run", "--headless", "--graph", "graph.yml"]);
+
+    let json = gidterm(&["report", "--estimates", "--graph", "graph.yml", "--json"]);
+    let report: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let rows = report.as_array().unwrap();
+    assert_eq!(rows.len(), 1, "tasks without an estimate are skipped");
+    assert_eq!(rows[0]["task_id"], "build");
+    assert_eq!(rows[0]["estimated_hours"], 2);
+    assert_eq!(rows[0]["overrun"], false);
+    assert!(rows[0]["variance_pct"].as_f64().unwrap() < -99.0);
+
+    let text = gidterm(&["report", "--estimates", "--graph", "graph.yml"]);
+    assert!(text.contains("TASK"), "{}", text);
+    assert!(text.lines().any(|l| l.starts_with("build") && l.contains("2h")), "{}", text);
+}
+
+#[test]
+fn test_group_tasks_by_component() {
+    use gidterm::app::UNGROUPED_COMPONENT;
+
+    let graph: Graph = serde_yaml::from_str(
+        r#"
+metadata:
+  project: component-test
+tasks:
+  schema:
+    description: s
+    component: db
+  api:
+    description: a
+    component: server
+    depends_on: [schema]
+  migrate:
+    description: m
+    component: db
+    depends_on: [schema]
+  docs:
+    description: d
+  cache:
+    description: c
+    component: server
+"#,
+    )
+    .unwrap();
+    let mut app = TestApp::new(graph);
+
+    assert_eq!(
+        app.get_tasks_by_component(),
+        vec![
+            ("db".to_string(), vec!["schema".to_string(), "migrate".to_string()]),
+            ("server".to_string(), vec!["cache".to_string(), "api".to_string()]),
+            (UNGROUPED_COMPONENT.to_string(), vec!["docs".to_string()]),
+        ]
+    );
+
+    // The flat order follows the groups, and the selection stays on the same task
+    app.selected_task = app.get_task_ids().iter().position(|id| id == "docs").unwrap();
+    app.toggle_component_grouping();
+    assert_eq!(app.get_task_ids(), vec!["schema", "migrate", "cache", "api", "docs"]);
+    assert_eq!(app.get_task_ids()[app.selected_task], "docs");
+
+    app.toggle_component_grouping();
+    assert_eq!(app.get_task_ids()[app.selected_task], "docs");
+}
+
+#[tokio::test]
+async fn test_quit_asks_while_tasks_run() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use gidterm::app::App;
+
+    let graph: Graph = serde_yaml::from_str(
+        "metadata:\n  project: quit-test\ntasks:\n  server:\n    description: Server\n    command: cat\n",
+    )
+    .unwrap();
+    let mut app = TestApp::new(graph);
+    let press = |app: &mut App, code| app.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+
+    // Nothing running: quits right away
+    press(&mut app, KeyCode::Char('q'));
+    assert!(app.should_quit);
+    app.should_quit = false;
+
+    app.executor.start_task("server", "cat", &HashMap::new(), &[0]).await.unwrap();
+    app.scheduler.mark_started("server").unwrap();
+
+    // Any key but `y` cancels
+    press(&mut app, KeyCode::Char('q'));
+    assert!(app.confirm_quit);
+    assert!(!app.should_quit);
+    press(&mut app, KeyCode::Char('n'));
+    assert!(!app.confirm_quit);
+    assert!(!app.should_quit);
+
+    press(&mut app, KeyCode::Char('q'));
+    press(&mut app, KeyCode::Char('y'));
+    assert!(app.should_quit);
+    app.should_quit = false;
+
+    // Ctrl-q and --force-quit skip the question
+    app.handle_key(KeyEvent::new(KeyCode::Char('q'), KeyModifiers::CONTROL));
+    assert!(app.should_quit && !app.confirm_quit);
+    app.should_quit = false;
+    app.force_quit = true;
+    press(&mut app, KeyCode::Char('q'));
+    assert!(app.should_quit && !app.confirm_quit);
+
+    app.executor.stop_all();
+}
+
+#[test]
+fn test_search_filters_tasks_and_projects() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use gidterm::app::{matches_search, App, ViewMode};
+
+    assert!(matches_search("API", "web-api"));
+    assert!(matches_search("web:b", "web:build"));
+    assert!(!matches_search("deploy", "web:build"));
+
+    let graph: Graph = serde_yaml::from_str(
+        r#"
+tasks:
+  "api:build":
+    description: b
+  "api:test":
+    description: t
+    depends_on: ["api:build"]
+  "web:build":
+    description: w
+"#,
+    )
+    .unwrap();
+    let mut app = TestApp::new(graph);
+    app.workspace_mode = true;
+    app.project_names = vec!["api".to_string(), "web".to_string()];
+    let press = |app: &mut App, code| app.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+
+    // Typing narrows the task list and keeps the selection on a match
+    app.selected_task = app.get_task_ids().iter().position(|id| id == "web:build").unwrap();
+    press(&mut app, KeyCode::Char('/'));
+    for c in "build".chars() {
+        press(&mut app, KeyCode::Char(c));
+    }
+    assert_eq!(app.get_task_ids(), vec!["api:build", "web:build"]);
+    assert_eq!(app.get_task_ids()[app.selected_task], "web:build");
+    assert!(app.search_indicator().contains("(2 matches)"));
+
+    // Enter keeps the filter; Esc on the dashboard clears it
+    press(&mut app, KeyCode::Enter);
+    assert!(!app.search_mode);
+    assert_eq!(app.get_task_ids().len(), 2);
+    press(&mut app, KeyCode::Esc);
+    assert_eq!(app.get_task_ids().len(), 3);
+    assert_eq!(app.get_task_ids()[app.selected_task], "web:build");
+
+    // Project names are filtered in the overview, and the selection follows
+    app.view_mode = ViewMode::ProjectOverview;
+    press(&mut app, KeyCode::Char('/'));
+    press(&mut app, KeyCode::Char('W'));
+    assert_eq!(app.visible_project_indices(), vec![1]);
+    assert_eq!(app.selected_project, 1);
+    assert_eq!(app.search_match_count(), 1);
+    press(&mut app, KeyCode::Up);
+    assert_eq!(app.selected_project, 1);
+
+    // Jump mode leaves the lists whole
+    press(&mut app, KeyCode::Tab);
+    assert!(!app.search_filter);
+    assert_eq!(app.visible_project_indices(), vec![0, 1]);
+    assert_eq!(app.get_task_ids().len(), 3);
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.selected_project, 1);
+    assert_eq!(app.get_task_ids()[app.selected_task], "web:build");
+}
+
+#[test]
+fn test_fuzzy_search_ranks_matches() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use gidterm::app::{matches_search, search_score, App, ViewMode};
+
+    assert!(matches_search("wapi", "web-api"));
+    assert!(matches_search("WAPI", "web-api"));
+    assert!(!matches_search("apiw", "web-api"));
+
+    // An exact prefix outranks the same letters later or spread out
+    let prefix = search_score("web", "web-api").unwrap();
+    assert!(prefix > search_score("web", "new-web").unwrap());
+    assert!(prefix > search_score("web", "www-eb").unwrap());
+
+    let graph: Graph = serde_yaml::from_str(
+        r#"
+tasks:
+  "new-web:build":
+    description: n
+  "web-api:build":
+    description: w
+  "web-api:deploy":
+    description: d
+    depends_on: ["web-api:build"]
+"#,
+    )
+    .unwrap();
+    let mut app = TestApp::new(graph);
+    app.workspace_mode = true;
+    app.project_names = vec!["new-web".to_string(), "web-api".to_string()];
+    let press = |app: &mut App, code| app.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+
+    // Filtering keeps list order but selects the best match
+    app.view_mode = ViewMode::ProjectOverview;
+    press(&mut app, KeyCode::Char('/'));
+    for c in "web".chars() {
+        press(&mut app, KeyCode::Char(c));
+    }
+    assert_eq!(app.visible_project_indices(), vec![0, 1]);
+    assert_eq!(app.selected_project, 1);
+
+    // Jump mode selects the best task once no project matches
+    press(&mut app, KeyCode::Esc);
+    app.view_mode = ViewMode::Dashboard;
+    app.search_filter = false;
+    press(&mut app, KeyCode::Char('/'));
+    for c in "wapdep".chars() {
+        press(&mut app, KeyCode::Char(c));
+    }
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.get_task_ids()[app.selected_task], "web-api:deploy");
+}
+
+#[tokio::test]
+async fn test_engine_runs_graph_to_completion() {
+    use gidterm::ai::GidEvent;
+    use gidterm::GidTermEngine;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    let graph: Graph = serde_yaml::from_str(
+        r#"
+metadata:
+  project: engine-test
+tasks:
+  build:
+    description: Build
+    command: echo built
+  test:
+    description: Test
+    command: "exit 3"
+    depends_on: [build]
+  deploy:
+    description: Deploy
+    command: echo deployed
+    depends_on: [test]
+"#,
+    )
+    .unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let completed = Arc::new(Mutex::new(Vec::new()));
+    let seen = completed.clone();
+
+    let engine = GidTermEngine::from_app(app_in(dir.path(), graph)).on_event(move |event| {
+        if let GidEvent::TaskCompleted { task_id, .. } = event {
+            seen.lock().unwrap().push(task_id.clone());
+        }
+    });
+    let mut stream = engine.subscribe();
+
+    // `deploy` can never run once `test` fails, so the run ends there
+    let summary = tokio::time::timeout(Duration::from_secs(10), engine.run_to_completion())
+        .await
+        .expect("run should finish")
+        .unwrap();
+    assert_eq!(summary.done, 1);
+    assert_eq!(summary.failed, 1);
+    assert_eq!(summary.pending, vec!["deploy"]);
+    assert_eq!(*completed.lock().unwrap(), vec!["build"]);
+
+    let mut failed = false;
+    while let Ok(event) = stream.try_recv() {
+        failed |= matches!(event, GidEvent::TaskFailed { ref task_id, .. } if task_id == "test");
+    }
+    assert!(failed);
+}
+
+#[tokio::test]
+async fn test_dropping_engine_run_kills_tasks() {
+    use gidterm::GidTermEngine;
+    use std::time::Duration;
+
+    let dir = tempfile::tempdir().unwrap();
+    let ticks = dir.path().join("ticks");
+    let graph: Graph = serde_yaml::from_str(&format!(
+        "tasks:\n  ticker:\n    description: Tick\n    command: \"while true; do echo tick >> {}; sleep 0.05; done\"\n",
+        ticks.display()
+    ))
+    .unwrap();
+
+    let engine = GidTermEngine::from_app(app_in(dir.path(), graph));
+    let run = engine.run_to_completion();
+    assert!(tokio::time::timeout(Duration::from_millis(500), run).await.is_err());
+
+    // The timeout dropped the run; the task stops writing
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let written = std::fs::read_to_string(&ticks).unwrap().len();
+    assert!(written > 0);
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert_eq!(std::fs::read_to_string(&ticks).unwrap().len(), written);
+}
+
+#[tokio::test]
+async fn test_engine_commands_and_stop_signal() {
+    use gidterm::ai::control::ControlCommand;
+    use gidterm::ai::ControlHandle;
+    use gidterm::GidTermEngine;
+    use std::time::Duration;
+
+    let graph: Graph =
+        serde_yaml::from_str("tasks:\n  server:\n    description: Serve\n    command: sleep 30\n").unwrap();
+    let dir = tempfile::tempdir().unwrap();
+
+    // A control command ends the run like it does `run --headless --serve`
+    let engine = GidTermEngine::from_app(app_in(dir.path(), graph.clone()));
+    let (handle, commands) = ControlHandle::new(engine.app().events.clone());
+    let mut engine = engine.with_commands(commands);
+    tokio::spawn(async move { handle.dispatch(ControlCommand::Quit).await });
+    let summary = tokio::time::timeout(Duration::from_secs(10), engine.run())
+        .await
+        .expect("quit should end the run")
+        .unwrap();
+    assert_eq!(summary.pending, vec!["server"]);
+    assert!(!engine.stopped());
+
+    // So does the stop signal (Ctrl-C for the CLI)
+    let mut engine = GidTermEngine::from_app(app_in(dir.path(), graph))
+        .stop_on(tokio::time::sleep(Duration::from_millis(200)));
+    let summary = tokio::time::timeout(Duration::from_secs(10), engine.run())
+        .await
+        .expect("the stop signal should end the run")
+        .unwrap();
+    assert_eq!(summary.pending, vec!["server"]);
+    assert!(engine.stopped());
+}
+
+#[test]
+fn test_graph_export_edges() {
+    const EDGES: [(&str, &str); 5] = [
+        ("hello", "world"),
+        ("world", "parallel1"),
+        ("world", "parallel2"),
+        ("parallel1", "final"),
+        ("parallel2", "final"),
+    ];
+
+    let output = assert_cmd::Command::cargo_bin("gidterm")
+        .unwrap()
+        .args(["graph", "-g", "test-gid-integration.yml", "--format", "dot"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let dot = String::from_utf8(output.stdout).unwrap();
+    assert!(dot.starts_with("digraph \"test-integration\" {"));
+    for (from, to) in EDGES {
+        assert!(dot.contains(&format!("\"{}\" -> \"{}\";", from, to)), "{}", dot);
+    }
+    assert_eq!(dot.matches("->").count(), EDGES.len());
+    assert!(dot.contains("{ rank=same; \"parallel1\"; \"parallel2\"; }"));
+
+    // Mermaid nodes are numbered; map them back to task IDs by label
+    let graph = Graph::from_file(Path::new("test-gid-integration.yml")).unwrap();
+    let mermaid = graph.to_mermaid(false);
+    assert!(mermaid.starts_with("flowchart LR\n"));
+    let id_of = |task: &str| {
+        let line = mermaid
+            .lines()
+            .find(|l| l.contains(&format!("[\"{}\"]", task)))
+            .unwrap();
+        line.trim().split('[').next().unwrap().to_string()
+    };
+    for (from, to) in EDGES {
+        assert!(mermaid.contains(&format!("  {} --> {}\n", id_of(from), id_of(to))), "{}", mermaid);
+    }
+    assert!(mermaid.contains(&format!("{}[\"hello\"]:::pending", id_of("hello"))));
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_executor_streams_output_to_fifo() {
+    use gidterm::core::fifo_path;
+    use gidterm::{Executor, TaskEvent};
+    use std::io::Read;
+    use std::os::unix::fs::OpenOptionsExt;
+    use std::time::Duration;
+
+    let dir = tempfile::tempdir().unwrap();
+    let (executor, mut event_rx) = Executor::new();
+    executor.set_fifo_dir(Some(dir.path().to_path_buf()));
+    executor
+        .start_task("web:build", "sleep 0.5; echo compiled; echo linked", &HashMap::new(), &[0])
+        .await
+        .unwrap();
+
+    // The FIFO exists as soon as the task starts; attach a reader before it prints
+    let path = fifo_path(dir.path(), "web:build");
+    assert!(path.exists());
+    let mut reader = std::fs::OpenOptions::new()
+        .read(true)
+        .custom_flags(libc::O_NONBLOCK)
+        .open(&path)
+        .unwrap();
+
+    let _ = tokio::time::timeout(Duration::from_secs(5), async {
+        while let Some(event) = event_rx.recv().await {
+            if matches!(event, TaskEvent::Completed { .. } | TaskEvent::Failed { .. }) {
+                break;
+            }
+        }
+    })
+    .await;
+
+    let mut text = String::new();
+    reader.read_to_string(&mut text).ok();
+    assert_eq!(text, "compiled\nlinked\n");
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_task_runs_in_its_shell() {
+    use gidterm::ai::GidEvent;
+    use gidterm::GidTermEngine;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    // `[[ ]]` and arrays are bash-only; the default `sh` may not support them
+    let graph: Graph = serde_yaml::from_str(
+        r#"
+tasks:
+  bashism:
+    description: Bash syntax
+    command: "arr=(a b c); [[ $${#arr[@]} == 3 ]] && echo \"count=$${#arr[@]}\""
+    shell: bash
+"#,
+    )
+    .unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let output = Arc::new(Mutex::new(Vec::new()));
+    let seen = output.clone();
+
+    let engine = GidTermEngine::from_app(app_in(dir.path(), graph)).on_event(move |event| {
+        if let GidEvent::TaskOutput { line, .. } = event {
+            seen.lock().unwrap().push(line.clone());
+        }
+    });
+    let summary = tokio::time::timeout(Duration::from_secs(10), engine.run_to_completion())
+        .await
+        .expect("run should finish")
+        .unwrap();
+    assert_eq!(summary.done, 1, "{:?}", output.lock().unwrap());
+    assert!(output.lock().unwrap().contains(&"count=3".to_string()));
+
+    // A shell that doesn't exist fails before anything is spawned
+    let graph: Graph = serde_yaml::from_str(
+        "tasks:\n  missing:\n    description: m\n    command: echo hi\n    shell: no-such-shell-gidterm\n",
+    )
+    .unwrap();
+    let engine = GidTermEngine::from_app(app_in(dir.path(), graph));
+    let err = engine.run_to_completion().await.unwrap_err();
+    assert!(format!("{:#}", err).contains("Shell 'no-such-shell-gidterm' not found"), "{:#}", err);
+}